@@ -43,7 +43,10 @@ use crate::{
             UserInterface,
         },
         material::Material,
-        scene::tilemap::{brush::TileMapBrush, tileset::TileSet},
+        scene::{
+            sound::SoundEvent,
+            tilemap::{brush::TileMapBrush, tileset::TileSet},
+        },
     },
     message::MessageSender,
     Message,
@@ -165,6 +168,17 @@ impl AssetItem {
                     Err(err) => Log::err(format!("Open tile_map_brush error: {err:?}")),
                 }
             }
+        } else if self
+            .path
+            .extension()
+            .is_some_and(|ext| ext == "sound_event")
+        {
+            if let Ok(path) = make_relative_path(&self.path) {
+                match block_on(resource_manager.request::<SoundEvent>(path)) {
+                    Ok(sound_event) => sender.send(Message::OpenSoundEventEditor(sound_event)),
+                    Err(err) => Log::err(format!("Open sound_event error: {err:?}")),
+                }
+            }
         } else if self.path.is_dir() {
             sender.send(Message::SetAssetBrowserCurrentDir(self.path.clone()));
         } else {
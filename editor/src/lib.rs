@@ -142,8 +142,8 @@ use crate::{
     plugins::{
         absm::AbsmEditor, absm::AbsmEditorPlugin, animation::AnimationEditorPlugin,
         collider::ColliderPlugin, curve_editor::CurveEditorPlugin, material::MaterialPlugin,
-        ragdoll::RagdollPlugin, settings::SettingsPlugin, stats::UiStatisticsPlugin,
-        tilemap::TileMapEditorPlugin,
+        ragdoll::RagdollPlugin, settings::SettingsPlugin, sound_event::SoundEventPlugin,
+        stats::UiStatisticsPlugin, tilemap::TileMapEditorPlugin,
     },
     scene::{
         commands::{
@@ -969,6 +969,7 @@ impl Editor {
                 .with(ColliderPlugin::default())
                 .with(TileMapEditorPlugin::default())
                 .with(MaterialPlugin::default())
+                .with(SoundEventPlugin::default())
                 .with(RagdollPlugin::default())
                 .with(SettingsPlugin::default())
                 .with(AnimationEditorPlugin::default())
@@ -28,7 +28,10 @@ use crate::{
         },
         gui::UiNode,
         material::MaterialResource,
-        scene::{camera::Projection, mesh::surface::SurfaceResource, node::Node},
+        scene::{
+            camera::Projection, mesh::surface::SurfaceResource, node::Node,
+            sound::SoundEventResource,
+        },
     },
     scene::Selection,
     SaveSceneConfirmationDialogAction,
@@ -67,6 +70,7 @@ pub enum Message {
     OpenMaterialEditor(MaterialResource),
     OpenTileSetEditor(TileSetResource),
     OpenTileMapBrushEditor(TileMapBrushResource),
+    OpenSoundEventEditor(SoundEventResource),
     OpenNodeRemovalDialog,
     ShowInAssetBrowser(PathBuf),
     LocateObject {
@@ -27,8 +27,8 @@ use crate::fyrox::{
         variable::InheritableVariable,
     },
     generic_animation::{
-        signal::AnimationSignal, track::Track, value::ValueBinding, Animation, AnimationContainer,
-        RootMotionSettings,
+        container::TrackDataContainer, signal::AnimationSignal, track::Track, value::ValueBinding,
+        Animation, AnimationContainer, RootMotionSettings,
     },
     graph::{BaseSceneGraph, SceneGraphNode},
 };
@@ -173,26 +173,64 @@ pub struct ReplaceTrackCurveCommand<N: Reflect> {
     pub animation_player: Handle<N>,
     pub animation: Handle<Animation<Handle<N>>>,
     pub curve: Curve,
+    // Quantized curves cannot be edited, so the data container of the track is dequantized by the
+    // command. The original (quantized) container is stored here to be restored on revert.
+    quantized: Option<TrackDataContainer>,
 }
 
 impl<N: Reflect> ReplaceTrackCurveCommand<N> {
-    fn swap(&mut self, context: &mut dyn CommandContext) {
+    pub fn new(
+        animation_player: Handle<N>,
+        animation: Handle<Animation<Handle<N>>>,
+        curve: Curve,
+    ) -> Self {
+        Self {
+            animation_player,
+            animation,
+            curve,
+            quantized: None,
+        }
+    }
+
+    fn swap(&mut self, context: &mut dyn CommandContext, execute: bool) {
         let animation =
             &mut fetch_animations_container(self.animation_player, context)[self.animation];
 
         let mut tracks_data_state = animation.tracks_data().state();
         let tracks_data = tracks_data_state.data().unwrap();
 
-        for track in tracks_data.tracks_mut() {
-            for curve in track.data_container_mut().curves_mut() {
-                if curve.id() == self.curve.id() {
-                    std::mem::swap(&mut self.curve, curve);
-                    return;
-                }
-            }
+        let Some(container) = tracks_data
+            .tracks_mut()
+            .iter_mut()
+            .map(|track| track.data_container_mut())
+            .find(|container| {
+                container
+                    .to_curves()
+                    .iter()
+                    .any(|curve| curve.id() == self.curve.id())
+            })
+        else {
+            Log::err(format!("There's no such curve with id {}", self.curve.id()));
+            return;
+        };
+
+        if execute && container.is_quantized() {
+            self.quantized = Some(container.clone());
         }
 
-        Log::err(format!("There's no such curve with id {}", self.curve.id()))
+        if let Some(curve) = container
+            .curves_mut()
+            .iter_mut()
+            .find(|curve| curve.id() == self.curve.id())
+        {
+            std::mem::swap(&mut self.curve, curve);
+        }
+
+        if !execute {
+            if let Some(quantized) = self.quantized.take() {
+                *container = quantized;
+            }
+        }
     }
 }
 
@@ -202,11 +240,11 @@ impl<N: Reflect> CommandTrait for ReplaceTrackCurveCommand<N> {
     }
 
     fn execute(&mut self, context: &mut dyn CommandContext) {
-        self.swap(context)
+        self.swap(context, true)
     }
 
     fn revert(&mut self, context: &mut dyn CommandContext) {
-        self.swap(context)
+        self.swap(context, false)
     }
 }
 
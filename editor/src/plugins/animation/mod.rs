@@ -351,11 +351,11 @@ impl AnimationEditor {
                                     .iter()
                                     .cloned()
                                     .map(|curve| {
-                                        Command::new(ReplaceTrackCurveCommand {
-                                            animation_player: selection.animation_player,
-                                            animation: selection.animation,
+                                        Command::new(ReplaceTrackCurveCommand::new(
+                                            selection.animation_player,
+                                            selection.animation,
                                             curve,
-                                        })
+                                        ))
                                     })
                                     .collect::<Vec<_>>(),
                             );
@@ -768,7 +768,7 @@ impl AnimationEditor {
                                 .find(|track| &track.id() == track_id)
                             {
                                 for (index, track_curve) in
-                                    track.data_container().curves_ref().iter().enumerate()
+                                    track.data_container().to_curves().iter().enumerate()
                                 {
                                     if !selected_curves
                                         .iter()
@@ -782,10 +782,10 @@ impl AnimationEditor {
                         SelectedEntity::Curve(curve_id) => {
                             if let Some((index, selected_curve)) =
                                 animation_tracks_data.tracks().iter().find_map(|t| {
-                                    t.data_container().curves_ref().iter().enumerate().find_map(
+                                    t.data_container().to_curves().iter().enumerate().find_map(
                                         |(i, c)| {
                                             if &c.id() == curve_id {
-                                                Some((i, c))
+                                                Some((i, c.clone()))
                                             } else {
                                                 None
                                             }
@@ -797,7 +797,7 @@ impl AnimationEditor {
                                     .iter()
                                     .any(|(_, curve)| curve.id == selected_curve.id)
                                 {
-                                    selected_curves.push((index, selected_curve.clone()));
+                                    selected_curves.push((index, selected_curve));
                                 }
                             }
                         }
@@ -806,7 +806,7 @@ impl AnimationEditor {
                 }
                 let mut background_curves = Vec::<Curve>::new();
                 for track in animation_tracks_data.tracks() {
-                    for curve in track.data_container().curves_ref() {
+                    for curve in track.data_container().to_curves().iter() {
                         if !selected_curves.iter().any(|(_, c)| c.id == curve.id) {
                             background_curves.push(curve.clone());
                         }
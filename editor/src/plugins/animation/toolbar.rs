@@ -24,7 +24,9 @@ use crate::fyrox::{
         algebra::Vector2, futures::executor::block_on, log::Log, math::Rect, pool::ErasedHandle,
         pool::Handle,
     },
-    generic_animation::{Animation, AnimationContainer, RootMotionSettings},
+    generic_animation::{
+        reduction::KeyframeReductionSettings, Animation, AnimationContainer, RootMotionSettings,
+    },
     graph::{BaseSceneGraph, PrefabData, SceneGraph, SceneGraphNode},
     gui::{
         border::BorderBuilder,
@@ -102,6 +104,8 @@ pub struct Toolbar {
     pub enabled: Handle<UiNode>,
     root_motion_dropdown_area: RootMotionDropdownArea,
     pub root_motion: Handle<UiNode>,
+    key_reduction_dropdown_area: KeyReductionDropdownArea,
+    pub reduce_keys: Handle<UiNode>,
     import_mode: ImportMode,
 }
 
@@ -394,6 +398,182 @@ impl RootMotionDropdownArea {
         }
     }
 }
+
+struct KeyReductionDropdownArea {
+    popup: Handle<UiNode>,
+    position_tolerance: Handle<UiNode>,
+    rotation_tolerance: Handle<UiNode>,
+    rotation_max_step: Handle<UiNode>,
+    scale_tolerance: Handle<UiNode>,
+    property_tolerance: Handle<UiNode>,
+    quantize: Handle<UiNode>,
+    reduce: Handle<UiNode>,
+    settings: KeyframeReductionSettings,
+}
+
+impl KeyReductionDropdownArea {
+    fn new(ctx: &mut BuildContext) -> Self {
+        fn text(text: &str, row: usize, ctx: &mut BuildContext) -> Handle<UiNode> {
+            TextBuilder::new(
+                WidgetBuilder::new()
+                    .with_vertical_alignment(VerticalAlignment::Center)
+                    .on_row(row)
+                    .on_column(0),
+            )
+            .with_text(text)
+            .build(ctx)
+        }
+
+        fn number(value: f32, row: usize, ctx: &mut BuildContext) -> Handle<UiNode> {
+            NumericUpDownBuilder::<f32>::new(
+                WidgetBuilder::new()
+                    .with_margin(Thickness::uniform(1.0))
+                    .on_row(row)
+                    .on_column(1),
+            )
+            .with_min_value(0.0)
+            .with_step(0.001)
+            .with_precision(4)
+            .with_value(value)
+            .build(ctx)
+        }
+
+        let settings = KeyframeReductionSettings::default();
+
+        let position_tolerance = number(settings.position_tolerance, 0, ctx);
+        let rotation_tolerance = number(settings.rotation_tolerance, 1, ctx);
+        let rotation_max_step = number(settings.rotation_max_step, 2, ctx);
+        let scale_tolerance = number(settings.scale_tolerance, 3, ctx);
+        let property_tolerance = number(settings.property_tolerance, 4, ctx);
+        let quantize = CheckBoxBuilder::new(
+            WidgetBuilder::new()
+                .with_width(18.0)
+                .with_height(18.0)
+                .with_margin(Thickness::uniform(1.0))
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .on_row(5)
+                .on_column(1),
+        )
+        .checked(Some(settings.quantize))
+        .build(ctx);
+        let reduce = ButtonBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(1.0))
+                .on_row(6)
+                .on_column(1),
+        )
+        .with_text("Reduce")
+        .build(ctx);
+        let popup = PopupBuilder::new(
+            WidgetBuilder::new()
+                .with_width(260.0)
+                .with_height(160.0)
+                .with_visibility(false),
+        )
+        .stays_open(false)
+        .with_content(
+            GridBuilder::new(
+                WidgetBuilder::new()
+                    .with_margin(Thickness::uniform(2.0))
+                    .with_child(text("Position Tolerance", 0, ctx))
+                    .with_child(position_tolerance)
+                    .with_child(text("Rotation Tolerance", 1, ctx))
+                    .with_child(rotation_tolerance)
+                    .with_child(text("Rotation Max Step", 2, ctx))
+                    .with_child(rotation_max_step)
+                    .with_child(text("Scale Tolerance", 3, ctx))
+                    .with_child(scale_tolerance)
+                    .with_child(text("Property Tolerance", 4, ctx))
+                    .with_child(property_tolerance)
+                    .with_child(text("Quantize", 5, ctx))
+                    .with_child(quantize)
+                    .with_child(reduce),
+            )
+            .add_column(Column::strict(130.0))
+            .add_column(Column::stretch())
+            .add_row(Row::strict(22.0))
+            .add_row(Row::strict(22.0))
+            .add_row(Row::strict(22.0))
+            .add_row(Row::strict(22.0))
+            .add_row(Row::strict(22.0))
+            .add_row(Row::strict(22.0))
+            .add_row(Row::strict(22.0))
+            .add_row(Row::stretch())
+            .build(ctx),
+        )
+        .build(ctx);
+
+        Self {
+            popup,
+            position_tolerance,
+            rotation_tolerance,
+            rotation_max_step,
+            scale_tolerance,
+            property_tolerance,
+            quantize,
+            reduce,
+            settings,
+        }
+    }
+
+    pub fn handle_ui_message<N>(
+        &mut self,
+        message: &UiMessage,
+        sender: &MessageSender,
+        ui: &UserInterface,
+        animation: &Animation<Handle<N>>,
+        selection: &AnimationSelection<N>,
+    ) where
+        N: SceneGraphNode,
+    {
+        if let Some(NumericUpDownMessage::<f32>::Value(value)) = message.data() {
+            if message.direction() == MessageDirection::FromWidget {
+                if message.destination() == self.position_tolerance {
+                    self.settings.position_tolerance = *value;
+                } else if message.destination() == self.rotation_tolerance {
+                    self.settings.rotation_tolerance = *value;
+                } else if message.destination() == self.rotation_max_step {
+                    self.settings.rotation_max_step = *value;
+                } else if message.destination() == self.scale_tolerance {
+                    self.settings.scale_tolerance = *value;
+                } else if message.destination() == self.property_tolerance {
+                    self.settings.property_tolerance = *value;
+                }
+            }
+        } else if let Some(CheckBoxMessage::Check(Some(value))) = message.data() {
+            if message.direction() == MessageDirection::FromWidget
+                && message.destination() == self.quantize
+            {
+                self.settings.quantize = *value;
+            }
+        } else if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.reduce {
+                let mut reduced = animation.clone();
+                let report = reduced.reduce_keys(&self.settings);
+                Log::info(format!(
+                    "Keyframe reduction of {} animation: {report}",
+                    animation.name()
+                ));
+
+                sender.do_command(ReplaceAnimationCommand {
+                    animation_player: selection.animation_player,
+                    animation_handle: selection.animation,
+                    animation: reduced,
+                });
+
+                ui.send_message(PopupMessage::close(self.popup, MessageDirection::ToWidget));
+            }
+        }
+    }
+
+    pub fn destroy(self, ui: &UserInterface) {
+        ui.send_message(WidgetMessage::remove(
+            self.popup,
+            MessageDirection::ToWidget,
+        ));
+    }
+}
 #[must_use]
 pub enum ToolbarAction {
     None,
@@ -414,6 +594,7 @@ impl Toolbar {
         let remove_current_animation;
         let rename_current_animation;
         let clone_current_animation;
+        let reduce_keys;
         let animation_name;
         let preview;
         let time_slice_start;
@@ -721,6 +902,22 @@ impl Toolbar {
                                     .build(ctx);
                                 root_motion
                             })
+                            .with_child({
+                                reduce_keys = ButtonBuilder::new(
+                                    WidgetBuilder::new()
+                                        .with_enabled(false)
+                                        .with_margin(Thickness::uniform(1.0))
+                                        .with_tooltip(make_simple_tooltip(
+                                            ctx,
+                                            "Reduce Keys.\n\
+                                            Shows keyframe reduction settings and removes \
+                                            redundant keys using them.",
+                                        )),
+                                )
+                                .with_text("RK")
+                                .build(ctx);
+                                reduce_keys
+                            })
                             .with_child({
                                 preview = CheckBoxBuilder::new(
                                     WidgetBuilder::new().with_enabled(false).with_margin(
@@ -830,6 +1027,7 @@ impl Toolbar {
         .build(ctx);
 
         let root_motion_dropdown_area = RootMotionDropdownArea::new(ctx);
+        let key_reduction_dropdown_area = KeyReductionDropdownArea::new(ctx);
 
         Self {
             panel,
@@ -853,7 +1051,9 @@ impl Toolbar {
             looping,
             enabled,
             root_motion,
+            reduce_keys,
             root_motion_dropdown_area,
+            key_reduction_dropdown_area,
             import_mode: ImportMode::Import,
         }
     }
@@ -868,6 +1068,7 @@ impl Toolbar {
             MessageDirection::ToWidget,
         ));
         self.root_motion_dropdown_area.destroy(ui);
+        self.key_reduction_dropdown_area.destroy(ui);
     }
 
     pub fn handle_ui_message<G, N>(
@@ -888,6 +1089,8 @@ impl Toolbar {
         if let Some(animation) = animations.try_get(selection.animation) {
             self.root_motion_dropdown_area
                 .handle_ui_message(message, graph, sender, ui, animation, root, selection);
+            self.key_reduction_dropdown_area
+                .handle_ui_message(message, sender, ui, animation, selection);
         }
 
         if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
@@ -974,6 +1177,16 @@ impl Toolbar {
                         animation_clone,
                     ));
                 }
+            } else if message.destination() == self.reduce_keys {
+                ui.send_message(PopupMessage::placement(
+                    self.key_reduction_dropdown_area.popup,
+                    MessageDirection::ToWidget,
+                    Placement::LeftBottom(self.reduce_keys),
+                ));
+                ui.send_message(PopupMessage::open(
+                    self.key_reduction_dropdown_area.popup,
+                    MessageDirection::ToWidget,
+                ));
             }
         } else if let Some(CheckBoxMessage::Check(Some(checked))) = message.data() {
             if message.direction() == MessageDirection::FromWidget {
@@ -1297,6 +1510,7 @@ impl Toolbar {
             self.time_slice_start,
             self.time_slice_end,
             self.clone_current_animation,
+            self.reduce_keys,
             self.looping,
             self.enabled,
             self.root_motion,
@@ -1416,7 +1416,7 @@ impl TrackList {
 
                         let curves = model_track
                             .data_container()
-                            .curves_ref()
+                            .to_curves()
                             .iter()
                             .enumerate()
                             .map(|(i, curve)| {
@@ -22,6 +22,7 @@ use crate::{
     fyrox::{
         asset::{untyped::UntypedResource, Resource},
         core::pool::{ErasedHandle, Handle},
+        generic_animation::reduction::KeyframeReductionSettings,
         gui::{
             self,
             font::FontResource,
@@ -79,7 +80,8 @@ use crate::{
             curve::{CurveResource, CurveResourceState},
             model::{MaterialSearchOptions, Model, ModelResource},
             texture::{
-                CompressionOptions, MipFilter, TextureMagnificationFilter,
+                ktx::TranscodingTarget, mip::MipContent, CompressionOptions, CubeMapConversion,
+                EnvironmentMapFilter, FloatPrecision, MipFilter, TextureMagnificationFilter,
                 TextureMinificationFilter, TextureResource, TextureWrapMode,
             },
         },
@@ -121,13 +123,19 @@ use crate::{
             rigidbody::RigidBodyType,
             sound::{
                 self,
+                chorus::Chorus,
+                compressor::Compressor,
+                convolution::ConvolutionReverb,
+                delay::Delay,
                 filter::{
                     AllPassFilterEffect, BandPassFilterEffect, HighPassFilterEffect,
                     HighShelfFilterEffect, LowPassFilterEffect, LowShelfFilterEffect,
                 },
                 reverb::Reverb,
-                Attenuate, AudioBus, Biquad, DistanceModel, Effect, SoundBuffer,
-                SoundBufferResource, Status,
+                reverb_zone::ReverbZoneShape,
+                waveshaper::{Waveshaper, WaveshaperCurve},
+                Attenuate, AudioBus, Biquad, DistanceModel, Ducking, Effect, SoundBuffer,
+                SoundBufferResource, SoundEvent, SoundEventResource, Status, VariationMode,
             },
             terrain::{Chunk, Layer},
             tilemap::brush::{TileMapBrush, TileMapBrushResource},
@@ -140,8 +148,8 @@ use crate::{
             light::point::PointLight, light::spot::SpotLight, mesh::Mesh,
             navmesh::NavigationalMesh, particle_system::ParticleSystem, pivot::Pivot,
             ragdoll::Ragdoll, rigidbody::RigidBody, rigidbody::RigidBodyMassPropertiesType,
-            sound::listener::Listener, sound::Sound, sprite::Sprite, terrain::Terrain,
-            tilemap::TileDefinitionHandle, tilemap::TileMap,
+            sound::listener::Listener, sound::reverb_zone::ReverbZone, sound::Sound,
+            sprite::Sprite, terrain::Terrain, tilemap::TileDefinitionHandle, tilemap::TileMap,
         },
     },
     message::MessageSender,
@@ -206,7 +214,10 @@ where
             BasePoseNode,
         },
         state::{StateAction, StateActionWrapper},
-        transition::{AndNode, LogicNode, NotNode, OrNode, XorNode},
+        transition::{
+            AndNode, CompareNode, ComparisonOperator, LogicNode, NotNode, OrNode, RangeNode,
+            TimeInStateNode, XorNode,
+        },
         BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput, Machine,
         PlayAnimation, PoseNode, PoseWeight, State,
     };
@@ -260,6 +271,10 @@ where
     container.insert(InspectablePropertyEditorDefinition::<OrNode<Handle<T>>>::new());
     container.insert(InspectablePropertyEditorDefinition::<XorNode<Handle<T>>>::new());
     container.insert(InspectablePropertyEditorDefinition::<NotNode<Handle<T>>>::new());
+    container.insert(InspectablePropertyEditorDefinition::<CompareNode>::new());
+    container.insert(InspectablePropertyEditorDefinition::<RangeNode>::new());
+    container.insert(InspectablePropertyEditorDefinition::<TimeInStateNode>::new());
+    container.insert(EnumPropertyEditorDefinition::<ComparisonOperator>::new());
 }
 
 macro_rules! reg_node_handle_editors {
@@ -344,6 +359,14 @@ pub fn make_property_editors_container(
     >::new());
     container.register_inheritable_vec_collection::<Option<SoundBufferResource>>();
 
+    container.insert(ResourceFieldPropertyEditorDefinition::<SoundEvent>::new(
+        sender.clone(),
+    ));
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<SoundEventResource>,
+    >::new());
+    container.register_inheritable_vec_collection::<Option<SoundEventResource>>();
+
     container
         .insert(ResourceFieldPropertyEditorDefinition::<CurveResourceState>::new(sender.clone()));
     container.insert(InheritablePropertyEditorDefinition::<Option<CurveResource>>::new());
@@ -408,6 +431,14 @@ pub fn make_property_editors_container(
     container.insert(InspectablePropertyEditorDefinition::<LowShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<HighShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<Reverb>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Compressor>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Delay>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Chorus>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Waveshaper>::new());
+    container.insert(InspectablePropertyEditorDefinition::<ConvolutionReverb>::new());
+    container.insert(EnumPropertyEditorDefinition::<WaveshaperCurve>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Ducking>::new());
+    container.insert(EnumPropertyEditorDefinition::<Ducking>::new_optional());
 
     container.register_inheritable_enum::<Emitter, _>();
 
@@ -431,8 +462,10 @@ pub fn make_property_editors_container(
     container.register_inheritable_option::<ColorGradingLut>();
     container.register_inheritable_option::<Biquad>();
     container.register_inheritable_option::<SkyBox>();
+    container.register_inheritable_option::<KeyframeReductionSettings>();
 
     container.register_inheritable_inspectable::<SkyBox>();
+    container.register_inheritable_inspectable::<KeyframeReductionSettings>();
 
     container.register_inheritable_enum::<dim2::collider::ColliderShape, _>();
     container.register_inheritable_enum::<CoefficientCombineRule, _>();
@@ -449,6 +482,8 @@ pub fn make_property_editors_container(
     container.register_inheritable_enum::<FrustumSplitOptions, _>();
     container.register_inheritable_enum::<MaterialSearchOptions, _>();
     container.register_inheritable_enum::<DistanceModel, _>();
+    container.register_inheritable_enum::<VariationMode, _>();
+    container.register_inheritable_enum::<ReverbZoneShape, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
     container.register_inheritable_enum::<CoordinateSystem, _>();
@@ -491,6 +526,16 @@ pub fn make_property_editors_container(
     register_absm_property_editors::<Node>(&container);
     register_absm_property_editors::<UiNode>(&container);
 
+    {
+        use crate::fyrox::scene::animation::ik::*;
+        container.register_inheritable_inspectable::<IkSolver>();
+        container.register_inheritable_vec_collection::<IkSolver>();
+        container.register_inheritable_enum::<IkSolverKind, _>();
+        container.register_inheritable_inspectable::<TwoBoneIk>();
+        container.register_inheritable_inspectable::<FabrikIk>();
+        container.register_inheritable_inspectable::<LookAtIk>();
+    }
+
     container.insert(VecCollectionPropertyEditorDefinition::<
         Handle<scene::animation::Animation>,
     >::new());
@@ -520,6 +565,11 @@ pub fn make_property_editors_container(
     container.insert(EnumPropertyEditorDefinition::<PolygonFillMode>::new());
 
     container.insert(EnumPropertyEditorDefinition::<MipFilter>::new());
+    container.insert(EnumPropertyEditorDefinition::<FloatPrecision>::new());
+    container.insert(EnumPropertyEditorDefinition::<CubeMapConversion>::new());
+    container.insert(EnumPropertyEditorDefinition::<EnvironmentMapFilter>::new());
+    container.insert(EnumPropertyEditorDefinition::<MipContent>::new());
+    container.insert(EnumPropertyEditorDefinition::<TranscodingTarget>::new());
 
     container.register_inheritable_inspectable::<Limb>();
     container.insert(VecCollectionPropertyEditorDefinition::<Limb>::new());
@@ -606,6 +656,7 @@ pub fn make_property_editors_container(
         ParticleSystem,
         Sound,
         Listener,
+        ReverbZone,
         Terrain,
         TileMap
     );
@@ -26,5 +26,6 @@ pub mod inspector;
 pub mod material;
 pub mod ragdoll;
 pub mod settings;
+pub mod sound_event;
 pub mod stats;
 pub mod tilemap;
@@ -0,0 +1,221 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sound event editor allows to edit sound event resources, every change is saved immediately.
+
+use crate::{
+    fyrox::{
+        asset::ResourceData,
+        core::{log::Log, pool::Handle, reflect::Reflect, some_or_return},
+        engine::Engine,
+        graph::BaseSceneGraph,
+        gui::{
+            dock::DockingManagerMessage,
+            inspector::{
+                Inspector, InspectorBuilder, InspectorContext, InspectorMessage, PropertyAction,
+            },
+            message::{MessageDirection, UiMessage},
+            scroll_viewer::ScrollViewerBuilder,
+            widget::{WidgetBuilder, WidgetMessage},
+            window::{WindowBuilder, WindowMessage, WindowTitle},
+            BuildContext, UiNode,
+        },
+        scene::sound::SoundEventResource,
+    },
+    plugin::EditorPlugin,
+    plugins::inspector::{EditorEnvironment, InspectorPlugin},
+    Editor, Message, MSG_SYNC_FLAG,
+};
+use std::sync::Arc;
+
+pub struct SoundEventEditor {
+    window: Handle<UiNode>,
+    inspector: Handle<UiNode>,
+    sound_event: Option<SoundEventResource>,
+}
+
+impl SoundEventEditor {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let inspector;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(500.0))
+            .open(false)
+            .with_title(WindowTitle::text("Sound Event Editor"))
+            .with_content(
+                ScrollViewerBuilder::new(WidgetBuilder::new())
+                    .with_content({
+                        inspector = InspectorBuilder::new(WidgetBuilder::new()).build(ctx);
+                        inspector
+                    })
+                    .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            inspector,
+            sound_event: None,
+        }
+    }
+
+    pub fn destroy(self, docking_manager: Handle<UiNode>, engine: &mut Engine) {
+        let ui = engine.user_interfaces.first();
+        ui.send_message(DockingManagerMessage::remove_floating_window(
+            docking_manager,
+            MessageDirection::ToWidget,
+            self.window,
+        ));
+        ui.send_message(WidgetMessage::remove(
+            self.window,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    pub fn set_sound_event(&mut self, sound_event: SoundEventResource, editor: &mut Editor) {
+        let engine = &mut editor.engine;
+        let environment = Arc::new(EditorEnvironment {
+            resource_manager: engine.resource_manager.clone(),
+            serialization_context: engine.serialization_context.clone(),
+            available_animations: Default::default(),
+            sender: editor.message_sender.clone(),
+        });
+        let property_editors = editor
+            .plugins
+            .get::<InspectorPlugin>()
+            .property_editors
+            .clone();
+
+        let ui = engine.user_interfaces.first_mut();
+        if sound_event.is_ok() {
+            let data = sound_event.data_ref();
+            let context = InspectorContext::from_object(
+                &*data as &dyn Reflect,
+                &mut ui.build_ctx(),
+                property_editors,
+                Some(environment),
+                MSG_SYNC_FLAG,
+                0,
+                true,
+                Default::default(),
+                150.0,
+            );
+            ui.send_message(InspectorMessage::context(
+                self.inspector,
+                MessageDirection::ToWidget,
+                context,
+            ));
+        }
+
+        let title = match engine.resource_manager.resource_path(sound_event.as_ref()) {
+            Some(path) => format!("Sound Event Editor - {}", path.display()),
+            None => "Sound Event Editor".to_string(),
+        };
+        ui.send_message(WindowMessage::title(
+            self.window,
+            MessageDirection::ToWidget,
+            WindowTitle::text(title),
+        ));
+
+        self.sound_event = Some(sound_event);
+    }
+
+    pub fn handle_ui_message(&mut self, message: &UiMessage, engine: &mut Engine) {
+        let sound_event = some_or_return!(self.sound_event.as_ref());
+
+        let Some(InspectorMessage::PropertyChanged(property_changed)) = message.data() else {
+            return;
+        };
+
+        if message.destination() != self.inspector || !sound_event.is_ok() {
+            return;
+        }
+
+        let mut data = sound_event.data_ref();
+        PropertyAction::from_field_kind(&property_changed.value).apply(
+            &property_changed.path(),
+            &mut *data as &mut dyn Reflect,
+            &mut |result| {
+                Log::verify(result);
+            },
+        );
+
+        if let Some(path) = engine.resource_manager.resource_path(sound_event.as_ref()) {
+            Log::verify(data.save(&path));
+        }
+
+        // Setters may clamp the values, so sync the inspector with the actual data.
+        let ui = engine.user_interfaces.first_mut();
+        let context = ui
+            .node(self.inspector)
+            .cast::<Inspector>()
+            .expect("Must be inspector")
+            .context()
+            .clone();
+        Log::verify(context.sync(&*data as &dyn Reflect, ui, 0, true, Default::default()));
+    }
+}
+
+#[derive(Default)]
+pub struct SoundEventPlugin {
+    sound_event_editor: Option<SoundEventEditor>,
+}
+
+impl EditorPlugin for SoundEventPlugin {
+    fn on_ui_message(&mut self, message: &mut UiMessage, editor: &mut Editor) {
+        let mut sound_event_editor = some_or_return!(self.sound_event_editor.take());
+
+        sound_event_editor.handle_ui_message(message, &mut editor.engine);
+
+        if let Some(WindowMessage::Close) = message.data() {
+            if message.destination() == sound_event_editor.window {
+                sound_event_editor.destroy(editor.docking_manager, &mut editor.engine);
+                return;
+            }
+        }
+
+        self.sound_event_editor = Some(sound_event_editor);
+    }
+
+    fn on_message(&mut self, message: &Message, editor: &mut Editor) {
+        let Message::OpenSoundEventEditor(sound_event) = message else {
+            return;
+        };
+
+        let mut sound_event_editor = self.sound_event_editor.take().unwrap_or_else(|| {
+            SoundEventEditor::new(&mut editor.engine.user_interfaces.first_mut().build_ctx())
+        });
+
+        sound_event_editor.set_sound_event(sound_event.clone(), editor);
+
+        let ui = editor.engine.user_interfaces.first_mut();
+        ui.send_message(WindowMessage::open(
+            sound_event_editor.window,
+            MessageDirection::ToWidget,
+            true,
+            true,
+        ));
+        ui.send_message(DockingManagerMessage::add_floating_window(
+            editor.docking_manager,
+            MessageDirection::ToWidget,
+            sound_event_editor.window,
+        ));
+
+        self.sound_event_editor = Some(sound_event_editor);
+    }
+}
@@ -24,13 +24,15 @@
 use crate::{
     core::{
         algebra::{Vector2, Vector3, Vector4},
-        math::curve::Curve,
+        math::curve::{Curve, CurveKey, CurveKeyKind},
         math::{quat_from_euler, RotationOrder},
         reflect::prelude::*,
         visitor::prelude::*,
     },
+    reduction::{self, QuantizedCurve},
     value::TrackValue,
 };
+use std::borrow::Cow;
 
 /// The kind of track output value, the animation system works only with numeric properties and the number
 /// of variants is small.
@@ -101,6 +103,9 @@ pub struct TrackDataContainer {
     /// Interpolation mode.
     #[visit(optional)] // Backward compatibility.
     pub mode: InterpolationMode,
+    #[visit(optional)]
+    #[reflect(hidden)]
+    quantized: Vec<QuantizedCurve>,
 }
 
 impl TrackDataContainer {
@@ -118,32 +123,51 @@ impl TrackDataContainer {
                 .map(|_| Curve::default())
                 .collect(),
             mode: Default::default(),
+            quantized: Default::default(),
         }
     }
 
     /// Adds a new curve to the container. Keep in mind, that the actual useful amount of curves has soft limit
-    /// of four due to [`TrackValueKind`], any excessive curves will be ignored.
+    /// of four due to [`TrackValueKind`], any excessive curves will be ignored. Quantized container is
+    /// dequantized first (see [`Self::dequantize`]).
     pub fn add_curve(&mut self, curve: Curve) {
+        self.dequantize();
         self.curves.push(curve)
     }
 
-    /// Tries to borrow a curve at a given index.
+    /// Tries to borrow a curve at a given index. Quantized container has no curves, see
+    /// [`Self::is_quantized`].
     pub fn curve(&self, index: usize) -> Option<&Curve> {
         self.curves.get(index)
     }
 
-    /// Tries to borrow a curve at a given index.
+    /// Tries to borrow a curve at a given index. Quantized container is dequantized first (see
+    /// [`Self::dequantize`]).
     pub fn curve_mut(&mut self, index: usize) -> Option<&mut Curve> {
+        self.dequantize();
         self.curves.get_mut(index)
     }
 
-    /// Returns a reference to curves container.
+    /// Returns a reference to curves container. Quantized container has no curves, see
+    /// [`Self::is_quantized`].
     pub fn curves_ref(&self) -> &[Curve] {
         &self.curves
     }
 
-    /// Tries to borrow a curve at a given index.
+    /// Returns the curves of the container. Unlike [`Self::curves_ref`], quantized curves are converted
+    /// back to regular curves (with the same ids), while the container itself stays quantized.
+    pub fn to_curves(&self) -> Cow<'_, [Curve]> {
+        if self.is_quantized() {
+            Cow::Owned(self.quantized.iter().map(|curve| curve.to_curve()).collect())
+        } else {
+            Cow::Borrowed(&self.curves)
+        }
+    }
+
+    /// Tries to borrow a curve at a given index. Quantized container is dequantized first (see
+    /// [`Self::dequantize`]).
     pub fn curves_mut(&mut self) -> &mut [Curve] {
+        self.dequantize();
         &mut self.curves
     }
 
@@ -242,6 +266,10 @@ impl TrackDataContainer {
     /// and cannot produce a desired value (for example, [`Vector3`] can be fetched only if the amount of curves
     /// is 3).
     pub fn fetch(&self, time: f32) -> Option<TrackValue> {
+        if !self.quantized.is_empty() {
+            return self.fetch_quantized(time);
+        }
+
         match self.kind {
             TrackValueKind::Real => Some(TrackValue::Real(self.curves.first()?.value_at(time))),
             TrackValueKind::Vector2 => self.fetch_vector2(time),
@@ -251,16 +279,392 @@ impl TrackDataContainer {
         }
     }
 
+    /// Makes the data of the container relative to the given reference value, so the container will produce
+    /// additive values. See [`TrackValue::relative_to`] docs for more info. Real numbers and vectors are offset
+    /// by the reference value exactly, while rotations are re-sampled at each key location of the curves.
+    pub fn make_relative_to(&mut self, reference: &TrackValue) {
+        self.dequantize();
+
+        let offsets = match (self.kind, reference) {
+            (TrackValueKind::Real, TrackValue::Real(v)) => vec![*v],
+            (TrackValueKind::Vector2, TrackValue::Vector2(v)) => v.as_slice().to_vec(),
+            (TrackValueKind::Vector3, TrackValue::Vector3(v)) => v.as_slice().to_vec(),
+            (TrackValueKind::Vector4, TrackValue::Vector4(v)) => v.as_slice().to_vec(),
+            (TrackValueKind::UnitQuaternion, TrackValue::UnitQuaternion(_)) => {
+                self.make_rotation_relative_to(reference);
+                return;
+            }
+            _ => return,
+        };
+
+        for (curve, offset) in self.curves.iter_mut().zip(offsets) {
+            for value in curve.keys_values() {
+                *value -= offset;
+            }
+        }
+    }
+
+    fn make_rotation_relative_to(&mut self, reference: &TrackValue) {
+        self.map_values(|value| value.relative_to(reference));
+    }
+
+    /// Re-samples the container at every key location of its curves and replaces each sampled value
+    /// with the one returned by the given function. Unlike direct modification of the curve keys, this
+    /// method allows the function to mix the components of a value (for example, to rotate a vector or
+    /// to multiply a rotation by another one). Rotations are stored as continuous Euler angles, so the
+    /// interpolation between keys does not take the long way around. Cubic keys, which values were
+    /// changed by the function, become linear, because their tangents do not match the new values.
+    pub fn map_values<F>(&mut self, mut func: F)
+    where
+        F: FnMut(TrackValue) -> TrackValue,
+    {
+        self.dequantize();
+
+        let component_count = match self.kind {
+            TrackValueKind::Real => 1,
+            TrackValueKind::Vector2 => 2,
+            TrackValueKind::Vector3 | TrackValueKind::UnitQuaternion => 3,
+            TrackValueKind::Vector4 => 4,
+        };
+        if self.curves.len() < component_count {
+            return;
+        }
+
+        let mut locations = self.curves[..component_count]
+            .iter()
+            .flat_map(|curve| curve.keys().iter().map(|key| key.location))
+            .collect::<Vec<_>>();
+        locations.sort_by(f32::total_cmp);
+        locations.dedup();
+
+        let mut keys = vec![Vec::<CurveKey>::new(); component_count];
+        let mut previous: Option<Vector3<f32>> = None;
+        for location in locations {
+            let Some(value) = self.fetch(location).map(&mut func) else {
+                continue;
+            };
+
+            let components = match value {
+                TrackValue::Real(value) => vec![value],
+                TrackValue::Vector2(value) => value.as_slice().to_vec(),
+                TrackValue::Vector3(value) => value.as_slice().to_vec(),
+                TrackValue::Vector4(value) => value.as_slice().to_vec(),
+                TrackValue::UnitQuaternion(rotation) => {
+                    let (x, y, z) = rotation.euler_angles();
+                    let mut angles = Vector3::new(x, y, z);
+                    if let Some(previous) = previous {
+                        // Keep the angles continuous, otherwise interpolation will take the long way around.
+                        for (angle, previous) in angles.iter_mut().zip(previous.iter()) {
+                            let turns = ((*previous - *angle) / std::f32::consts::TAU).round();
+                            *angle += turns * std::f32::consts::TAU;
+                        }
+                    }
+                    previous = Some(angles);
+                    angles.as_slice().to_vec()
+                }
+            };
+
+            for (i, (curve_keys, component)) in keys.iter_mut().zip(components).enumerate() {
+                let kind = match self.curves[i]
+                    .keys()
+                    .iter()
+                    .find(|key| key.location == location)
+                {
+                    Some(key)
+                        if matches!(key.kind, CurveKeyKind::Cubic { .. })
+                            && (key.value - component).abs()
+                                > 1.0e-6 * key.value.abs().max(1.0) =>
+                    {
+                        CurveKeyKind::Linear
+                    }
+                    Some(key) => key.kind.clone(),
+                    None => CurveKeyKind::Linear,
+                };
+                curve_keys.push(CurveKey::new(location, component, kind));
+            }
+        }
+
+        for (curve, curve_keys) in self.curves.iter_mut().zip(keys) {
+            curve.clear();
+            for key in curve_keys {
+                curve.add_key(key);
+            }
+        }
+    }
+
     /// Find a right-most key on one of the curves in the container and returns its position. This position
     /// can be treated as a maximum "length" of the container.
     pub fn time_length(&self) -> f32 {
         let mut length = 0.0;
-        for curve in self.curves.iter() {
-            let max_location = curve.max_location();
+        for max_location in self
+            .curves
+            .iter()
+            .map(|curve| curve.max_location())
+            .chain(self.quantized.iter().map(|curve| curve.max_location()))
+        {
             if max_location > length {
                 length = max_location;
             }
         }
         length
     }
+
+    #[inline(always)]
+    fn fetch_quantized(&self, time: f32) -> Option<TrackValue> {
+        let value = |index: usize| self.quantized.get(index).map(|curve| curve.value_at(time));
+        match self.kind {
+            TrackValueKind::Real => Some(TrackValue::Real(value(0)?)),
+            TrackValueKind::Vector2 => {
+                Some(TrackValue::Vector2(Vector2::new(value(0)?, value(1)?)))
+            }
+            TrackValueKind::Vector3 => Some(TrackValue::Vector3(Vector3::new(
+                value(0)?,
+                value(1)?,
+                value(2)?,
+            ))),
+            TrackValueKind::Vector4 => Some(TrackValue::Vector4(Vector4::new(
+                value(0)?,
+                value(1)?,
+                value(2)?,
+                value(3)?,
+            ))),
+            TrackValueKind::UnitQuaternion => {
+                let angle = |index: usize| {
+                    self.quantized.get(index).map(|curve| match self.mode {
+                        InterpolationMode::Default => curve.value_at(time),
+                        InterpolationMode::ShortPath => curve.angle_at(time),
+                    })
+                };
+                Some(TrackValue::UnitQuaternion(quat_from_euler(
+                    Vector3::new(angle(0)?, angle(1)?, angle(2)?),
+                    RotationOrder::XYZ,
+                )))
+            }
+        }
+    }
+
+    /// Removes the keys of the curves, that could be restored by linear interpolation with an error less
+    /// than `tolerance`. `max_step` limits the difference of values between adjacent keys. Quantized
+    /// containers and the curves with non-linear keys are left untouched. See
+    /// [`crate::reduction::KeyframeReductionSettings`] docs for more info.
+    pub fn reduce_keys(&mut self, tolerance: f32, max_step: f32) {
+        for curve in self.curves.iter_mut() {
+            reduction::reduce_curve(curve, tolerance, max_step);
+        }
+    }
+
+    /// Converts the curves of the container to the compact quantized format, see [`QuantizedCurve`] docs
+    /// for more info. Returns `false` if any of the curves contains non-linear keys, in this case the
+    /// container is left untouched. Quantized containers could be sampled as usual, but their curves
+    /// are not accessible (see [`Self::curves_ref`]) until [`Self::dequantize`] is called.
+    pub fn quantize(&mut self) -> bool {
+        if self.is_quantized() {
+            return true;
+        }
+
+        let Some(quantized) = self
+            .curves
+            .iter()
+            .map(QuantizedCurve::from_curve)
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+
+        if quantized.is_empty() {
+            return false;
+        }
+
+        self.quantized = quantized;
+        self.curves = Vec::new();
+        true
+    }
+
+    /// Converts quantized curves (if any) back to the curves with linear keys, so the data of the
+    /// container could be edited again.
+    pub fn dequantize(&mut self) {
+        if self.is_quantized() {
+            self.curves = self
+                .quantized
+                .iter()
+                .map(|curve| curve.to_curve())
+                .collect();
+            self.quantized = Vec::new();
+        }
+    }
+
+    /// Returns `true` if the container stores its data in quantized format, `false` - otherwise.
+    pub fn is_quantized(&self) -> bool {
+        !self.quantized.is_empty()
+    }
+
+    /// Returns the total amount of keys in the container.
+    pub fn key_count(&self) -> usize {
+        self.curves
+            .iter()
+            .map(|curve| curve.keys().len())
+            .chain(self.quantized.iter().map(|curve| curve.key_count()))
+            .sum()
+    }
+
+    /// Returns the amount of memory (in bytes) used by the curves of the container.
+    pub fn memory_usage(&self) -> usize {
+        self.curves
+            .iter()
+            .map(reduction::curve_memory_usage)
+            .chain(self.quantized.iter().map(|curve| curve.memory_usage()))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::{TrackDataContainer, TrackValueKind},
+        core::{
+            algebra::{UnitQuaternion, Vector2, Vector3},
+            math::curve::{CurveKey, CurveKeyKind},
+        },
+        value::TrackValue,
+    };
+
+    #[test]
+    fn test_make_relative_to() {
+        let mut position = TrackDataContainer::new(TrackValueKind::Vector3);
+        for (i, curve) in position.curves_mut().iter_mut().enumerate() {
+            curve.add_key(CurveKey::new(0.0, i as f32, CurveKeyKind::Linear));
+            curve.add_key(CurveKey::new(1.0, i as f32 + 1.0, CurveKeyKind::Linear));
+        }
+        position.make_relative_to(&TrackValue::Vector3(Vector3::new(0.0, 1.0, 2.0)));
+        assert_eq!(
+            position.fetch(1.0),
+            Some(TrackValue::Vector3(Vector3::new(1.0, 1.0, 1.0)))
+        );
+
+        let mut rotation = TrackDataContainer::new(TrackValueKind::UnitQuaternion);
+        for (curve, values) in
+            rotation
+                .curves_mut()
+                .iter_mut()
+                .zip([[0.0, 0.0], [0.3, 1.2], [0.0, 0.0]])
+        {
+            curve.add_key(CurveKey::new(0.0, values[0], CurveKeyKind::Linear));
+            curve.add_key(CurveKey::new(1.0, values[1], CurveKeyKind::Linear));
+        }
+        let reference = UnitQuaternion::from_euler_angles(0.0, 0.3, 0.0);
+        let original = rotation.fetch(1.0);
+        rotation.make_relative_to(&TrackValue::UnitQuaternion(reference));
+        let Some(TrackValue::UnitQuaternion(first)) = rotation.fetch(0.0) else {
+            unreachable!()
+        };
+        assert!(first.angle() < 1.0e-5);
+        let (Some(TrackValue::UnitQuaternion(original)), Some(TrackValue::UnitQuaternion(last))) =
+            (original, rotation.fetch(1.0))
+        else {
+            unreachable!()
+        };
+        assert!((reference * last).angle_to(&original) < 1.0e-5);
+    }
+
+    #[test]
+    fn test_map_values() {
+        let mut position = TrackDataContainer::new(TrackValueKind::Vector3);
+        let curves = position.curves_mut();
+        curves[0].add_key(CurveKey::new(0.0, 1.0, CurveKeyKind::Linear));
+        curves[1].add_key(CurveKey::new(1.0, 2.0, CurveKeyKind::Linear));
+        curves[2].add_key(CurveKey::new(0.0, 3.0, CurveKeyKind::Linear));
+        position.map_values(|value| match value {
+            TrackValue::Vector3(v) => TrackValue::Vector3(Vector3::new(v.y, v.x, v.z * 2.0)),
+            _ => value,
+        });
+        assert_eq!(
+            position.fetch(1.0),
+            Some(TrackValue::Vector3(Vector3::new(2.0, 1.0, 6.0)))
+        );
+        assert_eq!(position.curves_ref()[0].keys().len(), 2);
+    }
+
+    #[test]
+    fn test_map_values_cubic_keys() {
+        let mut position = TrackDataContainer::new(TrackValueKind::Vector2);
+        let cubic = CurveKeyKind::new_cubic(0.5, -0.5);
+        for curve in position.curves_mut() {
+            curve.add_key(CurveKey::new(0.0, 1.0, cubic.clone()));
+            curve.add_key(CurveKey::new(1.0, 2.0, cubic.clone()));
+        }
+        let Some(TrackValue::Vector2(original)) = position.fetch(0.5) else {
+            unreachable!()
+        };
+
+        position.map_values(|value| match value {
+            TrackValue::Vector2(v) => TrackValue::Vector2(Vector2::new(v.x * 2.0, v.y)),
+            _ => value,
+        });
+
+        // Tangents of the changed keys do not match the new values, so the keys become linear.
+        let curves = position.curves_ref();
+        assert!(curves[0]
+            .keys()
+            .iter()
+            .all(|key| key.kind == CurveKeyKind::Linear));
+        assert!(curves[1].keys().iter().all(|key| key.kind == cubic));
+        let Some(TrackValue::Vector2(mapped)) = position.fetch(0.5) else {
+            unreachable!()
+        };
+        assert_eq!(mapped.x, 3.0);
+        assert_eq!(mapped.y, original.y);
+    }
+
+    #[test]
+    fn test_reduce_and_quantize() {
+        let mut position = TrackDataContainer::new(TrackValueKind::Vector3);
+        for curve in position.curves_mut() {
+            for i in 0..=10 {
+                curve.add_key(CurveKey::new(
+                    i as f32 * 0.1,
+                    i as f32,
+                    CurveKeyKind::Linear,
+                ));
+            }
+        }
+        let memory_usage = position.memory_usage();
+        position.reduce_keys(0.001, f32::INFINITY);
+        assert_eq!(position.key_count(), 6);
+        assert!(position.memory_usage() < memory_usage);
+
+        let reduced = position.fetch(0.55);
+        let ids = position
+            .curves_ref()
+            .iter()
+            .map(|curve| curve.id())
+            .collect::<Vec<_>>();
+        assert!(position.quantize());
+        assert!(position.is_quantized());
+        assert!(position.curves_ref().is_empty());
+        // Curves could be viewed without dequantization.
+        assert!(position
+            .to_curves()
+            .iter()
+            .map(|curve| curve.id())
+            .eq(ids.iter().cloned()));
+        assert!(position.is_quantized());
+        let (Some(TrackValue::Vector3(reduced)), Some(TrackValue::Vector3(quantized))) =
+            (reduced, position.fetch(0.55))
+        else {
+            unreachable!()
+        };
+        assert!((reduced - quantized).norm() < 1.0e-3);
+        assert_eq!(position.time_length(), 1.0);
+
+        position.dequantize();
+        assert_eq!(position.curves_ref().len(), 3);
+        assert_eq!(position.curves_ref()[0].id(), ids[0]);
+        assert_eq!(position.key_count(), 6);
+
+        // Mutable access to the curves dequantizes the container.
+        assert!(position.quantize());
+        assert_eq!(position.curves_mut().len(), 3);
+        assert!(!position.is_quantized());
+        assert_eq!(position.key_count(), 6);
+    }
 }
@@ -35,6 +35,7 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
         ImmutableString, NameProvider,
     },
+    reduction::{KeyframeReductionReport, KeyframeReductionSettings},
     track::Track,
 };
 use fxhash::FxHashMap;
@@ -59,6 +60,7 @@ pub use signal::{AnimationEvent, AnimationSignal};
 pub mod container;
 pub mod machine;
 pub mod pose;
+pub mod reduction;
 pub mod signal;
 pub mod spritesheet;
 pub mod track;
@@ -114,6 +116,26 @@ impl AnimationTracksData {
     {
         self.tracks.retain(filter)
     }
+
+    /// Removes redundant keys of the tracks (and optionally quantizes them) using the given settings. See
+    /// [`KeyframeReductionSettings`] docs for more info. Returns a report with the amount of keys and memory
+    /// before and after the reduction.
+    pub fn reduce_keys(&mut self, settings: &KeyframeReductionSettings) -> KeyframeReductionReport {
+        let mut report = KeyframeReductionReport::default();
+        for track in self.tracks.iter_mut() {
+            let (tolerance, max_step) = settings.tolerance(track.value_binding());
+            let container = track.data_container_mut();
+            report.keys_before += container.key_count();
+            report.bytes_before += container.memory_usage();
+            container.reduce_keys(tolerance, max_step);
+            if settings.quantize {
+                container.quantize();
+            }
+            report.keys_after += container.key_count();
+            report.bytes_after += container.memory_usage();
+        }
+        report
+    }
 }
 
 impl Visit for AnimationTracksData {
@@ -958,26 +980,115 @@ impl<T: EntityId> Animation<T> {
         self.track_bindings.clear();
     }
 
-    fn update_pose(&mut self) {
+    /// Removes redundant keys of the tracks of the animation (and optionally quantizes them) using the given
+    /// settings. See [`KeyframeReductionSettings`] docs for more info. Returns a report with the amount of keys
+    /// and memory before and after the reduction.
+    ///
+    /// # Important notes
+    ///
+    /// Track data is usually shared between multiple animations (for example, it could be a part of a model
+    /// resource), so this method creates a new, embedded copy of the track data and leaves the original data
+    /// untouched.
+    pub fn reduce_keys(&mut self, settings: &KeyframeReductionSettings) -> KeyframeReductionReport {
+        let state = self.tracks_data.state();
+        let Some(tracks_data) = state.data_ref() else {
+            return Default::default();
+        };
+        let mut tracks_data = tracks_data.clone();
+        drop(state);
+
+        let report = tracks_data.reduce_keys(settings);
+        self.tracks_data = Resource::new_ok(Uuid::new_v4(), ResourceKind::Embedded, tracks_data);
+        report
+    }
+
+    /// Converts the animation into an additive animation, which tracks produce values relative to the given
+    /// reference pose. Such animations are meant to be used in additive layers of animation blending state
+    /// machines (see [`machine::LayerBlendMode`]), which allows to stack them on top of other animations. Typical
+    /// use cases are breathing, recoil, leaning and so on. The reference pose could be taken from any other
+    /// animation (see [`Self::pose_at`]), tracks that have no respective value in the reference pose will be made
+    /// relative to the first frame of the animation.
+    ///
+    /// # Important notes
+    ///
+    /// Track data is usually shared between multiple animations (for example, it could be a part of a model
+    /// resource), so this method creates a new, embedded copy of the track data and leaves the original data
+    /// untouched.
+    pub fn make_additive(&mut self, reference: &AnimationPose<T>) {
+        let state = self.tracks_data.state();
+        let Some(tracks_data) = state.data_ref() else {
+            return;
+        };
+        let mut tracks_data = tracks_data.clone();
+        drop(state);
+
+        for track in tracks_data.tracks.iter_mut() {
+            let Some(binding) = self.track_bindings.get(&track.id()) else {
+                continue;
+            };
+
+            let reference_value = reference
+                .poses()
+                .get(&binding.target())
+                .and_then(|pose| {
+                    pose.values
+                        .values
+                        .iter()
+                        .find(|value| &value.binding == track.value_binding())
+                })
+                .map(|value| value.value.clone())
+                .or_else(|| track.data_container().fetch(self.time_slice.start));
+
+            if let Some(reference_value) = reference_value {
+                track
+                    .data_container_mut()
+                    .make_relative_to(&reference_value);
+            }
+        }
+
+        self.tracks_data = Resource::new_ok(Uuid::new_v4(), ResourceKind::Embedded, tracks_data);
+    }
+
+    /// Converts the animation into an additive animation, that is relative to its own first frame. See
+    /// [`Self::make_additive`] docs for more info.
+    pub fn make_additive_to_first_frame(&mut self) {
+        self.make_additive(&AnimationPose::default())
+    }
+
+    /// Calculates a pose of the animation at the given time position. Unlike [`Self::pose`], this method does
+    /// not depend on the current playback state of the animation.
+    pub fn pose_at(&self, time: f32) -> AnimationPose<T> {
+        let mut pose = AnimationPose::default();
+        self.fill_pose(time, &mut pose);
+        pose
+    }
+
+    fn fill_pose(&self, time: f32, pose: &mut AnimationPose<T>) {
         let state = self.tracks_data.state();
         let Some(tracks_data) = state.data_ref() else {
             return;
         };
 
-        self.pose.reset();
+        pose.reset();
         for track in tracks_data.tracks.iter() {
             let Some(binding) = self.track_bindings.get(&track.id()) else {
                 continue;
             };
 
             if binding.is_enabled() {
-                if let Some(bound_value) = track.fetch(self.time_position) {
-                    self.pose.add_to_node_pose(binding.target(), bound_value);
+                if let Some(bound_value) = track.fetch(time) {
+                    pose.add_to_node_pose(binding.target(), bound_value);
                 }
             }
         }
     }
 
+    fn update_pose(&mut self) {
+        let mut pose = std::mem::take(&mut self.pose);
+        self.fill_pose(self.time_position, &mut pose);
+        self.pose = pose;
+    }
+
     /// Returns current pose of the animation (a final result that can be applied to a scene graph).
     pub fn pose(&self) -> &AnimationPose<T> {
         &self.pose
@@ -26,6 +26,7 @@ use crate::{
         log::{Log, MessageKind},
         pool::{Handle, Pool},
         reflect::prelude::*,
+        uuid_provider,
         visitor::prelude::*,
     },
     machine::{
@@ -35,6 +36,25 @@ use crate::{
     Animation, AnimationContainer, AnimationEvent, AnimationPose, EntityId,
 };
 use fyrox_core::{find_by_name_mut, find_by_name_ref, NameProvider};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines how a pose of a layer is combined with the poses of the previous layers of a state machine.
+#[derive(
+    Default, Debug, Visit, Reflect, Clone, Copy, PartialEq, Eq, AsRefStr, EnumString, VariantNames,
+)]
+pub enum LayerBlendMode {
+    /// The pose of the layer is blended with the poses of the previous layers using the weight of the layer,
+    /// weight of 1.0 means that the pose of the layer overrides the poses of the previous layers.
+    #[default]
+    Override,
+    /// The pose of the layer is considered relative and it is added on top of the poses of the previous layers
+    /// using the weight of the layer. Such layers should use additive animations, see
+    /// [`crate::Animation::make_additive`] docs for more info. It is useful to stack animations like breathing,
+    /// recoil or leaning on top of locomotion animations.
+    Additive,
+}
+
+uuid_provider!(LayerBlendMode = "f9a6a9f1-3b9d-4c0c-9b8a-5d8f2c6e4a71");
 
 /// Layer is a separate state graph. Layers mainly used to animate different parts of humanoid (but not only) characters. For
 /// example there could a layer for upper body and a layer for lower body. Upper body layer could contain animations for aiming,
@@ -87,6 +107,9 @@ pub struct MachineLayer<T: EntityId> {
 
     weight: f32,
 
+    #[visit(optional)]
+    blend_mode: LayerBlendMode,
+
     mask: LayerMask<T>,
 
     #[reflect(hidden)]
@@ -107,6 +130,10 @@ pub struct MachineLayer<T: EntityId> {
     #[reflect(hidden)]
     active_transition: Handle<Transition<T>>,
 
+    #[visit(skip)]
+    #[reflect(hidden)]
+    active_state_time: f32,
+
     #[visit(skip)]
     #[reflect(hidden)]
     final_pose: AnimationPose<T>,
@@ -177,7 +204,9 @@ impl<T: EntityId> MachineLayer<T> {
             active_state: Default::default(),
             entry_state: Default::default(),
             active_transition: Default::default(),
+            active_state_time: 0.0,
             weight: 1.0,
+            blend_mode: Default::default(),
             events: FixedEventQueue::new(2048),
             debug: false,
             mask: Default::default(),
@@ -207,7 +236,9 @@ impl<T: EntityId> MachineLayer<T> {
     #[inline]
     pub fn set_entry_state(&mut self, entry_state: Handle<State<T>>) {
         self.active_state = entry_state;
+        self.active_state_time = 0.0;
         self.entry_state = entry_state;
+        self.reset_conditions(entry_state);
     }
 
     /// Returns a handle of current entry state.
@@ -229,6 +260,7 @@ impl<T: EntityId> MachineLayer<T> {
         let state = self.states.spawn(state);
         if self.active_state.is_none() {
             self.active_state = state;
+            self.active_state_time = 0.0;
         }
         state
     }
@@ -289,9 +321,21 @@ impl<T: EntityId> MachineLayer<T> {
     pub fn reset(&mut self) {
         for transition in self.transitions.iter_mut() {
             transition.reset();
+            transition.condition.reset();
         }
 
         self.active_state = self.entry_state;
+        self.active_state_time = 0.0;
+    }
+
+    // Transition conditions could remember their values from the last evaluation (see `RangeNode` docs), they
+    // must be reset when their source state is entered.
+    fn reset_conditions(&self, state: Handle<State<T>>) {
+        for transition in self.transitions.iter() {
+            if transition.source() == state {
+                transition.condition.reset();
+            }
+        }
     }
 
     /// Fetches animation events from an active state (or a transition). It could be used to fetch animation events from a layer
@@ -432,6 +476,12 @@ impl<T: EntityId> MachineLayer<T> {
         self.active_state
     }
 
+    /// Returns the amount of time (in seconds), that passed since the current active state became active.
+    #[inline]
+    pub fn active_state_time(&self) -> f32 {
+        self.active_state_time
+    }
+
     /// Returns a handle of active transition. It is not empty only while a transition is active (doing blending
     /// between states).
     #[inline]
@@ -536,6 +586,18 @@ impl<T: EntityId> MachineLayer<T> {
         self.weight
     }
 
+    /// Sets new blend mode of the layer. See docs of [`LayerBlendMode`] for more info.
+    #[inline]
+    pub fn set_blend_mode(&mut self, blend_mode: LayerBlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    /// Returns the blend mode of the layer.
+    #[inline]
+    pub fn blend_mode(&self) -> LayerBlendMode {
+        self.blend_mode
+    }
+
     /// Sets new layer mask. See docs of [`LayerMask`] for more info about layer masks.
     #[inline]
     pub fn set_mask(&mut self, mask: LayerMask<T>) -> LayerMask<T> {
@@ -601,6 +663,8 @@ impl<T: EntityId> MachineLayer<T> {
             }
 
             if self.active_transition.is_none() {
+                self.active_state_time += dt;
+
                 // Find transition.
                 for (handle, transition) in self.transitions.pair_iter_mut() {
                     if transition.dest() == self.active_state
@@ -609,7 +673,11 @@ impl<T: EntityId> MachineLayer<T> {
                         continue;
                     }
 
-                    if transition.condition.calculate_value(parameters, animations) {
+                    if transition.condition.calculate_value(
+                        parameters,
+                        animations,
+                        self.active_state_time,
+                    ) {
                         if let Some(active_state) = self.states.try_borrow(self.active_state) {
                             for action in active_state.on_leave_actions.iter() {
                                 action.apply(animations);
@@ -673,10 +741,12 @@ impl<T: EntityId> MachineLayer<T> {
                         .push(Event::ActiveTransitionChanged(self.active_transition));
 
                     self.active_state = transition.dest();
+                    self.active_state_time = 0.0;
                     self.events.push(Event::ActiveStateChanged {
                         prev: transition.source(),
                         new: transition.dest(),
                     });
+                    self.reset_conditions(self.active_state);
 
                     if self.debug {
                         Log::writeln(
@@ -37,7 +37,7 @@ use fxhash::FxHashSet;
 pub use event::Event;
 use fyrox_core::pool::Handle;
 use fyrox_core::{find_by_name_mut, find_by_name_ref};
-pub use layer::MachineLayer;
+pub use layer::{LayerBlendMode, MachineLayer};
 pub use mask::LayerMask;
 pub use node::{
     blend::{BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput},
@@ -355,9 +355,13 @@ impl<T: EntityId> Machine<T> {
 
         for layer in self.layers.iter_mut() {
             let weight = layer.weight();
+            let blend_mode = layer.blend_mode();
             let pose = layer.evaluate_pose(animations, &self.parameters, dt);
 
-            self.final_pose.blend_with(pose, weight);
+            match blend_mode {
+                LayerBlendMode::Override => self.final_pose.blend_with(pose, weight),
+                LayerBlendMode::Additive => self.final_pose.add_with(pose, weight),
+            }
         }
 
         &self.final_pose
@@ -55,6 +55,18 @@ impl Default for Parameter {
     }
 }
 
+impl Parameter {
+    /// Returns a numeric value of the parameter. Only `Weight` and `Index` parameters have numeric values,
+    /// the method returns [`None`] for other kinds of parameters.
+    pub fn numeric_value(&self) -> Option<f32> {
+        match self {
+            Parameter::Weight(weight) => Some(*weight),
+            Parameter::Index(index) => Some(*index as f32),
+            _ => None,
+        }
+    }
+}
+
 /// Specific animation pose weight.
 #[derive(Debug, Visit, Clone, PartialEq, Reflect, VariantNames, EnumString, AsRefStr)]
 pub enum PoseWeight {
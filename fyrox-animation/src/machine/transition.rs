@@ -26,8 +26,11 @@ use crate::{
     Animation, AnimationContainer, EntityId,
 };
 use fyrox_core::uuid::{uuid, Uuid};
-use fyrox_core::{NameProvider, TypeUuidProvider};
-use std::any::{type_name, Any, TypeId};
+use fyrox_core::{uuid_provider, NameProvider, TypeUuidProvider};
+use std::{
+    any::{type_name, Any, TypeId},
+    cell::Cell,
+};
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 macro_rules! define_two_args_node {
@@ -349,6 +352,135 @@ impl<T: EntityId> Reflect for NotNode<T> {
     }
 }
 
+/// An operator, that is used to compare two numbers.
+#[derive(
+    Default, Debug, Visit, Clone, Copy, Reflect, PartialEq, Eq, AsRefStr, EnumString, VariantNames,
+)]
+pub enum ComparisonOperator {
+    /// `lhs < rhs`
+    Less,
+    /// `lhs <= rhs`
+    LessOrEqual,
+    /// `lhs > rhs`
+    #[default]
+    Greater,
+    /// `lhs >= rhs`
+    GreaterOrEqual,
+    /// `lhs == rhs`, the numbers are considered equal if their difference is less than [`f32::EPSILON`].
+    Equal,
+    /// `lhs != rhs`, the numbers are considered equal if their difference is less than [`f32::EPSILON`].
+    NotEqual,
+}
+
+uuid_provider!(ComparisonOperator = "a7c9b4f3-6c2e-4b5e-8f1d-2e6a9d3c7b85");
+
+impl ComparisonOperator {
+    /// Compares two numbers using the operator.
+    pub fn compare(self, lhs: f32, rhs: f32) -> bool {
+        match self {
+            ComparisonOperator::Less => lhs < rhs,
+            ComparisonOperator::LessOrEqual => lhs <= rhs,
+            ComparisonOperator::Greater => lhs > rhs,
+            ComparisonOperator::GreaterOrEqual => lhs >= rhs,
+            ComparisonOperator::Equal => (lhs - rhs).abs() <= f32::EPSILON,
+            ComparisonOperator::NotEqual => (lhs - rhs).abs() > f32::EPSILON,
+        }
+    }
+}
+
+/// Compares a value of a numeric (`Weight` or `Index`) parameter with a constant. Output value will be `false` if the
+/// parameter is not found or it is not numeric.
+#[derive(Default, Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct CompareNode {
+    /// A name of a numeric parameter.
+    pub parameter: String,
+    /// An operator, that will be used to compare the value of the parameter (left side) with the constant (right
+    /// side).
+    pub operator: ComparisonOperator,
+    /// A constant, that will be compared with the value of the parameter.
+    pub value: f32,
+}
+
+/// Checks whether a value of a numeric (`Weight` or `Index`) parameter is within the given range (inclusive). Output
+/// value will be `false` if the parameter is not found or it is not numeric.
+///
+/// # Hysteresis
+///
+/// The node remembers its last output value and once the value of the parameter is within the range, the range is
+/// extended by the hysteresis value on both sides. This prevents rapid switching back and forth when the value of
+/// the parameter fluctuates near the bounds of the range. The remembered value is reset every time when the source
+/// state of a transition is entered, and it is not taken into account when comparing nodes.
+#[derive(Default, Debug, Visit, Clone, Reflect)]
+pub struct RangeNode {
+    /// A name of a numeric parameter.
+    pub parameter: String,
+    /// Lower bound of the range.
+    pub min: f32,
+    /// Upper bound of the range.
+    pub max: f32,
+    /// An amount by which the range will be extended on both sides, when the value of the parameter is within
+    /// the range.
+    #[reflect(min_value = 0.0)]
+    pub hysteresis: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    inside: Cell<bool>,
+}
+
+impl RangeNode {
+    /// Creates a new range node for the given parameter.
+    pub fn new(parameter: &str, min: f32, max: f32, hysteresis: f32) -> Self {
+        Self {
+            parameter: parameter.to_owned(),
+            min,
+            max,
+            hysteresis,
+            inside: Default::default(),
+        }
+    }
+
+    fn calculate_value(&self, parameters: &ParameterContainer) -> bool {
+        let inside = parameters
+            .get(&self.parameter)
+            .and_then(|p| p.numeric_value())
+            .is_some_and(|value| {
+                let extent = if self.inside.get() {
+                    self.hysteresis.max(0.0)
+                } else {
+                    0.0
+                };
+                value >= self.min - extent && value <= self.max + extent
+            });
+        self.inside.set(inside);
+        inside
+    }
+
+    fn reset(&self) {
+        self.inside.set(false);
+    }
+}
+
+impl PartialEq for RangeNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.parameter == other.parameter
+            && self.min == other.min
+            && self.max == other.max
+            && self.hysteresis == other.hysteresis
+    }
+}
+
+/// Compares the amount of time (in seconds), that passed since the source state of a transition became active, with a
+/// constant. It could be used to create transitions that happen after some delay, or transitions that are only
+/// possible within a short period of time after entering a state.
+#[derive(Default, Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct TimeInStateNode {
+    /// An operator, that will be used to compare the time in state (left side) with the constant (right side).
+    pub operator: ComparisonOperator,
+    /// A time (in seconds), that will be compared with the time in state.
+    #[reflect(min_value = 0.0)]
+    pub time: f32,
+}
+
 /// A node responsible for logical operations evaluation. It can have any number of descendant nodes.
 ///
 /// # Examples
@@ -373,7 +505,7 @@ impl<T: EntityId> Reflect for NotNode<T> {
 ///     rhs: Box::new(LogicNode::Parameter("Jump".to_string())),
 /// });
 ///
-/// assert_eq!(transition_logic.calculate_value(&parameters, &AnimationContainer::default()), true);
+/// assert_eq!(transition_logic.calculate_value(&parameters, &AnimationContainer::default(), 0.0), true);
 /// ```
 #[derive(Debug, Visit, Clone, Reflect, PartialEq, AsRefStr, EnumString, VariantNames)]
 pub enum LogicNode<T: EntityId> {
@@ -389,6 +521,13 @@ pub enum LogicNode<T: EntityId> {
     Not(NotNode<T>),
     /// Returns `true` if the animation has ended, `false` - otherwise.
     IsAnimationEnded(Handle<Animation<T>>),
+    /// Compares a value of a numeric parameter with a constant. See [`CompareNode`] docs for more info.
+    Compare(CompareNode),
+    /// Checks whether a value of a numeric parameter is within a range. See [`RangeNode`] docs for more info.
+    InRange(RangeNode),
+    /// Compares the time spent in the source state of a transition with a constant. See [`TimeInStateNode`] docs
+    /// for more info.
+    TimeInState(TimeInStateNode),
 }
 
 impl<T: EntityId> TypeUuidProvider for LogicNode<T> {
@@ -404,11 +543,13 @@ impl<T: EntityId> Default for LogicNode<T> {
 }
 
 impl<T: EntityId> LogicNode<T> {
-    /// Calculates final value of the logic node.
+    /// Calculates final value of the logic node. `time_in_state` is the amount of time (in seconds), that passed
+    /// since the source state of a transition became active.
     pub fn calculate_value(
         &self,
         parameters: &ParameterContainer,
         animations: &AnimationContainer<T>,
+        time_in_state: f32,
     ) -> bool {
         match self {
             LogicNode::Parameter(rule_name) => parameters.get(rule_name).is_some_and(|p| {
@@ -419,24 +560,70 @@ impl<T: EntityId> LogicNode<T> {
                 }
             }),
             LogicNode::And(and) => {
-                let lhs_value = and.lhs.calculate_value(parameters, animations);
-                let rhs_value = and.rhs.calculate_value(parameters, animations);
+                let lhs_value = and
+                    .lhs
+                    .calculate_value(parameters, animations, time_in_state);
+                let rhs_value = and
+                    .rhs
+                    .calculate_value(parameters, animations, time_in_state);
                 lhs_value & rhs_value
             }
             LogicNode::Or(or) => {
-                let lhs_value = or.lhs.calculate_value(parameters, animations);
-                let rhs_value = or.rhs.calculate_value(parameters, animations);
+                let lhs_value = or
+                    .lhs
+                    .calculate_value(parameters, animations, time_in_state);
+                let rhs_value = or
+                    .rhs
+                    .calculate_value(parameters, animations, time_in_state);
                 lhs_value | rhs_value
             }
             LogicNode::Xor(or) => {
-                let lhs_value = or.lhs.calculate_value(parameters, animations);
-                let rhs_value = or.rhs.calculate_value(parameters, animations);
+                let lhs_value = or
+                    .lhs
+                    .calculate_value(parameters, animations, time_in_state);
+                let rhs_value = or
+                    .rhs
+                    .calculate_value(parameters, animations, time_in_state);
                 lhs_value ^ rhs_value
             }
-            LogicNode::Not(node) => !node.lhs.calculate_value(parameters, animations),
-            LogicNode::IsAnimationEnded(animation) => animations
-                .try_get(*animation)
-                .is_none_or(|a| a.has_ended()),
+            LogicNode::Not(node) => {
+                !node
+                    .lhs
+                    .calculate_value(parameters, animations, time_in_state)
+            }
+            LogicNode::IsAnimationEnded(animation) => {
+                animations.try_get(*animation).is_none_or(|a| a.has_ended())
+            }
+            LogicNode::Compare(compare) => parameters
+                .get(&compare.parameter)
+                .and_then(|p| p.numeric_value())
+                .is_some_and(|value| compare.operator.compare(value, compare.value)),
+            LogicNode::InRange(range) => range.calculate_value(parameters),
+            LogicNode::TimeInState(node) => node.operator.compare(time_in_state, node.time),
+        }
+    }
+
+    /// Resets the values remembered by the nodes (see [`RangeNode`] docs for more info).
+    pub(super) fn reset(&self) {
+        match self {
+            LogicNode::And(and) => {
+                and.lhs.reset();
+                and.rhs.reset();
+            }
+            LogicNode::Or(or) => {
+                or.lhs.reset();
+                or.rhs.reset();
+            }
+            LogicNode::Xor(xor) => {
+                xor.lhs.reset();
+                xor.rhs.reset();
+            }
+            LogicNode::Not(node) => node.lhs.reset(),
+            LogicNode::InRange(range) => range.reset(),
+            LogicNode::Parameter(_)
+            | LogicNode::IsAnimationEnded(_)
+            | LogicNode::Compare(_)
+            | LogicNode::TimeInState(_) => (),
         }
     }
 }
@@ -591,3 +778,65 @@ impl<T: EntityId> Transition<T> {
         self.blend_factor = self.elapsed_time / self.transition_time;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        machine::{
+            transition::{CompareNode, ComparisonOperator, LogicNode, RangeNode, TimeInStateNode},
+            Parameter, ParameterContainer,
+        },
+        AnimationContainer,
+    };
+    use fyrox_core::pool::ErasedHandle;
+
+    #[test]
+    fn test_numeric_conditions() {
+        let animations = AnimationContainer::<ErasedHandle>::default();
+        let mut parameters = ParameterContainer::default();
+        parameters.add("Speed", Parameter::Weight(0.5));
+        parameters.add("Weapon", Parameter::Index(2));
+
+        let compare = LogicNode::Compare(CompareNode {
+            parameter: "Speed".to_string(),
+            operator: ComparisonOperator::Greater,
+            value: 0.1,
+        });
+        assert!(compare.calculate_value(&parameters, &animations, 0.0));
+
+        let compare = LogicNode::Compare(CompareNode {
+            parameter: "Weapon".to_string(),
+            operator: ComparisonOperator::Equal,
+            value: 2.0,
+        });
+        assert!(compare.calculate_value(&parameters, &animations, 0.0));
+
+        let range = LogicNode::InRange(RangeNode::new("Speed", 0.4, 1.0, 0.2));
+        assert!(range.calculate_value(&parameters, &animations, 0.0));
+        // Within the hysteresis band, the node must remember that it was inside of the range.
+        *parameters.get_mut("Speed").unwrap() = Parameter::Weight(0.3);
+        assert!(range.calculate_value(&parameters, &animations, 0.0));
+        *parameters.get_mut("Speed").unwrap() = Parameter::Weight(0.1);
+        assert!(!range.calculate_value(&parameters, &animations, 0.0));
+        *parameters.get_mut("Speed").unwrap() = Parameter::Weight(0.3);
+        assert!(!range.calculate_value(&parameters, &animations, 0.0));
+
+        // The remembered value must be forgotten on reset and must not affect comparison.
+        *parameters.get_mut("Speed").unwrap() = Parameter::Weight(0.5);
+        assert!(range.calculate_value(&parameters, &animations, 0.0));
+        assert_eq!(
+            range,
+            LogicNode::InRange(RangeNode::new("Speed", 0.4, 1.0, 0.2))
+        );
+        range.reset();
+        *parameters.get_mut("Speed").unwrap() = Parameter::Weight(0.3);
+        assert!(!range.calculate_value(&parameters, &animations, 0.0));
+
+        let time_in_state = LogicNode::<ErasedHandle>::TimeInState(TimeInStateNode {
+            operator: ComparisonOperator::GreaterOrEqual,
+            time: 1.0,
+        });
+        assert!(!time_in_state.calculate_value(&parameters, &animations, 0.5));
+        assert!(time_in_state.calculate_value(&parameters, &animations, 1.5));
+    }
+}
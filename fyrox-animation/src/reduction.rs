@@ -0,0 +1,578 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Keyframe reduction (animation compression). See [`KeyframeReductionSettings`] docs for more info.
+
+use crate::{
+    core::{
+        math::curve::{Curve, CurveKey, CurveKeyKind},
+        reflect::prelude::*,
+        uuid::Uuid,
+        uuid_provider,
+        visitor::prelude::*,
+    },
+    value::ValueBinding,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Debug, Display, Formatter},
+    ops::AddAssign,
+};
+
+/// Settings of keyframe reduction. Keyframe reduction removes the keys of animation curves, that could be
+/// restored by linear interpolation of their neighbour keys with an error that does not exceed a tolerance.
+/// Each kind of tracks has its own tolerance, because they're measured in different units. Optionally, the
+/// curves could be quantized to cut the memory usage even further, see [`QuantizedCurve`] docs for more
+/// info.
+///
+/// Only the curves that consist of linear keys are reduced (or quantized), the curves with constant or
+/// cubic keys are left untouched.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyframeReductionSettings {
+    /// Max allowed deviation of position tracks (in local units of the animated node).
+    #[reflect(min_value = 0.0, step = 0.001)]
+    pub position_tolerance: f32,
+    /// Max allowed deviation of rotation tracks (in radians per Euler angle).
+    #[reflect(min_value = 0.0, step = 0.001)]
+    pub rotation_tolerance: f32,
+    /// Max allowed difference of an Euler angle between two adjacent keys (in radians). Euler angles are
+    /// interpolated independently, so large steps between keys could produce a motion that differs from
+    /// the original one even if the error at the removed keys is small.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub rotation_max_step: f32,
+    /// Max allowed deviation of scale tracks.
+    #[reflect(min_value = 0.0, step = 0.001)]
+    pub scale_tolerance: f32,
+    /// Max allowed deviation of tracks bound to arbitrary properties (blend shape weights, etc.).
+    #[reflect(min_value = 0.0, step = 0.001)]
+    pub property_tolerance: f32,
+    /// If set, the curves will be stored in the quantized format after the reduction. See
+    /// [`QuantizedCurve`] docs for more info.
+    pub quantize: bool,
+}
+
+// Tolerances are never NaN in practice, so the settings could be used as a part of other types that
+// implement `Eq` (import options, etc.).
+impl Eq for KeyframeReductionSettings {}
+
+uuid_provider!(KeyframeReductionSettings = "0f3a3b52-4a37-4d8e-9b0c-6c2f4b8e7d15");
+
+impl Default for KeyframeReductionSettings {
+    fn default() -> Self {
+        Self {
+            position_tolerance: 0.001,
+            rotation_tolerance: std::f32::consts::PI / 180.0,
+            rotation_max_step: std::f32::consts::FRAC_PI_4,
+            scale_tolerance: 0.1,
+            property_tolerance: 0.001,
+            quantize: false,
+        }
+    }
+}
+
+impl KeyframeReductionSettings {
+    /// Returns a pair `(tolerance, max_step)` for a track with the given value binding.
+    pub fn tolerance(&self, binding: &ValueBinding) -> (f32, f32) {
+        match binding {
+            ValueBinding::Position => (self.position_tolerance, f32::INFINITY),
+            ValueBinding::Rotation => (self.rotation_tolerance, self.rotation_max_step),
+            ValueBinding::Scale => (self.scale_tolerance, f32::INFINITY),
+            ValueBinding::Property { .. } => (self.property_tolerance, f32::INFINITY),
+        }
+    }
+}
+
+/// A report of keyframe reduction, that contains the amount of keys and the memory used by the curves
+/// before and after the reduction.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyframeReductionReport {
+    /// Total amount of keys before the reduction.
+    pub keys_before: usize,
+    /// Total amount of keys after the reduction.
+    pub keys_after: usize,
+    /// Memory used by the curves before the reduction (in bytes).
+    pub bytes_before: usize,
+    /// Memory used by the curves after the reduction (in bytes).
+    pub bytes_after: usize,
+}
+
+impl KeyframeReductionReport {
+    /// Returns the amount of saved memory (in bytes).
+    pub fn saved_bytes(&self) -> usize {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+
+    /// Returns the fraction of saved memory in `[0; 1]` range.
+    pub fn saved_fraction(&self) -> f32 {
+        if self.bytes_before == 0 {
+            0.0
+        } else {
+            self.saved_bytes() as f32 / self.bytes_before as f32
+        }
+    }
+}
+
+impl AddAssign for KeyframeReductionReport {
+    fn add_assign(&mut self, rhs: Self) {
+        self.keys_before += rhs.keys_before;
+        self.keys_after += rhs.keys_after;
+        self.bytes_before += rhs.bytes_before;
+        self.bytes_after += rhs.bytes_after;
+    }
+}
+
+impl Display for KeyframeReductionReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {} keys, {} -> {} bytes ({:.1}% saved)",
+            self.keys_before,
+            self.keys_after,
+            self.bytes_before,
+            self.bytes_after,
+            self.saved_fraction() * 100.0
+        )
+    }
+}
+
+/// A point of a curve, that could be simplified using [`simplify`].
+pub trait CurvePoint {
+    /// Location of the point.
+    fn x(&self) -> f32;
+    /// Value of the point.
+    fn y(&self) -> f32;
+}
+
+impl CurvePoint for CurveKey {
+    fn x(&self) -> f32 {
+        self.location()
+    }
+
+    fn y(&self) -> f32 {
+        self.value
+    }
+}
+
+/// Removes the points, that could be restored by linear interpolation of the remaining points with an
+/// error less than `epsilon` (Ramer-Douglas-Peucker algorithm). `max_step` limits the difference of values
+/// between two adjacent points, pass [`f32::INFINITY`] to disable the limit.
+pub fn simplify<P: CurvePoint + Clone + Debug>(
+    points: &[P],
+    epsilon: f32,
+    max_step: f32,
+) -> Vec<P> {
+    find_important_points(points, epsilon, max_step)
+        .into_iter()
+        .map(|i| points[i].clone())
+        .collect()
+}
+
+/// Returns the indices of the points, that must be kept by [`simplify`].
+pub fn find_important_points<P: CurvePoint + Debug>(
+    points: &[P],
+    epsilon: f32,
+    max_step: f32,
+) -> Vec<usize> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let mut keep_flags: Vec<bool> = Vec::new();
+    keep_flags.resize(points.len(), false);
+    let end = keep_flags.len() - 1;
+    keep_flags[0] = true;
+    keep_flags[end] = true;
+    find_points_in_span(points, keep_flags.as_mut_slice(), 0, end, epsilon);
+    if max_step.is_finite() {
+        limit_step_size(points, keep_flags.as_mut_slice(), max_step);
+    }
+    let mut result: Vec<usize> = Vec::new();
+    for (i, k) in keep_flags.into_iter().enumerate() {
+        if k {
+            result.push(i)
+        }
+    }
+    if result.len() == 2 && f32::abs(points[result[0]].y() - points[result[1]].y()) < epsilon {
+        result.pop();
+    }
+    result
+}
+
+fn limit_step_size<P: CurvePoint>(points: &[P], keep_flags: &mut [bool], max_step: f32) {
+    let end = points.len() - 1;
+    let mut i: usize = 1;
+    while i < end {
+        if keep_flags[i] {
+            i += 1;
+        } else {
+            let next = find_step(i - 1, points, keep_flags, max_step);
+            keep_flags[next] = true;
+            i = usize::max(next + 1, i + 1);
+        }
+    }
+}
+
+fn find_step<P: CurvePoint>(
+    start: usize,
+    points: &[P],
+    keep_flags: &mut [bool],
+    max_step: f32,
+) -> usize {
+    let start_y = points[start].y();
+    for i in start + 1..points.len() {
+        let step = f32::abs(points[i].y() - start_y);
+        if step > max_step {
+            return usize::max(i - 1, start + 1);
+        } else if keep_flags[i] {
+            return i;
+        }
+    }
+    points.len() - 1
+}
+
+#[allow(clippy::needless_range_loop)]
+fn find_points_in_span<P: CurvePoint + Debug>(
+    points: &[P],
+    keep_flags: &mut [bool],
+    start: usize,
+    end: usize,
+    epsilon: f32,
+) {
+    if end <= start + 1 {
+        return;
+    }
+    let x0 = points[start].x();
+    let y0 = points[start].y();
+    let slope = (points[end].y() - y0) / (points[end].x() - x0);
+    let mut far_point_index: usize = 0;
+    let mut far_point_dist: f32 = 0.0;
+    for i in start + 1..end {
+        let (x, y) = (points[i].x(), points[i].y());
+        let y_line: f32 = y0 + slope * (x - x0);
+        let dist: f32 = (y - y_line).abs();
+        if far_point_dist < dist {
+            far_point_dist = dist;
+            far_point_index = i;
+        }
+    }
+    if far_point_index == 0 || far_point_dist < epsilon {
+        return;
+    }
+    keep_flags[far_point_index] = true;
+    find_points_in_span(points, keep_flags, start, far_point_index, epsilon);
+    find_points_in_span(points, keep_flags, far_point_index, end, epsilon);
+}
+
+fn is_linear(curve: &Curve) -> bool {
+    curve
+        .keys()
+        .iter()
+        .all(|key| key.kind == CurveKeyKind::Linear)
+}
+
+/// Removes the keys of the curve, that could be restored by linear interpolation with an error less than
+/// `tolerance`. See [`simplify`] for more info. Returns `false` if the curve contains non-linear keys and
+/// thus was left untouched.
+pub fn reduce_curve(curve: &mut Curve, tolerance: f32, max_step: f32) -> bool {
+    if !is_linear(curve) {
+        return false;
+    }
+    curve.keys = simplify(curve.keys(), tolerance, max_step);
+    true
+}
+
+/// Returns the amount of memory (in bytes) used by the curve.
+pub fn curve_memory_usage(curve: &Curve) -> usize {
+    std::mem::size_of::<Curve>()
+        + curve.name.capacity()
+        + curve.keys.capacity() * std::mem::size_of::<CurveKey>()
+}
+
+/// Compact (lossy) representation of a curve with linear keys. Locations and values of the keys are
+/// normalized to the time and value ranges of the curve and stored as 16-bit integers, which takes 4 bytes
+/// per key instead of the full [`CurveKey`]. The quantization error is `1/65535` of the respective range.
+/// Quantized curves could be sampled directly, but they must be converted back to [`Curve`]s (see
+/// [`Self::to_curve`]) to be edited. The id of the source curve is preserved, so the restored curve
+/// could be matched with the source one.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct QuantizedCurve {
+    id: Uuid,
+    start: f32,
+    duration: f32,
+    min: f32,
+    range: f32,
+    locations: Vec<u16>,
+    values: Vec<u16>,
+}
+
+fn quantize(value: f32, min: f32, range: f32) -> u16 {
+    if range > 0.0 {
+        (((value - min) / range).clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+    } else {
+        0
+    }
+}
+
+fn dequantize(value: u16, min: f32, range: f32) -> f32 {
+    min + (value as f32 / u16::MAX as f32) * range
+}
+
+impl QuantizedCurve {
+    /// Creates a quantized curve from the given curve. Returns [`None`] if the curve contains non-linear
+    /// keys.
+    pub fn from_curve(curve: &Curve) -> Option<Self> {
+        if !is_linear(curve) {
+            return None;
+        }
+
+        let keys = curve.keys();
+        let start = keys.first().map(|key| key.location()).unwrap_or_default();
+        let end = keys.last().map(|key| key.location()).unwrap_or_default();
+        let min = keys
+            .iter()
+            .map(|key| key.value)
+            .min_by(f32::total_cmp)
+            .unwrap_or_default();
+        let max = keys
+            .iter()
+            .map(|key| key.value)
+            .max_by(f32::total_cmp)
+            .unwrap_or_default();
+        let duration = end - start;
+        let range = max - min;
+
+        Some(Self {
+            id: curve.id(),
+            start,
+            duration,
+            min,
+            range,
+            locations: keys
+                .iter()
+                .map(|key| quantize(key.location(), start, duration))
+                .collect(),
+            values: keys
+                .iter()
+                .map(|key| quantize(key.value, min, range))
+                .collect(),
+        })
+    }
+
+    /// Converts the quantized curve back to a curve with linear keys. The curve has the same id as
+    /// the source curve.
+    pub fn to_curve(&self) -> Curve {
+        let mut curve = Curve::from(
+            (0..self.key_count())
+                .map(|i| CurveKey::new(self.location(i), self.value(i), CurveKeyKind::Linear))
+                .collect::<Vec<_>>(),
+        );
+        curve.set_id(self.id);
+        curve
+    }
+
+    /// Returns the amount of keys in the curve.
+    pub fn key_count(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Returns the amount of memory (in bytes) used by the curve.
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + (self.locations.capacity() + self.values.capacity()) * std::mem::size_of::<u16>()
+    }
+
+    /// Returns the location of the last key of the curve.
+    pub fn max_location(&self) -> f32 {
+        if self.locations.is_empty() {
+            0.0
+        } else {
+            self.start + self.duration
+        }
+    }
+
+    fn location(&self, index: usize) -> f32 {
+        dequantize(self.locations[index], self.start, self.duration)
+    }
+
+    fn value(&self, index: usize) -> f32 {
+        dequantize(self.values[index], self.min, self.range)
+    }
+
+    fn fetch_at<I>(&self, location: f32, interpolator: I) -> f32
+    where
+        I: FnOnce(f32, f32, f32) -> f32,
+    {
+        let count = self.key_count();
+        if count == 0 {
+            0.0
+        } else if location <= self.location(0) {
+            self.value(0)
+        } else if location >= self.location(count - 1) {
+            self.value(count - 1)
+        } else {
+            let pos = self
+                .locations
+                .partition_point(|key| dequantize(*key, self.start, self.duration) < location);
+            let left = pos.saturating_sub(1);
+            let (left_location, right_location) = (self.location(left), self.location(pos));
+            let t = (location - left_location) / (right_location - left_location);
+            interpolator(self.value(left), self.value(pos), t)
+        }
+    }
+
+    /// Returns an interpolated value at the given location.
+    pub fn value_at(&self, location: f32) -> f32 {
+        self.fetch_at(location, |a, b, t| a + (b - a) * t)
+    }
+
+    /// Returns an interpolated angle at the given location, the angles are interpolated using the
+    /// shortest path.
+    pub fn angle_at(&self, location: f32) -> f32 {
+        self.fetch_at(location, |mut a, mut b, t| {
+            if (b - a).abs() > std::f32::consts::PI {
+                if b > a {
+                    a += std::f32::consts::TAU;
+                } else {
+                    b += std::f32::consts::TAU;
+                }
+            }
+            a + (b - a) * t
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    type Point = (f32, f32);
+    impl CurvePoint for Point {
+        fn x(&self) -> f32 {
+            self.0
+        }
+        fn y(&self) -> f32 {
+            self.1
+        }
+    }
+    #[test]
+    fn empty() {
+        let points: Vec<Point> = Vec::new();
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result.len(), 0);
+    }
+    #[test]
+    fn size_1() {
+        let points: Vec<Point> = vec![(0.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0]);
+    }
+    #[test]
+    fn size_2() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 1]);
+    }
+    #[test]
+    fn size_3() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 1, 2]);
+    }
+    #[test]
+    fn size_4() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, -1.0), (3.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 1, 2, 3]);
+    }
+    #[test]
+    fn size_5() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, -1.0), (3.0, 0.0), (4.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 1, 2, 3, 4]);
+    }
+    #[test]
+    fn irregular_x() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (4.0, -1.0), (6.0, 0.0), (10.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 1, 2, 3, 4]);
+    }
+    #[test]
+    fn irregular_x_remove_2() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (4.0, 4.0), (6.0, 2.0), (10.0, -2.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 2, 4]);
+    }
+    #[test]
+    fn remove_middle() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 2]);
+    }
+    #[test]
+    fn remove_all_but_one() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 0.00001), (2.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0]);
+    }
+    #[test]
+    fn remove_2() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 1.0), (4.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, f32::INFINITY);
+        assert_eq!(result, vec![0, 2, 4]);
+    }
+    #[test]
+    fn small_step_size() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 1.0), (4.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, 0.5);
+        assert_eq!(result, vec![0, 1, 2, 3, 4]);
+    }
+    #[test]
+    fn large_step_size() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 1.0), (4.0, 0.0)];
+        let result = find_important_points(points.as_slice(), 0.001, 2.0);
+        assert_eq!(result, vec![0, 2, 4]);
+    }
+    #[test]
+    fn mid_step_size() {
+        let points: Vec<Point> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 1.5), (4.0, 1.0)];
+        let result = find_important_points(points.as_slice(), 0.001, 1.0);
+        assert_eq!(result, vec![0, 1, 2, 4]);
+    }
+    #[test]
+    fn quantized_curve() {
+        let curve = Curve::from(vec![
+            CurveKey::new(0.0, -1.0, CurveKeyKind::Linear),
+            CurveKey::new(0.5, 2.0, CurveKeyKind::Linear),
+            CurveKey::new(2.0, 0.0, CurveKeyKind::Linear),
+        ]);
+        let quantized = QuantizedCurve::from_curve(&curve).unwrap();
+        for location in [-1.0, 0.0, 0.25, 0.5, 1.0, 1.9, 3.0] {
+            assert!((quantized.value_at(location) - curve.value_at(location)).abs() < 1.0e-3);
+        }
+        assert!(quantized.memory_usage() < curve_memory_usage(&curve));
+        assert_eq!(quantized.to_curve().keys().len(), 3);
+        assert_eq!(quantized.to_curve().id(), curve.id());
+        assert!(QuantizedCurve::from_curve(&Curve::from(vec![CurveKey::new(
+            0.0,
+            1.0,
+            CurveKeyKind::Constant
+        )]))
+        .is_none());
+    }
+}
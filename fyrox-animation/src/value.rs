@@ -233,6 +233,39 @@ impl TrackValue {
         }
     }
 
+    /// Adds an additive (relative) value to the current value using the given weight. Real numbers and vectors
+    /// are summed, while quaternions are multiplied (the relative rotation is applied on top of the current one).
+    /// Addition is possible only if the types are the same. See [`Self::relative_to`] for more info about additive
+    /// values.
+    pub fn add_with(&mut self, other: &Self, weight: f32) {
+        match (self, other) {
+            (Self::Real(a), Self::Real(b)) => *a += *b * weight,
+            (Self::Vector2(a), Self::Vector2(b)) => *a += b.scale(weight),
+            (Self::Vector3(a), Self::Vector3(b)) => *a += b.scale(weight),
+            (Self::Vector4(a), Self::Vector4(b)) => *a += b.scale(weight),
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => {
+                *a *= nlerp(UnitQuaternion::identity(), b, weight)
+            }
+            _ => (),
+        }
+    }
+
+    /// Calculates a value, that is relative to the given reference value. Such values are called additive and
+    /// they could be added on top of any other value using [`Self::add_with`]. Returns a copy of the current value
+    /// if the types are different.
+    pub fn relative_to(&self, reference: &Self) -> Self {
+        match (self, reference) {
+            (Self::Real(a), Self::Real(b)) => Self::Real(*a - *b),
+            (Self::Vector2(a), Self::Vector2(b)) => Self::Vector2(a - b),
+            (Self::Vector3(a), Self::Vector3(b)) => Self::Vector3(a - b),
+            (Self::Vector4(a), Self::Vector4(b)) => Self::Vector4(a - b),
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => {
+                Self::UnitQuaternion(b.inverse() * a)
+            }
+            _ => self.clone(),
+        }
+    }
+
     /// Tries to perform a numeric type casting of the current value to some other and returns a boxed value, that can
     /// be used to set the value using reflection.
     pub fn apply_to_any(&self, any: &mut dyn Any, value_type: ValueType) -> bool {
@@ -403,6 +436,13 @@ impl BoundValue {
         self.value.blend_with(&other.value, weight);
     }
 
+    /// Adds an additive value to the current value using the given weight. See [`TrackValue::add_with`] for
+    /// more info.
+    pub fn add_with(&mut self, other: &Self, weight: f32) {
+        assert_eq!(self.binding, other.binding);
+        self.value.add_with(&other.value, weight);
+    }
+
     /// Sets a property of the given object.
     pub fn apply_to_object(
         &self,
@@ -452,6 +492,16 @@ impl BoundValueCollection {
             }
         }
     }
+
+    /// Tries to add each additive value of the other collection to a respective (by binding) value in the current
+    /// collection. See [`TrackValue::add_with`] docs for more info.
+    pub fn add_with(&mut self, other: &Self, weight: f32) {
+        for value in self.values.iter_mut() {
+            if let Some(other_value) = other.values.iter().find(|v| v.binding == value.binding) {
+                value.add_with(other_value, weight);
+            }
+        }
+    }
 }
 
 /// Interpolates from `a` to `b` using nlerp, including an additional check to ensure
@@ -549,4 +599,26 @@ mod test {
         assert_eq!(object.other_struct.field, 123);
         assert!(object.other_struct.inheritable_variable.is_modified());
     }
+
+    #[test]
+    fn test_additive_values() {
+        use fyrox_core::algebra::{UnitQuaternion, Vector3};
+
+        let reference = TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0));
+        let value = TrackValue::Vector3(Vector3::new(2.0, 4.0, 6.0));
+        let mut base = TrackValue::Vector3(Vector3::new(10.0, 10.0, 10.0));
+        base.add_with(&value.relative_to(&reference), 0.5);
+        assert_eq!(base, TrackValue::Vector3(Vector3::new(10.5, 11.0, 11.5)));
+
+        let reference = UnitQuaternion::from_euler_angles(0.0, 0.5, 0.0);
+        let value = UnitQuaternion::from_euler_angles(0.0, 0.5, 1.0);
+        let delta =
+            TrackValue::UnitQuaternion(value).relative_to(&TrackValue::UnitQuaternion(reference));
+        let mut base = TrackValue::UnitQuaternion(reference);
+        base.add_with(&delta, 1.0);
+        let TrackValue::UnitQuaternion(result) = base else {
+            unreachable!()
+        };
+        assert!(result.angle_to(&value) < 1.0e-5);
+    }
 }
@@ -1169,6 +1169,19 @@ impl GraphicsServer for GlGraphicsServer {
                     .get_parameter_i32(glow::UNIFORM_BUFFER_OFFSET_ALIGNMENT)
                     as usize,
                 max_lod_bias: gl.get_parameter_f32(glow::MAX_TEXTURE_LOD_BIAS),
+                s3tc_compression: [
+                    "GL_EXT_texture_compression_s3tc",
+                    "WEBGL_compressed_texture_s3tc",
+                ]
+                .iter()
+                .any(|extension| gl.supported_extensions().contains(*extension)),
+                bptc_compression: [
+                    "GL_ARB_texture_compression_bptc",
+                    "GL_EXT_texture_compression_bptc",
+                    "EXT_texture_compression_bptc",
+                ]
+                .iter()
+                .any(|extension| gl.supported_extensions().contains(*extension)),
             }
         }
     }
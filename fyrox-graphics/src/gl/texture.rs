@@ -26,7 +26,10 @@ use crate::{
         GpuTextureDescriptor, GpuTextureKind, GpuTextureTrait, PixelKind,
     },
 };
-use glow::{HasContext, PixelUnpackData, COMPRESSED_RED_RGTC1, COMPRESSED_RG_RGTC2};
+use glow::{
+    HasContext, PixelUnpackData, COMPRESSED_RED_RGTC1, COMPRESSED_RGBA_BPTC_UNORM,
+    COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT, COMPRESSED_RG_RGTC2,
+};
 use std::cell::Cell;
 use std::{
     marker::PhantomData,
@@ -127,6 +130,8 @@ impl PixelKind {
             PixelKind::DXT5RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, None),
             PixelKind::R8RGTC => (0, 0, COMPRESSED_RED_RGTC1, None),
             PixelKind::RG8RGTC => (0, 0, COMPRESSED_RG_RGTC2, None),
+            PixelKind::BC6H => (0, 0, COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT, None),
+            PixelKind::BC7 => (0, 0, COMPRESSED_RGBA_BPTC_UNORM, None),
             PixelKind::RGB32F => (glow::FLOAT, glow::RGB, glow::RGB32F, None),
             PixelKind::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F, None),
             PixelKind::RGBA16F => (glow::HALF_FLOAT, glow::RGBA, glow::RGBA16F, None),
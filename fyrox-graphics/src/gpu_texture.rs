@@ -128,6 +128,10 @@ pub enum PixelKind {
     R8RGTC,
     /// Compressed RG8 texture (RGTC).
     RG8RGTC,
+    /// Compressed RGB texture with unsigned floating-point values (BPTC, BC6H).
+    BC6H,
+    /// Compressed RGBA texture (BPTC, BC7).
+    BC7,
     /// Floating-point RGB texture with 11-bit for Red and Green channels, 10-bit for Blue channel.
     R11G11B10F,
     /// Red, Green, Blue (8-bit) + Alpha (2-bit).
@@ -178,7 +182,9 @@ impl PixelKind {
             | Self::DXT3RGBA
             | Self::DXT5RGBA
             | Self::R8RGTC
-            | Self::RG8RGTC => None,
+            | Self::RG8RGTC
+            | Self::BC6H
+            | Self::BC7 => None,
         }
     }
 
@@ -190,7 +196,9 @@ impl PixelKind {
             | Self::DXT3RGBA
             | Self::DXT5RGBA
             | Self::R8RGTC
-            | Self::RG8RGTC => true,
+            | Self::RG8RGTC
+            | Self::BC6H
+            | Self::BC7 => true,
             // Explicit match for rest of formats instead of _ will help to not forget
             // to add new entry here.
             Self::RGBA16
@@ -235,7 +243,8 @@ impl PixelKind {
             | Self::RGBA16F
             | Self::RGB16F
             | Self::D32F
-            | Self::R11G11B10F => PixelElementKind::Float,
+            | Self::R11G11B10F
+            | Self::BC6H => PixelElementKind::Float,
             Self::D16
             | Self::D24S8
             | Self::RGBA8
@@ -256,6 +265,7 @@ impl PixelKind {
             | Self::DXT5RGBA
             | Self::R8RGTC
             | Self::RG8RGTC
+            | Self::BC7
             | Self::RGB10A2
             | Self::LA8
             | Self::L8
@@ -307,7 +317,11 @@ pub fn image_3d_size_bytes(
             let block_size = 8;
             ceil_div_4(width) * ceil_div_4(height) * ceil_div_4(depth) * block_size
         }
-        PixelKind::DXT3RGBA | PixelKind::DXT5RGBA | PixelKind::RG8RGTC => {
+        PixelKind::DXT3RGBA
+        | PixelKind::DXT5RGBA
+        | PixelKind::RG8RGTC
+        | PixelKind::BC6H
+        | PixelKind::BC7 => {
             let block_size = 16;
             ceil_div_4(width) * ceil_div_4(height) * ceil_div_4(depth) * block_size
         }
@@ -346,7 +360,11 @@ pub fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -
             let block_size = 8;
             ceil_div_4(width) * ceil_div_4(height) * block_size
         }
-        PixelKind::DXT3RGBA | PixelKind::DXT5RGBA | PixelKind::RG8RGTC => {
+        PixelKind::DXT3RGBA
+        | PixelKind::DXT5RGBA
+        | PixelKind::RG8RGTC
+        | PixelKind::BC6H
+        | PixelKind::BC7 => {
             let block_size = 16;
             ceil_div_4(width) * ceil_div_4(height) * block_size
         }
@@ -384,7 +402,11 @@ pub fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
             let block_size = 8;
             ceil_div_4(length) * block_size
         }
-        PixelKind::DXT3RGBA | PixelKind::DXT5RGBA | PixelKind::RG8RGTC => {
+        PixelKind::DXT3RGBA
+        | PixelKind::DXT5RGBA
+        | PixelKind::RG8RGTC
+        | PixelKind::BC6H
+        | PixelKind::BC7 => {
             let block_size = 16;
             ceil_div_4(length) * block_size
         }
@@ -49,6 +49,10 @@ pub struct ServerCapabilities {
     /// The maximum, absolute value of the texture level-of-detail bias. The value must be at least
     /// 2.0.
     pub max_lod_bias: f32,
+    /// True if S3TC block-compressed formats (BC1, BC2, BC3) are supported.
+    pub s3tc_compression: bool,
+    /// True if BPTC block-compressed formats (BC6H, BC7) are supported.
+    pub bptc_compression: bool,
 }
 
 /// A shared reference to a graphics server.
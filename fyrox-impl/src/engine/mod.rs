@@ -76,8 +76,18 @@ use crate::{
     renderer::{framework::error::FrameworkError, Renderer},
     resource::{
         curve::{loader::CurveLoader, CurveResourceState},
-        model::{loader::ModelLoader, Model, ModelResource},
-        texture::{self, loader::TextureLoader, Texture, TextureKind},
+        model::{
+            loader::ModelLoader,
+            retarget::{BoneMapping, BoneMappingLoader},
+            Model, ModelResource,
+        },
+        texture::{
+            self,
+            basis::BuiltInBasisTranscoder,
+            ktx::SupportedCompressedFormats,
+            loader::{ProceduralTextureLoader, TextureLoader},
+            Texture, TextureKind,
+        },
     },
     scene::{
         base::NodeScriptMessage,
@@ -110,6 +120,7 @@ use fyrox_graphics::server::SharedGraphicsServer;
 use fyrox_resource::registry::ResourceRegistry;
 use fyrox_sound::{
     buffer::{loader::SoundBufferLoader, SoundBuffer},
+    event::{SoundEvent, SoundEventLoader},
     renderer::hrtf::{HrirSphereLoader, HrirSphereResourceData},
 };
 use std::cell::Cell;
@@ -1275,6 +1286,7 @@ pub(crate) fn initialize_resource_manager_loaders(
     state.constructors_container.add::<Model>();
     state.constructors_container.add::<CurveResourceState>();
     state.constructors_container.add::<SoundBuffer>();
+    state.constructors_container.add::<SoundEvent>();
     state.constructors_container.add::<HrirSphereResourceData>();
     state.constructors_container.add::<Material>();
     state.constructors_container.add::<Font>();
@@ -1286,6 +1298,7 @@ pub(crate) fn initialize_resource_manager_loaders(
     state.constructors_container.add::<CustomTileCollider>();
     state.constructors_container.add::<AnimationTracksData>();
     state.constructors_container.add::<Style>();
+    state.constructors_container.add::<BoneMapping>();
 
     let mut loaders = state.loaders.lock();
     let gltf_loader = super::resource::gltf::GltfLoader {
@@ -1296,12 +1309,19 @@ pub(crate) fn initialize_resource_manager_loaders(
     loaders.set(model_loader);
     loaders.set(TextureLoader {
         default_import_options: Default::default(),
+        basis_transcoder: Some(Arc::new(BuiltInBasisTranscoder)),
+        supported_compressed_formats: Default::default(),
     });
+    loaders.set(ProceduralTextureLoader);
     loaders.set(SoundBufferLoader {
         default_import_options: Default::default(),
     });
+    loaders.set(SoundEventLoader {
+        resource_manager: resource_manager.clone(),
+    });
     loaders.set(ShaderLoader);
     loaders.set(CurveLoader);
+    loaders.set(BoneMappingLoader);
     loaders.set(HrirSphereLoader);
     loaders.set(MaterialLoader {
         resource_manager: resource_manager.clone(),
@@ -1484,6 +1504,20 @@ impl Engine {
                 params.graphics_server_constructor.0(params, window_target, window_builder)?;
             let frame_size = (window.inner_size().width, window.inner_size().height);
 
+            let capabilities = server.capabilities();
+            if let Some(texture_loader) = self
+                .resource_manager
+                .state()
+                .loaders
+                .lock()
+                .find_mut::<TextureLoader>()
+            {
+                texture_loader.supported_compressed_formats = SupportedCompressedFormats {
+                    s3tc: capabilities.s3tc_compression,
+                    bptc: capabilities.bptc_compression,
+                };
+            }
+
             let renderer = Renderer::new(server, frame_size, &self.resource_manager)?;
 
             for ui in self.user_interfaces.iter_mut() {
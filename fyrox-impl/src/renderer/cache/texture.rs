@@ -86,12 +86,15 @@ fn convert_pixel_kind(texture_kind: TexturePixelKind) -> PixelKind {
         TexturePixelKind::RGB16 => PixelKind::RGB16,
         TexturePixelKind::RGBA16 => PixelKind::RGBA16,
         TexturePixelKind::RGB16F => PixelKind::RGB16F,
+        TexturePixelKind::RGBA16F => PixelKind::RGBA16F,
         TexturePixelKind::DXT1RGB => PixelKind::DXT1RGB,
         TexturePixelKind::DXT1RGBA => PixelKind::DXT1RGBA,
         TexturePixelKind::DXT3RGBA => PixelKind::DXT3RGBA,
         TexturePixelKind::DXT5RGBA => PixelKind::DXT5RGBA,
         TexturePixelKind::R8RGTC => PixelKind::R8RGTC,
         TexturePixelKind::RG8RGTC => PixelKind::RG8RGTC,
+        TexturePixelKind::BC6H => PixelKind::BC6H,
+        TexturePixelKind::BC7 => PixelKind::BC7,
         TexturePixelKind::RGB32F => PixelKind::RGB32F,
         TexturePixelKind::RGBA32F => PixelKind::RGBA32F,
         TexturePixelKind::Luminance8 => PixelKind::L8,
@@ -19,7 +19,6 @@
 // SOFTWARE.
 
 use super::iter::*;
-use super::simplify::*;
 use crate::core::algebra::{Quaternion, Unit, UnitQuaternion, Vector3};
 use crate::core::log::Log;
 use crate::core::math::curve::{Curve, CurveKey, CurveKeyKind};
@@ -58,14 +57,6 @@ impl ImportedBinding {
             ImportedBinding::Weight(_) => 0.001,
         }
     }
-    fn max_step(&self) -> f32 {
-        match self {
-            ImportedBinding::Position => f32::INFINITY,
-            ImportedBinding::Rotation => std::f32::consts::PI / 4.0,
-            ImportedBinding::Scale => f32::INFINITY,
-            ImportedBinding::Weight(_) => f32::INFINITY,
-        }
-    }
     fn morph_index(&self) -> Result<usize> {
         match self {
             ImportedBinding::Position => Err(()),
@@ -150,26 +141,22 @@ impl ImportedTrack {
             },
         }
     }
-    fn simplify_curves(&mut self) {
-        for curve in self.curves.iter_mut() {
-            *curve = simplify(
-                curve.as_slice(),
-                self.target.binding.epsilon(),
-                self.target.binding.max_step(),
-            );
-        }
-    }
     fn fixed_value(&self) -> Option<Box<[f32]>> {
         let mut result: Box<[f32]> = if let ImportedBinding::Weight(_) = self.target.binding {
             <[f32; 1]>::default().into()
         } else {
             <[f32; 3]>::default().into()
         };
+        let epsilon = self.target.binding.epsilon();
         for (i, curve) in self.curves.iter().enumerate() {
-            if curve.len() != 1 {
+            let first = curve.first()?.value;
+            if curve
+                .iter()
+                .any(|key| f32::abs(key.value - first) > epsilon)
+            {
                 return None;
             }
-            result[i] = curve[0].value;
+            result[i] = first;
         }
         Some(result)
     }
@@ -214,11 +201,6 @@ impl ImportedAnimation {
     fn remove_target(&mut self, target: ImportedTarget) {
         self.tracks.retain(|t| t.target != target);
     }
-    fn simplify_curves(&mut self) {
-        for t in self.tracks.iter_mut() {
-            t.simplify_curves();
-        }
-    }
     fn into_animation(self) -> Animation {
         let mut result = Animation::default();
         result.set_name(self.name);
@@ -286,8 +268,7 @@ pub fn import_animations(
 ) -> Vec<Animation> {
     let mut imports: Vec<ImportedAnimation> = Vec::with_capacity(doc.animations().len());
     for animation in doc.animations() {
-        if let Ok(mut import) = import_animation(&animation, node_handles, buffers) {
-            import.simplify_curves();
+        if let Ok(import) = import_animation(&animation, node_handles, buffers) {
             imports.push(import);
         } else {
             Log::err(format!(
@@ -720,12 +701,3 @@ where
     }
     Ok(track)
 }
-
-impl CurvePoint for CurveKey {
-    fn x(&self) -> f32 {
-        self.location
-    }
-    fn y(&self) -> f32 {
-        self.value
-    }
-}
@@ -55,7 +55,6 @@ mod animation;
 mod iter;
 mod material;
 mod node_names;
-mod simplify;
 mod surface;
 mod uri;
 
@@ -321,7 +320,7 @@ async fn load(
         io,
         resource_manager,
         model_path: path.clone(),
-        search_options: options.material_search_options,
+        search_options: options.material_search_options.clone(),
     };
     let root_name = path
         .file_name()
@@ -331,6 +330,9 @@ async fn load(
     scene.graph[root].set_name(root_name.clone());
     import_from_path(&mut scene.graph, &context).await?;
     node_names::resolve_name_conflicts(context.model_path.as_path(), &mut scene.graph);
+    // glTF animations are sampled, so they're always reduced, unless the settings are specified
+    // explicitly.
+    options.apply_to_animations(&path, &mut scene.graph, Some(&Default::default()));
     Ok(Model::new(NodeMapping::UseNames, scene))
 }
 
@@ -55,11 +55,21 @@ use crate::{
         NameProvider, TypeUuidProvider,
     },
     engine::SerializationContext,
-    generic_animation::AnimationContainer,
+    generic_animation::{
+        reduction::{KeyframeReductionReport, KeyframeReductionSettings},
+        AnimationContainer,
+    },
     graph::{BaseSceneGraph, NodeHandleMap, NodeMapping, PrefabData, SceneGraph, SceneGraphNode},
-    resource::fbx::{self, error::FbxError},
+    resource::{
+        fbx::{self, error::FbxError},
+        model::retarget::BoneMapping,
+    },
     scene::{
-        animation::Animation, base::SceneNodeId, graph::Graph, node::Node, transform::Transform,
+        animation::{Animation, AnimationPlayer},
+        base::SceneNodeId,
+        graph::Graph,
+        node::Node,
+        transform::Transform,
         Scene, SceneLoader,
     },
 };
@@ -77,6 +87,7 @@ use std::{
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 pub mod loader;
+pub mod retarget;
 
 /// See module docs.
 #[derive(Debug, Visit, Reflect)]
@@ -438,6 +449,17 @@ pub trait ModelResourceExtension: Sized {
     /// Panics if there's no animation player in the given hierarchy (descendant nodes of `root`).
     fn retarget_animations(&self, root: Handle<Node>, graph: &mut Graph) -> Vec<Handle<Animation>>;
 
+    /// Retargets animations from given model resource to a node hierarchy starting from `root` on a
+    /// given scene using the given bone mapping. Unlike [`Self::retarget_animations_directly`], this
+    /// method transfers rotations relative to rest poses of the skeletons and scales translations
+    /// according to proportions of the skeletons. See [`BoneMapping`] docs for more info.
+    fn retarget_animations_with_mapping(
+        &self,
+        root: Handle<Node>,
+        graph: &Graph,
+        mapping: &BoneMapping,
+    ) -> Vec<Animation>;
+
     /// Generates a set of unique IDs for every node in the model. Use this method in pair with
     /// [`ModelResource::begin_instantiation`].
     fn generate_ids(&self) -> FxHashMap<Handle<Node>, SceneNodeId>;
@@ -586,6 +608,37 @@ impl ModelResourceExtension for ModelResource {
         }
     }
 
+    fn retarget_animations_with_mapping(
+        &self,
+        root: Handle<Node>,
+        graph: &Graph,
+        mapping: &BoneMapping,
+    ) -> Vec<Animation> {
+        let header = self.state();
+        let Some(model) = header.data_ref() else {
+            return Default::default();
+        };
+
+        let model_graph = &model.scene.graph;
+        let mut retargeted_animations = Vec::new();
+        for node in model_graph.linear_iter() {
+            if let Some(animations) =
+                node.component_ref::<InheritableVariable<AnimationContainer<Handle<Node>>>>()
+            {
+                for animation in animations.iter() {
+                    retargeted_animations.push(mapping.retarget(
+                        animation,
+                        model_graph,
+                        model_graph.get_root(),
+                        graph,
+                        root,
+                    ));
+                }
+            }
+        }
+        retargeted_animations
+    }
+
     fn generate_ids(&self) -> FxHashMap<Handle<Node>, SceneNodeId> {
         let data = self.data_ref();
         data.scene
@@ -717,10 +770,59 @@ pub struct ModelImportOptions {
     /// See [`MaterialSearchOptions`] docs for more info.
     #[serde(default)]
     pub material_search_options: MaterialSearchOptions,
+    /// If set, every animation of the model will be converted to an additive animation, that is relative
+    /// to its first frame. Such animations could be played in additive layers of animation blending state
+    /// machines (breathing, recoil, leaning, etc.). See [`Animation::make_additive`] docs for more info.
+    #[serde(default)]
+    pub additive_animations: bool,
+    /// Keyframe reduction (animation compression) settings, that will be applied to every animation of the
+    /// model. See [`KeyframeReductionSettings`] docs for more info. Default is [`None`], which means that
+    /// FBX animations are imported intact, while glTF animations (which are usually densely sampled) are
+    /// reduced using the default settings.
+    #[serde(default)]
+    pub keyframe_reduction: Option<KeyframeReductionSettings>,
 }
 
 impl ImportOptions for ModelImportOptions {}
 
+impl ModelImportOptions {
+    pub(crate) fn apply_to_animations(
+        &self,
+        path: &Path,
+        graph: &mut Graph,
+        default_keyframe_reduction: Option<&KeyframeReductionSettings>,
+    ) {
+        let keyframe_reduction = self
+            .keyframe_reduction
+            .as_ref()
+            .or(default_keyframe_reduction);
+        let mut report = KeyframeReductionReport::default();
+        for node in graph.linear_iter_mut() {
+            if let Some(animation_player) = node.component_mut::<AnimationPlayer>() {
+                for animation in animation_player
+                    .animations_mut()
+                    .get_value_mut_silent()
+                    .iter_mut()
+                {
+                    if self.additive_animations {
+                        animation.make_additive_to_first_frame();
+                    }
+                    if let Some(keyframe_reduction) = keyframe_reduction {
+                        report += animation.reduce_keys(keyframe_reduction);
+                    }
+                }
+            }
+        }
+
+        if report.keys_before > 0 {
+            Log::info(format!(
+                "Keyframe reduction of {} animations: {report}",
+                path.display()
+            ));
+        }
+    }
+}
+
 /// All possible errors that may occur while trying to load model from some
 /// data source.
 #[derive(Debug)]
@@ -795,6 +897,7 @@ impl Model {
                     &model_import_options,
                 )
                 .await?;
+                model_import_options.apply_to_animations(path.as_ref(), &mut scene.graph, None);
                 // Set NodeMapping::UseNames as mapping here because FBX does not have
                 // any persistent unique ids, and we have to use names.
                 (scene, NodeMapping::UseNames)
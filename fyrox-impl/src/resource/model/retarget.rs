@@ -0,0 +1,956 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Bone mapping resource and proportion-aware animation retargeting. See [`BoneMapping`] docs for
+//! more info.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        state::LoadError,
+        untyped::ResourceKind,
+        Resource, ResourceData,
+    },
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        io::FileError,
+        log::Log,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::Uuid,
+        uuid_provider,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    generic_animation::value::{TrackValue, ValueBinding},
+    scene::{animation::Animation, graph::Graph, node::Node, transform::Transform},
+};
+use fxhash::FxHashMap;
+use fyrox_graph::{BaseSceneGraph, SceneGraph, SceneGraphNode};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// A bone of a standard humanoid skeleton. It is used to describe skeletons with arbitrary bone names
+/// in a unified way, see [`HumanoidAvatar`] for more info.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum HumanoidBone {
+    /// Pelvis of a character, usually it is the root bone of a skeleton.
+    #[default]
+    Hips,
+    /// Lower part of the spine.
+    Spine,
+    /// Middle part of the spine.
+    Chest,
+    /// Upper part of the spine.
+    UpperChest,
+    /// Neck.
+    Neck,
+    /// Head.
+    Head,
+    /// Left clavicle.
+    LeftShoulder,
+    /// Left upper arm.
+    LeftUpperArm,
+    /// Left forearm.
+    LeftLowerArm,
+    /// Left hand (wrist).
+    LeftHand,
+    /// Right clavicle.
+    RightShoulder,
+    /// Right upper arm.
+    RightUpperArm,
+    /// Right forearm.
+    RightLowerArm,
+    /// Right hand (wrist).
+    RightHand,
+    /// Left thigh.
+    LeftUpperLeg,
+    /// Left calf.
+    LeftLowerLeg,
+    /// Left foot (ankle).
+    LeftFoot,
+    /// Left toes.
+    LeftToes,
+    /// Right thigh.
+    RightUpperLeg,
+    /// Right calf.
+    RightLowerLeg,
+    /// Right foot (ankle).
+    RightFoot,
+    /// Right toes.
+    RightToes,
+}
+
+uuid_provider!(HumanoidBone = "d0ea497d-2015-4e63-81dd-bc672898e3f3");
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl HumanoidBone {
+    /// All humanoid bones, ordered from the root of a skeleton to its extremities.
+    pub const ALL: [HumanoidBone; 22] = [
+        HumanoidBone::Hips,
+        HumanoidBone::Spine,
+        HumanoidBone::Chest,
+        HumanoidBone::UpperChest,
+        HumanoidBone::Neck,
+        HumanoidBone::Head,
+        HumanoidBone::LeftShoulder,
+        HumanoidBone::LeftUpperArm,
+        HumanoidBone::LeftLowerArm,
+        HumanoidBone::LeftHand,
+        HumanoidBone::RightShoulder,
+        HumanoidBone::RightUpperArm,
+        HumanoidBone::RightLowerArm,
+        HumanoidBone::RightHand,
+        HumanoidBone::LeftUpperLeg,
+        HumanoidBone::LeftLowerLeg,
+        HumanoidBone::LeftFoot,
+        HumanoidBone::LeftToes,
+        HumanoidBone::RightUpperLeg,
+        HumanoidBone::RightLowerLeg,
+        HumanoidBone::RightFoot,
+        HumanoidBone::RightToes,
+    ];
+
+    fn side(self) -> Option<Side> {
+        match self {
+            HumanoidBone::LeftShoulder
+            | HumanoidBone::LeftUpperArm
+            | HumanoidBone::LeftLowerArm
+            | HumanoidBone::LeftHand
+            | HumanoidBone::LeftUpperLeg
+            | HumanoidBone::LeftLowerLeg
+            | HumanoidBone::LeftFoot
+            | HumanoidBone::LeftToes => Some(Side::Left),
+            HumanoidBone::RightShoulder
+            | HumanoidBone::RightUpperArm
+            | HumanoidBone::RightLowerArm
+            | HumanoidBone::RightHand
+            | HumanoidBone::RightUpperLeg
+            | HumanoidBone::RightLowerLeg
+            | HumanoidBone::RightFoot
+            | HumanoidBone::RightToes => Some(Side::Right),
+            _ => None,
+        }
+    }
+
+    // Commonly used bone names (without side markers) in popular skeleton conventions (Mixamo, Unreal,
+    // Rigify, etc.).
+    fn known_names(self) -> &'static [&'static str] {
+        match self {
+            HumanoidBone::Hips => &["hips", "pelvis", "hip"],
+            HumanoidBone::Spine => &["spine", "spine01"],
+            HumanoidBone::Chest => &["spine1", "spine02", "chest"],
+            HumanoidBone::UpperChest => &["spine2", "spine03", "upperchest"],
+            HumanoidBone::Neck => &["neck", "neck01", "neck1"],
+            HumanoidBone::Head => &["head"],
+            HumanoidBone::LeftShoulder | HumanoidBone::RightShoulder => {
+                &["shoulder", "clavicle", "collar"]
+            }
+            HumanoidBone::LeftUpperArm | HumanoidBone::RightUpperArm => {
+                &["arm", "upperarm", "uparm"]
+            }
+            HumanoidBone::LeftLowerArm | HumanoidBone::RightLowerArm => {
+                &["forearm", "lowerarm", "elbow"]
+            }
+            HumanoidBone::LeftHand | HumanoidBone::RightHand => &["hand", "wrist"],
+            HumanoidBone::LeftUpperLeg | HumanoidBone::RightUpperLeg => {
+                &["upleg", "thigh", "upperleg"]
+            }
+            HumanoidBone::LeftLowerLeg | HumanoidBone::RightLowerLeg => {
+                &["leg", "calf", "shin", "lowerleg", "knee"]
+            }
+            HumanoidBone::LeftFoot | HumanoidBone::RightFoot => &["foot", "ankle"],
+            HumanoidBone::LeftToes | HumanoidBone::RightToes => &["toebase", "toe", "toes", "ball"],
+        }
+    }
+}
+
+// Splits a bone name into a side marker and a "base" name, which is lowercase and contains only
+// alphanumeric characters. For example `mixamorig:LeftForeArm` becomes `(Left, "forearm")` and
+// `upperarm_r` becomes `(Right, "upperarm")`.
+fn normalize_bone_name(name: &str) -> (Option<Side>, String) {
+    let name = name.rsplit(':').next().unwrap_or(name).to_lowercase();
+
+    let mut side = None;
+    let mut name = if name.contains("left") {
+        side = Some(Side::Left);
+        name.replacen("left", "", 1)
+    } else if name.contains("right") {
+        side = Some(Side::Right);
+        name.replacen("right", "", 1)
+    } else {
+        name
+    };
+
+    if side.is_none() {
+        'search: for separator in ['_', '.', ' ', '-'] {
+            for (marker, marker_side) in [('l', Side::Left), ('r', Side::Right)] {
+                let stripped = name
+                    .strip_suffix(&format!("{separator}{marker}"))
+                    .or_else(|| name.strip_prefix(&format!("{marker}{separator}")));
+                if let Some(stripped) = stripped {
+                    side = Some(marker_side);
+                    name = stripped.to_string();
+                    break 'search;
+                }
+            }
+        }
+    }
+
+    name.retain(|c| c.is_ascii_alphanumeric());
+
+    (side, name)
+}
+
+/// A pair of a humanoid bone and a name of a node, that represents the bone in a particular skeleton.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct HumanoidAvatarBone {
+    /// Humanoid bone.
+    pub bone: HumanoidBone,
+    /// Name of a node in a skeleton, that corresponds to the bone.
+    pub name: String,
+}
+
+uuid_provider!(HumanoidAvatarBone = "adc04087-900d-4597-94ce-e7b1f6bb939e");
+
+/// Humanoid avatar definition describes a skeleton with arbitrary bone names in terms of standard
+/// [`HumanoidBone`]s. Two avatars could be used to create a [`BoneMapping`] between skeletons that
+/// use different naming conventions, see [`BoneMapping::from_avatars`].
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct HumanoidAvatar {
+    /// A list of bones of the avatar.
+    pub bones: Vec<HumanoidAvatarBone>,
+}
+
+impl HumanoidAvatar {
+    /// Tries to create an avatar definition for a skeleton, that starts from the given root, using
+    /// the names of the nodes. Commonly used naming conventions (Mixamo, Unreal, Rigify, etc.) are
+    /// supported, the result should be checked and adjusted manually for other skeletons.
+    pub fn guess(graph: &Graph, root: Handle<Node>) -> Self {
+        let mut avatar = Self::default();
+        for (_, node) in graph.traverse_iter(root) {
+            let (side, name) = normalize_bone_name(node.name());
+            // The traversal goes from the root of the hierarchy, so the first match is the closest
+            // one to the root, which prevents twist or helper bones from being picked.
+            if let Some(bone) = HumanoidBone::ALL.iter().find(|bone| {
+                bone.side() == side
+                    && avatar.bone_name(**bone).is_none()
+                    && bone.known_names().contains(&name.as_str())
+            }) {
+                avatar.set_bone_name(*bone, node.name());
+            }
+        }
+        avatar
+    }
+
+    /// Returns a name of a node, that corresponds to the given humanoid bone.
+    pub fn bone_name(&self, bone: HumanoidBone) -> Option<&str> {
+        self.bones
+            .iter()
+            .find(|entry| entry.bone == bone)
+            .map(|entry| entry.name.as_str())
+    }
+
+    /// Sets a name of a node, that corresponds to the given humanoid bone.
+    pub fn set_bone_name(&mut self, bone: HumanoidBone, name: &str) {
+        if let Some(entry) = self.bones.iter_mut().find(|entry| entry.bone == bone) {
+            entry.name = name.to_string();
+        } else {
+            self.bones.push(HumanoidAvatarBone {
+                bone,
+                name: name.to_string(),
+            });
+        }
+    }
+}
+
+/// A pair of names of a source bone and a target bone.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Visit, Reflect)]
+pub struct BoneMappingEntry {
+    /// Name of a bone in a skeleton, that is used as a source of animations.
+    pub source: String,
+    /// Name of a bone in a skeleton, to which the animations will be retargeted.
+    pub target: String,
+}
+
+uuid_provider!(BoneMappingEntry = "6df1af77-e43c-431e-9552-c4a59c5ba6eb");
+
+/// Defines how translation tracks are transferred to a target skeleton.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum TranslationRetargeting {
+    /// Translations are applied relative to the rest pose of the target bones and scaled by the
+    /// ratio of the bone lengths (a distance from a bone to its parent) of the target and source
+    /// skeletons. For example, if a target character is two times taller than a source character,
+    /// its hips will move two times further.
+    #[default]
+    Scaled,
+    /// Translations are copied as is.
+    Source,
+    /// Translation tracks are discarded and target bones keep their rest positions.
+    Rest,
+}
+
+uuid_provider!(TranslationRetargeting = "e958dd99-4d33-434c-b5d7-81013bf2b832");
+
+/// Bone mapping defines which bones of a source skeleton drive which bones of a target skeleton and
+/// allows you to transfer animations between skeletons with different bone names, rest poses and
+/// proportions. Unlike [`super::ModelResourceExtension::retarget_animations_directly`], which matches
+/// bones by their names and copies the animated values as is, retargeting with a bone mapping:
+///
+/// - matches bones using explicit mapping table; bones without an entry are not animated;
+/// - transfers rotations relative to the rest poses of both skeletons, so skeletons with different
+/// bone orientations (for example, a T-pose and an A-pose skeletons) could share animations;
+/// - scales translations by the ratio of the bone lengths (see [`TranslationRetargeting`]).
+///
+/// Bone mapping is a resource, so it could be saved once and reused for any animation of the source
+/// skeleton. The mapping could be filled manually, or created from two [`HumanoidAvatar`]s using
+/// [`BoneMapping::from_avatars`].
+///
+/// # Rest pose
+///
+/// Current local transforms of the bones are treated as their rest poses. This means that the target
+/// hierarchy must be in its rest pose when the retargeting is performed (a freshly instantiated model
+/// is a good candidate), animations of the source model are always retargeted from its rest pose.
+///
+/// # Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::pool::Handle,
+/// #     resource::model::{
+/// #         retarget::{BoneMapping, HumanoidAvatar},
+/// #         ModelResource, ModelResourceExtension,
+/// #     },
+/// #     scene::{animation::Animation, node::Node, Scene},
+/// # };
+/// # use fyrox_graph::BaseSceneGraph;
+/// fn retarget(animation: &ModelResource, character: Handle<Node>, scene: &Scene) -> Vec<Animation> {
+///     let source = animation.data_ref();
+///     let source_graph = &source.get_scene().graph;
+///     let mapping = BoneMapping::from_avatars(
+///         &HumanoidAvatar::guess(source_graph, source_graph.get_root()),
+///         &HumanoidAvatar::guess(&scene.graph, character),
+///     );
+///     drop(source);
+///     animation.retarget_animations_with_mapping(character, &scene.graph, &mapping)
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct BoneMapping {
+    /// A list of bone pairs.
+    pub entries: Vec<BoneMappingEntry>,
+    /// Defines how translation tracks are transferred to the target skeleton.
+    pub translation_retargeting: TranslationRetargeting,
+}
+
+uuid_provider!(BoneMapping = "5b1f1e6c-9f57-4d2b-8a0e-3c4c8a3f1d27");
+
+impl BoneMapping {
+    /// Creates a bone mapping between two skeletons, described by the given humanoid avatars. Only the
+    /// bones that are present in both avatars are mapped.
+    pub fn from_avatars(source: &HumanoidAvatar, target: &HumanoidAvatar) -> Self {
+        Self {
+            entries: source
+                .bones
+                .iter()
+                .filter_map(|entry| {
+                    target.bone_name(entry.bone).map(|target| BoneMappingEntry {
+                        source: entry.name.clone(),
+                        target: target.to_string(),
+                    })
+                })
+                .collect(),
+            translation_retargeting: Default::default(),
+        }
+    }
+
+    /// Adds a new pair of bones to the mapping. If there's an entry for the source bone already, its
+    /// target will be replaced.
+    pub fn set_target(&mut self, source: &str, target: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.source == source) {
+            entry.target = target.to_string();
+        } else {
+            self.entries.push(BoneMappingEntry {
+                source: source.to_string(),
+                target: target.to_string(),
+            });
+        }
+    }
+
+    /// Adds a new pair of bones to the mapping. See [`Self::set_target`] for more info.
+    pub fn with_entry(mut self, source: &str, target: &str) -> Self {
+        self.set_target(source, target);
+        self
+    }
+
+    /// Sets the way how translation tracks are transferred to the target skeleton.
+    pub fn with_translation_retargeting(mut self, retargeting: TranslationRetargeting) -> Self {
+        self.translation_retargeting = retargeting;
+        self
+    }
+
+    /// Returns a name of a target bone, that corresponds to the given source bone.
+    pub fn target_of(&self, source: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.source == source)
+            .map(|entry| entry.target.as_str())
+    }
+
+    /// Retargets the given animation, which tracks are bound to the nodes of the source graph, to
+    /// the hierarchy of the target graph starting from the `target_root`. `source_root` and
+    /// `target_root` define the space of the skeletons, their own transforms do not affect the result.
+    /// Tracks of unmapped bones are removed from the resulting animation. The resulting animation uses
+    /// its own (embedded) copy of the tracks data.
+    pub fn retarget(
+        &self,
+        animation: &Animation,
+        source_graph: &Graph,
+        source_root: Handle<Node>,
+        target_graph: &Graph,
+        target_root: Handle<Node>,
+    ) -> Animation {
+        let mut retargeted = animation.clone();
+
+        let state = animation.tracks_data().state();
+        let Some(tracks_data) = state.data_ref() else {
+            return retargeted;
+        };
+        let mut tracks_data = tracks_data.clone();
+        drop(state);
+
+        let mut bindings = FxHashMap::default();
+        tracks_data.tracks.retain_mut(|track| {
+            let Some(binding) = animation.track_bindings().get(&track.id()) else {
+                return false;
+            };
+            let Some(target) =
+                self.find_target(binding.target(), source_graph, target_graph, target_root)
+            else {
+                return false;
+            };
+
+            let source_rest = RestPose::new(source_graph, source_root, binding.target());
+            let target_rest = RestPose::new(target_graph, target_root, target);
+            match track.value_binding() {
+                ValueBinding::Rotation => {
+                    let (left, right) = source_rest.rotation_transfer(&target_rest);
+                    track.data_container_mut().map_values(|value| match value {
+                        TrackValue::UnitQuaternion(rotation) => {
+                            TrackValue::UnitQuaternion(left * rotation * right)
+                        }
+                        _ => value,
+                    });
+                }
+                ValueBinding::Position => match self.translation_retargeting {
+                    TranslationRetargeting::Scaled => {
+                        let basis = source_rest.basis_transfer(&target_rest);
+                        let scale = source_rest.length_ratio(&target_rest);
+                        track.data_container_mut().map_values(|value| match value {
+                            TrackValue::Vector3(position) => TrackValue::Vector3(
+                                target_rest.position
+                                    + basis.transform_vector(
+                                        &((position - source_rest.position) * scale),
+                                    ),
+                            ),
+                            _ => value,
+                        });
+                    }
+                    TranslationRetargeting::Source => (),
+                    TranslationRetargeting::Rest => return false,
+                },
+                _ => (),
+            }
+
+            bindings.insert(track.id(), binding.clone().with_target(target));
+
+            true
+        });
+
+        *retargeted.track_bindings_mut() = bindings;
+        retargeted.set_tracks_data(Resource::new_ok(
+            Uuid::new_v4(),
+            ResourceKind::Embedded,
+            tracks_data,
+        ));
+
+        if let Some(settings) = retargeted.root_motion_settings_mut() {
+            settings.node = self
+                .find_target(settings.node, source_graph, target_graph, target_root)
+                .unwrap_or_default();
+        }
+
+        retargeted
+    }
+
+    fn find_target(
+        &self,
+        source: Handle<Node>,
+        source_graph: &Graph,
+        target_graph: &Graph,
+        target_root: Handle<Node>,
+    ) -> Option<Handle<Node>> {
+        let source_name = source_graph.try_get(source)?.name();
+        let target_name = self.target_of(source_name)?;
+        match target_graph.find_by_name(target_root, target_name) {
+            Some((target, _)) => Some(target),
+            None => {
+                Log::warn(format!(
+                    "Unable to retarget animation of {source_name} bone, because there's no \
+                    {target_name} bone in the target hierarchy!"
+                ));
+                None
+            }
+        }
+    }
+}
+
+// Rest pose of a bone, relative to the root of its skeleton.
+struct RestPose {
+    position: Vector3<f32>,
+    pre_rotation: UnitQuaternion<f32>,
+    post_rotation: UnitQuaternion<f32>,
+    // Complete local rotation (pre-rotation, rotation and post-rotation).
+    local_rotation: UnitQuaternion<f32>,
+    parent_rotation: UnitQuaternion<f32>,
+}
+
+fn full_rotation(transform: &Transform) -> UnitQuaternion<f32> {
+    **transform.pre_rotation() * **transform.rotation() * **transform.post_rotation()
+}
+
+impl RestPose {
+    fn new(graph: &Graph, root: Handle<Node>, bone: Handle<Node>) -> Self {
+        let node = &graph[bone];
+        let transform = node.local_transform();
+
+        let mut parent_rotation = UnitQuaternion::identity();
+        let mut parent = node.parent();
+        while parent != root {
+            let Some(parent_node) = graph.try_get(parent) else {
+                break;
+            };
+            parent_rotation = full_rotation(parent_node.local_transform()) * parent_rotation;
+            parent = parent_node.parent();
+        }
+
+        Self {
+            position: **transform.position(),
+            pre_rotation: **transform.pre_rotation(),
+            post_rotation: **transform.post_rotation(),
+            local_rotation: full_rotation(transform),
+            parent_rotation,
+        }
+    }
+
+    // Rotation, that converts vectors from the space of the parent of this bone to the space of the
+    // parent of the target bone (both in rest poses).
+    fn basis_transfer(&self, target: &RestPose) -> UnitQuaternion<f32> {
+        target.parent_rotation.inverse() * self.parent_rotation
+    }
+
+    // Returns a pair of rotations `(left, right)`, so the animated rotation of the target bone is
+    // `left * source * right`. The retargeted rotation gives the same change of the orientation
+    // (relative to the rest pose) in the skeleton space as the source rotation does.
+    fn rotation_transfer(&self, target: &RestPose) -> (UnitQuaternion<f32>, UnitQuaternion<f32>) {
+        let basis = self.basis_transfer(target);
+        let left = target.pre_rotation.inverse() * basis * self.pre_rotation;
+        let right = self.post_rotation
+            * self.local_rotation.inverse()
+            * basis.inverse()
+            * target.local_rotation
+            * target.post_rotation.inverse();
+        (left, right)
+    }
+
+    fn length_ratio(&self, target: &RestPose) -> f32 {
+        let source_length = self.position.norm();
+        if source_length > f32::EPSILON {
+            target.position.norm() / source_length
+        } else {
+            1.0
+        }
+    }
+}
+
+impl ResourceData for BoneMapping {
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("BoneMapping", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+/// An error that may occur during bone mapping resource loading.
+#[derive(Debug)]
+pub enum BoneMappingError {
+    /// An i/o error has occurred.
+    Io(FileError),
+
+    /// An error that may occur due to version incompatibilities.
+    Visit(VisitError),
+}
+
+impl Display for BoneMappingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoneMappingError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            BoneMappingError::Visit(v) => {
+                write!(
+                    f,
+                    "An error that may occur due to version incompatibilities. {v:?}"
+                )
+            }
+        }
+    }
+}
+
+impl From<FileError> for BoneMappingError {
+    fn from(e: FileError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<VisitError> for BoneMappingError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+impl BoneMapping {
+    /// Loads a bone mapping from the specific file path.
+    pub async fn from_file(path: &Path, io: &dyn ResourceIo) -> Result<Self, BoneMappingError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        let mut mapping = BoneMapping::default();
+        mapping.visit("BoneMapping", &mut visitor)?;
+        Ok(mapping)
+    }
+}
+
+/// A loader for bone mapping resources.
+pub struct BoneMappingLoader;
+
+impl ResourceLoader for BoneMappingLoader {
+    fn extensions(&self) -> &[&str] {
+        &["bone_mapping"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        <BoneMapping as TypeUuidProvider>::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let mapping = BoneMapping::from_file(&path, io.as_ref())
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(mapping))
+        })
+    }
+}
+
+/// Bone mapping resource.
+pub type BoneMappingResource = Resource<BoneMapping>;
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{untyped::ResourceKind, Resource},
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            math::curve::{CurveKey, CurveKeyKind},
+            pool::Handle,
+        },
+        generic_animation::{
+            container::{TrackDataContainer, TrackValueKind},
+            track::TrackBinding,
+            value::{TrackValue, ValueBinding},
+            AnimationTracksData,
+        },
+        resource::model::retarget::{
+            BoneMapping, HumanoidAvatar, HumanoidBone, TranslationRetargeting,
+        },
+        scene::{
+            animation::{Animation, Track},
+            base::BaseBuilder,
+            graph::Graph,
+            node::Node,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+    use fyrox_graph::SceneGraph;
+
+    fn bone(
+        graph: &mut Graph,
+        name: &str,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        children: &[Handle<Node>],
+    ) -> Handle<Node> {
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_name(name)
+                .with_children(children)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .with_local_rotation(rotation)
+                        .build(),
+                ),
+        )
+        .build(graph)
+    }
+
+    fn track(binding: ValueBinding, kind: TrackValueKind, keys: &[[f32; 3]; 2]) -> Track {
+        let mut container = TrackDataContainer::new(kind);
+        for (i, curve) in container.curves_mut().iter_mut().enumerate() {
+            curve.add_key(CurveKey::new(0.0, keys[0][i], CurveKeyKind::Linear));
+            curve.add_key(CurveKey::new(1.0, keys[1][i], CurveKeyKind::Linear));
+        }
+        Track::new(container, binding)
+    }
+
+    fn fetch(animation: &Animation, graph: &Graph, name: &str, time: f32) -> TrackValue {
+        let target = graph.find_by_name_from_root(name).unwrap().0;
+        let tracks_data = animation.tracks_data().data_ref();
+        tracks_data
+            .tracks
+            .iter()
+            .find(|track| animation.track_bindings()[&track.id()].target() == target)
+            .and_then(|track| track.fetch(time))
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn test_guess_avatar() {
+        let mut graph = Graph::new();
+        let hand = bone(
+            &mut graph,
+            "mixamorig:LeftHand",
+            Vector3::default(),
+            UnitQuaternion::identity(),
+            &[],
+        );
+        let forearm = bone(
+            &mut graph,
+            "lowerarm_l",
+            Vector3::default(),
+            UnitQuaternion::identity(),
+            &[hand],
+        );
+        let thigh = bone(
+            &mut graph,
+            "thigh.R",
+            Vector3::default(),
+            UnitQuaternion::identity(),
+            &[],
+        );
+        bone(
+            &mut graph,
+            "Hips",
+            Vector3::default(),
+            UnitQuaternion::identity(),
+            &[forearm, thigh],
+        );
+
+        let avatar = HumanoidAvatar::guess(&graph, graph.get_root());
+        assert_eq!(avatar.bone_name(HumanoidBone::Hips), Some("Hips"));
+        assert_eq!(
+            avatar.bone_name(HumanoidBone::LeftLowerArm),
+            Some("lowerarm_l")
+        );
+        assert_eq!(
+            avatar.bone_name(HumanoidBone::LeftHand),
+            Some("mixamorig:LeftHand")
+        );
+        assert_eq!(
+            avatar.bone_name(HumanoidBone::RightUpperLeg),
+            Some("thigh.R")
+        );
+        assert_eq!(avatar.bone_name(HumanoidBone::RightHand), None);
+    }
+
+    #[test]
+    fn test_retarget_with_mapping() {
+        // Source skeleton is one unit tall, its spine has identity rest rotation.
+        let mut source_graph = Graph::new();
+        let spine = bone(
+            &mut source_graph,
+            "Spine",
+            Vector3::new(0.0, 0.5, 0.0),
+            UnitQuaternion::identity(),
+            &[],
+        );
+        let hips = bone(
+            &mut source_graph,
+            "Hips",
+            Vector3::new(0.0, 1.0, 0.0),
+            UnitQuaternion::identity(),
+            &[spine],
+        );
+
+        // Target skeleton is two units tall, with different names and a rotated spine rest pose.
+        let mut target_graph = Graph::new();
+        let spine_rest = UnitQuaternion::from_euler_angles(0.0, 0.7, 0.0);
+        let target_spine = bone(
+            &mut target_graph,
+            "spine_01",
+            Vector3::new(0.0, 1.0, 0.0),
+            spine_rest,
+            &[],
+        );
+        bone(
+            &mut target_graph,
+            "pelvis",
+            Vector3::new(0.0, 2.0, 0.0),
+            UnitQuaternion::identity(),
+            &[target_spine],
+        );
+
+        let mut tracks_data = AnimationTracksData::default();
+        let mut animation = Animation::default();
+        for (target, track) in [
+            (
+                hips,
+                track(
+                    ValueBinding::Position,
+                    TrackValueKind::Vector3,
+                    &[[0.0, 1.0, 0.0], [1.0, 1.0, 0.0]],
+                ),
+            ),
+            (
+                spine,
+                track(
+                    ValueBinding::Rotation,
+                    TrackValueKind::UnitQuaternion,
+                    &[[0.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+                ),
+            ),
+        ] {
+            animation
+                .track_bindings_mut()
+                .insert(track.id(), TrackBinding::new(target));
+            tracks_data.add_track(track);
+        }
+        animation.set_tracks_data(Resource::new_ok(
+            Default::default(),
+            ResourceKind::Embedded,
+            tracks_data,
+        ));
+
+        let mapping = BoneMapping::from_avatars(
+            &HumanoidAvatar::guess(&source_graph, source_graph.get_root()),
+            &HumanoidAvatar::guess(&target_graph, target_graph.get_root()),
+        );
+        assert_eq!(mapping.target_of("Hips"), Some("pelvis"));
+        assert_eq!(mapping.target_of("Spine"), Some("spine_01"));
+
+        let retargeted = mapping.retarget(
+            &animation,
+            &source_graph,
+            source_graph.get_root(),
+            &target_graph,
+            target_graph.get_root(),
+        );
+
+        // Hips movement is scaled by the height ratio.
+        assert_eq!(
+            fetch(&retargeted, &target_graph, "pelvis", 1.0),
+            TrackValue::Vector3(Vector3::new(2.0, 2.0, 0.0))
+        );
+
+        // Rest pose maps to rest pose, the rotation is applied on top of the rest pose.
+        let TrackValue::UnitQuaternion(first) = fetch(&retargeted, &target_graph, "spine_01", 0.0)
+        else {
+            unreachable!()
+        };
+        assert!(first.angle_to(&spine_rest) < 1.0e-4);
+        let TrackValue::UnitQuaternion(last) = fetch(&retargeted, &target_graph, "spine_01", 1.0)
+        else {
+            unreachable!()
+        };
+        let expected = UnitQuaternion::from_euler_angles(0.0, 0.0, 1.0) * spine_rest;
+        assert!(last.angle_to(&expected) < 1.0e-4);
+
+        // Translations could be discarded.
+        let retargeted = mapping
+            .clone()
+            .with_translation_retargeting(TranslationRetargeting::Rest)
+            .retarget(
+                &animation,
+                &source_graph,
+                source_graph.get_root(),
+                &target_graph,
+                target_graph.get_root(),
+            );
+        assert_eq!(retargeted.tracks_data().data_ref().tracks.len(), 1);
+    }
+}
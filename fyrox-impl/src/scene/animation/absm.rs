@@ -33,7 +33,7 @@ use crate::{
         visitor::prelude::*,
     },
     scene::{
-        animation::prelude::*,
+        animation::{ik::IkSolver, prelude::*},
         base::{Base, BaseBuilder},
         graph::Graph,
         node::{Node, NodeTrait, UpdateContext},
@@ -112,8 +112,10 @@ pub mod prelude {
         RootMotionSettings, State, StateAction, StateActionWrapper, Transition, XorNode,
     };
     pub use crate::generic_animation::machine::{
+        layer::LayerBlendMode,
         node::AnimationEventCollectionStrategy,
         parameter::{Parameter, ParameterContainer, ParameterDefinition, PoseWeight},
+        transition::{CompareNode, ComparisonOperator, RangeNode, TimeInStateNode},
     };
 }
 
@@ -225,6 +227,8 @@ pub struct AnimationBlendingStateMachine {
     machine: InheritableVariable<Machine>,
     #[component(include)]
     animation_player: InheritableVariable<Handle<Node>>,
+    #[visit(optional)]
+    ik_solvers: InheritableVariable<Vec<IkSolver>>,
 }
 
 impl AnimationBlendingStateMachine {
@@ -254,6 +258,22 @@ impl AnimationBlendingStateMachine {
     pub fn animation_player(&self) -> Handle<Node> {
         *self.animation_player
     }
+
+    /// Sets new set of inverse kinematics solvers, that will be applied to the pose produced by the
+    /// state machine. See [`IkSolver`] docs for more info.
+    pub fn set_ik_solvers(&mut self, ik_solvers: Vec<IkSolver>) {
+        self.ik_solvers.set_value_and_mark_modified(ik_solvers);
+    }
+
+    /// Returns a slice of inverse kinematics solvers used by the node.
+    pub fn ik_solvers(&self) -> &[IkSolver] {
+        &self.ik_solvers
+    }
+
+    /// Returns a mutable reference to the inverse kinematics solvers used by the node.
+    pub fn ik_solvers_mut(&mut self) -> &mut InheritableVariable<Vec<IkSolver>> {
+        &mut self.ik_solvers
+    }
 }
 
 impl TypeUuidProvider for AnimationBlendingStateMachine {
@@ -312,21 +332,40 @@ impl NodeTrait for AnimationBlendingStateMachine {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
-        if let Some(animation_player) = context
+        let Some(animation_player) = context
             .nodes
             .try_borrow_mut(*self.animation_player)
             .and_then(|n| n.component_mut::<AnimationPlayer>())
-        {
-            // Prevent animation player to apply animation to scene nodes. The animation will
-            // do than instead.
-            animation_player.set_auto_apply(false);
-
-            let pose = self.machine.get_value_mut_silent().evaluate_pose(
-                animation_player.animations.get_value_mut_silent(),
-                context.dt,
+        else {
+            // IK solvers correct the pose of the current frame, without it they would accumulate
+            // corrections of previous frames.
+            return;
+        };
+
+        // Prevent animation player to apply animation to scene nodes. The animation will
+        // do than instead.
+        animation_player.set_auto_apply(false);
+
+        let pose = self.machine.get_value_mut_silent().evaluate_pose(
+            animation_player.animations.get_value_mut_silent(),
+            context.dt,
+        );
+
+        pose.apply_internal(context.nodes);
+
+        let owner_transform = self.global_transform();
+        for solver in self.ik_solvers.iter() {
+            // Bones, that aren't animated, keep the corrections of previous frames.
+            if !solver.is_animated_in(context.nodes, self.machine.pose()) {
+                continue;
+            }
+
+            solver.solve(
+                context.nodes,
+                self.machine.parameters(),
+                self.handle(),
+                owner_transform,
             );
-
-            pose.apply_internal(context.nodes);
         }
     }
 
@@ -353,6 +392,7 @@ pub struct AnimationBlendingStateMachineBuilder {
     base_builder: BaseBuilder,
     machine: Machine,
     animation_player: Handle<Node>,
+    ik_solvers: Vec<IkSolver>,
 }
 
 impl AnimationBlendingStateMachineBuilder {
@@ -362,6 +402,7 @@ impl AnimationBlendingStateMachineBuilder {
             base_builder,
             machine: Default::default(),
             animation_player: Default::default(),
+            ik_solvers: Default::default(),
         }
     }
 
@@ -377,12 +418,19 @@ impl AnimationBlendingStateMachineBuilder {
         self
     }
 
+    /// Sets the desired set of inverse kinematics solvers.
+    pub fn with_ik_solvers(mut self, ik_solvers: Vec<IkSolver>) -> Self {
+        self.ik_solvers = ik_solvers;
+        self
+    }
+
     /// Creates new node.
     pub fn build_node(self) -> Node {
         Node::new(AnimationBlendingStateMachine {
             base: self.base_builder.build_base(),
             machine: self.machine.into(),
             animation_player: self.animation_player.into(),
+            ik_solvers: self.ik_solvers.into(),
         })
     }
 
@@ -0,0 +1,771 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Inverse kinematics solvers, that are used to post-process poses produced by animation blending
+//! state machines. See [`IkSolver`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        math::Matrix4Ext,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid_provider,
+        visitor::prelude::*,
+    },
+    generic_animation::{
+        machine::parameter::{ParameterContainer, PoseWeight},
+        value::ValueBinding,
+    },
+    scene::{animation::AnimationPose, graph::NodePool, node::Node},
+};
+use fyrox_graph::SceneGraphNode;
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Two-bone analytic inverse kinematics solver. It is suitable for limbs (arms and legs), where
+/// the `root` is a shoulder (hip), the `middle` is an elbow (knee) and the `end` is a wrist (ankle).
+/// The solver rotates the root and the middle bones so the end bone reaches the target (if it is
+/// reachable), the bending direction is defined by the pole node.
+#[derive(Default, Debug, Clone, PartialEq, Reflect, Visit)]
+pub struct TwoBoneIk {
+    /// A handle of the first bone of the chain (for example - a hip).
+    pub root: Handle<Node>,
+    /// A handle of the second bone of the chain (for example - a knee). It must be a descendant
+    /// of the root bone.
+    pub middle: Handle<Node>,
+    /// A handle of the last bone of the chain (for example - an ankle). It must be a descendant of
+    /// the middle bone.
+    pub end: Handle<Node>,
+    /// A handle of a node, which position will be used as a target for the end bone.
+    pub target: Handle<Node>,
+    /// A handle of a node, that defines the bending direction of the chain (for example - a point
+    /// in front of a knee). Could be unassigned, in this case the current bending direction of the
+    /// animated pose will be used.
+    pub pole: Handle<Node>,
+    /// If set, the end bone will be rotated to match the rotation of the target node. It could be
+    /// useful to align a foot with ground surface or a hand with a grip.
+    pub align_end_rotation: bool,
+}
+
+/// Forward And Backward Reaching Inverse Kinematics (FABRIK) solver. It is an iterative solver,
+/// that works with chains of arbitrary length (for example - tails, tentacles, spines).
+#[derive(Debug, Clone, PartialEq, Reflect, Visit)]
+pub struct FabrikIk {
+    /// A handle of the first bone of the chain.
+    pub root: Handle<Node>,
+    /// A handle of the last bone of the chain. It must be a descendant of the root bone, every
+    /// node between the root and the end bones will be a part of the chain.
+    pub end: Handle<Node>,
+    /// A handle of a node, which position will be used as a target for the end bone.
+    pub target: Handle<Node>,
+    /// Maximum amount of iterations of the solver.
+    #[reflect(min_value = 1.0, max_value = 64.0, step = 1.0)]
+    pub iterations: u32,
+    /// Maximum distance between the end bone and the target, at which the solver stops.
+    #[reflect(min_value = 0.0, step = 0.001)]
+    pub tolerance: f32,
+}
+
+impl Default for FabrikIk {
+    fn default() -> Self {
+        Self {
+            root: Default::default(),
+            end: Default::default(),
+            target: Default::default(),
+            iterations: 10,
+            tolerance: 0.001,
+        }
+    }
+}
+
+/// Look-at (aim) constraint. It rotates the node so its local axis points towards the target. It
+/// could be used to make a character look at something or aim a weapon.
+#[derive(Debug, Clone, PartialEq, Reflect, Visit)]
+pub struct LookAtIk {
+    /// A handle of a node, that will be rotated (for example - a head).
+    pub node: Handle<Node>,
+    /// A handle of a node, which position will be used as a target.
+    pub target: Handle<Node>,
+    /// An axis in local coordinates of the node, that should point towards the target.
+    pub axis: Vector3<f32>,
+    /// Maximum angle (in radians) by which the node can be rotated away from its animated pose.
+    #[reflect(min_value = 0.0, max_value = 3.141_592_7, step = 0.01)]
+    pub max_angle: f32,
+}
+
+impl Default for LookAtIk {
+    fn default() -> Self {
+        Self {
+            node: Default::default(),
+            target: Default::default(),
+            axis: Vector3::z(),
+            max_angle: std::f32::consts::PI,
+        }
+    }
+}
+
+/// Kind of an inverse kinematics solver.
+#[derive(Debug, Clone, PartialEq, Reflect, Visit, AsRefStr, EnumString, VariantNames)]
+pub enum IkSolverKind {
+    /// See [`TwoBoneIk`] docs.
+    TwoBone(TwoBoneIk),
+    /// See [`FabrikIk`] docs.
+    Fabrik(FabrikIk),
+    /// See [`LookAtIk`] docs.
+    LookAt(LookAtIk),
+}
+
+uuid_provider!(IkSolverKind = "b6f4f1f6-3a4c-4b3f-9f2e-0f6a4d7e2c51");
+
+impl Default for IkSolverKind {
+    fn default() -> Self {
+        Self::TwoBone(Default::default())
+    }
+}
+
+impl IkSolverKind {
+    /// Returns a list of bones, which local rotations are modified by the solver. Returns `None` if
+    /// the solver is not properly configured (for example, if the end of a chain is not a
+    /// descendant of its root).
+    pub fn bones(&self, nodes: &NodePool) -> Option<Vec<Handle<Node>>> {
+        match self {
+            IkSolverKind::TwoBone(ik) => {
+                let mut bones = vec![ik.root, ik.middle];
+                if ik.align_end_rotation {
+                    bones.push(ik.end);
+                }
+                Some(bones)
+            }
+            IkSolverKind::Fabrik(ik) => {
+                let mut chain = ik.chain(nodes)?;
+                // The end of the chain is never rotated.
+                chain.pop();
+                Some(chain)
+            }
+            IkSolverKind::LookAt(ik) => Some(vec![ik.node]),
+        }
+    }
+}
+
+/// Inverse kinematics solver, that modifies a pose produced by an animation blending state machine.
+/// Solvers are applied after the pose is applied to scene nodes, in the same order as they're
+/// defined in the state machine node. Every solver has its own weight, which defines how much the
+/// result of the solver is blended with the animated pose. The weight could be either a constant
+/// or a name of a weight parameter of the state machine, which allows you to fade solvers in and
+/// out (for example - disable foot placement when a character is jumping).
+///
+/// # Important notes
+///
+/// Solvers modify local rotations of the bones, so the rotations of the bones must be animated by
+/// the state machine. Otherwise the results of a solver would accumulate over time, that's why a
+/// solver is skipped if at least one of its bones has no rotation in the pose produced by the state
+/// machine.
+#[derive(Debug, Clone, PartialEq, Reflect, Visit)]
+pub struct IkSolver {
+    /// Enables or disables the solver.
+    pub enabled: bool,
+    /// A weight of the solver. Zero means that the animated pose won't be changed, one means that
+    /// the animated pose will be fully replaced with the result of the solver.
+    pub weight: PoseWeight,
+    /// Actual solver.
+    pub kind: IkSolverKind,
+}
+
+uuid_provider!(IkSolver = "5d0c9a7e-1f3b-4e8a-a2c6-7b9d4e3f8a10");
+
+impl Default for IkSolver {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            weight: PoseWeight::Constant(1.0),
+            kind: Default::default(),
+        }
+    }
+}
+
+impl IkSolver {
+    /// Creates new enabled solver with the given kind and full weight.
+    pub fn new(kind: IkSolverKind) -> Self {
+        Self {
+            kind,
+            ..Default::default()
+        }
+    }
+
+    /// Sets a new weight of the solver.
+    pub fn with_weight(mut self, weight: PoseWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Checks whether the rotations of all the bones of the solver are animated in the given pose.
+    /// Solvers correct the pose of the current frame, so they must be applied only to animated
+    /// bones.
+    pub fn is_animated_in(&self, nodes: &NodePool, pose: &AnimationPose) -> bool {
+        self.kind.bones(nodes).is_some_and(|bones| {
+            bones.iter().all(|bone| {
+                pose.poses().get(bone).is_some_and(|node_pose| {
+                    node_pose
+                        .values
+                        .values
+                        .iter()
+                        .any(|value| value.binding == ValueBinding::Rotation)
+                })
+            })
+        })
+    }
+
+    /// Applies the solver to the nodes of the given pool. `owner` is a handle of a node, that is
+    /// currently taken out of the pool (usually it is the state machine node) and `owner_transform`
+    /// is its global transform.
+    pub fn solve(
+        &self,
+        nodes: &mut NodePool,
+        parameters: &ParameterContainer,
+        owner: Handle<Node>,
+        owner_transform: Matrix4<f32>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        let Some(weight) = self.weight.value(parameters) else {
+            return;
+        };
+        let weight = weight.clamp(0.0, 1.0);
+        if weight <= 0.0 {
+            return;
+        }
+
+        let transforms = GlobalTransforms {
+            owner,
+            owner_transform,
+        };
+
+        match self.kind {
+            IkSolverKind::TwoBone(ref ik) => ik.solve(nodes, &transforms, weight),
+            IkSolverKind::Fabrik(ref ik) => ik.solve(nodes, &transforms, weight),
+            IkSolverKind::LookAt(ref ik) => ik.solve(nodes, &transforms, weight),
+        }
+    }
+}
+
+// Global transforms of the nodes are not up-to-date at the time when the solvers are applied (the
+// pose was just applied to local transforms), so they're calculated from the local transforms.
+struct GlobalTransforms {
+    owner: Handle<Node>,
+    owner_transform: Matrix4<f32>,
+}
+
+impl GlobalTransforms {
+    fn get(&self, nodes: &NodePool, handle: Handle<Node>) -> Option<Matrix4<f32>> {
+        if handle == self.owner {
+            return Some(self.owner_transform);
+        }
+
+        let node = nodes.try_borrow(handle)?;
+        let mut transform = node.local_transform().matrix();
+        let mut parent = node.parent();
+        while parent.is_some() {
+            if parent == self.owner {
+                return Some(self.owner_transform * transform);
+            }
+            let parent_node = nodes.try_borrow(parent)?;
+            transform = parent_node.local_transform().matrix() * transform;
+            parent = parent_node.parent();
+        }
+        Some(transform)
+    }
+
+    fn parent(&self, nodes: &NodePool, handle: Handle<Node>) -> Option<Matrix4<f32>> {
+        let parent = nodes.try_borrow(handle)?.parent();
+        if parent.is_some() {
+            self.get(nodes, parent)
+        } else {
+            Some(Matrix4::identity())
+        }
+    }
+}
+
+fn rotation_of(transform: &Matrix4<f32>) -> UnitQuaternion<f32> {
+    let mut basis = transform.basis();
+    for mut column in basis.column_iter_mut() {
+        let norm = column.norm();
+        if norm > f32::EPSILON {
+            column /= norm;
+        }
+    }
+    UnitQuaternion::from_matrix_eps(&basis, f32::EPSILON, 16, UnitQuaternion::identity())
+}
+
+struct Joint {
+    handle: Handle<Node>,
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+    parent_rotation: UnitQuaternion<f32>,
+}
+
+impl Joint {
+    fn new(nodes: &NodePool, transforms: &GlobalTransforms, handle: Handle<Node>) -> Option<Self> {
+        let transform = transforms.get(nodes, handle)?;
+        Some(Self {
+            handle,
+            position: transform.position(),
+            rotation: rotation_of(&transform),
+            parent_rotation: rotation_of(&transforms.parent(nodes, handle)?),
+        })
+    }
+}
+
+// Rotates the local rotation of a node by the given delta, that is defined in the parent space of
+// the node. Pre- and post-rotations of the node are taken into account.
+fn rotate_local(node: &mut Node, delta: UnitQuaternion<f32>, weight: f32) {
+    let transform = node.local_transform_mut();
+    let pre_rotation = **transform.pre_rotation();
+    let post_rotation = **transform.post_rotation();
+    let rotation = **transform.rotation();
+    let local = pre_rotation * rotation * post_rotation;
+    let new_rotation = pre_rotation.inverse() * delta * local * post_rotation.inverse();
+    transform.set_rotation(rotation.slerp(&new_rotation, weight));
+}
+
+// Rotates the joints of a chain so their positions match the desired ones. Returns an accumulated
+// world-space rotation of the last joint of the chain.
+fn apply_chain(
+    nodes: &mut NodePool,
+    joints: &[Joint],
+    desired: &[Vector3<f32>],
+    weight: f32,
+) -> UnitQuaternion<f32> {
+    let mut accumulated = UnitQuaternion::identity();
+    for (i, pair) in joints.windows(2).enumerate() {
+        let (joint, next) = (&pair[0], &pair[1]);
+        let current_dir = accumulated * (next.position - joint.position);
+        let desired_dir = desired[i + 1] - desired[i];
+        let delta = UnitQuaternion::rotation_between(&current_dir, &desired_dir)
+            .unwrap_or_else(UnitQuaternion::identity);
+        if let Some(node) = nodes.try_borrow_mut(joint.handle) {
+            // Convert the world-space delta to the space of the (moved) parent.
+            let parent = joint.parent_rotation;
+            let local_delta =
+                parent.inverse() * accumulated.inverse() * delta * accumulated * parent;
+            rotate_local(node, local_delta, weight);
+        }
+        accumulated = delta * accumulated;
+    }
+    accumulated
+}
+
+impl TwoBoneIk {
+    fn solve(&self, nodes: &mut NodePool, transforms: &GlobalTransforms, weight: f32) {
+        let (Some(root), Some(middle), Some(end), Some(target)) = (
+            Joint::new(nodes, transforms, self.root),
+            Joint::new(nodes, transforms, self.middle),
+            Joint::new(nodes, transforms, self.end),
+            transforms.get(nodes, self.target),
+        ) else {
+            return;
+        };
+
+        let a = root.position;
+        let upper_len = (middle.position - a).norm();
+        let lower_len = (end.position - middle.position).norm();
+        if upper_len <= f32::EPSILON || lower_len <= f32::EPSILON {
+            return;
+        }
+
+        let to_target = target.position() - a;
+        let Some(dir) = to_target.try_normalize(f32::EPSILON) else {
+            return;
+        };
+        let min_len = (upper_len - lower_len).abs() + 1.0e-4;
+        let max_len = upper_len + lower_len - 1.0e-4;
+        let distance = to_target.norm().clamp(min_len, max_len.max(min_len));
+
+        let bend = transforms
+            .get(nodes, self.pole)
+            .map(|pole| pole.position() - a)
+            .unwrap_or(middle.position - a);
+        let bend = (bend - dir.scale(dir.dot(&bend)))
+            .try_normalize(f32::EPSILON)
+            .or_else(|| {
+                let fallback = middle.position - a;
+                (fallback - dir.scale(dir.dot(&fallback))).try_normalize(f32::EPSILON)
+            })
+            .unwrap_or_else(|| {
+                dir.cross(&Vector3::x())
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or(Vector3::y())
+            });
+
+        let cos_root = ((upper_len * upper_len + distance * distance - lower_len * lower_len)
+            / (2.0 * upper_len * distance))
+            .clamp(-1.0, 1.0);
+        let sin_root = (1.0 - cos_root * cos_root).sqrt();
+        let desired = [
+            a,
+            a + dir.scale(upper_len * cos_root) + bend.scale(upper_len * sin_root),
+            a + dir.scale(distance),
+        ];
+
+        let end_parent_rotation = end.parent_rotation;
+        let accumulated = apply_chain(nodes, &[root, middle, end], &desired, weight);
+
+        if self.align_end_rotation {
+            if let Some(node) = nodes.try_borrow_mut(self.end) {
+                // Desired world rotation of the end bone, expressed in the space of its parent.
+                let parent = accumulated * end_parent_rotation;
+                let transform = node.local_transform();
+                let local = **transform.pre_rotation()
+                    * **transform.rotation()
+                    * **transform.post_rotation();
+                let delta = parent.inverse() * rotation_of(&target) * local.inverse();
+                rotate_local(node, delta, weight);
+            }
+        }
+    }
+}
+
+impl FabrikIk {
+    // Collects the chain of bones from the root to the end.
+    fn chain(&self, nodes: &NodePool) -> Option<Vec<Handle<Node>>> {
+        let mut chain = vec![self.end];
+        let mut current = self.end;
+        while current != self.root {
+            current = nodes.try_borrow(current)?.parent();
+            if current.is_none() {
+                return None;
+            }
+            chain.push(current);
+        }
+        chain.reverse();
+        Some(chain)
+    }
+
+    fn solve(&self, nodes: &mut NodePool, transforms: &GlobalTransforms, weight: f32) {
+        let Some(target) = transforms.get(nodes, self.target) else {
+            return;
+        };
+        let target = target.position();
+
+        let Some(chain) = self.chain(nodes) else {
+            return;
+        };
+
+        let Some(joints) = chain
+            .iter()
+            .map(|handle| Joint::new(nodes, transforms, *handle))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        if joints.len() < 2 {
+            return;
+        }
+
+        let lengths = joints
+            .windows(2)
+            .map(|pair| (pair[1].position - pair[0].position).norm())
+            .collect::<Vec<_>>();
+        let mut positions = joints.iter().map(|j| j.position).collect::<Vec<_>>();
+        let origin = positions[0];
+        let last = positions.len() - 1;
+
+        if (target - origin).norm() >= lengths.iter().sum::<f32>() {
+            // The target is unreachable, stretch the chain towards it.
+            for i in 0..last {
+                let dir = (target - positions[i])
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                positions[i + 1] = positions[i] + dir.scale(lengths[i]);
+            }
+        } else {
+            for _ in 0..self.iterations.max(1) {
+                // Backward pass.
+                positions[last] = target;
+                for i in (0..last).rev() {
+                    let dir = (positions[i] - positions[i + 1])
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or_default();
+                    positions[i] = positions[i + 1] + dir.scale(lengths[i]);
+                }
+
+                // Forward pass.
+                positions[0] = origin;
+                for i in 0..last {
+                    let dir = (positions[i + 1] - positions[i])
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or_default();
+                    positions[i + 1] = positions[i] + dir.scale(lengths[i]);
+                }
+
+                if (positions[last] - target).norm() <= self.tolerance {
+                    break;
+                }
+            }
+        }
+
+        apply_chain(nodes, &joints, &positions, weight);
+    }
+}
+
+impl LookAtIk {
+    fn solve(&self, nodes: &mut NodePool, transforms: &GlobalTransforms, weight: f32) {
+        let (Some(joint), Some(target)) = (
+            Joint::new(nodes, transforms, self.node),
+            transforms.get(nodes, self.target),
+        ) else {
+            return;
+        };
+
+        let current_dir = joint.rotation * self.axis;
+        let desired_dir = target.position() - joint.position;
+        let Some(mut delta) = UnitQuaternion::rotation_between(&current_dir, &desired_dir) else {
+            return;
+        };
+        if let Some((axis, angle)) = delta.axis_angle() {
+            if angle > self.max_angle {
+                delta = UnitQuaternion::from_axis_angle(&axis, self.max_angle);
+            }
+        }
+
+        if let Some(node) = nodes.try_borrow_mut(self.node) {
+            let parent = joint.parent_rotation;
+            rotate_local(node, parent.inverse() * delta * parent, weight);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+        },
+        generic_animation::track::TrackBinding,
+        scene::{
+            animation::{
+                absm::{AnimationBlendingStateMachineBuilder, Machine, PoseNode, State},
+                ik::{FabrikIk, IkSolver, IkSolverKind, LookAtIk, TwoBoneIk},
+                Animation, AnimationContainer, AnimationPlayerBuilder, Track,
+            },
+            base::BaseBuilder,
+            graph::{Graph, GraphUpdateSwitches},
+            node::Node,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    fn pivot(graph: &mut Graph, position: Vector3<f32>, children: &[Handle<Node>]) -> Handle<Node> {
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(children)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+        )
+        .build(graph)
+    }
+
+    // Creates a vertical chain of nodes with the given amount of unit-length bones.
+    fn make_chain(graph: &mut Graph, bones: usize) -> Vec<Handle<Node>> {
+        let mut chain = vec![pivot(graph, Vector3::zeros(), &[])];
+        for _ in 0..bones {
+            let bone = pivot(graph, Vector3::new(0.0, -1.0, 0.0), &[]);
+            graph.link_nodes(bone, *chain.last().unwrap());
+            chain.push(bone);
+        }
+        chain
+    }
+
+    fn update(graph: &mut Graph) {
+        graph.update(
+            Vector2::new(1.0, 1.0),
+            1.0 / 60.0,
+            GraphUpdateSwitches::default(),
+        );
+        graph.update_hierarchical_data();
+    }
+
+    // Creates a state machine with the given solver, that plays an animation, which animates
+    // rotations of the given nodes.
+    fn add_solver(graph: &mut Graph, animated: &[Handle<Node>], solver: IkSolverKind) {
+        let mut animation = Animation::default();
+        for node in animated {
+            animation.add_track_with_binding(TrackBinding::new(*node), Track::new_rotation());
+        }
+        let mut animations = AnimationContainer::new();
+        let animation = animations.add(animation);
+        let animation_player = AnimationPlayerBuilder::new(BaseBuilder::new())
+            .with_animations(animations)
+            .build(graph);
+
+        let mut machine = Machine::new();
+        let layer = machine.layers_mut().first_mut().unwrap();
+        let pose = layer.add_node(PoseNode::make_play_animation(animation));
+        layer.add_state(State::new("Animated", pose));
+
+        AnimationBlendingStateMachineBuilder::new(BaseBuilder::new())
+            .with_machine(machine)
+            .with_animation_player(animation_player)
+            .with_ik_solvers(vec![IkSolver::new(solver)])
+            .build(graph);
+    }
+
+    fn solve(graph: &mut Graph, animated: &[Handle<Node>], solver: IkSolverKind) {
+        add_solver(graph, animated, solver);
+        update(graph);
+    }
+
+    #[test]
+    fn test_two_bone_ik() {
+        let mut graph = Graph::new();
+        let chain = make_chain(&mut graph, 2);
+        let target_position = Vector3::new(1.0, -1.0, 0.0);
+        let target = pivot(&mut graph, target_position, &[]);
+        let pole = pivot(&mut graph, Vector3::new(0.0, -1.0, 1.0), &[]);
+
+        solve(
+            &mut graph,
+            &chain,
+            IkSolverKind::TwoBone(TwoBoneIk {
+                root: chain[0],
+                middle: chain[1],
+                end: chain[2],
+                target,
+                pole,
+                align_end_rotation: false,
+            }),
+        );
+
+        let root = graph[chain[0]].global_position();
+        let middle = graph[chain[1]].global_position();
+        let end = graph[chain[2]].global_position();
+        assert!((end - target_position).norm() < 1.0e-3);
+        assert!(((middle - root).norm() - 1.0).abs() < 1.0e-3);
+        assert!(((end - middle).norm() - 1.0).abs() < 1.0e-3);
+        // The chain must bend towards the pole.
+        assert!(middle.z > 0.5);
+    }
+
+    #[test]
+    fn test_fabrik_ik() {
+        let mut graph = Graph::new();
+        let chain = make_chain(&mut graph, 4);
+        let target_position = Vector3::new(2.0, -1.0, 1.0);
+        let target = pivot(&mut graph, target_position, &[]);
+
+        solve(
+            &mut graph,
+            &chain,
+            IkSolverKind::Fabrik(FabrikIk {
+                root: chain[0],
+                end: chain[4],
+                target,
+                iterations: 32,
+                tolerance: 1.0e-4,
+            }),
+        );
+
+        let positions = chain
+            .iter()
+            .map(|handle| graph[*handle].global_position())
+            .collect::<Vec<_>>();
+        assert!((positions[4] - target_position).norm() < 1.0e-2);
+        for pair in positions.windows(2) {
+            assert!(((pair[1] - pair[0]).norm() - 1.0).abs() < 1.0e-3);
+        }
+    }
+
+    #[test]
+    fn test_look_at_ik_max_angle() {
+        let look_at = |graph: &mut Graph, max_angle: f32| {
+            let node = pivot(graph, Vector3::zeros(), &[]);
+            let target = pivot(graph, Vector3::new(1.0, 0.0, 0.0), &[]);
+            (
+                node,
+                IkSolverKind::LookAt(LookAtIk {
+                    node,
+                    target,
+                    axis: Vector3::z(),
+                    max_angle,
+                }),
+            )
+        };
+
+        let mut graph = Graph::new();
+        let (node, solver) = look_at(&mut graph, std::f32::consts::PI);
+        solve(&mut graph, &[node], solver);
+        let look = graph[node].look_vector().normalize();
+        assert!((look - Vector3::x()).norm() < 1.0e-3, "{look:?}");
+
+        // The rotation is clamped by the max angle, and it must stay clamped over multiple frames,
+        // since the animation resets the rotation every frame.
+        let mut graph = Graph::new();
+        let (node, solver) = look_at(&mut graph, std::f32::consts::FRAC_PI_4);
+        add_solver(&mut graph, &[node], solver);
+        let expected = Vector3::new(1.0, 0.0, 1.0).normalize();
+        for _ in 0..10 {
+            update(&mut graph);
+            let look = graph[node].look_vector().normalize();
+            assert!((look - expected).norm() < 1.0e-3, "{look:?}");
+        }
+
+        // Solvers are not applied if there's no animated pose.
+        let mut graph = Graph::new();
+        let (node, solver) = look_at(&mut graph, std::f32::consts::PI);
+        AnimationBlendingStateMachineBuilder::new(BaseBuilder::new())
+            .with_ik_solvers(vec![IkSolver::new(solver)])
+            .build(&mut graph);
+        update(&mut graph);
+        let look = graph[node].look_vector().normalize();
+        assert!((look - Vector3::z()).norm() < 1.0e-3, "{look:?}");
+    }
+
+    #[test]
+    fn test_ik_solver_skips_bones_without_animation() {
+        let mut graph = Graph::new();
+        let node = pivot(&mut graph, Vector3::zeros(), &[]);
+        let other = pivot(&mut graph, Vector3::zeros(), &[]);
+        let target = pivot(&mut graph, Vector3::new(1.0, 0.0, 0.0), &[]);
+
+        // The animation animates some other node, so the corrections of the solver would
+        // accumulate over multiple frames and exceed the max angle.
+        add_solver(
+            &mut graph,
+            &[other],
+            IkSolverKind::LookAt(LookAtIk {
+                node,
+                target,
+                axis: Vector3::z(),
+                max_angle: std::f32::consts::FRAC_PI_4,
+            }),
+        );
+        for _ in 0..10 {
+            update(&mut graph);
+            let look = graph[node].look_vector().normalize();
+            assert!((look - Vector3::z()).norm() < 1.0e-3, "{look:?}");
+        }
+    }
+}
@@ -45,6 +45,7 @@ use fyrox_graph::BaseSceneGraph;
 use std::ops::{Deref, DerefMut};
 
 pub mod absm;
+pub mod ik;
 pub mod spritesheet;
 
 /// Scene specific animation.
@@ -60,6 +61,7 @@ pub type NodePose = crate::generic_animation::NodePose<Handle<Node>>;
 
 /// Standard prelude for animations, that contains all most commonly used types and traits.
 pub mod prelude {
+    pub use super::ik::{FabrikIk, IkSolver, IkSolverKind, LookAtIk, TwoBoneIk};
     pub use super::{
         Animation, AnimationContainer, AnimationContainerExt, AnimationPlayer,
         AnimationPlayerBuilder, AnimationPose, AnimationPoseExt, BoundValueCollectionExt, NodePose,
@@ -581,6 +581,16 @@ pub struct Collider {
     #[reflect(setter = "set_restitution_combine_rule")]
     pub(crate) restitution_combine_rule: InheritableVariable<CoefficientCombineRule>,
 
+    #[visit(optional)]
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        setter = "set_occlusion_factor",
+        description = "Defines how much the collider occludes sounds behind it. 0.0 - the collider \
+        is fully transparent for sounds, 1.0 - the collider fully occludes sounds."
+    )]
+    pub(crate) occlusion_factor: InheritableVariable<f32>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ColliderHandle>,
@@ -599,6 +609,7 @@ impl Default for Collider {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            occlusion_factor: InheritableVariable::new_modified(1.0),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -631,6 +642,7 @@ impl Clone for Collider {
             solver_groups: self.solver_groups.clone(),
             friction_combine_rule: self.friction_combine_rule.clone(),
             restitution_combine_rule: self.restitution_combine_rule.clone(),
+            occlusion_factor: self.occlusion_factor.clone(),
             // Do not copy. The copy will have its own native representation (for example - Rapier's collider)
             native: Cell::new(ColliderHandle::invalid()),
         }
@@ -823,6 +835,20 @@ impl Collider {
         *self.restitution_combine_rule
     }
 
+    /// Sets how much the collider occludes sounds behind it. It is used by [`crate::scene::sound::Sound`]
+    /// nodes with enabled occlusion: occlusion factors of all colliders between a sound and the listener
+    /// are summed. 0.0 - the collider is transparent for sounds, 1.0 - the collider fully occludes
+    /// sounds. Sensors never occlude sounds. Default is 1.0.
+    pub fn set_occlusion_factor(&mut self, occlusion_factor: f32) -> f32 {
+        self.occlusion_factor
+            .set_value_and_mark_modified(occlusion_factor.max(0.0))
+    }
+
+    /// Returns sound occlusion factor of the collider.
+    pub fn occlusion_factor(&self) -> f32 {
+        *self.occlusion_factor
+    }
+
     /// Returns an iterator that yields contact information for the collider.
     /// Contacts checks between two regular colliders
     pub fn contacts<'a>(
@@ -976,6 +1002,7 @@ pub struct ColliderBuilder {
     solver_groups: InteractionGroups,
     friction_combine_rule: CoefficientCombineRule,
     restitution_combine_rule: CoefficientCombineRule,
+    occlusion_factor: f32,
 }
 
 impl ColliderBuilder {
@@ -992,6 +1019,7 @@ impl ColliderBuilder {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            occlusion_factor: 1.0,
         }
     }
 
@@ -1019,6 +1047,12 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets desired sound occlusion factor. See [`Collider::set_occlusion_factor`] for more info.
+    pub fn with_occlusion_factor(mut self, occlusion_factor: f32) -> Self {
+        self.occlusion_factor = occlusion_factor;
+        self
+    }
+
     /// Sets whether this collider will be used a sensor or not.
     pub fn with_sensor(mut self, sensor: bool) -> Self {
         self.is_sensor = sensor;
@@ -1062,6 +1096,7 @@ impl ColliderBuilder {
             solver_groups: self.solver_groups.into(),
             friction_combine_rule: self.friction_combine_rule.into(),
             restitution_combine_rule: self.restitution_combine_rule.into(),
+            occlusion_factor: self.occlusion_factor.into(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
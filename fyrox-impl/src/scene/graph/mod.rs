@@ -1225,6 +1225,8 @@ impl Graph {
         self.performance_statistics.sound_update_time =
             self.sound_context.state().full_render_duration();
 
+        self.sound_context.reset_occlusion_queries();
+
         if let Some(overrides) = switches.node_overrides.as_ref() {
             for handle in overrides {
                 self.update_node(*handle, frame_size, dt, switches.delete_dead_nodes);
@@ -1239,6 +1241,9 @@ impl Graph {
                 );
             }
         }
+
+        self.sound_context.apply_reverb_zones();
+        self.sound_context.prepare_effects();
     }
 
     /// Returns capacity of internal pool. Can be used to iterate over all **potentially**
@@ -34,7 +34,7 @@ use crate::scene::{
     particle_system::ParticleSystem,
     pivot::Pivot,
     ragdoll::Ragdoll,
-    sound::{listener::Listener, Sound},
+    sound::{listener::Listener, reverb_zone::ReverbZone, Sound},
     sprite::Sprite,
     terrain::Terrain,
     tilemap::TileMap,
@@ -62,6 +62,7 @@ pub fn new_node_constructor_container() -> NodeConstructorContainer {
     container.add::<ParticleSystem>();
     container.add::<Sound>();
     container.add::<Listener>();
+    container.add::<ReverbZone>();
     container.add::<Camera>();
     container.add::<scene::collider::Collider>();
     container.add::<Decal>();
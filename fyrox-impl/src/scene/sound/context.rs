@@ -22,16 +22,25 @@
 
 use crate::{
     core::{
+        algebra::Vector3,
         log::{Log, MessageKind},
         pool::Handle,
         visitor::prelude::*,
     },
-    scene::{node::Node, sound::Sound},
+    scene::{
+        node::Node,
+        sound::{
+            reverb_zone::{ReverbZoneMixer, ReverbZoneParameters},
+            Sound,
+        },
+    },
 };
 use fxhash::FxHashSet;
 use fyrox_sound::{
     bus::AudioBusGraph,
-    context::DistanceModel,
+    context::{DistanceModel, VoiceStatistics},
+    layout::ChannelLayout,
+    offline::OfflineRenderer,
     renderer::Renderer,
     source::{SoundSource, SoundSourceBuilder, Status},
 };
@@ -42,6 +51,12 @@ use std::{sync::MutexGuard, time::Duration};
 pub struct SoundContext {
     #[visit(optional)]
     pub(crate) native: fyrox_sound::context::SoundContext,
+    #[visit(optional)]
+    max_occlusion_queries_per_frame: usize,
+    #[visit(skip)]
+    occlusion_queries_left: usize,
+    #[visit(skip)]
+    reverb_zones: ReverbZoneMixer,
 }
 
 /// Proxy for guarded access to the sound context.
@@ -80,6 +95,73 @@ impl SoundContextGuard<'_> {
         self.guard.distance_model()
     }
 
+    /// Sets the channel layout in which the context renders its sounds. See
+    /// [`fyrox_sound::context::State::set_channel_layout`] for more info.
+    pub fn set_channel_layout(&mut self, channel_layout: ChannelLayout) {
+        self.guard.set_channel_layout(channel_layout);
+    }
+
+    /// Returns the channel layout in which the context renders its sounds.
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.guard.channel_layout()
+    }
+
+    /// Sets new Doppler factor. See [`fyrox_sound::context::State::set_doppler_factor`] for more info.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) {
+        self.guard.set_doppler_factor(doppler_factor);
+    }
+
+    /// Returns current Doppler factor.
+    pub fn doppler_factor(&self) -> f32 {
+        self.guard.doppler_factor()
+    }
+
+    /// Sets new speed of sound in world units per second. It is used to calculate Doppler effect.
+    pub fn set_speed_of_sound(&mut self, speed_of_sound: f32) {
+        self.guard.set_speed_of_sound(speed_of_sound);
+    }
+
+    /// Returns current speed of sound.
+    pub fn speed_of_sound(&self) -> f32 {
+        self.guard.speed_of_sound()
+    }
+
+    /// Sets maximum amount of sounds that can be mixed at the same time. See
+    /// [`fyrox_sound::context::State::set_max_voices`] for more info.
+    pub fn set_max_voices(&mut self, max_voices: usize) {
+        self.guard.set_max_voices(max_voices);
+    }
+
+    /// Returns maximum amount of sounds that can be mixed at the same time.
+    pub fn max_voices(&self) -> usize {
+        self.guard.max_voices()
+    }
+
+    /// Sets new virtualization threshold. See
+    /// [`fyrox_sound::context::State::set_virtualization_threshold`] for more info.
+    pub fn set_virtualization_threshold(&mut self, threshold: f32) {
+        self.guard.set_virtualization_threshold(threshold);
+    }
+
+    /// Returns current virtualization threshold.
+    pub fn virtualization_threshold(&self) -> f32 {
+        self.guard.virtualization_threshold()
+    }
+
+    /// Returns voice statistics gathered during the last render of the context.
+    pub fn voice_statistics(&self) -> VoiceStatistics {
+        self.guard.voice_statistics()
+    }
+
+    /// Returns `true` if the native sound source of the given sound was virtualized during the last
+    /// render of the context. See [`fyrox_sound::source::SoundSource::is_virtual`] for more info.
+    pub fn is_sound_virtual(&self, sound: &Sound) -> bool {
+        self.guard
+            .sources()
+            .try_borrow(sound.native.get())
+            .is_some_and(|source| source.is_virtual())
+    }
+
     /// Normalizes given frequency using context's sampling rate. Normalized frequency then can be used
     /// to create filters.
     pub fn normalize_frequency(&self, f: f32) -> f32 {
@@ -91,6 +173,13 @@ impl SoundContextGuard<'_> {
         self.guard.full_render_duration()
     }
 
+    /// Renders the context for the given duration using the given offline renderer. See
+    /// [`OfflineRenderer`] docs for more info. Keep in mind, that the context is also rendered by
+    /// the output device of the engine (if any), so it should be disabled during offline rendering.
+    pub fn render_offline(&mut self, renderer: &mut OfflineRenderer, duration: Duration) {
+        renderer.render_context(&mut self.guard, duration)
+    }
+
     /// Returns current renderer.
     pub fn renderer(&self) -> Renderer {
         self.guard.renderer().clone()
@@ -124,11 +213,19 @@ impl Default for SoundContext {
         // There's no need to serialize native sources, because they'll be re-created automatically.
         state.serialization_options.skip_sources = true;
         drop(state);
-        Self { native }
+        Self {
+            native,
+            max_occlusion_queries_per_frame: Self::DEFAULT_MAX_OCCLUSION_QUERIES_PER_FRAME,
+            occlusion_queries_left: Self::DEFAULT_MAX_OCCLUSION_QUERIES_PER_FRAME,
+            reverb_zones: Default::default(),
+        }
     }
 }
 
 impl SoundContext {
+    /// Default amount of occlusion queries per frame. See [`Self::set_max_occlusion_queries_per_frame`].
+    pub const DEFAULT_MAX_OCCLUSION_QUERIES_PER_FRAME: usize = 32;
+
     pub(crate) fn new() -> Self {
         Default::default()
     }
@@ -137,9 +234,59 @@ impl SoundContext {
     pub fn deep_clone(&self) -> Self {
         Self {
             native: self.native.deep_clone(),
+            max_occlusion_queries_per_frame: self.max_occlusion_queries_per_frame,
+            occlusion_queries_left: self.occlusion_queries_left,
+            reverb_zones: Default::default(),
         }
     }
 
+    /// Sets maximum amount of occlusion queries (ray casts) per frame. Sounds with enabled occlusion
+    /// that did not fit into the limit will update their occlusion on next frames. This limit
+    /// prevents performance spikes when there are lots of sounds with occlusion in a scene.
+    pub fn set_max_occlusion_queries_per_frame(&mut self, max_queries: usize) {
+        self.max_occlusion_queries_per_frame = max_queries;
+    }
+
+    /// Returns maximum amount of occlusion queries per frame.
+    pub fn max_occlusion_queries_per_frame(&self) -> usize {
+        self.max_occlusion_queries_per_frame
+    }
+
+    pub(crate) fn reset_occlusion_queries(&mut self) {
+        self.occlusion_queries_left = self.max_occlusion_queries_per_frame;
+    }
+
+    pub(crate) fn try_take_occlusion_query(&mut self) -> bool {
+        if self.occlusion_queries_left > 0 {
+            self.occlusion_queries_left -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn add_reverb_zone_contribution(
+        &mut self,
+        bus: &str,
+        weight: f32,
+        parameters: ReverbZoneParameters,
+    ) {
+        self.reverb_zones.add(bus, weight, parameters);
+    }
+
+    pub(crate) fn apply_reverb_zones(&mut self) {
+        let mut state = self.native.state();
+        self.reverb_zones.apply(state.bus_graph_mut());
+    }
+
+    pub(crate) fn prepare_effects(&self) {
+        self.native.prepare_effects();
+    }
+
+    pub(crate) fn listener_position(&self) -> Vector3<f32> {
+        self.native.state().listener().position()
+    }
+
     /// Returns locked inner state of the sound context.
     pub fn state(&self) -> SoundContextGuard {
         SoundContextGuard {
@@ -162,6 +309,18 @@ impl SoundContext {
         }
     }
 
+    pub(crate) fn set_sound_velocity(&mut self, sound: &Sound, velocity: Vector3<f32>) {
+        if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
+            source.set_velocity(velocity);
+        }
+    }
+
+    pub(crate) fn set_sound_occlusion(&mut self, sound: &Sound, occlusion: f32) {
+        if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
+            source.set_occlusion(occlusion);
+        }
+    }
+
     pub(crate) fn sync_with_sound(&self, sound: &mut Sound) {
         if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
             // Sync back.
@@ -233,6 +392,9 @@ impl SoundContext {
             sound.audio_bus.try_sync_model(|audio_bus| {
                 source.set_bus(audio_bus);
             });
+            sound.priority.try_sync_model(|v| {
+                source.set_priority(v);
+            });
         } else {
             match SoundSourceBuilder::new()
                 .with_gain(sound.gain())
@@ -247,6 +409,7 @@ impl SoundContext {
                 .with_max_distance(sound.max_distance())
                 .with_bus(sound.audio_bus())
                 .with_rolloff_factor(sound.rolloff_factor())
+                .with_priority(sound.priority())
                 .build()
             {
                 Ok(source) => {
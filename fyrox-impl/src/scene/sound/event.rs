@@ -0,0 +1,128 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Allows to instantiate [`SoundEvent`] resources as scene nodes.
+
+use crate::{
+    core::{algebra::Vector3, pool::Handle},
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        node::Node,
+        sound::{Sound, SoundBuilder, SoundEvent, SoundEventResource, Status},
+        transform::TransformBuilder,
+    },
+};
+use fyrox_graph::SceneGraph;
+
+/// Extension methods for [`SoundEventResource`].
+pub trait SoundEventResourceExt {
+    /// Creates a new one-shot [`Sound`] node at the given position (in global coordinates) using
+    /// the next variation of the sound event. The node is removed automatically when it finishes
+    /// playing. Returns `None` if the resource is not loaded, if it has no buffers or if the
+    /// instance limit of the sound event is reached.
+    fn instantiate(&self, graph: &mut Graph, position: Vector3<f32>) -> Option<Handle<Node>>;
+}
+
+impl SoundEventResourceExt for SoundEventResource {
+    fn instantiate(&self, graph: &mut Graph, position: Vector3<f32>) -> Option<Handle<Node>> {
+        if !self.is_ok() {
+            return None;
+        }
+
+        let mut event = self.data_ref();
+        let event: &mut SoundEvent = &mut event;
+        let variation = event.next_variation(|instance| {
+            graph
+                .try_get_of_type::<Sound>(instance.into())
+                .is_some_and(|sound| sound.status() == Status::Playing)
+        })?;
+
+        let sound = SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_buffer(Some(variation.buffer))
+        .with_gain(variation.gain)
+        .with_pitch(variation.pitch)
+        .with_audio_bus(event.audio_bus().to_owned())
+        .with_spatial_blend_factor(event.spatial_blend())
+        .with_radius(event.radius())
+        .with_rolloff_factor(event.rolloff_factor())
+        .with_max_distance(event.max_distance())
+        .with_priority(event.priority())
+        .with_play_once(true)
+        .with_status(Status::Playing)
+        .build(graph);
+
+        event.add_instance(sound.into());
+
+        Some(sound)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            graph::Graph,
+            sound::{
+                event::SoundEventResourceExt, DataSource, Sound, SoundBufferResource, SoundEvent,
+                SoundEventResource, SAMPLE_RATE,
+            },
+        },
+    };
+    use fyrox_graph::BaseSceneGraph;
+    use fyrox_sound::buffer::SoundBufferResourceExtension;
+
+    #[test]
+    fn test_sound_event_instantiation() {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples: vec![1.0; 1024],
+        })
+        .unwrap();
+        let mut event = SoundEvent::new();
+        event.set_buffers(vec![Some(buffer.clone())]);
+        event.set_max_instances(Some(1));
+        event.set_audio_bus("SFX");
+        let event = SoundEventResource::new_embedded(event);
+
+        let mut graph = Graph::new();
+        let position = Vector3::new(1.0, 2.0, 3.0);
+        let sound = event.instantiate(&mut graph, position).unwrap();
+        let sound_ref = graph[sound].cast::<Sound>().unwrap();
+        assert_eq!(sound_ref.buffer(), Some(buffer));
+        assert!(sound_ref.is_play_once());
+        assert_eq!(sound_ref.audio_bus(), "SFX");
+        assert_eq!(**sound_ref.local_transform().position(), position);
+
+        // The limit is reached.
+        assert!(event.instantiate(&mut graph, position).is_none());
+
+        graph.remove_node(sound);
+        assert!(event.instantiate(&mut graph, position).is_some());
+    }
+}
@@ -26,6 +26,7 @@
 use crate::scene::node::constructor::NodeConstructor;
 use crate::{
     core::{
+        algebra::Vector3,
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
         reflect::prelude::*,
@@ -36,7 +37,7 @@ use crate::{
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
-        node::{Node, NodeTrait, SyncContext},
+        node::{Node, NodeTrait, SyncContext, UpdateContext},
     },
 };
 use fyrox_graph::constructor::ConstructorProvider;
@@ -61,6 +62,11 @@ use std::ops::{Deref, DerefMut};
 #[reflect(derived_type = "Node")]
 pub struct Listener {
     base: Base,
+
+    // Global position from the previous update, it is used to calculate velocity of the listener.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    prev_position: Option<Vector3<f32>>,
 }
 
 impl Deref for Listener {
@@ -123,6 +129,20 @@ impl NodeTrait for Listener {
         native.set_position(self.global_position());
         native.set_orientation_lh(self.look_vector(), self.up_vector());
     }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let position = self.global_position();
+        if let Some(prev_position) = self.prev_position.replace(position) {
+            if context.dt > 0.0 {
+                context
+                    .sound_context
+                    .native
+                    .state()
+                    .listener_mut()
+                    .set_velocity((position - prev_position).scale(1.0 / context.dt));
+            }
+        }
+    }
 }
 
 /// Allows you to create listener in declarative manner.
@@ -140,6 +160,7 @@ impl ListenerBuilder {
     pub fn build_listener(self) -> Listener {
         Listener {
             base: self.base_builder.build_base(),
+            prev_position: None,
         }
     }
 
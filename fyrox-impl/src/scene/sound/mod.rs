@@ -22,7 +22,8 @@
 
 use crate::{
     core::{
-        algebra::Matrix4,
+        algebra::{Matrix4, Point3, Vector3},
+        arrayvec::ArrayVec,
         math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
         pool::Handle,
         reflect::prelude::*,
@@ -34,7 +35,11 @@ use crate::{
     define_with,
     scene::{
         base::{Base, BaseBuilder},
-        graph::Graph,
+        collider::Collider,
+        graph::{
+            physics::{Intersection, PhysicsWorld, RayCastOptions},
+            Graph, NodePool,
+        },
         node::{Node, NodeTrait, SyncContext, UpdateContext},
     },
 };
@@ -52,7 +57,13 @@ pub use fyrox_sound::{
     effects::*,
     engine::SoundEngine,
     error::SoundError,
+    event::{
+        SoundEvent, SoundEventError, SoundEventLoader, SoundEventResource, SoundEventVariation,
+        VariationMode,
+    },
     hrtf::HrirSphere,
+    layout::{ChannelLayout, Speaker},
+    meter::{MeterHandle, MeterSnapshot},
     renderer::{hrtf::*, Renderer},
     source::Status,
 };
@@ -70,7 +81,9 @@ use std::{
 };
 
 pub mod context;
+pub mod event;
 pub mod listener;
+pub mod reverb_zone;
 
 /// Sound source.
 #[derive(Visit, Reflect, Debug, ComponentProvider)]
@@ -127,9 +140,63 @@ pub struct Sound {
     )]
     audio_bus: InheritableVariable<String>,
 
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_occlusion_enabled",
+        description = "Enables physics-based occlusion of the sound. Colliders between the sound \
+        and the listener will muffle the sound."
+    )]
+    occlusion_enabled: InheritableVariable<bool>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_occlusion_update_interval",
+        min_value = 0.0,
+        step = 0.05,
+        description = "Time (in seconds) between two consecutive occlusion queries."
+    )]
+    occlusion_update_interval: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_occlusion_transition_time",
+        min_value = 0.0,
+        step = 0.05,
+        description = "Time (in seconds) that is needed to change occlusion from 0.0 to 1.0 and \
+        vice versa. Smooths sudden changes of occlusion."
+    )]
+    occlusion_transition_time: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_priority",
+        description = "Priority of the sound. When there are more playing sounds than the voice \
+        limit allows, sounds with higher priority are mixed first, the rest are virtualized."
+    )]
+    priority: InheritableVariable<i32>,
+
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) native: Cell<Handle<SoundSource>>,
+
+    // Global position from the previous update, it is used to calculate velocity of the sound.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    prev_position: Option<Vector3<f32>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion: OcclusionState,
+}
+
+#[derive(Default, Debug, Clone)]
+struct OcclusionState {
+    // Occlusion value that is calculated by the last query.
+    target: f32,
+    // Smoothed occlusion value that is passed to the native sound source.
+    current: f32,
+    // Time until the next query.
+    timer: f32,
 }
 
 impl Deref for Sound {
@@ -163,7 +230,17 @@ impl Default for Sound {
             playback_time: Default::default(),
             spatial_blend: InheritableVariable::new_modified(1.0),
             audio_bus: InheritableVariable::new_modified(AudioBusGraph::PRIMARY_BUS.to_string()),
+            occlusion_enabled: InheritableVariable::new_modified(false),
+            occlusion_update_interval: InheritableVariable::new_modified(
+                Self::DEFAULT_OCCLUSION_UPDATE_INTERVAL,
+            ),
+            occlusion_transition_time: InheritableVariable::new_modified(
+                Self::DEFAULT_OCCLUSION_TRANSITION_TIME,
+            ),
+            priority: InheritableVariable::new_modified(0),
             native: Default::default(),
+            prev_position: None,
+            occlusion: Default::default(),
         }
     }
 }
@@ -185,8 +262,14 @@ impl Clone for Sound {
             playback_time: self.playback_time.clone(),
             spatial_blend: self.spatial_blend.clone(),
             audio_bus: self.audio_bus.clone(),
+            occlusion_enabled: self.occlusion_enabled.clone(),
+            occlusion_update_interval: self.occlusion_update_interval.clone(),
+            occlusion_transition_time: self.occlusion_transition_time.clone(),
+            priority: self.priority.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Default::default(),
+            prev_position: None,
+            occlusion: Default::default(),
         }
     }
 }
@@ -198,6 +281,12 @@ impl TypeUuidProvider for Sound {
 }
 
 impl Sound {
+    /// Default time (in seconds) between two consecutive occlusion queries.
+    pub const DEFAULT_OCCLUSION_UPDATE_INTERVAL: f32 = 0.1;
+
+    /// Default time (in seconds) that is needed to change occlusion from 0.0 to 1.0 and vice versa.
+    pub const DEFAULT_OCCLUSION_TRANSITION_TIME: f32 = 0.25;
+
     /// Changes buffer of source. Source will continue playing from beginning, old
     /// position will be discarded.
     pub fn set_buffer(
@@ -387,6 +476,142 @@ impl Sound {
     pub fn audio_bus(&self) -> &str {
         &self.audio_bus
     }
+
+    /// Enables or disables physics-based occlusion of the sound. When enabled, the sound casts rays
+    /// between itself and the listener and sums occlusion factors of all colliders (see
+    /// [`Collider::set_occlusion_factor`]) in between. Occluded sounds are attenuated and low-pass
+    /// filtered. Amount of rays cast per frame is limited, see
+    /// [`SoundContext::set_max_occlusion_queries_per_frame`](context::SoundContext::set_max_occlusion_queries_per_frame).
+    pub fn set_occlusion_enabled(&mut self, enabled: bool) -> bool {
+        self.occlusion_enabled.set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns true if physics-based occlusion is enabled, false - otherwise.
+    pub fn is_occlusion_enabled(&self) -> bool {
+        *self.occlusion_enabled
+    }
+
+    /// Sets time (in seconds) between two consecutive occlusion queries. Larger values improve
+    /// performance, but occlusion will react slower to changes in the scene.
+    pub fn set_occlusion_update_interval(&mut self, interval: f32) -> f32 {
+        self.occlusion_update_interval
+            .set_value_and_mark_modified(interval.max(0.0))
+    }
+
+    /// Returns time between two consecutive occlusion queries.
+    pub fn occlusion_update_interval(&self) -> f32 {
+        *self.occlusion_update_interval
+    }
+
+    /// Sets time (in seconds) that is needed to change occlusion from 0.0 to 1.0 and vice versa.
+    /// It smooths sudden changes of occlusion, for example when a sound goes behind a wall.
+    pub fn set_occlusion_transition_time(&mut self, time: f32) -> f32 {
+        self.occlusion_transition_time
+            .set_value_and_mark_modified(time.max(0.0))
+    }
+
+    /// Returns occlusion transition time.
+    pub fn occlusion_transition_time(&self) -> f32 {
+        *self.occlusion_transition_time
+    }
+
+    /// Sets new priority of the sound. When there are more playing sounds than the voice limit of
+    /// the sound context (or of the audio bus) allows, sounds with higher priority are mixed first,
+    /// the rest are virtualized. See [`fyrox_sound::source::SoundSource::set_priority`] for more info.
+    pub fn set_priority(&mut self, priority: i32) -> i32 {
+        self.priority.set_value_and_mark_modified(priority)
+    }
+
+    /// Returns current priority of the sound.
+    pub fn priority(&self) -> i32 {
+        *self.priority
+    }
+
+    /// Returns current (smoothed) occlusion of the sound in `[0; 1]` range.
+    pub fn occlusion(&self) -> f32 {
+        self.occlusion.current
+    }
+
+    fn update_occlusion(&mut self, context: &mut UpdateContext) {
+        if *self.occlusion_enabled && *self.spatial_blend > 0.0 && *self.status == Status::Playing {
+            self.occlusion.timer -= context.dt;
+            if self.occlusion.timer <= 0.0 && context.sound_context.try_take_occlusion_query() {
+                self.occlusion.timer = *self.occlusion_update_interval;
+                self.occlusion.target = calculate_occlusion(
+                    context.sound_context.listener_position(),
+                    self.global_position(),
+                    context.physics,
+                    context.nodes,
+                );
+            }
+        } else {
+            self.occlusion.target = 0.0;
+            self.occlusion.timer = 0.0;
+        }
+
+        let prev_occlusion = self.occlusion.current;
+        let max_delta = if *self.occlusion_transition_time > 0.0 {
+            context.dt / *self.occlusion_transition_time
+        } else {
+            1.0
+        };
+        self.occlusion.current +=
+            (self.occlusion.target - self.occlusion.current).clamp(-max_delta, max_delta);
+
+        if self.occlusion.current != prev_occlusion {
+            context
+                .sound_context
+                .set_sound_occlusion(self, self.occlusion.current);
+        }
+    }
+}
+
+// Casts rays from the listener to the sound and vice versa and sums occlusion factors of colliders
+// that were hit by both rays. Two rays are needed to ignore colliders that contain the listener or
+// the sound (for example, a capsule of a character that has the listener attached to its camera).
+fn calculate_occlusion(
+    listener_position: Vector3<f32>,
+    sound_position: Vector3<f32>,
+    physics: &PhysicsWorld,
+    nodes: &NodePool,
+) -> f32 {
+    let direction = sound_position - listener_position;
+    let distance = direction.norm();
+    if distance <= f32::EPSILON {
+        return 0.0;
+    }
+
+    let cast = |origin: Vector3<f32>, direction: Vector3<f32>| {
+        let mut intersections = ArrayVec::<Intersection, 32>::new();
+        physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(origin),
+                ray_direction: direction,
+                max_len: distance,
+                groups: Default::default(),
+                sort_results: false,
+            },
+            &mut intersections,
+        );
+        intersections
+            .into_iter()
+            // Skip colliders that contain the ray origin.
+            .filter(|intersection| intersection.toi > 0.0)
+            .map(|intersection| intersection.collider)
+            .collect::<ArrayVec<_, 32>>()
+    };
+
+    let forward = cast(listener_position, direction);
+    let backward = cast(sound_position, -direction);
+
+    forward
+        .iter()
+        .filter(|collider| backward.contains(collider))
+        .filter_map(|collider| nodes.try_borrow(*collider)?.cast::<Collider>())
+        .filter(|collider| !collider.is_sensor())
+        .map(|collider| collider.occlusion_factor())
+        .sum::<f32>()
+        .min(1.0)
 }
 
 impl ConstructorProvider<Node, Graph> for Sound {
@@ -449,6 +674,17 @@ impl NodeTrait for Sound {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
+        self.update_occlusion(context);
+
+        let position = self.global_position();
+        if let Some(prev_position) = self.prev_position.replace(position) {
+            if context.dt > 0.0 {
+                context
+                    .sound_context
+                    .set_sound_velocity(self, (position - prev_position).scale(1.0 / context.dt));
+            }
+        }
+
         context.sound_context.sync_with_sound(self);
     }
 
@@ -490,6 +726,10 @@ pub struct SoundBuilder {
     playback_time: Duration,
     spatial_blend: f32,
     audio_bus: String,
+    occlusion_enabled: bool,
+    occlusion_update_interval: f32,
+    occlusion_transition_time: f32,
+    priority: i32,
 }
 
 impl SoundBuilder {
@@ -510,6 +750,10 @@ impl SoundBuilder {
             spatial_blend: 1.0,
             playback_time: Default::default(),
             audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            occlusion_enabled: false,
+            occlusion_update_interval: Sound::DEFAULT_OCCLUSION_UPDATE_INTERVAL,
+            occlusion_transition_time: Sound::DEFAULT_OCCLUSION_TRANSITION_TIME,
+            priority: 0,
         }
     }
 
@@ -578,6 +822,26 @@ impl SoundBuilder {
         fn with_audio_bus(audio_bus: String)
     );
 
+    define_with!(
+        /// Enables or disables occlusion. See [`Sound::set_occlusion_enabled`] for more info.
+        fn with_occlusion_enabled(occlusion_enabled: bool)
+    );
+
+    define_with!(
+        /// Sets desired occlusion update interval. See [`Sound::set_occlusion_update_interval`] for more info.
+        fn with_occlusion_update_interval(occlusion_update_interval: f32)
+    );
+
+    define_with!(
+        /// Sets desired occlusion transition time. See [`Sound::set_occlusion_transition_time`] for more info.
+        fn with_occlusion_transition_time(occlusion_transition_time: f32)
+    );
+
+    define_with!(
+        /// Sets desired priority. See [`Sound::set_priority`] for more info.
+        fn with_priority(priority: i32)
+    );
+
     /// Creates a new [`Sound`] node.
     #[must_use]
     pub fn build_sound(self) -> Sound {
@@ -596,7 +860,13 @@ impl SoundBuilder {
             playback_time: self.playback_time.as_secs_f32().into(),
             spatial_blend: self.spatial_blend.into(),
             audio_bus: self.audio_bus.into(),
+            occlusion_enabled: self.occlusion_enabled.into(),
+            occlusion_update_interval: self.occlusion_update_interval.into(),
+            occlusion_transition_time: self.occlusion_transition_time.into(),
+            priority: self.priority.into(),
             native: Default::default(),
+            prev_position: None,
+            occlusion: Default::default(),
         }
     }
 
@@ -611,3 +881,76 @@ impl SoundBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::core::{
+        algebra::{Vector2, Vector3},
+        pool::Handle,
+    };
+    use crate::scene::{
+        base::BaseBuilder,
+        collider::{ColliderBuilder, ColliderShape},
+        graph::Graph,
+        node::Node,
+        rigidbody::{RigidBodyBuilder, RigidBodyType},
+        sound::{listener::ListenerBuilder, Sound, SoundBuilder, Status},
+        transform::TransformBuilder,
+    };
+
+    #[test]
+    fn test_sound_occlusion() {
+        let mut graph = Graph::new();
+
+        ListenerBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        // A wall between the listener and the first sound.
+        let wall = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(2.0, 2.0, 0.25))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.0, 5.0))
+                        .build(),
+                )
+                .with_children(&[wall]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let mut make_sound = |position| {
+            SoundBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+            )
+            .with_status(Status::Playing)
+            .with_looping(true)
+            .with_occlusion_enabled(true)
+            .build(&mut graph)
+        };
+
+        let occluded = make_sound(Vector3::new(0.0, 0.0, 10.0));
+        let clear = make_sound(Vector3::new(10.0, 0.0, 0.0));
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        }
+
+        let occlusion = |graph: &Graph, handle: Handle<Node>| {
+            graph[handle].cast::<Sound>().unwrap().occlusion()
+        };
+        assert_eq!(occlusion(&graph, occluded), 1.0);
+        assert_eq!(occlusion(&graph, clear), 0.0);
+
+        graph[wall].as_collider_mut().set_occlusion_factor(0.5);
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        }
+        assert!((occlusion(&graph, occluded) - 0.5).abs() < 1.0e-5);
+    }
+}
@@ -0,0 +1,870 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reverb zone is a volume that defines reverberation and equalization parameters for a region
+//! of a scene.
+//!
+//! See [`ReverbZone`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait, UpdateContext},
+        sound::{
+            filter::{HighPassFilterEffect, LowPassFilterEffect},
+            reverb::Reverb,
+            AudioBusGraph, Effect, SAMPLE_RATE,
+        },
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use fyrox_graph::{constructor::ConstructorProvider, BaseSceneGraph};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Shape of a reverb zone. The shape is defined in local coordinates of the zone, so it is affected
+/// by the global transform of the zone.
+#[derive(Visit, Copy, Clone, PartialEq, Debug, Reflect, AsRefStr, EnumString, VariantNames)]
+pub enum ReverbZoneShape {
+    /// Box with given half extents.
+    Box {
+        /// Half extents of the box along each axis.
+        half_extents: Vector3<f32>,
+    },
+    /// Sphere with given radius.
+    Sphere {
+        /// Radius of the sphere.
+        #[reflect(min_value = 0.0, step = 0.1)]
+        radius: f32,
+    },
+}
+
+uuid_provider!(ReverbZoneShape = "6f0e7a2b-3c8d-4e51-9a4f-1b2c3d4e5f60");
+
+impl Default for ReverbZoneShape {
+    fn default() -> Self {
+        Self::Box {
+            half_extents: Vector3::new(5.0, 5.0, 5.0),
+        }
+    }
+}
+
+impl ReverbZoneShape {
+    /// Returns true if the given point (in local coordinates of the zone) is inside the shape.
+    pub fn contains(&self, point: Vector3<f32>) -> bool {
+        match *self {
+            Self::Box { half_extents } => {
+                point.x.abs() <= half_extents.x
+                    && point.y.abs() <= half_extents.y
+                    && point.z.abs() <= half_extents.z
+            }
+            Self::Sphere { radius } => point.norm() <= radius,
+        }
+    }
+
+    /// Returns the closest point of the shape to the given point. Both points are in local
+    /// coordinates of the zone.
+    pub fn closest_point(&self, point: Vector3<f32>) -> Vector3<f32> {
+        match *self {
+            Self::Box { half_extents } => Vector3::new(
+                point.x.clamp(-half_extents.x, half_extents.x),
+                point.y.clamp(-half_extents.y, half_extents.y),
+                point.z.clamp(-half_extents.z, half_extents.z),
+            ),
+            Self::Sphere { radius } => {
+                if point.norm() <= radius {
+                    point
+                } else {
+                    point.try_normalize(f32::EPSILON).unwrap_or_default() * radius
+                }
+            }
+        }
+    }
+
+    /// Returns local bounding box of the shape.
+    pub fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        match *self {
+            Self::Box { half_extents } => {
+                AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+            }
+            Self::Sphere { radius } => AxisAlignedBoundingBox::from_radius(radius),
+        }
+    }
+}
+
+/// A set of parameters that is applied to an audio bus by reverb zones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReverbZoneParameters {
+    /// Amount of the unprocessed signal. See [`Reverb::set_dry`].
+    pub dry: f32,
+    /// Gain of the reverberated signal. See [`Reverb::set_gain`].
+    pub reverb_gain: f32,
+    /// Duration of reverberation in seconds. See [`Reverb::set_decay_time`].
+    pub decay_time: f32,
+    /// Cutoff frequency (in Hz) of the low-pass filters of the reverberator. See [`Reverb::set_fc`].
+    pub damping_hz: f32,
+    /// Cutoff frequency (in Hz) of the low-pass filter of the bus.
+    pub low_pass_cutoff_hz: f32,
+    /// Cutoff frequency (in Hz) of the high-pass filter of the bus.
+    pub high_pass_cutoff_hz: f32,
+}
+
+impl Default for ReverbZoneParameters {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+// Interpolates frequencies in logarithmic scale, which matches human perception.
+fn lerp_frequency(a: f32, b: f32, t: f32) -> f32 {
+    (a.max(f32::EPSILON).ln() * (1.0 - t) + b.max(f32::EPSILON).ln() * t).exp()
+}
+
+impl ReverbZoneParameters {
+    /// Parameters that do not change the sound. Zones fade to these parameters with the distance
+    /// to the listener.
+    pub const NEUTRAL: Self = Self {
+        dry: 1.0,
+        reverb_gain: 0.0,
+        decay_time: 2.0,
+        damping_hz: 11296.0,
+        low_pass_cutoff_hz: 20000.0,
+        high_pass_cutoff_hz: 20.0,
+    };
+
+    /// Blends parameters of the zones using their weights (in `[0; 1]` range). If the total weight
+    /// is less than 1.0, the result is blended with [`Self::NEUTRAL`] parameters, otherwise the
+    /// result is a weighted average of the parameters.
+    pub fn blend(zones: &[(f32, ReverbZoneParameters)]) -> Self {
+        let total_weight = zones.iter().map(|(weight, _)| *weight).sum::<f32>();
+        if total_weight <= 0.0 {
+            return Self::NEUTRAL;
+        }
+
+        let mut mix = Self {
+            dry: 0.0,
+            reverb_gain: 0.0,
+            decay_time: 0.0,
+            damping_hz: 0.0,
+            low_pass_cutoff_hz: 0.0,
+            high_pass_cutoff_hz: 0.0,
+        };
+        for (weight, parameters) in zones {
+            let k = *weight / total_weight;
+            mix.dry += parameters.dry * k;
+            mix.reverb_gain += parameters.reverb_gain * k;
+            mix.decay_time += parameters.decay_time * k;
+            // Frequencies are averaged in logarithmic scale, the exponent is taken below.
+            mix.damping_hz += parameters.damping_hz.max(f32::EPSILON).ln() * k;
+            mix.low_pass_cutoff_hz += parameters.low_pass_cutoff_hz.max(f32::EPSILON).ln() * k;
+            mix.high_pass_cutoff_hz += parameters.high_pass_cutoff_hz.max(f32::EPSILON).ln() * k;
+        }
+
+        let influence = total_weight.min(1.0);
+        let neutral = Self::NEUTRAL;
+        Self {
+            dry: neutral.dry + (mix.dry - neutral.dry) * influence,
+            reverb_gain: mix.reverb_gain * influence,
+            decay_time: mix.decay_time,
+            damping_hz: mix.damping_hz.exp(),
+            low_pass_cutoff_hz: lerp_frequency(
+                neutral.low_pass_cutoff_hz,
+                mix.low_pass_cutoff_hz.exp(),
+                influence,
+            ),
+            high_pass_cutoff_hz: lerp_frequency(
+                neutral.high_pass_cutoff_hz,
+                mix.high_pass_cutoff_hz.exp(),
+                influence,
+            ),
+        }
+    }
+
+    /// Applies the parameters to an audio bus with the given name. Zones have their own reverb,
+    /// low-pass and high-pass filter effects, that are added to the transient effects of the bus
+    /// (see [`super::AudioBus::transient_effects`]), so the effects of the bus itself are left intact and
+    /// the effects of the zones are never saved.
+    pub fn apply(&self, bus_graph: &mut AudioBusGraph, bus_name: &str) {
+        let Some(bus) = bus_graph
+            .buses_iter_mut()
+            .find(|bus| bus.name() == bus_name)
+        else {
+            return;
+        };
+        let effects = bus.transient_effects_mut();
+
+        if !effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::HighPassFilter(_)))
+        {
+            let mut filter = HighPassFilterEffect::default();
+            filter.set_quality(std::f32::consts::FRAC_1_SQRT_2);
+            effects.push(Effect::HighPassFilter(filter));
+        }
+        if !effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::LowPassFilter(_)))
+        {
+            let mut filter = LowPassFilterEffect::default();
+            filter.set_quality(std::f32::consts::FRAC_1_SQRT_2);
+            effects.push(Effect::LowPassFilter(filter));
+        }
+        if !effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::Reverb(_)))
+        {
+            effects.push(Effect::Reverb(Reverb::new()));
+        }
+
+        for effect in effects.iter_mut() {
+            match effect {
+                Effect::Reverb(reverb) => {
+                    reverb.set_dry(self.dry);
+                    reverb.set_gain(self.reverb_gain);
+                    if reverb.decay_time() != self.decay_time {
+                        reverb.set_decay_time(self.decay_time);
+                    }
+                    let fc = self.damping_hz / SAMPLE_RATE as f32;
+                    if reverb.fc() != fc {
+                        reverb.set_fc(fc);
+                    }
+                }
+                Effect::LowPassFilter(filter)
+                    if filter.cutoff_frequency_hz() != self.low_pass_cutoff_hz =>
+                {
+                    filter.set_cutoff_frequency_hz(self.low_pass_cutoff_hz);
+                }
+                Effect::HighPassFilter(filter)
+                    if filter.cutoff_frequency_hz() != self.high_pass_cutoff_hz =>
+                {
+                    filter.set_cutoff_frequency_hz(self.high_pass_cutoff_hz);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Removes the effects of the zones from an audio bus with the given name, so the bus sounds
+    /// as if there were no zones at all.
+    pub fn remove(bus_graph: &mut AudioBusGraph, bus_name: &str) {
+        if let Some(bus) = bus_graph
+            .buses_iter_mut()
+            .find(|bus| bus.name() == bus_name)
+        {
+            bus.transient_effects_mut().retain(|effect| {
+                !matches!(
+                    effect,
+                    Effect::Reverb(_) | Effect::LowPassFilter(_) | Effect::HighPassFilter(_)
+                )
+            });
+        }
+    }
+}
+
+/// Collects contributions of reverb zones during a frame and applies blended parameters to
+/// respective audio buses at the end of the frame.
+#[derive(Default, Debug)]
+pub(crate) struct ReverbZoneMixer {
+    contributions: FxHashMap<String, Vec<(f32, ReverbZoneParameters)>>,
+    active_buses: FxHashSet<String>,
+}
+
+impl ReverbZoneMixer {
+    pub(crate) fn add(&mut self, bus: &str, weight: f32, parameters: ReverbZoneParameters) {
+        if let Some(contributions) = self.contributions.get_mut(bus) {
+            contributions.push((weight, parameters));
+        } else {
+            self.contributions
+                .insert(bus.to_string(), vec![(weight, parameters)]);
+        }
+    }
+
+    pub(crate) fn apply(&mut self, bus_graph: &mut AudioBusGraph) {
+        let prev_active_buses = std::mem::take(&mut self.active_buses);
+
+        for (bus, contributions) in self.contributions.iter_mut() {
+            if contributions.is_empty() {
+                continue;
+            }
+            ReverbZoneParameters::blend(contributions).apply(bus_graph, bus);
+            contributions.clear();
+            self.active_buses.insert(bus.clone());
+        }
+
+        // Reset buses, that are not affected by any zone anymore.
+        for bus in prev_active_buses {
+            if !self.active_buses.contains(&bus) {
+                ReverbZoneParameters::remove(bus_graph, &bus);
+            }
+        }
+    }
+}
+
+/// Reverb zone is a volume (box or sphere) that defines reverberation and equalization parameters
+/// for a region of a scene. When the [`super::listener::Listener`] is inside one or more zones,
+/// the engine blends the parameters of the zones and applies them to the audio bus of the zones
+/// (see [`ReverbZoneParameters::apply`] for more info). Every zone has a fade distance, that
+/// defines a distance outside of the zone at which the zone stops affecting the sound, the
+/// influence of the zone decreases linearly with the distance. When the listener is outside of all
+/// the zones of a bus, the effects of the zones are removed from the bus.
+///
+/// Typical usage is to assign different reverberation to rooms, caves, corridors and so on.
+/// Zones with different buses could be used to process different groups of sounds (for example,
+/// the music should not be affected by the reverberation of a cave).
+///
+/// # Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         node::Node,
+/// #         sound::reverb_zone::{ReverbZoneBuilder, ReverbZoneShape},
+/// #     },
+/// # };
+/// fn create_cave_zone(graph: &mut Graph) -> Handle<Node> {
+///     ReverbZoneBuilder::new(BaseBuilder::new())
+///         .with_shape(ReverbZoneShape::Sphere { radius: 20.0 })
+///         .with_fade_distance(5.0)
+///         .with_reverb_gain(0.8)
+///         .with_decay_time(6.0)
+///         .with_low_pass_cutoff_hz(8000.0)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Visit, Reflect, Clone, Debug, ComponentProvider)]
+#[reflect(derived_type = "Node")]
+pub struct ReverbZone {
+    base: Base,
+
+    #[reflect(setter = "set_shape")]
+    shape: InheritableVariable<ReverbZoneShape>,
+
+    #[reflect(
+        setter = "set_fade_distance",
+        min_value = 0.0,
+        step = 0.1,
+        description = "Distance outside of the zone at which the zone stops affecting the sound."
+    )]
+    fade_distance: InheritableVariable<f32>,
+
+    #[reflect(
+        setter = "set_audio_bus",
+        description = "A name of an audio bus to which the parameters of the zone will be applied."
+    )]
+    audio_bus: InheritableVariable<String>,
+
+    #[reflect(
+        setter = "set_dry",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05,
+        description = "Amount of the unprocessed signal."
+    )]
+    dry: InheritableVariable<f32>,
+
+    #[reflect(
+        setter = "set_reverb_gain",
+        min_value = 0.0,
+        step = 0.05,
+        description = "Gain of the reverberated signal."
+    )]
+    reverb_gain: InheritableVariable<f32>,
+
+    #[reflect(
+        setter = "set_decay_time",
+        min_value = 0.0,
+        step = 0.1,
+        description = "Duration of reverberation in seconds."
+    )]
+    decay_time: InheritableVariable<f32>,
+
+    #[reflect(
+        setter = "set_damping_hz",
+        min_value = 20.0,
+        max_value = 20000.0,
+        description = "Cutoff frequency (in Hz) of reflections. Lower values make reflections \
+        duller."
+    )]
+    damping_hz: InheritableVariable<f32>,
+
+    #[reflect(
+        setter = "set_low_pass_cutoff_hz",
+        min_value = 20.0,
+        max_value = 20000.0,
+        description = "Cutoff frequency (in Hz) of the low-pass filter of the bus."
+    )]
+    low_pass_cutoff_hz: InheritableVariable<f32>,
+
+    #[reflect(
+        setter = "set_high_pass_cutoff_hz",
+        min_value = 20.0,
+        max_value = 20000.0,
+        description = "Cutoff frequency (in Hz) of the high-pass filter of the bus."
+    )]
+    high_pass_cutoff_hz: InheritableVariable<f32>,
+}
+
+impl Default for ReverbZone {
+    fn default() -> Self {
+        ReverbZoneBuilder::new(BaseBuilder::new()).build_reverb_zone()
+    }
+}
+
+impl Deref for ReverbZone {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for ReverbZone {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for ReverbZone {
+    fn type_uuid() -> Uuid {
+        uuid!("0b7c2f4e-5d1a-4c3b-8e9f-a0b1c2d3e4f5")
+    }
+}
+
+impl ReverbZone {
+    /// Sets new shape of the zone.
+    pub fn set_shape(&mut self, shape: ReverbZoneShape) -> ReverbZoneShape {
+        self.shape.set_value_and_mark_modified(shape)
+    }
+
+    /// Returns current shape of the zone.
+    pub fn shape(&self) -> ReverbZoneShape {
+        *self.shape
+    }
+
+    /// Sets a distance outside of the zone at which the zone stops affecting the sound. The
+    /// influence of the zone decreases linearly with the distance from the zone.
+    pub fn set_fade_distance(&mut self, distance: f32) -> f32 {
+        self.fade_distance
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns current fade distance.
+    pub fn fade_distance(&self) -> f32 {
+        *self.fade_distance
+    }
+
+    /// Sets a name of an audio bus to which the parameters of the zone will be applied.
+    pub fn set_audio_bus(&mut self, name: String) -> String {
+        self.audio_bus.set_value_and_mark_modified(name)
+    }
+
+    /// Returns the name of the audio bus of the zone.
+    pub fn audio_bus(&self) -> &str {
+        &self.audio_bus
+    }
+
+    /// Sets amount of the unprocessed signal in `[0; 1]` range.
+    pub fn set_dry(&mut self, dry: f32) -> f32 {
+        self.dry.set_value_and_mark_modified(dry.clamp(0.0, 1.0))
+    }
+
+    /// Returns amount of the unprocessed signal.
+    pub fn dry(&self) -> f32 {
+        *self.dry
+    }
+
+    /// Sets gain of the reverberated signal.
+    pub fn set_reverb_gain(&mut self, gain: f32) -> f32 {
+        self.reverb_gain.set_value_and_mark_modified(gain.max(0.0))
+    }
+
+    /// Returns gain of the reverberated signal.
+    pub fn reverb_gain(&self) -> f32 {
+        *self.reverb_gain
+    }
+
+    /// Sets duration of reverberation in seconds.
+    pub fn set_decay_time(&mut self, decay_time: f32) -> f32 {
+        self.decay_time
+            .set_value_and_mark_modified(decay_time.max(0.0))
+    }
+
+    /// Returns duration of reverberation in seconds.
+    pub fn decay_time(&self) -> f32 {
+        *self.decay_time
+    }
+
+    /// Sets cutoff frequency (in Hz) of reflections. Lower values make reflections duller.
+    pub fn set_damping_hz(&mut self, frequency: f32) -> f32 {
+        self.damping_hz.set_value_and_mark_modified(frequency)
+    }
+
+    /// Returns cutoff frequency of reflections.
+    pub fn damping_hz(&self) -> f32 {
+        *self.damping_hz
+    }
+
+    /// Sets cutoff frequency (in Hz) of the low-pass filter of the bus.
+    pub fn set_low_pass_cutoff_hz(&mut self, frequency: f32) -> f32 {
+        self.low_pass_cutoff_hz
+            .set_value_and_mark_modified(frequency)
+    }
+
+    /// Returns cutoff frequency of the low-pass filter of the bus.
+    pub fn low_pass_cutoff_hz(&self) -> f32 {
+        *self.low_pass_cutoff_hz
+    }
+
+    /// Sets cutoff frequency (in Hz) of the high-pass filter of the bus.
+    pub fn set_high_pass_cutoff_hz(&mut self, frequency: f32) -> f32 {
+        self.high_pass_cutoff_hz
+            .set_value_and_mark_modified(frequency)
+    }
+
+    /// Returns cutoff frequency of the high-pass filter of the bus.
+    pub fn high_pass_cutoff_hz(&self) -> f32 {
+        *self.high_pass_cutoff_hz
+    }
+
+    /// Returns parameters of the zone.
+    pub fn parameters(&self) -> ReverbZoneParameters {
+        ReverbZoneParameters {
+            dry: *self.dry,
+            reverb_gain: *self.reverb_gain,
+            decay_time: *self.decay_time,
+            damping_hz: *self.damping_hz,
+            low_pass_cutoff_hz: *self.low_pass_cutoff_hz,
+            high_pass_cutoff_hz: *self.high_pass_cutoff_hz,
+        }
+    }
+
+    /// Calculates influence of the zone at the given point in world coordinates. It returns 1.0
+    /// if the point is inside the zone, and decreases linearly to zero in the fade distance
+    /// around the zone.
+    pub fn weight(&self, point: Vector3<f32>) -> f32 {
+        let global_transform = self.global_transform();
+        let Some(inv_global_transform) = global_transform.try_inverse() else {
+            return 0.0;
+        };
+
+        let local_point = inv_global_transform
+            .transform_point(&Point3::from(point))
+            .coords;
+        if self.shape.contains(local_point) {
+            return 1.0;
+        }
+
+        if *self.fade_distance <= 0.0 {
+            return 0.0;
+        }
+
+        let closest_point = global_transform
+            .transform_point(&Point3::from(self.shape.closest_point(local_point)))
+            .coords;
+        (1.0 - closest_point.metric_distance(&point) / *self.fade_distance).max(0.0)
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for ReverbZone {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Reverb Zone", |_| {
+                ReverbZoneBuilder::new(BaseBuilder::new().with_name("Reverb Zone"))
+                    .build_node()
+                    .into()
+            })
+            .with_group("Sound")
+    }
+}
+
+impl NodeTrait for ReverbZone {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.shape.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let weight = self.weight(context.sound_context.listener_position());
+        if weight > 0.0 {
+            context.sound_context.add_reverb_zone_contribution(
+                &self.audio_bus,
+                weight,
+                self.parameters(),
+            );
+        }
+    }
+}
+
+/// Allows you to create reverb zones in declarative manner.
+pub struct ReverbZoneBuilder {
+    base_builder: BaseBuilder,
+    shape: ReverbZoneShape,
+    fade_distance: f32,
+    audio_bus: String,
+    parameters: ReverbZoneParameters,
+}
+
+impl ReverbZoneBuilder {
+    /// Creates new reverb zone builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            shape: Default::default(),
+            fade_distance: 2.0,
+            audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            parameters: ReverbZoneParameters {
+                reverb_gain: 0.5,
+                ..ReverbZoneParameters::NEUTRAL
+            },
+        }
+    }
+
+    /// Sets desired shape of the zone.
+    pub fn with_shape(mut self, shape: ReverbZoneShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets desired fade distance of the zone. See [`ReverbZone::set_fade_distance`].
+    pub fn with_fade_distance(mut self, distance: f32) -> Self {
+        self.fade_distance = distance;
+        self
+    }
+
+    /// Sets desired audio bus of the zone.
+    pub fn with_audio_bus(mut self, audio_bus: String) -> Self {
+        self.audio_bus = audio_bus;
+        self
+    }
+
+    /// Sets desired amount of the unprocessed signal.
+    pub fn with_dry(mut self, dry: f32) -> Self {
+        self.parameters.dry = dry;
+        self
+    }
+
+    /// Sets desired gain of the reverberated signal.
+    pub fn with_reverb_gain(mut self, gain: f32) -> Self {
+        self.parameters.reverb_gain = gain;
+        self
+    }
+
+    /// Sets desired duration of reverberation in seconds.
+    pub fn with_decay_time(mut self, decay_time: f32) -> Self {
+        self.parameters.decay_time = decay_time;
+        self
+    }
+
+    /// Sets desired cutoff frequency (in Hz) of reflections.
+    pub fn with_damping_hz(mut self, frequency: f32) -> Self {
+        self.parameters.damping_hz = frequency;
+        self
+    }
+
+    /// Sets desired cutoff frequency (in Hz) of the low-pass filter of the bus.
+    pub fn with_low_pass_cutoff_hz(mut self, frequency: f32) -> Self {
+        self.parameters.low_pass_cutoff_hz = frequency;
+        self
+    }
+
+    /// Sets desired cutoff frequency (in Hz) of the high-pass filter of the bus.
+    pub fn with_high_pass_cutoff_hz(mut self, frequency: f32) -> Self {
+        self.parameters.high_pass_cutoff_hz = frequency;
+        self
+    }
+
+    /// Creates reverb zone instance.
+    pub fn build_reverb_zone(self) -> ReverbZone {
+        ReverbZone {
+            base: self.base_builder.build_base(),
+            shape: self.shape.into(),
+            fade_distance: self.fade_distance.into(),
+            audio_bus: self.audio_bus.into(),
+            dry: self.parameters.dry.into(),
+            reverb_gain: self.parameters.reverb_gain.into(),
+            decay_time: self.parameters.decay_time.into(),
+            damping_hz: self.parameters.damping_hz.into(),
+            low_pass_cutoff_hz: self.parameters.low_pass_cutoff_hz.into(),
+            high_pass_cutoff_hz: self.parameters.high_pass_cutoff_hz.into(),
+        }
+    }
+
+    /// Creates [`ReverbZone`] node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_reverb_zone())
+    }
+
+    /// Creates [`ReverbZone`] node and adds it to the scene graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::algebra::{Vector2, Vector3};
+    use crate::scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        sound::{
+            listener::ListenerBuilder,
+            reverb::Reverb,
+            reverb_zone::{ReverbZone, ReverbZoneBuilder, ReverbZoneParameters, ReverbZoneShape},
+            Effect,
+        },
+        transform::TransformBuilder,
+    };
+
+    fn zone(reverb_gain: f32, decay_time: f32) -> ReverbZoneParameters {
+        ReverbZoneParameters {
+            dry: 0.5,
+            reverb_gain,
+            decay_time,
+            ..ReverbZoneParameters::NEUTRAL
+        }
+    }
+
+    #[test]
+    fn test_reverb_zone_blending() {
+        assert_eq!(
+            ReverbZoneParameters::blend(&[]),
+            ReverbZoneParameters::NEUTRAL
+        );
+
+        let full = ReverbZoneParameters::blend(&[(1.0, zone(1.0, 4.0))]);
+        assert_eq!(full.reverb_gain, 1.0);
+        assert_eq!(full.dry, 0.5);
+        assert_eq!(full.decay_time, 4.0);
+
+        // Half-faded zone is blended with neutral parameters.
+        let half = ReverbZoneParameters::blend(&[(0.5, zone(1.0, 4.0))]);
+        assert_eq!(half.reverb_gain, 0.5);
+        assert_eq!(half.dry, 0.75);
+        assert_eq!(half.decay_time, 4.0);
+
+        // Overlapping zones are averaged.
+        let overlap = ReverbZoneParameters::blend(&[(1.0, zone(1.0, 2.0)), (1.0, zone(0.5, 4.0))]);
+        assert_eq!(overlap.reverb_gain, 0.75);
+        assert_eq!(overlap.decay_time, 3.0);
+    }
+
+    #[test]
+    fn test_reverb_zone_weight() {
+        let mut graph = Graph::new();
+        let zone = ReverbZoneBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(10.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_shape(ReverbZoneShape::Box {
+            half_extents: Vector3::new(1.0, 1.0, 1.0),
+        })
+        .with_fade_distance(2.0)
+        .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let zone = graph[zone].cast::<ReverbZone>().unwrap();
+        assert_eq!(zone.weight(Vector3::new(10.5, 0.0, 0.0)), 1.0);
+        assert_eq!(zone.weight(Vector3::new(12.0, 0.0, 0.0)), 0.5);
+        assert_eq!(zone.weight(Vector3::new(20.0, 0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn test_reverb_zone_applies_to_bus() {
+        let mut graph = Graph::new();
+        let listener = ListenerBuilder::new(BaseBuilder::new()).build(&mut graph);
+        ReverbZoneBuilder::new(BaseBuilder::new())
+            .with_shape(ReverbZoneShape::Sphere { radius: 5.0 })
+            .with_reverb_gain(0.8)
+            .with_decay_time(4.0)
+            .build(&mut graph);
+
+        // Own effects of the bus must not be touched by the zone.
+        let mut own_reverb = Reverb::new();
+        own_reverb.set_gain(0.3);
+        graph
+            .sound_context
+            .state()
+            .bus_graph_mut()
+            .primary_bus_mut()
+            .add_effect(Effect::Reverb(own_reverb.clone()));
+
+        let reverb_gain = |graph: &Graph| {
+            let state = graph.sound_context.state();
+            let gain = state
+                .bus_graph_ref()
+                .primary_bus_ref()
+                .transient_effects()
+                .iter()
+                .find_map(|effect| match effect {
+                    Effect::Reverb(reverb) => Some(reverb.gain()),
+                    _ => None,
+                });
+            gain
+        };
+        let own_effects = |graph: &Graph| {
+            let state = graph.sound_context.state();
+            let effects = state
+                .bus_graph_ref()
+                .primary_bus_ref()
+                .effects()
+                .cloned()
+                .collect::<Vec<_>>();
+            effects
+        };
+
+        graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        assert_eq!(reverb_gain(&graph), Some(0.8));
+        assert_eq!(own_effects(&graph), [Effect::Reverb(own_reverb.clone())]);
+
+        // Leaving the zone removes its effects.
+        graph[listener]
+            .local_transform_mut()
+            .set_position(Vector3::new(100.0, 0.0, 0.0));
+        graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        assert_eq!(reverb_gain(&graph), None);
+        assert_eq!(own_effects(&graph), [Effect::Reverb(own_reverb)]);
+    }
+}
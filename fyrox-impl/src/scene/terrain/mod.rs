@@ -714,6 +714,16 @@ impl Chunk {
                                 }
                                 convert::<Rgb16F, _>(new_height_map_texture, |v| v.r.to_f32())
                             }
+                            TexturePixelKind::RGBA16F => {
+                                #[repr(C)]
+                                struct Rgba16F {
+                                    r: f16,
+                                    g: f16,
+                                    b: f16,
+                                    a: f16,
+                                }
+                                convert::<Rgba16F, _>(new_height_map_texture, |v| v.r.to_f32())
+                            }
                             TexturePixelKind::R32F => {
                                 convert::<f32, _>(new_height_map_texture, |v| *v)
                             }
@@ -21,7 +21,7 @@
 //! Resource dependency graph. See [`ResourceDependencyGraph`] docs for more info.
 
 use crate::{collect_used_resources, state::ResourceState, untyped::UntypedResource};
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 
 /// A node of [`ResourceDependencyGraph`].
 pub struct ResourceGraphNode {
@@ -113,10 +113,166 @@ impl ResourceDependencyGraph {
         out
     }
 }
+
+/// Collects a set of external resources directly referenced by the given resource. Embedded resources
+/// are considered as a part of the resource that owns them, so the search goes through them and collects
+/// external resources referenced by them too.
+pub fn collect_direct_references(resource: &UntypedResource) -> FxHashSet<UntypedResource> {
+    let mut references = FxHashSet::default();
+    let mut visited = FxHashSet::default();
+    let mut stack = vec![resource.clone()];
+
+    while let Some(resource) = stack.pop() {
+        if !visited.insert(resource.clone()) {
+            continue;
+        }
+
+        let mut used_resources = FxHashSet::default();
+        let header = resource.0.lock();
+        if let ResourceState::Ok { ref data, .. } = header.state {
+            (**data).as_reflect(&mut |entity| {
+                collect_used_resources(entity, &mut used_resources);
+            });
+        }
+        drop(header);
+
+        for used_resource in used_resources {
+            if used_resource.is_embedded() {
+                stack.push(used_resource);
+            } else {
+                references.insert(used_resource);
+            }
+        }
+    }
+
+    references.remove(resource);
+
+    references
+}
+
+/// A set of direct references between resources. Unlike [`ResourceDependencyGraph`], which is a tree
+/// of dependencies of a single resource, this structure stores references of arbitrary amount of
+/// resources and could be used to answer two questions: which resources are used by a resource and
+/// which resources are using a resource. It is also capable to find unused resources - the ones that
+/// cannot be reached from a given set of root resources (usually scenes).
+#[derive(Default)]
+pub struct ResourceReferences {
+    dependencies: FxHashMap<UntypedResource, FxHashSet<UntypedResource>>,
+}
+
+impl ResourceReferences {
+    /// Creates a new set of references by scanning every resource from the given list. Resources must
+    /// be fully loaded, otherwise their dependencies will not be found.
+    pub fn new<'a>(resources: impl IntoIterator<Item = &'a UntypedResource>) -> Self {
+        let mut references = Self::default();
+        for resource in resources {
+            references.add(resource);
+        }
+        references
+    }
+
+    /// Scans the given resource and remembers its direct references. Resources must be fully loaded,
+    /// otherwise their dependencies will not be found.
+    pub fn add(&mut self, resource: &UntypedResource) {
+        self.dependencies
+            .insert(resource.clone(), collect_direct_references(resource));
+    }
+
+    /// Returns an iterator over every scanned resource.
+    pub fn resources(&self) -> impl Iterator<Item = &UntypedResource> {
+        self.dependencies.keys()
+    }
+
+    /// Returns an iterator over the resources directly referenced by the given resource. The iterator
+    /// will be empty if the resource wasn't scanned.
+    pub fn dependencies_of<'a>(
+        &'a self,
+        resource: &UntypedResource,
+    ) -> impl Iterator<Item = &'a UntypedResource> {
+        self.dependencies.get(resource).into_iter().flatten()
+    }
+
+    /// Returns a list of scanned resources that directly reference the given resource. This is the
+    /// "used by" relation.
+    pub fn users_of(&self, resource: &UntypedResource) -> Vec<UntypedResource> {
+        self.dependencies
+            .iter()
+            .filter_map(|(user, dependencies)| {
+                if dependencies.contains(resource) {
+                    Some(user.clone())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Collects every resource that can be reached from the given set of root resources, including the
+    /// roots themselves.
+    pub fn reachable_from<'a>(
+        &self,
+        roots: impl IntoIterator<Item = &'a UntypedResource>,
+    ) -> FxHashSet<UntypedResource> {
+        let mut reachable = FxHashSet::default();
+        let mut stack = roots.into_iter().cloned().collect::<Vec<_>>();
+        while let Some(resource) = stack.pop() {
+            if reachable.insert(resource.clone()) {
+                stack.extend(self.dependencies_of(&resource).cloned());
+            }
+        }
+        reachable
+    }
+
+    /// Returns a list of scanned resources that cannot be reached from the given set of root resources.
+    pub fn unreachable_from<'a>(
+        &self,
+        roots: impl IntoIterator<Item = &'a UntypedResource>,
+    ) -> Vec<UntypedResource> {
+        let reachable = self.reachable_from(roots);
+        self.dependencies
+            .keys()
+            .filter(|resource| !reachable.contains(*resource))
+            .cloned()
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::untyped::ResourceKind;
+    use crate::{untyped::ResourceKind, ResourceData};
+    use fyrox_core::{reflect::prelude::*, uuid, visitor::prelude::*, TypeUuidProvider, Uuid};
+    use std::{error::Error, path::Path};
+
+    #[derive(Default, Debug, Visit, Reflect, TypeUuidProvider)]
+    #[type_uuid(id = "1c3a7b0e-3a33-4d7d-a0d5-7c2a7c1f4d8e")]
+    struct Stub {
+        references: Vec<UntypedResource>,
+    }
+
+    impl ResourceData for Stub {
+        fn type_uuid(&self) -> Uuid {
+            <Self as TypeUuidProvider>::type_uuid()
+        }
+
+        fn save(&mut self, _path: &Path) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn can_be_saved(&self) -> bool {
+            false
+        }
+    }
+
+    fn stub(kind: ResourceKind, references: &[&UntypedResource]) -> UntypedResource {
+        UntypedResource::new_ok(
+            Uuid::new_v4(),
+            kind,
+            Stub {
+                references: references.iter().map(|r| (*r).clone()).collect(),
+            },
+        )
+    }
 
     #[test]
     fn resource_graph_node_new() {
@@ -170,4 +326,38 @@ mod test {
         let s = graph.pretty_print();
         assert_eq!(s, "External\n\tExternal\n".to_string());
     }
+
+    #[test]
+    fn resource_references_through_embedded() {
+        let texture = stub(ResourceKind::External, &[]);
+        let material = stub(ResourceKind::Embedded, &[&texture]);
+        let scene = stub(ResourceKind::External, &[&material]);
+
+        let references = collect_direct_references(&scene);
+        assert_eq!(references.len(), 1);
+        assert!(references.contains(&texture));
+    }
+
+    #[test]
+    fn resource_references_users_and_unused() {
+        let texture = stub(ResourceKind::External, &[]);
+        let unused_texture = stub(ResourceKind::External, &[]);
+        let prefab = stub(ResourceKind::External, &[&texture]);
+        let scene = stub(ResourceKind::External, &[&prefab, &texture]);
+
+        let references = ResourceReferences::new([&texture, &unused_texture, &prefab, &scene]);
+
+        let mut users = references.users_of(&texture);
+        users.sort_by_key(|r| r.key());
+        let mut expected = vec![prefab.clone(), scene.clone()];
+        expected.sort_by_key(|r| r.key());
+        assert_eq!(users, expected);
+        assert!(references.users_of(&scene).is_empty());
+
+        let reachable = references.reachable_from([&scene]);
+        assert_eq!(reachable.len(), 3);
+        assert!(!reachable.contains(&unused_texture));
+
+        assert_eq!(references.unreachable_from([&scene]), vec![unused_texture]);
+    }
 }
@@ -35,6 +35,7 @@ use crate::{
     },
     entry::{TimedEntry, DEFAULT_RESOURCE_LIFETIME},
     event::{ResourceEvent, ResourceEventBroadcaster},
+    graph::ResourceReferences,
     io::{FsResourceIo, ResourceIo},
     loader::{ResourceLoader, ResourceLoadersContainer},
     metadata::ResourceMetadata,
@@ -471,6 +472,56 @@ impl ResourceManager {
         let resources = self.state().reload_resources();
         join_all(resources).await;
     }
+
+    /// Loads every resource from the resource registry and collects direct references between them.
+    /// Resources that failed to load are ignored. This method is **very** heavy, because it loads
+    /// all the resources of a project, it should be used by tools only (editor, export, etc.).
+    pub async fn collect_resource_references(&self) -> ResourceReferences {
+        let registry = self.state().resource_registry.clone();
+        let registry_status = registry.lock().status.clone();
+        registry_status.await;
+
+        let paths = registry.lock().paths.values().cloned().collect::<Vec<_>>();
+        let resources = paths
+            .iter()
+            .map(|path| self.request_untyped(path))
+            .collect::<Vec<_>>();
+        let loaded = join_all(resources).await;
+
+        ResourceReferences::new(loaded.iter().filter_map(|resource| resource.as_ref().ok()))
+    }
+
+    /// Searches for registered resources that cannot be reached from the given set of root resources
+    /// (usually scenes) and returns their paths sorted alphabetically. Such resources could be safely
+    /// excluded from a final build of a game. See [`Self::collect_resource_references`] for more info.
+    pub async fn find_unused_resources(&self, roots: &[PathBuf]) -> Vec<PathBuf> {
+        let references = self.collect_resource_references().await;
+        let roots = roots
+            .iter()
+            .map(|path| self.request_untyped(path))
+            .collect::<Vec<_>>();
+        let mut unused = references
+            .unreachable_from(roots.iter())
+            .iter()
+            .filter_map(|resource| self.resource_path(resource))
+            .collect::<Vec<_>>();
+        unused.sort();
+        unused
+    }
+
+    /// Searches for registered resources that directly reference the resource at the given path and
+    /// returns their paths sorted alphabetically. See [`Self::collect_resource_references`] for more info.
+    pub async fn find_resource_users(&self, path: impl AsRef<Path>) -> Vec<PathBuf> {
+        let references = self.collect_resource_references().await;
+        let resource = self.request_untyped(path);
+        let mut users = references
+            .users_of(&resource)
+            .iter()
+            .filter_map(|user| self.resource_path(user))
+            .collect::<Vec<_>>();
+        users.sort();
+        users
+    }
 }
 
 impl ResourceManagerState {
@@ -0,0 +1,89 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use fyrox_sound::{
+    buffer::{
+        procedural::{sample_queue, Envelope, Generator, NoiseColor, Waveform},
+        DataSource, SoundBufferResource, SoundBufferResourceExtension,
+    },
+    context::{SoundContext, SAMPLE_RATE},
+    engine::SoundEngine,
+    source::{SoundSourceBuilder, Status},
+};
+use std::{thread, time::Duration};
+
+fn main() {
+    // Initialize sound engine with default output device.
+    let engine = SoundEngine::new().unwrap();
+
+    // Initialize new sound context.
+    let context = SoundContext::new();
+
+    engine.state().add_context(context.clone());
+
+    // Play a short beep with an envelope, the source will be removed once the beep is finished.
+    let beep = Generator::oscillator(Waveform::Square, 440.0)
+        .with_amplitude(0.25)
+        .with_envelope(Envelope {
+            attack: 0.01,
+            decay: 0.1,
+            sustain_level: 0.5,
+            sustain_time: 0.3,
+            release: 0.2,
+        });
+    let source = SoundSourceBuilder::new()
+        .with_buffer(
+            SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(beep))).unwrap(),
+        )
+        .with_status(Status::Playing)
+        .with_play_once(true)
+        .build()
+        .unwrap();
+    context.state().add_source(source);
+
+    thread::sleep(Duration::from_secs(1));
+
+    // Feed pink noise from another thread via sample queue, as if it was a voice chat stream.
+    let (mut producer, queue) = sample_queue(SAMPLE_RATE as usize, 1, SAMPLE_RATE as usize / 10);
+    let source = SoundSourceBuilder::new()
+        .with_buffer(
+            SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(queue))).unwrap(),
+        )
+        .with_status(Status::Playing)
+        .with_play_once(true)
+        .build()
+        .unwrap();
+    context.state().add_source(source);
+
+    let mut noise = Generator::noise(NoiseColor::Pink).with_amplitude(0.5);
+    let mut block = vec![0.0; 1024];
+    for _ in 0..200 {
+        block.fill_with(|| noise.next().unwrap());
+        let mut pushed = 0;
+        while pushed < block.len() {
+            pushed += producer.push(&block[pushed..]);
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+    // Dropping the producer stops the source once all samples are played.
+    drop(producer);
+
+    thread::sleep(Duration::from_secs(1));
+}
@@ -20,14 +20,15 @@
 
 use fyrox_resource::io::FsResourceIo;
 use fyrox_sound::buffer::SoundBufferResourceExtension;
-use fyrox_sound::engine::State;
 use fyrox_sound::{
     buffer::{DataSource, SoundBufferResource},
     context::SoundContext,
     engine::SoundEngine,
+    offline::{OfflineRenderer, WavSampleFormat},
     pool::Handle,
     source::{SoundSource, SoundSourceBuilder, Status},
 };
+use std::time::Duration;
 
 fn main() {
     // Initialize sound engine without output device.
@@ -61,29 +62,11 @@ fn main() {
     // and returns pool handle to it by which it can be accessed later on if needed.
     let _source_handle: Handle<SoundSource> = context.state().add_source(source);
 
-    // Create output wav file. The sample rate is currently fixed.
-    let wav_spec = hound::WavSpec {
-        channels: 2,
-        sample_rate: fyrox_sound::context::SAMPLE_RATE,
-        bits_per_sample: 32,
-        sample_format: hound::SampleFormat::Float,
-    };
-    let mut wav_writer = hound::WavWriter::create("output.wav", wav_spec).unwrap();
-
-    // Create an output buffer.
-    let buf_len = State::render_buffer_len();
-    let mut buf = vec![(0.0f32, 0.0f32); buf_len];
-    let mut samples_written = 0;
-
-    // Wait until sound will play completely.
-    while samples_written < 3 * fyrox_sound::context::SAMPLE_RATE {
-        engine.state().render(&mut buf);
-        for &(l, r) in buf.iter() {
-            wav_writer.write_sample(l).unwrap();
-            wav_writer.write_sample(r).unwrap();
-        }
-        samples_written += buf_len as u32;
-    }
-
-    wav_writer.finalize().unwrap();
+    // Render 3 seconds of sound (until the sound will play completely) and write it to a wav file.
+    // The sample rate is currently fixed.
+    let mut renderer = OfflineRenderer::new();
+    renderer.render_engine(&mut engine.state(), Duration::from_secs(3));
+    renderer
+        .save_wav("output.wav", WavSampleFormat::Float32)
+        .unwrap();
 }
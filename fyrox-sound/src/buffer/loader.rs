@@ -42,6 +42,21 @@ pub struct SoundBufferImportOptions {
 
 impl ImportOptions for SoundBufferImportOptions {}
 
+/// A list of file extensions supported by [`SoundBufferLoader`]. WAV and Ogg/Vorbis files are always
+/// supported, other formats depend on enabled features of the crate (`flac`, `mp3`, `aac`, `alac`).
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "wav",
+    "ogg",
+    #[cfg(feature = "flac")]
+    "flac",
+    #[cfg(feature = "mp3")]
+    "mp3",
+    #[cfg(feature = "aac")]
+    "aac",
+    #[cfg(any(feature = "aac", feature = "alac"))]
+    "m4a",
+];
+
 /// Default implementation for sound buffer loading.
 pub struct SoundBufferLoader {
     /// Default import options for sound buffer resources.
@@ -50,7 +65,7 @@ pub struct SoundBufferLoader {
 
 impl ResourceLoader for SoundBufferLoader {
     fn extensions(&self) -> &[&str] {
-        &["wav", "ogg"]
+        SUPPORTED_EXTENSIONS
     }
 
     fn data_type_uuid(&self) -> Uuid {
@@ -52,6 +52,7 @@ use symphonia::core::io::MediaSource;
 
 pub mod generic;
 pub mod loader;
+pub mod procedural;
 pub mod streaming;
 
 /// Data source enumeration. Provides unified way of selecting data source for sound buffers. It can be either
@@ -67,7 +68,8 @@ pub enum DataSource {
         data: Box<dyn FileReader>,
     },
 
-    /// Data source is a memory block. Memory block must be in valid format (wav or vorbis/ogg). This variant can
+    /// Data source is a memory block. Memory block must be in valid format (see
+    /// [`loader::SUPPORTED_EXTENSIONS`] for the list of supported formats). This variant can
     /// be used together with virtual file system.
     Memory(Cursor<Vec<u8>>),
 
@@ -124,6 +126,13 @@ pub trait RawStreamingDataSource: Iterator<Item = f32> + Send + Sync + Debug {
     fn channel_duration_in_samples(&self) -> usize {
         0
     }
+
+    /// Returns amount of samples (per channel) that a streaming buffer reads from the source at
+    /// once. Smaller values reduce latency, which is important for sources that are fed in real
+    /// time. Default is [`StreamingBuffer::STREAM_SAMPLE_COUNT`].
+    fn block_size(&self) -> usize {
+        StreamingBuffer::STREAM_SAMPLE_COUNT
+    }
 }
 
 impl DataSource {
@@ -358,3 +367,136 @@ impl ResourceData for SoundBuffer {
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::buffer::{generic::GenericBuffer, streaming::StreamingBuffer, DataSource};
+    use std::{path::Path, time::Duration};
+
+    fn load(path: &str) -> DataSource {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+        DataSource::from_memory(std::fs::read(path).unwrap())
+    }
+
+    // The reference sound. FLAC and ALAC files contain first 4410 samples of it.
+    fn reference_samples() -> Vec<f32> {
+        let buffer = GenericBuffer::new(load("examples/data/sine_440hz.wav")).unwrap();
+        buffer.samples()[..4410].to_vec()
+    }
+
+    #[test]
+    fn test_wav_and_ogg_decoding() {
+        for path in [
+            "examples/data/sine_440hz.wav",
+            "examples/data/waterfall.ogg",
+        ] {
+            let buffer = GenericBuffer::new(load(path)).unwrap();
+            assert!(!buffer.is_empty());
+            assert_ne!(buffer.sample_rate(), 0);
+        }
+    }
+
+    #[cfg(feature = "flac")]
+    #[test]
+    fn test_flac_generic_decoding() {
+        let buffer = GenericBuffer::new(load("test_data/sine_440hz.flac")).unwrap();
+        assert_eq!(buffer.channel_count(), 1);
+        assert_eq!(buffer.sample_rate(), 44100);
+        // FLAC is lossless, so the samples must match exactly.
+        assert_eq!(buffer.samples(), reference_samples().as_slice());
+    }
+
+    #[cfg(feature = "flac")]
+    #[test]
+    fn test_flac_streaming_decoding() {
+        let mut buffer = StreamingBuffer::new(load("test_data/sine_440hz.flac")).unwrap();
+        assert_eq!(buffer.channel_count(), 1);
+        assert_eq!(buffer.sample_rate(), 44100);
+        let reference = reference_samples();
+        assert_eq!(buffer.samples(), reference.as_slice());
+
+        // The whole sound fits in a single block, the next block must be empty.
+        buffer.read_next_block();
+        assert!(buffer.is_empty());
+
+        buffer.rewind().unwrap();
+        buffer.read_next_block();
+        assert_eq!(buffer.samples(), reference.as_slice());
+
+        // Seeking must be sample-accurate in both directions.
+        buffer.time_seek(Duration::from_millis(50)).unwrap();
+        buffer.read_next_block();
+        assert_eq!(buffer.samples(), &reference[2205..]);
+
+        buffer.time_seek(Duration::from_millis(10)).unwrap();
+        buffer.read_next_block();
+        assert_eq!(buffer.samples(), &reference[441..]);
+    }
+
+    #[cfg(feature = "mp3")]
+    #[test]
+    fn test_mp3_generic_decoding() {
+        // 20 frames of silence, 1152 samples each.
+        let buffer = GenericBuffer::new(load("test_data/silence.mp3")).unwrap();
+        assert_eq!(buffer.channel_count(), 1);
+        assert_eq!(buffer.sample_rate(), 44100);
+        assert_eq!(buffer.samples().len(), 20 * 1152);
+        assert!(buffer.samples().iter().all(|s| *s == 0.0));
+    }
+
+    #[cfg(feature = "mp3")]
+    #[test]
+    fn test_mp3_streaming_decoding() {
+        let mut buffer = StreamingBuffer::new(load("test_data/silence.mp3")).unwrap();
+        assert_eq!(buffer.channel_count(), 1);
+        assert_eq!(buffer.sample_rate(), 44100);
+        assert_eq!(buffer.samples().len(), 20 * 1152);
+
+        buffer.rewind().unwrap();
+        buffer.read_next_block();
+        assert_eq!(buffer.samples().len(), 20 * 1152);
+    }
+
+    #[cfg(feature = "alac")]
+    #[test]
+    fn test_alac_generic_decoding() {
+        let buffer = GenericBuffer::new(load("test_data/sine_440hz_alac.m4a")).unwrap();
+        assert_eq!(buffer.channel_count(), 1);
+        assert_eq!(buffer.sample_rate(), 44100);
+        // ALAC is lossless, so the samples must match exactly.
+        assert_eq!(buffer.samples(), reference_samples().as_slice());
+    }
+
+    #[cfg(feature = "alac")]
+    #[test]
+    fn test_alac_streaming_decoding() {
+        let mut buffer = StreamingBuffer::new(load("test_data/sine_440hz_alac.m4a")).unwrap();
+        let reference = reference_samples();
+        assert_eq!(buffer.samples(), reference.as_slice());
+
+        buffer.time_seek(Duration::from_millis(90)).unwrap();
+        buffer.read_next_block();
+        assert_eq!(buffer.samples(), &reference[3969..]);
+
+        buffer.rewind().unwrap();
+        buffer.read_next_block();
+        assert_eq!(buffer.samples(), reference.as_slice());
+    }
+
+    #[cfg(feature = "aac")]
+    #[test]
+    fn test_aac_decoding() {
+        // 20 frames of silence, 1024 samples each.
+        let buffer = GenericBuffer::new(load("test_data/silence.aac")).unwrap();
+        assert_eq!(buffer.channel_count(), 1);
+        assert_eq!(buffer.sample_rate(), 44100);
+        assert_eq!(buffer.samples().len(), 20 * 1024);
+        assert!(buffer.samples().iter().all(|s| *s == 0.0));
+
+        let mut buffer = StreamingBuffer::new(load("test_data/silence.aac")).unwrap();
+        assert_eq!(buffer.samples().len(), 20 * 1024);
+        buffer.rewind().unwrap();
+        buffer.read_next_block();
+        assert_eq!(buffer.samples().len(), 20 * 1024);
+    }
+}
@@ -0,0 +1,702 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Procedural and callback-driven data sources.
+//!
+//! # Overview
+//!
+//! This module contains a set of [`RawStreamingDataSource`] implementations that generate samples on
+//! the fly instead of decoding them from a file:
+//!
+//! - [`Generator`] - oscillators (sine, square, sawtooth, triangle) and noise (white, pink) with an
+//!   optional [`Envelope`].
+//! - [`CallbackSource`] - pulls samples from a user-defined callback. The callback is called from
+//!   the mixer thread.
+//! - [`SampleQueueSource`] - plays samples that are pushed by some other thread via lock-free ring
+//!   buffer (see [`sample_queue`]). Useful for voice chat playback and other real-time data.
+//!
+//! Every data source must be wrapped into a streaming buffer, then it can be used by a sound source
+//! as usual, so it passes through the same processing pipeline (spatialization, effects, audio buses)
+//! as any other sound.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox_sound::{
+//!     buffer::{
+//!         procedural::{Envelope, Generator, Waveform},
+//!         DataSource, SoundBufferResource, SoundBufferResourceExtension,
+//!     },
+//!     context::SoundContext,
+//!     pool::Handle,
+//!     source::{SoundSource, SoundSourceBuilder, Status},
+//! };
+//!
+//! fn play_beep(context: &SoundContext) -> Handle<SoundSource> {
+//!     let generator = Generator::oscillator(Waveform::Sine, 880.0)
+//!         .with_amplitude(0.5)
+//!         .with_envelope(Envelope {
+//!             attack: 0.01,
+//!             decay: 0.05,
+//!             sustain_level: 0.7,
+//!             sustain_time: 0.1,
+//!             release: 0.1,
+//!         });
+//!
+//!     let buffer =
+//!         SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(generator)))
+//!             .unwrap();
+//!
+//!     let source = SoundSourceBuilder::new()
+//!         .with_buffer(buffer)
+//!         .with_status(Status::Playing)
+//!         .with_play_once(true)
+//!         .build()
+//!         .unwrap();
+//!
+//!     context.state().add_source(source)
+//! }
+//! ```
+
+use crate::{buffer::RawStreamingDataSource, context::SAMPLE_RATE, error::SoundError};
+use crossbeam_queue::ArrayQueue;
+use std::{
+    fmt::{Debug, Formatter},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Shape of an oscillator signal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Waveform {
+    /// Pure tone.
+    Sine,
+    /// Square wave with 50% duty cycle.
+    Square,
+    /// Rising sawtooth wave.
+    Sawtooth,
+    /// Triangle wave.
+    Triangle,
+}
+
+impl Waveform {
+    // Returns signal value at the given phase in `[0; 1)` range.
+    fn sample(self, phase: f32) -> f32 {
+        match self {
+            Waveform::Sine => (2.0 * std::f32::consts::PI * phase).sin(),
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Sawtooth => 2.0 * phase - 1.0,
+            Waveform::Triangle => 4.0 * (phase - 0.5).abs() - 1.0,
+        }
+    }
+}
+
+/// Color of a noise signal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NoiseColor {
+    /// Noise with equal power at every frequency.
+    White,
+    /// Noise with power decreasing by 3 dB per octave, it sounds softer than white noise.
+    Pink,
+}
+
+/// Linear ADSR (attack, decay, sustain, release) envelope. All times are in seconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Envelope {
+    /// Time to rise from silence to the full amplitude.
+    pub attack: f32,
+    /// Time to fall from the full amplitude to the sustain level.
+    pub decay: f32,
+    /// Amplitude level (in `[0; 1]` range) that is held during sustain stage.
+    pub sustain_level: f32,
+    /// Duration of the sustain stage.
+    pub sustain_time: f32,
+    /// Time to fall from the sustain level to silence.
+    pub release: f32,
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self {
+            attack: 0.01,
+            decay: 0.1,
+            sustain_level: 0.7,
+            sustain_time: 0.5,
+            release: 0.2,
+        }
+    }
+}
+
+impl Envelope {
+    /// Returns total duration of the envelope.
+    pub fn duration(&self) -> f32 {
+        self.attack.max(0.0)
+            + self.decay.max(0.0)
+            + self.sustain_time.max(0.0)
+            + self.release.max(0.0)
+    }
+
+    /// Returns envelope gain at the given time (in seconds) or `None` if the envelope is finished.
+    pub fn gain(&self, mut time: f32) -> Option<f32> {
+        if time < self.attack {
+            return Some(time / self.attack);
+        }
+        time -= self.attack.max(0.0);
+        if time < self.decay {
+            return Some(1.0 + (self.sustain_level - 1.0) * time / self.decay);
+        }
+        time -= self.decay.max(0.0);
+        if time < self.sustain_time {
+            return Some(self.sustain_level);
+        }
+        time -= self.sustain_time.max(0.0);
+        if time < self.release {
+            return Some(self.sustain_level * (1.0 - time / self.release));
+        }
+        None
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Signal {
+    Oscillator { waveform: Waveform, frequency: f32 },
+    Noise(NoiseColor),
+}
+
+/// Mono signal generator. It produces an infinite signal, unless it has an envelope - in this case
+/// it stops when the envelope is finished. Use [`Generator::oscillator`] or [`Generator::noise`] to
+/// create one. Frequency of an oscillator could be changed at runtime by changing pitch of a sound
+/// source that plays the generator.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    signal: Signal,
+    amplitude: f32,
+    envelope: Option<Envelope>,
+    sample_rate: usize,
+    // Amount of generated samples.
+    position: usize,
+    // State of xorshift random number generator.
+    random: u32,
+    // State of pink noise filter.
+    pink: [f32; 7],
+}
+
+impl Generator {
+    fn new(signal: Signal) -> Self {
+        Self {
+            signal,
+            amplitude: 1.0,
+            envelope: None,
+            sample_rate: SAMPLE_RATE as usize,
+            position: 0,
+            random: 0x9E37_79B9,
+            pink: Default::default(),
+        }
+    }
+
+    /// Creates a new oscillator with the given waveform and frequency (in Hz).
+    pub fn oscillator(waveform: Waveform, frequency: f32) -> Self {
+        Self::new(Signal::Oscillator {
+            waveform,
+            frequency,
+        })
+    }
+
+    /// Creates a new noise generator with the given color.
+    pub fn noise(color: NoiseColor) -> Self {
+        Self::new(Signal::Noise(color))
+    }
+
+    /// Sets desired amplitude of the signal. Default is 1.0.
+    pub fn with_amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Sets desired envelope of the signal. Default is `None` - the signal is infinite.
+    pub fn with_envelope(mut self, envelope: Envelope) -> Self {
+        self.envelope = Some(envelope);
+        self
+    }
+
+    /// Sets desired sample rate of the signal. Default is [`SAMPLE_RATE`].
+    pub fn with_sample_rate(mut self, sample_rate: usize) -> Self {
+        self.sample_rate = sample_rate.max(1);
+        self
+    }
+
+    fn white_noise(&mut self) -> f32 {
+        // xorshift32
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 17;
+        self.random ^= self.random << 5;
+        (self.random as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    fn pink_noise(&mut self) -> f32 {
+        // Paul Kellet's refined method.
+        let white = self.white_noise();
+        let b = &mut self.pink;
+        b[0] = 0.99886 * b[0] + white * 0.0555179;
+        b[1] = 0.99332 * b[1] + white * 0.0750759;
+        b[2] = 0.96900 * b[2] + white * 0.153852;
+        b[3] = 0.86650 * b[3] + white * 0.3104856;
+        b[4] = 0.55000 * b[4] + white * 0.5329522;
+        b[5] = -0.7616 * b[5] - white * 0.0168980;
+        let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+        b[6] = white * 0.115926;
+        pink * 0.11
+    }
+}
+
+impl Iterator for Generator {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let gain = match self.envelope {
+            Some(envelope) => envelope.gain(self.position as f32 / self.sample_rate as f32)?,
+            None => 1.0,
+        };
+
+        let value = match self.signal {
+            Signal::Oscillator {
+                waveform,
+                frequency,
+            } => {
+                // Use double precision, otherwise phase will drift noticeably over time.
+                let phase = (frequency as f64 * self.position as f64 / self.sample_rate as f64)
+                    .rem_euclid(1.0);
+                waveform.sample(phase as f32)
+            }
+            Signal::Noise(NoiseColor::White) => self.white_noise(),
+            Signal::Noise(NoiseColor::Pink) => self.pink_noise(),
+        };
+
+        self.position += 1;
+
+        Some(value * self.amplitude * gain)
+    }
+}
+
+impl RawStreamingDataSource for Generator {
+    fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    fn channel_count(&self) -> usize {
+        1
+    }
+
+    fn time_seek(&mut self, duration: Duration) -> Result<(), SoundError> {
+        self.position = (duration.as_secs_f64() * self.sample_rate as f64) as usize;
+        Ok(())
+    }
+
+    fn channel_duration_in_samples(&self) -> usize {
+        self.envelope
+            .map(|envelope| (envelope.duration() as f64 * self.sample_rate as f64) as usize)
+            .unwrap_or_default()
+    }
+}
+
+/// A callback that fills the given slice with interleaved samples and returns the amount of written
+/// samples. If the amount is less than the length of the slice, the source is considered finished.
+pub type SampleCallback = dyn FnMut(&mut [f32]) -> usize + Send + Sync;
+
+/// A data source that pulls samples from a user-defined callback. The callback is called from the
+/// mixer thread (or from the thread that creates the streaming buffer, for the very first block),
+/// so it must be fast and must not block.
+pub struct CallbackSource {
+    sample_rate: usize,
+    channel_count: usize,
+    block: Vec<f32>,
+    // Read position in the block and amount of valid samples in it.
+    position: usize,
+    len: usize,
+    finished: bool,
+    callback: Box<SampleCallback>,
+}
+
+impl Debug for CallbackSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackSource")
+            .field("sample_rate", &self.sample_rate)
+            .field("channel_count", &self.channel_count)
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+impl CallbackSource {
+    /// Default amount of samples (per channel) requested from the callback at once.
+    pub const DEFAULT_BLOCK_SIZE: usize = 512;
+
+    /// Creates a new callback source with the given sample rate and channel count (1 or 2). See
+    /// [`SampleCallback`] docs for more info about the callback.
+    pub fn new<F>(sample_rate: usize, channel_count: usize, callback: F) -> Self
+    where
+        F: FnMut(&mut [f32]) -> usize + Send + Sync + 'static,
+    {
+        assert!(
+            channel_count == 1 || channel_count == 2,
+            "Only mono and stereo sources are supported!"
+        );
+
+        Self {
+            sample_rate,
+            channel_count,
+            block: vec![0.0; Self::DEFAULT_BLOCK_SIZE * channel_count],
+            position: 0,
+            len: 0,
+            finished: false,
+            callback: Box::new(callback),
+        }
+    }
+
+    /// Sets amount of samples (per channel) that will be requested from the callback at once.
+    /// Smaller values reduce latency, but increase amount of callback calls.
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block = vec![0.0; block_size.max(1) * self.channel_count];
+        self
+    }
+}
+
+impl Iterator for CallbackSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position == self.len {
+            if self.finished {
+                return None;
+            }
+            let written = (self.callback)(&mut self.block).min(self.block.len());
+            if written < self.block.len() {
+                self.finished = true;
+            }
+            // Keep only complete frames, otherwise channels will be mixed up.
+            self.len = written - written % self.channel_count;
+            self.position = 0;
+            if self.len == 0 {
+                return None;
+            }
+        }
+
+        let sample = self.block[self.position];
+        self.position += 1;
+        Some(sample)
+    }
+}
+
+impl RawStreamingDataSource for CallbackSource {
+    fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    fn block_size(&self) -> usize {
+        self.block.len() / self.channel_count
+    }
+}
+
+#[derive(Debug)]
+struct SampleQueueShared {
+    queue: ArrayQueue<f32>,
+    closed: AtomicBool,
+    underruns: AtomicUsize,
+}
+
+/// Creates a new lock-free single-producer single-consumer sample queue that can hold `capacity`
+/// samples (per channel). The producer can be sent to any thread to push samples, while the
+/// source should be wrapped into a streaming buffer and played by a sound source. Channel count
+/// must be either 1 or 2.
+pub fn sample_queue(
+    sample_rate: usize,
+    channel_count: usize,
+    capacity: usize,
+) -> (SampleQueueProducer, SampleQueueSource) {
+    assert!(
+        channel_count == 1 || channel_count == 2,
+        "Only mono and stereo sources are supported!"
+    );
+
+    let shared = Arc::new(SampleQueueShared {
+        queue: ArrayQueue::new(capacity.max(1) * channel_count),
+        closed: AtomicBool::new(false),
+        underruns: AtomicUsize::new(0),
+    });
+
+    (
+        SampleQueueProducer {
+            shared: shared.clone(),
+            channel_count,
+        },
+        SampleQueueSource {
+            shared,
+            sample_rate,
+            channel_count,
+            channel: 0,
+            silent_frame: false,
+        },
+    )
+}
+
+/// Writing end of a sample queue, see [`sample_queue`]. The source will stop playing once the
+/// producer is dropped and all pushed samples are played.
+#[derive(Debug)]
+pub struct SampleQueueProducer {
+    shared: Arc<SampleQueueShared>,
+    channel_count: usize,
+}
+
+impl SampleQueueProducer {
+    /// Pushes interleaved samples to the queue and returns the amount of pushed samples. Only complete
+    /// frames are pushed and only if there's enough free space for them, the rest of the samples should
+    /// be pushed later.
+    pub fn push(&mut self, samples: &[f32]) -> usize {
+        let free = self.shared.queue.capacity() - self.shared.queue.len();
+        let count = samples.len().min(free);
+        let count = count - count % self.channel_count;
+        for &sample in &samples[..count] {
+            // There's only one producer, so the free space can only grow meanwhile.
+            let _ = self.shared.queue.push(sample);
+        }
+        count
+    }
+
+    /// Returns amount of samples that can be pushed to the queue right now.
+    pub fn free_space(&self) -> usize {
+        self.shared.queue.capacity() - self.shared.queue.len()
+    }
+
+    /// Returns amount of samples that are waiting to be played.
+    pub fn len(&self) -> usize {
+        self.shared.queue.len()
+    }
+
+    /// Returns `true` if there's no samples waiting to be played.
+    pub fn is_empty(&self) -> bool {
+        self.shared.queue.is_empty()
+    }
+
+    /// Returns the amount of frames that were filled with silence, because the queue was empty. A
+    /// constantly growing value means that samples are pushed too slowly.
+    pub fn underrun_count(&self) -> usize {
+        self.shared.underruns.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for SampleQueueProducer {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+}
+
+/// Reading end of a sample queue, see [`sample_queue`]. It plays silence when the queue is empty.
+#[derive(Debug)]
+pub struct SampleQueueSource {
+    shared: Arc<SampleQueueShared>,
+    sample_rate: usize,
+    channel_count: usize,
+    // Index of the channel of the next sample.
+    channel: usize,
+    // Whether the current frame is filled with silence.
+    silent_frame: bool,
+}
+
+impl Iterator for SampleQueueSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.channel == 0 {
+            // The producer could be in the middle of pushing a frame, so start a new frame only
+            // if it is complete.
+            let closed = self.shared.closed.load(Ordering::Acquire);
+            if self.shared.queue.len() >= self.channel_count {
+                self.silent_frame = false;
+            } else if closed {
+                return None;
+            } else {
+                self.silent_frame = true;
+                self.shared.underruns.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let sample = if self.silent_frame {
+            0.0
+        } else {
+            self.shared.queue.pop().unwrap_or_default()
+        };
+
+        self.channel = (self.channel + 1) % self.channel_count;
+
+        Some(sample)
+    }
+}
+
+impl RawStreamingDataSource for SampleQueueSource {
+    fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    fn block_size(&self) -> usize {
+        CallbackSource::DEFAULT_BLOCK_SIZE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{
+            procedural::{sample_queue, CallbackSource, Envelope, Generator, Waveform},
+            DataSource, RawStreamingDataSource, SoundBufferResource, SoundBufferResourceExtension,
+        },
+        context::{SoundContext, SAMPLE_RATE},
+        source::{SoundSourceBuilder, Status},
+    };
+
+    #[test]
+    fn test_envelope() {
+        let envelope = Envelope {
+            attack: 1.0,
+            decay: 1.0,
+            sustain_level: 0.5,
+            sustain_time: 1.0,
+            release: 1.0,
+        };
+
+        assert_eq!(envelope.duration(), 4.0);
+        assert_eq!(envelope.gain(0.5), Some(0.5));
+        assert_eq!(envelope.gain(1.5), Some(0.75));
+        assert_eq!(envelope.gain(2.5), Some(0.5));
+        assert_eq!(envelope.gain(3.5), Some(0.25));
+        assert_eq!(envelope.gain(4.5), None);
+    }
+
+    #[test]
+    fn test_generator() {
+        let generator = Generator::oscillator(Waveform::Square, 1.0)
+            .with_sample_rate(4)
+            .with_amplitude(0.5);
+        assert_eq!(
+            generator.take(8).collect::<Vec<_>>(),
+            [0.5, 0.5, -0.5, -0.5, 0.5, 0.5, -0.5, -0.5]
+        );
+
+        let mut generator = Generator::oscillator(Waveform::Sine, 440.0).with_envelope(Envelope {
+            attack: 0.0,
+            decay: 0.0,
+            sustain_level: 1.0,
+            sustain_time: 0.5,
+            release: 0.0,
+        });
+        let len = SAMPLE_RATE as usize / 2;
+        assert_eq!(generator.channel_duration_in_samples(), len);
+        assert_eq!(generator.by_ref().count(), len);
+        generator.rewind().unwrap();
+        assert!(generator.next().is_some());
+    }
+
+    #[test]
+    fn test_generator_stops_source() {
+        let generator = Generator::oscillator(Waveform::Triangle, 440.0).with_envelope(Envelope {
+            attack: 0.0,
+            decay: 0.0,
+            sustain_level: 1.0,
+            sustain_time: 0.01,
+            release: 0.0,
+        });
+        let buffer =
+            SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(generator)))
+                .unwrap();
+
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let source = state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_status(Status::Playing)
+                .build()
+                .unwrap(),
+        );
+
+        let mut output = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render(&mut output);
+
+        assert!(output.iter().any(|(left, _)| *left != 0.0));
+        assert_eq!(state.source(source).status(), Status::Stopped);
+    }
+
+    #[test]
+    fn test_callback_source() {
+        let mut counter = 0;
+        let callback = CallbackSource::new(SAMPLE_RATE as usize, 2, move |samples| {
+            if counter == 2 {
+                return 3;
+            }
+            counter += 1;
+            samples.fill(1.0);
+            samples.len()
+        })
+        .with_block_size(2);
+
+        assert_eq!(callback.block_size(), 2);
+        // Two complete blocks and one incomplete frame that must be discarded.
+        assert_eq!(callback.collect::<Vec<_>>(), [1.0; 10]);
+    }
+
+    #[test]
+    fn test_sample_queue() {
+        let (mut producer, mut source) = sample_queue(SAMPLE_RATE as usize, 2, 2);
+
+        // Incomplete frames must not be pushed.
+        assert_eq!(producer.push(&[1.0, 2.0, 3.0]), 2);
+        assert_eq!(producer.push(&[3.0, 4.0, 5.0, 6.0]), 2);
+        assert_eq!(producer.free_space(), 0);
+
+        assert_eq!(
+            source.by_ref().take(4).collect::<Vec<_>>(),
+            [1.0, 2.0, 3.0, 4.0]
+        );
+
+        // Underrun produces silence.
+        assert_eq!(source.by_ref().take(2).collect::<Vec<_>>(), [0.0, 0.0]);
+        assert_eq!(producer.underrun_count(), 1);
+
+        producer.push(&[7.0, 8.0]);
+        drop(producer);
+        assert_eq!(source.collect::<Vec<_>>(), [7.0, 8.0]);
+    }
+}
@@ -141,10 +141,20 @@ impl StreamingSource {
         }
     }
 
+    #[inline]
+    fn block_size(&self) -> usize {
+        match self {
+            StreamingSource::Raw(raw) => raw.block_size().max(1),
+            StreamingSource::Decoder(_) | StreamingSource::Null => {
+                StreamingBuffer::STREAM_SAMPLE_COUNT
+            }
+        }
+    }
+
     #[inline]
     fn read_next_samples_block_into(&mut self, buffer: &mut Vec<f32>) -> usize {
         buffer.clear();
-        let count = StreamingBuffer::STREAM_SAMPLE_COUNT * self.channel_count();
+        let count = self.block_size() * self.channel_count();
         match self {
             StreamingSource::Decoder(decoder) => {
                 for _ in 0..count {
@@ -173,11 +183,12 @@ impl StreamingSource {
 
 impl StreamingBuffer {
     /// Defines amount of samples `per channel` which each streaming buffer will use for internal buffer.
+    /// Raw streaming sources could override it, see [`RawStreamingDataSource::block_size`].
     pub const STREAM_SAMPLE_COUNT: usize = 44100;
 
     /// Creates new streaming buffer using given data source. May fail if data source has unsupported format
     /// or it has corrupted data. Length of internal generic buffer cannot be changed but can be fetched from
-    /// `StreamingBuffer::STREAM_SAMPLE_COUNT`
+    /// [`StreamingBuffer::block_size`]
     ///
     /// # Notes
     ///
@@ -209,6 +220,12 @@ impl StreamingBuffer {
             .read_next_samples_block_into(&mut self.generic.samples);
     }
 
+    /// Returns amount of samples (per channel) that the buffer reads from its source at once.
+    #[inline]
+    pub fn block_size(&self) -> usize {
+        self.streaming_source.block_size()
+    }
+
     #[inline]
     pub(crate) fn rewind(&mut self) -> Result<(), SoundError> {
         self.streaming_source.rewind()
@@ -21,10 +21,18 @@
 //! Everything related to audio buses and audio bus graphs. See docs of [`AudioBus`] and [`AudioBusGraph`]
 //! for more info and examples
 
-use crate::effects::{Effect, EffectRenderTrait};
+use crate::{
+    dsp::{db_to_linear, linear_to_db},
+    effects::{
+        compressor::{compute_gain_reduction, follow_envelope, time_constant_coefficient},
+        Effect, EffectRenderTrait,
+    },
+    meter::{BusMeter, MeterHandle},
+};
 use fyrox_core::{
     pool::{Handle, Pool, Ticket},
     reflect::prelude::*,
+    uuid_provider,
     visitor::prelude::*,
 };
 use std::fmt::{Debug, Formatter};
@@ -94,6 +102,201 @@ impl PingPongBuffer {
             &mut self.buffer2
         }
     }
+
+    fn prepare(&mut self, size: usize) {
+        if self.capacity() < size {
+            self.resize(size);
+        } else {
+            self.clear();
+        }
+    }
+}
+
+// Extra channel pair of multichannel layouts. Each pair is processed by its own instances of the
+// effects of the bus, so the effects keep independent state (delay lines, envelopes, etc.) for
+// each pair.
+#[derive(Default, Clone, Debug)]
+struct ChannelPair {
+    buffer: PingPongBuffer,
+    effects: Vec<Effect>,
+}
+
+impl ChannelPair {
+    // Only parameters of the effect are copied here, the instances are created off the audio
+    // thread by `AudioBus::prepare_channel_pairs`.
+    fn apply_effect(&mut self, index: usize, prototype: &Effect) {
+        let effect = &mut self.effects[index];
+        effect.sync_parameters(prototype);
+        let (input, output) = self.buffer.input_output_buffers();
+        effect.render(input, output);
+        self.buffer.swap();
+    }
+
+    fn has_instance_of(&self, index: usize, prototype: &Effect) -> bool {
+        self.effects
+            .get(index)
+            .is_some_and(|effect| effect.is_same_kind(prototype))
+    }
+}
+
+/// Sidechain ducking reduces the volume of an audio bus when the signal of some other audio bus
+/// (sidechain) exceeds the threshold. A typical example is to make music quieter when a character
+/// speaks: "Music" bus is ducked by "Dialogue" bus. The sidechain bus is bound by its name, just
+/// like sound sources are bound to audio buses.
+///
+/// The level of the sidechain bus is measured on its processed signal (after its effects and
+/// gain). The gain reduction is applied to the processed signal of the ducked bus (after its
+/// effects, before its gain), so the ducked signal is mixed to the parent bus. Signals of child
+/// buses are mixed after that, so they're neither measured nor ducked - add ducking to a child bus
+/// if it needs it.
+///
+/// # Examples
+///
+/// ```rust
+/// use fyrox_sound::bus::{AudioBus, AudioBusGraph, Ducking};
+///
+/// let mut graph = AudioBusGraph::new();
+/// let primary_bus = graph.primary_bus_handle();
+///
+/// graph.add_bus(AudioBus::new("Dialogue".to_string()), primary_bus);
+///
+/// let mut music = AudioBus::new("Music".to_string());
+/// let mut ducking = Ducking::new("Dialogue");
+/// ducking.set_threshold_db(-30.0);
+/// ducking.set_ratio(6.0);
+/// music.set_ducking(Some(ducking));
+/// graph.add_bus(music, primary_bus);
+/// ```
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Ducking {
+    #[reflect(description = "Name of an audio bus, which signal level drives the ducking.")]
+    sidechain_bus: String,
+
+    #[reflect(
+        description = "A level (in decibels) of the sidechain signal above which the ducking starts.",
+        setter = "set_threshold_db",
+        max_value = 0.0
+    )]
+    threshold_db: f32,
+
+    #[reflect(
+        description = "Ducking ratio. For example, 4.0 means that every 4 dB of the sidechain \
+        signal above the threshold reduce the volume by 3 dB.",
+        setter = "set_ratio",
+        min_value = 1.0
+    )]
+    ratio: f32,
+
+    #[reflect(
+        description = "Time (in milliseconds) which is needed to reduce the volume.",
+        setter = "set_attack_ms",
+        min_value = 0.0
+    )]
+    attack_ms: f32,
+
+    #[reflect(
+        description = "Time (in milliseconds) which is needed to restore the volume after the \
+        sidechain signal became quiet.",
+        setter = "set_release_ms",
+        min_value = 0.0
+    )]
+    release_ms: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    envelope_db: f32,
+}
+
+uuid_provider!(Ducking = "a3c1f9e2-6b4d-4f8a-9e07-2d5b8c1f6a93");
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self::new(AudioBusGraph::PRIMARY_BUS)
+    }
+}
+
+impl Ducking {
+    /// Creates new ducking driven by an audio bus with the given name. Default parameters are
+    /// -30 dB threshold, 4:1 ratio, 20 ms attack and 300 ms release.
+    pub fn new<S: AsRef<str>>(sidechain_bus: S) -> Self {
+        Self {
+            sidechain_bus: sidechain_bus.as_ref().to_owned(),
+            threshold_db: -30.0,
+            ratio: 4.0,
+            attack_ms: 20.0,
+            release_ms: 300.0,
+            envelope_db: 0.0,
+        }
+    }
+
+    /// Sets the name of an audio bus, which signal level drives the ducking.
+    pub fn set_sidechain_bus<S: AsRef<str>>(&mut self, name: S) {
+        name.as_ref().clone_into(&mut self.sidechain_bus);
+    }
+
+    /// Returns the name of an audio bus, which signal level drives the ducking.
+    pub fn sidechain_bus(&self) -> &str {
+        &self.sidechain_bus
+    }
+
+    /// Sets a level (in decibels) of the sidechain signal above which the ducking starts.
+    pub fn set_threshold_db(&mut self, threshold_db: f32) {
+        self.threshold_db = threshold_db.min(0.0);
+    }
+
+    /// Returns current threshold in decibels.
+    pub fn threshold_db(&self) -> f32 {
+        self.threshold_db
+    }
+
+    /// Sets ducking ratio. The value is clamped to `[1.0; +inf)` range.
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.max(1.0);
+    }
+
+    /// Returns current ducking ratio.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Sets attack time in milliseconds.
+    pub fn set_attack_ms(&mut self, attack_ms: f32) {
+        self.attack_ms = attack_ms.max(0.0);
+    }
+
+    /// Returns attack time in milliseconds.
+    pub fn attack_ms(&self) -> f32 {
+        self.attack_ms
+    }
+
+    /// Sets release time in milliseconds.
+    pub fn set_release_ms(&mut self, release_ms: f32) {
+        self.release_ms = release_ms.max(0.0);
+    }
+
+    /// Returns release time in milliseconds.
+    pub fn release_ms(&self) -> f32 {
+        self.release_ms
+    }
+
+    /// Returns current gain reduction in decibels (non-positive value).
+    pub fn gain_reduction_db(&self) -> f32 {
+        self.envelope_db
+    }
+
+    /// Advances the envelope by one sample with the given peak level of the sidechain signal and
+    /// returns the gain of the ducked signal.
+    fn next_gain(&mut self, level: f32, attack_coefficient: f32, release_coefficient: f32) -> f32 {
+        let target =
+            compute_gain_reduction(linear_to_db(level), self.threshold_db, self.ratio, 0.0);
+        self.envelope_db = follow_envelope(
+            self.envelope_db,
+            target,
+            attack_coefficient,
+            release_coefficient,
+        );
+        db_to_linear(self.envelope_db)
+    }
 }
 
 /// Audio bus is a top-level audio processing unit. It takes data from multiple audio sources and passes their
@@ -106,6 +309,27 @@ pub struct AudioBus {
     effects: Vec<Effect>,
     gain: f32,
 
+    #[visit(optional)]
+    ducking: Option<Ducking>,
+
+    #[visit(optional)]
+    max_voices: Option<usize>,
+
+    #[visit(optional)]
+    #[reflect(
+        description = "Whether to measure peak, RMS and loudness of the output of the audio bus.",
+        setter = "set_metering_enabled"
+    )]
+    metering: bool,
+
+    #[visit(optional)]
+    #[reflect(
+        description = "Whether to compute magnitude spectrum of the output of the audio bus. \
+        Works only if metering is enabled.",
+        setter = "set_spectrum_analysis_enabled"
+    )]
+    spectrum_analysis: bool,
+
     #[reflect(hidden)]
     child_buses: Vec<Handle<AudioBus>>,
 
@@ -115,6 +339,25 @@ pub struct AudioBus {
     #[reflect(hidden)]
     #[visit(skip)]
     ping_pong_buffer: PingPongBuffer,
+
+    // Channel pairs after the front one for multichannel layouts.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    extra_channel_pairs: Vec<ChannelPair>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) voice_count: usize,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    meter: BusMeter,
+
+    // Effects that are controlled by the game engine (for example, by reverb zones). They're
+    // processed after the regular effects and never serialized.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    transient_effects: Vec<Effect>,
 }
 
 impl Default for AudioBus {
@@ -124,8 +367,16 @@ impl Default for AudioBus {
             child_buses: Default::default(),
             effects: Default::default(),
             gain: 1.0,
+            ducking: None,
+            max_voices: None,
+            metering: false,
+            spectrum_analysis: false,
             ping_pong_buffer: Default::default(),
+            extra_channel_pairs: Default::default(),
             parent_bus: Default::default(),
+            voice_count: 0,
+            meter: Default::default(),
+            transient_effects: Default::default(),
         }
     }
 }
@@ -172,35 +423,232 @@ impl AudioBus {
         self.gain
     }
 
+    /// Sets new sidechain ducking of the audio bus, `None` disables ducking. See [`Ducking`] docs
+    /// for more info.
+    pub fn set_ducking(&mut self, ducking: Option<Ducking>) {
+        self.ducking = ducking;
+    }
+
+    /// Returns a reference to current sidechain ducking of the audio bus (if any).
+    pub fn ducking(&self) -> Option<&Ducking> {
+        self.ducking.as_ref()
+    }
+
+    /// Returns a reference to current sidechain ducking of the audio bus (if any).
+    pub fn ducking_mut(&mut self) -> Option<&mut Ducking> {
+        self.ducking.as_mut()
+    }
+
+    /// Sets maximum amount of sound sources that can be mixed into the audio bus at the same time,
+    /// `None` means that the amount is limited only by the global voice limit of the sound context.
+    /// Sources that do not fit into the limit are virtualized, see
+    /// [`SoundSource::is_virtual`](crate::source::SoundSource::is_virtual) for more info.
+    pub fn set_max_voices(&mut self, max_voices: Option<usize>) {
+        self.max_voices = max_voices;
+    }
+
+    /// Returns maximum amount of sound sources that can be mixed into the audio bus at the same time.
+    pub fn max_voices(&self) -> Option<usize> {
+        self.max_voices
+    }
+
+    /// Returns amount of sound sources that were mixed into the audio bus during the last render.
+    pub fn voice_count(&self) -> usize {
+        self.voice_count
+    }
+
+    /// Enables or disables metering of the audio bus output. When enabled, the audio bus measures
+    /// peak, RMS and loudness of its output each render, the measurements could be fetched using
+    /// [`Self::meter`].
+    pub fn set_metering_enabled(&mut self, enabled: bool) {
+        self.metering = enabled;
+        if enabled {
+            self.meter.prepare(self.spectrum_analysis);
+        } else {
+            self.meter.release();
+        }
+    }
+
+    /// Returns `true` if metering of the audio bus output is enabled.
+    pub fn is_metering_enabled(&self) -> bool {
+        self.metering
+    }
+
+    /// Enables or disables spectrum analysis of the audio bus output. It has effect only if metering
+    /// is enabled, see [`Self::set_metering_enabled`].
+    pub fn set_spectrum_analysis_enabled(&mut self, enabled: bool) {
+        self.spectrum_analysis = enabled;
+        if self.metering {
+            self.meter.prepare(enabled);
+        }
+    }
+
+    /// Returns `true` if spectrum analysis of the audio bus output is enabled.
+    pub fn is_spectrum_analysis_enabled(&self) -> bool {
+        self.spectrum_analysis
+    }
+
+    /// Returns a thread-safe handle to the measurements of the audio bus output, or `None` if
+    /// metering is disabled. See [`crate::meter`] module docs for more info.
+    pub fn meter(&self) -> Option<MeterHandle> {
+        self.metering.then(|| self.meter.handle())
+    }
+
+    /// Resets integrated loudness measurement, so it starts from scratch.
+    pub fn reset_integrated_loudness(&mut self) {
+        self.meter.reset_integrated_loudness();
+    }
+
+    // Allocates metering state, which is missing after cloning or deserialization of the bus.
+    pub(crate) fn prepare_meter(&mut self) {
+        if self.metering {
+            self.meter.prepare(self.spectrum_analysis);
+        }
+    }
+
     pub(crate) fn input_buffer(&mut self) -> &mut [(f32, f32)] {
         self.ping_pong_buffer.input_mut()
     }
 
+    pub(crate) fn channel_pair_input_buffers(&mut self) -> impl Iterator<Item = &mut [(f32, f32)]> {
+        std::iter::once(self.ping_pong_buffer.input_mut()).chain(
+            self.extra_channel_pairs
+                .iter_mut()
+                .map(|pair| pair.buffer.input_mut()),
+        )
+    }
+
+    fn channel_pair_outputs(&self) -> impl Iterator<Item = &[(f32, f32)]> {
+        std::iter::once(self.ping_pong_buffer.input_ref()).chain(
+            self.extra_channel_pairs
+                .iter()
+                .map(|pair| pair.buffer.input_ref()),
+        )
+    }
+
+    // Creates extra channel pairs of the layout along with their own instances of the effects,
+    // so the audio thread only copies parameters of the effects.
+    fn prepare_channel_pairs(&mut self, channel_pair_count: usize) {
+        self.extra_channel_pairs
+            .resize_with(channel_pair_count.saturating_sub(1), Default::default);
+        let prototypes = || self.effects.iter().chain(&self.transient_effects);
+        for pair in self.extra_channel_pairs.iter_mut() {
+            pair.effects.truncate(prototypes().count());
+            for (effect, prototype) in pair.effects.iter_mut().zip(prototypes()) {
+                effect.prepare_instance(prototype);
+            }
+            let prepared = pair.effects.len();
+            pair.effects.extend(prototypes().skip(prepared).cloned());
+        }
+    }
+
     pub(crate) fn begin_render(&mut self, buffer_size: usize) {
-        if self.ping_pong_buffer.capacity() < buffer_size {
-            self.ping_pong_buffer.resize(buffer_size);
-        } else {
-            self.ping_pong_buffer.clear();
+        self.ping_pong_buffer.prepare(buffer_size);
+        for pair in self.extra_channel_pairs.iter_mut() {
+            pair.buffer.prepare(buffer_size);
         }
     }
 
     fn apply_effects(&mut self) {
         // Pass through the chain of effects.
-        for effect in self.effects.iter_mut() {
+        for (index, effect) in self
+            .effects
+            .iter_mut()
+            .chain(self.transient_effects.iter_mut())
+            .enumerate()
+        {
+            // An effect that was replaced by an effect of other kind (or added bypassing
+            // `add_effect`) has no instances for the extra channel pairs until they're prepared
+            // off the audio thread. It is bypassed in every channel pair meanwhile, so the
+            // channels do not diverge.
+            if !self
+                .extra_channel_pairs
+                .iter()
+                .all(|pair| pair.has_instance_of(index, effect))
+            {
+                continue;
+            }
+
             let (input, output) = self.ping_pong_buffer.input_output_buffers();
             effect.render(input, output);
             self.ping_pong_buffer.swap();
+
+            for pair in self.extra_channel_pairs.iter_mut() {
+                pair.apply_effect(index, effect);
+            }
+        }
+    }
+
+    // Peak level of the processed signal at the given sample across all channel pairs.
+    fn output_peak(&self, sample: usize) -> f32 {
+        self.channel_pair_outputs()
+            .filter_map(|pair| pair.get(sample))
+            .fold(0.0f32, |peak, (left, right)| {
+                peak.max(left.abs()).max(right.abs())
+            })
+            * self.gain
+    }
+
+    // Must be called after `apply_effects`, so the input buffers contain the processed signal.
+    fn apply_ducking(&mut self, sidechain: Option<&AudioBus>) {
+        let Some(ducking) = self.ducking.as_mut() else {
+            return;
+        };
+        let attack_coefficient = time_constant_coefficient(ducking.attack_ms);
+        let release_coefficient = time_constant_coefficient(ducking.release_ms);
+        for i in 0..self.ping_pong_buffer.input_ref().len() {
+            // Missing sidechain bus is treated as silence, so the volume is restored smoothly.
+            let level = sidechain.map_or(0.0, |sidechain| sidechain.output_peak(i));
+            let gain = ducking.next_gain(level, attack_coefficient, release_coefficient);
+            for (left, right) in std::iter::once(self.ping_pong_buffer.input_mut())
+                .chain(
+                    self.extra_channel_pairs
+                        .iter_mut()
+                        .map(|pair| pair.buffer.input_mut()),
+                )
+                .filter_map(|pair| pair.get_mut(i))
+            {
+                *left *= gain;
+                *right *= gain;
+            }
+        }
+    }
+
+    pub(crate) fn reserve_effect_buffers(&mut self) {
+        for effect in self
+            .effects
+            .iter_mut()
+            .chain(self.transient_effects.iter_mut())
+            .chain(
+                self.extra_channel_pairs
+                    .iter_mut()
+                    .flat_map(|p| &mut p.effects),
+            )
+        {
+            effect.reserve_buffers();
         }
     }
 
     /// Adds new effect to the effects chain.
     pub fn add_effect(&mut self, effect: Effect) {
+        // Transient effects of the channel pairs go after the regular ones.
+        let index = self.effects.len();
+        for pair in self.extra_channel_pairs.iter_mut() {
+            if index <= pair.effects.len() {
+                pair.effects.insert(index, effect.clone());
+            }
+        }
         self.effects.push(effect)
     }
 
     /// Removes an effect by the given handle.
     pub fn remove_effect(&mut self, index: usize) {
         self.effects.remove(index);
+        for pair in self.extra_channel_pairs.iter_mut() {
+            if index < pair.effects.len() {
+                pair.effects.remove(index);
+            }
+        }
     }
 
     /// Returns a shared reference to an effect at the given handle.
@@ -208,7 +656,9 @@ impl AudioBus {
         self.effects.get(index)
     }
 
-    /// Returns mutable reference to effect at given handle.
+    /// Returns mutable reference to effect at given handle. If the effect is replaced by an
+    /// effect of other kind and the output has more than two channels, the effect is bypassed
+    /// until [`crate::context::SoundContext::prepare_effects`] is called.
     pub fn effect_mut(&mut self, index: usize) -> Option<&mut Effect> {
         self.effects.get_mut(index)
     }
@@ -222,6 +672,19 @@ impl AudioBus {
     pub fn effects_mut(&mut self) -> impl Iterator<Item = &mut Effect> {
         self.effects.iter_mut()
     }
+
+    /// Returns a shared reference to the chain of transient effects. Transient effects are
+    /// processed after the regular effects of the audio bus, but they're not serialized. They're
+    /// intended to be controlled by the game engine at runtime, for example by reverb zones.
+    pub fn transient_effects(&self) -> &[Effect] {
+        &self.transient_effects
+    }
+
+    /// Returns a mutable reference to the chain of transient effects, see
+    /// [`Self::transient_effects`] for more info.
+    pub fn transient_effects_mut(&mut self) -> &mut Vec<Effect> {
+        &mut self.transient_effects
+    }
 }
 
 /// Audio bus graph is a complex audio data processing entity; it allows you to route samples from
@@ -311,6 +774,10 @@ impl AudioBus {
 pub struct AudioBusGraph {
     buses: Pool<AudioBus>,
     root: Handle<AudioBus>,
+    // Amount of channel pairs of the layout of the sound context.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    channel_pair_count: usize,
 }
 
 impl AudioBusGraph {
@@ -323,7 +790,11 @@ impl AudioBusGraph {
         let root = AudioBus::new(Self::PRIMARY_BUS.to_string());
         let mut buses = Pool::new();
         let root = buses.spawn(root);
-        Self { buses, root }
+        Self {
+            buses,
+            root,
+            channel_pair_count: 1,
+        }
     }
 
     /// Adds a new audio bus to the graph and attaches it to the given parent. `parent` handle must be
@@ -344,6 +815,7 @@ impl AudioBusGraph {
     /// ```
     pub fn add_bus(&mut self, mut bus: AudioBus, parent: Handle<AudioBus>) -> Handle<AudioBus> {
         bus.parent_bus = parent;
+        bus.prepare_channel_pairs(self.channel_pair_count);
         let bus = self.buses.spawn(bus);
         self.buses[parent].child_buses.push(bus);
         bus
@@ -371,14 +843,38 @@ impl AudioBusGraph {
         self.buses[parent].child_buses.push(child);
     }
 
-    pub(crate) fn try_get_bus_input_buffer(&mut self, name: &str) -> Option<&mut [(f32, f32)]> {
-        self.buses.iter_mut().find_map(|bus| {
-            if bus.name == name {
-                Some(bus.input_buffer())
-            } else {
-                None
-            }
-        })
+    // Returns a handle of the audio bus with the given name. The previously resolved handle is
+    // checked first, so the search is needed only when the bus was renamed or removed.
+    pub(crate) fn resolve_bus(&self, name: &str, cached: Handle<AudioBus>) -> Handle<AudioBus> {
+        if self
+            .buses
+            .try_borrow(cached)
+            .is_some_and(|bus| bus.name == name)
+        {
+            return cached;
+        }
+        self.buses
+            .pair_iter()
+            .find_map(|(handle, bus)| (bus.name == name).then_some(handle))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn try_get_bus_input_buffer(
+        &mut self,
+        handle: Handle<AudioBus>,
+    ) -> Option<&mut [(f32, f32)]> {
+        self.buses
+            .try_borrow_mut(handle)
+            .map(|bus| bus.input_buffer())
+    }
+
+    pub(crate) fn try_get_bus_channel_pair_input_buffers(
+        &mut self,
+        handle: Handle<AudioBus>,
+    ) -> Option<impl Iterator<Item = &mut [(f32, f32)]>> {
+        self.buses
+            .try_borrow_mut(handle)
+            .map(|bus| bus.channel_pair_input_buffers())
     }
 
     /// Removes an audio bus at the given handle.
@@ -477,55 +973,125 @@ impl AudioBusGraph {
         self.buses.pair_iter_mut()
     }
 
+    // Prepares all buses for rendering in a layout with the given amount of channel pairs. It
+    // allocates memory, so it must be called off the audio thread.
+    pub(crate) fn prepare_channel_pairs(&mut self, channel_pair_count: usize) {
+        self.channel_pair_count = channel_pair_count;
+        for bus in self.buses.iter_mut() {
+            bus.prepare_channel_pairs(channel_pair_count);
+        }
+    }
+
     pub(crate) fn begin_render(&mut self, output_device_buffer_size: usize) {
         for bus in self.buses.iter_mut() {
             bus.begin_render(output_device_buffer_size);
         }
     }
 
-    pub(crate) fn end_render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
-        let mut leafs = Vec::new();
-        for (handle, bus) in self.buses.pair_iter_mut() {
+    fn apply_ducking(&mut self) {
+        for index in 0..self.buses.get_capacity() {
+            let Some(sidechain_bus) = self
+                .buses
+                .at(index)
+                .and_then(|bus| bus.ducking.as_ref())
+                .map(|ducking| ducking.sidechain_bus.as_str())
+            else {
+                continue;
+            };
+            let handle = self.buses.handle_from_index(index);
+            let sidechain = self
+                .buses
+                .pair_iter()
+                .find(|(_, sidechain)| sidechain.name == sidechain_bus)
+                .map(|(sidechain_handle, _)| sidechain_handle)
+                .filter(|sidechain_handle| *sidechain_handle != handle);
+
+            let (ticket, mut bus) = self.buses.take_reserve(handle);
+            bus.apply_ducking(sidechain.and_then(|sidechain| self.buses.try_borrow(sidechain)));
+            self.buses.put_back(ticket, bus);
+        }
+    }
+
+    // Each output buffer contains samples of a channel pair of the layout, front pair goes first.
+    pub(crate) fn end_render<B>(&mut self, output_device_buffers: &mut [B])
+    where
+        B: AsMut<[(f32, f32)]>,
+    {
+        for bus in self.buses.iter_mut() {
             bus.apply_effects();
+        }
 
-            if bus.child_buses.is_empty() {
-                leafs.push(handle);
+        self.apply_ducking();
+
+        for index in 0..self.buses.get_capacity() {
+            if !self
+                .buses
+                .at(index)
+                .is_some_and(|bus| bus.child_buses.is_empty())
+            {
+                continue;
             }
-        }
 
-        for mut leaf in leafs {
+            let mut leaf = self.buses.handle_from_index(index);
             while leaf.is_some() {
                 let ctx = self.buses.begin_multi_borrow();
 
                 let leaf_ref = ctx.try_get_mut(leaf).expect("Malformed bus graph!");
 
-                let input_buffer = leaf_ref.ping_pong_buffer.input_ref();
                 let leaf_gain = leaf_ref.gain;
                 let mut parent_buffer = ctx.try_get_mut(leaf_ref.parent_bus);
-                let output_buffer = parent_buffer
-                    .as_mut()
-                    .map(|parent| parent.ping_pong_buffer.input_mut())
-                    // Special case for the root bus - it writes directly to the output device buffer.
-                    .unwrap_or(&mut *output_device_buffer);
-                for ((input_left, input_right), (output_left, output_right)) in
-                    input_buffer.iter().zip(output_buffer)
-                {
-                    *output_left += *input_left * leaf_gain;
-                    *output_right += *input_right * leaf_gain;
+                for (pair, input_buffer) in leaf_ref.channel_pair_outputs().enumerate() {
+                    let output_buffer = match parent_buffer.as_mut() {
+                        Ok(parent) => {
+                            if pair == 0 {
+                                parent.ping_pong_buffer.input_mut()
+                            } else if let Some(parent_pair) =
+                                parent.extra_channel_pairs.get_mut(pair - 1)
+                            {
+                                parent_pair.buffer.input_mut()
+                            } else {
+                                break;
+                            }
+                        }
+                        // Special case for the root bus - it writes directly to the output device buffer.
+                        Err(_) => match output_device_buffers.get_mut(pair) {
+                            Some(output_device_buffer) => output_device_buffer.as_mut(),
+                            None => break,
+                        },
+                    };
+                    for ((input_left, input_right), (output_left, output_right)) in
+                        input_buffer.iter().zip(output_buffer)
+                    {
+                        *output_left += *input_left * leaf_gain;
+                        *output_right += *input_right * leaf_gain;
+                    }
                 }
 
                 leaf = leaf_ref.parent_bus;
             }
         }
+
+        // At this point input buffer of each bus contains its final output. Metering uses the
+        // front channel pair only.
+        for bus in self.buses.iter_mut() {
+            if bus.metering {
+                bus.meter
+                    .measure(bus.ping_pong_buffer.input_ref(), bus.gain);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
-        bus::{AudioBus, AudioBusGraph},
-        effects::{Attenuate, Effect},
+        bus::{AudioBus, AudioBusGraph, Ducking},
+        effects::{
+            waveshaper::{Waveshaper, WaveshaperCurve},
+            Attenuate, Effect,
+        },
     };
+    use fyrox_core::visitor::{Visit, Visitor};
 
     #[test]
     fn test_multi_bus_data_flow() {
@@ -549,7 +1115,7 @@ mod test {
             *right = 1.0;
         }
 
-        graph.end_render(&mut output_buffer);
+        graph.end_render(&mut [&mut output_buffer]);
 
         assert_eq!(output_buffer[0], (2.0, 2.0));
     }
@@ -568,7 +1134,7 @@ mod test {
             *right = 1.0;
         }
 
-        graph.end_render(&mut output_buffer);
+        graph.end_render(&mut [&mut output_buffer]);
 
         assert_eq!(output_buffer[0], (1.0, 1.0));
     }
@@ -602,8 +1168,175 @@ mod test {
             *right = 1.0;
         }
 
-        graph.end_render(&mut output_buffer);
+        graph.end_render(&mut [&mut output_buffer]);
 
         assert_eq!(output_buffer[0], (0.75, 0.75));
     }
+
+    #[test]
+    fn test_sidechain_ducking() {
+        let mut graph = AudioBusGraph::new();
+
+        let mut music = AudioBus::new("Music".to_string());
+        let mut ducking = Ducking::new("Dialogue");
+        ducking.set_threshold_db(-20.0);
+        ducking.set_ratio(1000.0);
+        ducking.set_attack_ms(0.0);
+        ducking.set_release_ms(0.0);
+        music.set_ducking(Some(ducking));
+        let music = graph.add_bus(music, graph.root);
+        let dialogue = graph.add_bus(AudioBus::new("Dialogue".to_string()), graph.root);
+
+        let mut render = |dialogue_level: f32| {
+            let mut output_buffer = [(0.0f32, 0.0f32)];
+            graph.begin_render(output_buffer.len());
+            for (left, right) in graph.buses[music].input_buffer() {
+                *left = 1.0;
+                *right = 1.0;
+            }
+            for (left, right) in graph.buses[dialogue].input_buffer() {
+                *left = dialogue_level;
+                *right = dialogue_level;
+            }
+            graph.end_render(&mut [&mut output_buffer]);
+            graph.buses[music].ping_pong_buffer.input_ref()[0].0
+        };
+
+        // Quiet dialogue does not affect music.
+        assert_eq!(render(0.01), 1.0);
+
+        // Loud dialogue (0 dB) reduces music volume by ~20 dB.
+        let music_level = render(1.0);
+        assert!((music_level - 0.1).abs() < 0.01, "{music_level}");
+
+        // Music volume is restored when dialogue stops.
+        assert_eq!(render(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_ducking_uses_processed_signals() {
+        let mut graph = AudioBusGraph::new();
+
+        // Music is clipped by its effect, so ducking before the effect would have no effect on
+        // loud signals.
+        let mut music = AudioBus::new("Music".to_string());
+        let mut clipper = Waveshaper::new();
+        clipper.set_curve(WaveshaperCurve::HardClip);
+        clipper.set_drive_db(0.0);
+        clipper.set_output_gain_db(0.0);
+        music.add_effect(Effect::Waveshaper(clipper));
+        let mut ducking = Ducking::new("Dialogue");
+        ducking.set_threshold_db(-20.0);
+        ducking.set_ratio(1000.0);
+        ducking.set_attack_ms(0.0);
+        ducking.set_release_ms(0.0);
+        music.set_ducking(Some(ducking));
+        let music = graph.add_bus(music, graph.root);
+
+        // Dialogue is measured after its effects and gain.
+        let mut dialogue = AudioBus::new("Dialogue".to_string());
+        dialogue.add_effect(Effect::Attenuate(Attenuate::new(0.5)));
+        let dialogue = graph.add_bus(dialogue, graph.root);
+
+        let mut render = |dialogue_level: f32, dialogue_gain: f32| {
+            let mut output_buffer = [(0.0f32, 0.0f32)];
+            graph.buses[dialogue].set_gain(dialogue_gain);
+            graph.begin_render(output_buffer.len());
+            for (left, right) in graph.buses[music].input_buffer() {
+                *left = 4.0;
+                *right = 4.0;
+            }
+            for (left, right) in graph.buses[dialogue].input_buffer() {
+                *left = dialogue_level;
+                *right = dialogue_level;
+            }
+            graph.end_render(&mut [&mut output_buffer]);
+            graph.buses[music].ping_pong_buffer.input_ref()[0].0
+        };
+
+        // 0.4 * 0.5 * 0.5 is -20 dB, right at the threshold.
+        assert_eq!(render(0.4, 0.5), 1.0);
+
+        // 0 dB, the clipped music is reduced by ~20 dB.
+        let music_level = render(4.0, 0.5);
+        assert!((music_level - 0.1).abs() < 0.01, "{music_level}");
+
+        // Zero gain of the sidechain bus silences it.
+        assert_eq!(render(4.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_channel_pair_effects_are_prepared_off_audio_thread() {
+        let mut graph = AudioBusGraph::new();
+        graph.prepare_channel_pairs(2);
+
+        let render = |graph: &mut AudioBusGraph| {
+            let mut front = [(0.0f32, 0.0f32)];
+            let mut rear = [(0.0f32, 0.0f32)];
+            graph.begin_render(1);
+            for buffer in graph.primary_bus_mut().channel_pair_input_buffers() {
+                buffer[0] = (1.0, 1.0);
+            }
+            graph.end_render(&mut [&mut front, &mut rear]);
+            (front[0].0, rear[0].0)
+        };
+
+        graph
+            .primary_bus_mut()
+            .add_effect(Effect::Attenuate(Attenuate::new(0.5)));
+        assert_eq!(render(&mut graph), (0.5, 0.5));
+
+        // An effect that was added bypassing `add_effect` has no instance for the rear pair until
+        // the pairs are prepared, so it is bypassed in both pairs.
+        graph
+            .primary_bus_mut()
+            .transient_effects_mut()
+            .push(Effect::Attenuate(Attenuate::new(0.5)));
+        assert_eq!(render(&mut graph), (0.5, 0.5));
+        graph.prepare_channel_pairs(2);
+        assert_eq!(render(&mut graph), (0.25, 0.25));
+
+        // Parameters are copied during rendering.
+        *graph.primary_bus_mut().effect_mut(0).unwrap() = Effect::Attenuate(Attenuate::new(0.25));
+        assert_eq!(render(&mut graph), (0.125, 0.125));
+
+        // Effects of other kind are re-created only when prepared, until then the effect is
+        // bypassed in every pair.
+        *graph.primary_bus_mut().effect_mut(0).unwrap() = Effect::Waveshaper(Default::default());
+        assert_eq!(render(&mut graph), (0.5, 0.5));
+        graph.prepare_channel_pairs(2);
+        let (front, rear) = render(&mut graph);
+        assert_eq!(front, rear);
+        assert_ne!(front, 0.5);
+    }
+
+    #[test]
+    fn test_transient_effects() {
+        let mut graph = AudioBusGraph::new();
+        graph.prepare_channel_pairs(2);
+        let bus = graph.primary_bus_mut();
+        bus.add_effect(Effect::Attenuate(Attenuate::new(0.5)));
+        bus.transient_effects_mut()
+            .push(Effect::Attenuate(Attenuate::new(0.5)));
+        graph.prepare_channel_pairs(2);
+
+        let mut front = [(0.0f32, 0.0f32)];
+        let mut rear = [(0.0f32, 0.0f32)];
+        graph.begin_render(1);
+        for buffer in graph.primary_bus_mut().channel_pair_input_buffers() {
+            buffer[0] = (1.0, 1.0);
+        }
+        graph.end_render(&mut [&mut front, &mut rear]);
+        assert_eq!((front[0].0, rear[0].0), (0.25, 0.25));
+
+        // Transient effects are not serialized.
+        let mut visitor = Visitor::new();
+        graph.primary_bus_mut().visit("Bus", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut bus = AudioBus::default();
+        bus.visit("Bus", &mut visitor).unwrap();
+        assert_eq!(bus.effects().count(), 1);
+        assert!(bus.transient_effects().is_empty());
+    }
 }
@@ -32,9 +32,11 @@
 
 use crate::bus::AudioBusGraph;
 use crate::{
+    effects::{convolution::ConvolutionProcessor, Effect},
+    layout::ChannelLayout,
     listener::Listener,
     pool::Ticket,
-    renderer::{render_source_default, Renderer},
+    renderer::{render_source_default, render_source_multichannel, Renderer},
     source::{SoundSource, Status},
 };
 use fyrox_core::{
@@ -128,8 +130,29 @@ pub struct SerializationOptions {
     pub skip_bus_graph: bool,
 }
 
+/// Statistics of voices (sound sources) of a sound context, gathered during the last render. It
+/// could be used for profiling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoiceStatistics {
+    /// Total amount of playing sound sources.
+    pub playing_sources: usize,
+    /// Amount of sound sources that were mixed.
+    pub real_sources: usize,
+    /// Amount of sound sources that were virtualized, because they were too quiet.
+    pub inaudible_sources: usize,
+    /// Amount of sound sources that were virtualized, because they did not fit into the voice limits.
+    pub culled_sources: usize,
+}
+
+impl VoiceStatistics {
+    /// Returns total amount of virtualized sound sources.
+    pub fn virtual_sources(&self) -> usize {
+        self.inaudible_sources + self.culled_sources
+    }
+}
+
 /// Internal state of context.
-#[derive(Default, Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect)]
 pub struct State {
     sources: Pool<SoundSource>,
     listener: Listener,
@@ -137,13 +160,52 @@ pub struct State {
     renderer: Renderer,
     bus_graph: AudioBusGraph,
     distance_model: DistanceModel,
+    #[reflect(min_value = 0.0, step = 0.05)]
+    doppler_factor: f32,
+    #[reflect(min_value = 0.0, step = 1.0)]
+    speed_of_sound: f32,
+    max_voices: usize,
+    #[reflect(min_value = 0.0, step = 0.001)]
+    virtualization_threshold: f32,
+    #[reflect(hidden)]
+    voice_statistics: VoiceStatistics,
+    // Playing sources that are loud enough to be mixed, along with their priority and audibility.
+    #[reflect(hidden)]
+    voice_candidates: Vec<(Handle<SoundSource>, i32, f32)>,
     paused: bool,
+    channel_layout: ChannelLayout,
+    // Channel pairs of the context layout, used when it differs from the output layout.
+    #[reflect(hidden)]
+    channel_buffers: Vec<Vec<(f32, f32)>>,
     /// A set of flags, that can be used to define what should be skipped during the
     /// serialization of a sound context.
     #[reflect(hidden)]
     pub serialization_options: SerializationOptions,
 }
 
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            sources: Pool::new(),
+            listener: Listener::new(),
+            render_duration: Default::default(),
+            renderer: Renderer::Default,
+            bus_graph: AudioBusGraph::new(),
+            distance_model: DistanceModel::InverseDistance,
+            doppler_factor: 1.0,
+            speed_of_sound: SoundContext::DEFAULT_SPEED_OF_SOUND,
+            max_voices: SoundContext::DEFAULT_MAX_VOICES,
+            virtualization_threshold: SoundContext::DEFAULT_VIRTUALIZATION_THRESHOLD,
+            voice_statistics: Default::default(),
+            voice_candidates: Default::default(),
+            paused: false,
+            channel_layout: Default::default(),
+            channel_buffers: Default::default(),
+            serialization_options: Default::default(),
+        }
+    }
+}
+
 impl State {
     /// Extracts a source from the context and reserves its handle. It is used to temporarily take
     /// ownership over source, and then put node back using given ticket.
@@ -188,6 +250,61 @@ impl State {
         self.distance_model
     }
 
+    /// Sets new Doppler factor. It scales velocities of sound sources and listener when calculating
+    /// Doppler effect: 0.0 - disables the effect, 1.0 - physically correct effect, values larger
+    /// than 1.0 exaggerate it. Negative values are clamped to zero. Default is 1.0.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) {
+        self.doppler_factor = doppler_factor.max(0.0);
+    }
+
+    /// Returns current Doppler factor.
+    pub fn doppler_factor(&self) -> f32 {
+        self.doppler_factor
+    }
+
+    /// Sets new speed of sound in world units per second, it is used to calculate Doppler effect.
+    /// Default is [`SoundContext::DEFAULT_SPEED_OF_SOUND`] which is the speed of sound in the air
+    /// in meters per second. Change it if your game uses some other units.
+    pub fn set_speed_of_sound(&mut self, speed_of_sound: f32) {
+        self.speed_of_sound = speed_of_sound.max(0.0);
+    }
+
+    /// Returns current speed of sound.
+    pub fn speed_of_sound(&self) -> f32 {
+        self.speed_of_sound
+    }
+
+    /// Sets maximum amount of sound sources that can be mixed at the same time. Playing sources
+    /// that do not fit into the limit are virtualized (see [`SoundSource::is_virtual`]), sources with
+    /// higher priority and then louder sources are mixed first. Each audio bus could also have its own
+    /// limit, see [`crate::bus::AudioBus::set_max_voices`]. Default is
+    /// [`SoundContext::DEFAULT_MAX_VOICES`].
+    pub fn set_max_voices(&mut self, max_voices: usize) {
+        self.max_voices = max_voices;
+    }
+
+    /// Returns maximum amount of sound sources that can be mixed at the same time.
+    pub fn max_voices(&self) -> usize {
+        self.max_voices
+    }
+
+    /// Sets new virtualization threshold. Playing sources with estimated gain (which includes source
+    /// gain, distance attenuation and occlusion) less than the threshold are virtualized (see
+    /// [`SoundSource::is_virtual`]). Default is [`SoundContext::DEFAULT_VIRTUALIZATION_THRESHOLD`].
+    pub fn set_virtualization_threshold(&mut self, threshold: f32) {
+        self.virtualization_threshold = threshold.max(0.0);
+    }
+
+    /// Returns current virtualization threshold.
+    pub fn virtualization_threshold(&self) -> f32 {
+        self.virtualization_threshold
+    }
+
+    /// Returns voice statistics gathered during the last render of the context.
+    pub fn voice_statistics(&self) -> VoiceStatistics {
+        self.voice_statistics
+    }
+
     /// Normalizes given frequency using context's sampling rate. Normalized frequency then can be used
     /// to create filters.
     pub fn normalize_frequency(&self, f: f32) -> f32 {
@@ -274,54 +391,223 @@ impl State {
         &mut self.bus_graph
     }
 
+    /// Sets the channel layout in which the context renders its sounds. Spatial sources are panned
+    /// between the speakers of the layout, audio buses process each channel pair of the layout
+    /// with their effects. The output of the context is then remixed to the layout of the output
+    /// device (see [`crate::engine::State::set_channel_layout`]), so the layout of the context
+    /// should usually match the layout of the output device. Keep in mind, that HRTF renderer
+    /// is used only for stereo layout, other layouts always use vector-base amplitude panning.
+    pub fn set_channel_layout(&mut self, channel_layout: ChannelLayout) {
+        self.channel_layout = channel_layout;
+        self.bus_graph
+            .prepare_channel_pairs(channel_layout.channel_pair_count());
+        self.prepare_channel_buffers();
+    }
+
+    // Allocates channel pair buffers of the context layout, so the audio thread only fills them.
+    fn prepare_channel_buffers(&mut self) {
+        self.channel_buffers
+            .resize_with(self.channel_layout.channel_pair_count(), Default::default);
+        for buffer in self.channel_buffers.iter_mut() {
+            buffer.reserve(SoundContext::SAMPLES_PER_CHANNEL);
+        }
+    }
+
+    /// Returns the channel layout in which the context renders its sounds.
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.channel_layout
+    }
+
+    // Stereo rendering shortcut for tests.
+    #[cfg(test)]
     pub(crate) fn render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
+        self.render_with_layout(ChannelLayout::Stereo, output_device_buffer)
+    }
+
+    // Renders the context into interleaved channel pairs of the given output layout. The result
+    // is added to the samples of the output buffer.
+    pub(crate) fn render_with_layout(
+        &mut self,
+        output_layout: ChannelLayout,
+        output_device_buffer: &mut [(f32, f32)],
+    ) {
         let last_time = fyrox_core::instant::Instant::now();
 
         if !self.paused {
-            self.sources.retain(|source| {
-                let done = source.is_play_once() && source.status() == Status::Stopped;
-                !done
-            });
-
-            self.bus_graph.begin_render(output_device_buffer.len());
-
-            // Render sounds to respective audio buses.
-            for source in self
-                .sources
-                .iter_mut()
-                .filter(|s| s.status() == Status::Playing)
+            let output_pair_count = output_layout.channel_pair_count();
+            let sample_count = output_device_buffer.len() / output_pair_count;
+
+            if self.channel_layout == ChannelLayout::Stereo
+                && output_layout == ChannelLayout::Stereo
             {
-                if let Some(bus_input_buffer) = self.bus_graph.try_get_bus_input_buffer(&source.bus)
+                self.render_channel_pairs(&mut [output_device_buffer], sample_count);
+            } else {
+                // The buffers are allocated off the audio thread (see
+                // `Self::prepare_channel_buffers`), so this does not allocate memory unless the
+                // output buffer is larger than the one of the sound engine.
+                let mut channel_buffers = std::mem::take(&mut self.channel_buffers);
+                channel_buffers
+                    .resize_with(self.channel_layout.channel_pair_count(), Default::default);
+                for buffer in channel_buffers.iter_mut() {
+                    buffer.clear();
+                    buffer.resize(sample_count, (0.0, 0.0));
+                }
+
+                self.render_channel_pairs(&mut channel_buffers, sample_count);
+
+                let matrix = self.channel_layout.remix_matrix(output_layout);
+                let mut channels = [0.0; ChannelLayout::MAX_CHANNELS];
+                for (i, frame) in output_device_buffer
+                    .chunks_exact_mut(output_pair_count)
+                    .enumerate()
                 {
-                    source.render(output_device_buffer.len());
-
-                    match self.renderer {
-                        Renderer::Default => {
-                            // Simple rendering path. Much faster (4-5 times) than HRTF path.
-                            render_source_default(
-                                source,
-                                &self.listener,
-                                self.distance_model,
-                                bus_input_buffer,
-                            );
-                        }
-                        Renderer::HrtfRenderer(ref mut hrtf_renderer) => {
-                            hrtf_renderer.render_source(
-                                source,
-                                &self.listener,
-                                self.distance_model,
-                                bus_input_buffer,
-                            );
+                    for (pair, buffer) in channel_buffers.iter().enumerate() {
+                        (channels[pair * 2], channels[pair * 2 + 1]) = buffer[i];
+                    }
+                    for (pair, (left, right)) in frame.iter_mut().enumerate() {
+                        for (channel, sample) in channels.iter().enumerate() {
+                            *left += matrix[pair * 2][channel] * sample;
+                            *right += matrix[pair * 2 + 1][channel] * sample;
                         }
                     }
                 }
-            }
 
-            self.bus_graph.end_render(output_device_buffer);
+                self.channel_buffers = channel_buffers;
+            }
         }
 
         self.render_duration = fyrox_core::instant::Instant::now() - last_time;
     }
+
+    // Renders the context in its own layout, each output buffer contains a channel pair.
+    fn render_channel_pairs<B>(&mut self, output_device_buffers: &mut [B], sample_count: usize)
+    where
+        B: AsMut<[(f32, f32)]>,
+    {
+        self.sources.retain(|source| {
+            let done = source.is_play_once() && source.status() == Status::Stopped;
+            !done
+        });
+
+        self.bus_graph.begin_render(sample_count);
+
+        self.update_voices();
+
+        // Render sounds to respective audio buses.
+        for source in self
+            .sources
+            .iter_mut()
+            .filter(|s| s.status() == Status::Playing)
+        {
+            if source.is_virtual() {
+                source.advance(sample_count);
+                continue;
+            }
+
+            if self.channel_layout != ChannelLayout::Stereo {
+                if let Some(channel_pairs) = self
+                    .bus_graph
+                    .try_get_bus_channel_pair_input_buffers(source.bus_handle)
+                {
+                    source.render(sample_count);
+
+                    render_source_multichannel(
+                        source,
+                        &self.listener,
+                        self.distance_model,
+                        self.channel_layout,
+                        channel_pairs,
+                    );
+                }
+            } else if let Some(bus_input_buffer) =
+                self.bus_graph.try_get_bus_input_buffer(source.bus_handle)
+            {
+                source.render(sample_count);
+
+                match self.renderer {
+                    Renderer::Default => {
+                        // Simple rendering path. Much faster (4-5 times) than HRTF path.
+                        render_source_default(
+                            source,
+                            &self.listener,
+                            self.distance_model,
+                            bus_input_buffer,
+                        );
+                    }
+                    Renderer::HrtfRenderer(ref mut hrtf_renderer) => {
+                        hrtf_renderer.render_source(
+                            source,
+                            &self.listener,
+                            self.distance_model,
+                            bus_input_buffer,
+                        );
+                    }
+                }
+            }
+        }
+
+        self.bus_graph.end_render(output_device_buffers);
+    }
+
+    // Decides which of the playing sources should be mixed and which should be virtualized.
+    fn update_voices(&mut self) {
+        let mut statistics = VoiceStatistics::default();
+
+        for bus in self.bus_graph.buses_iter_mut() {
+            bus.voice_count = 0;
+        }
+
+        self.voice_candidates.clear();
+        for (handle, source) in self.sources.pair_iter_mut() {
+            if source.status() != Status::Playing {
+                continue;
+            }
+
+            statistics.playing_sources += 1;
+
+            source.bus_handle = self.bus_graph.resolve_bus(&source.bus, source.bus_handle);
+
+            source.doppler_shift = source.calculate_doppler_shift(
+                &self.listener,
+                self.doppler_factor,
+                self.speed_of_sound,
+            );
+
+            let audibility = source.calculate_audibility(&self.listener, self.distance_model);
+            if audibility < self.virtualization_threshold {
+                source.set_virtual(true);
+                statistics.inaudible_sources += 1;
+            } else {
+                self.voice_candidates
+                    .push((handle, source.priority(), audibility));
+            }
+        }
+
+        // Sources with higher priority go first, then the loudest ones.
+        self.voice_candidates
+            .sort_unstable_by(|a, b| b.1.cmp(&a.1).then(b.2.total_cmp(&a.2)));
+
+        for &(handle, _, _) in self.voice_candidates.iter() {
+            let source = &mut self.sources[handle];
+            let Some(bus) = self.bus_graph.try_get_bus_mut(source.bus_handle) else {
+                // There's no such bus, the source won't be rendered anyway.
+                continue;
+            };
+
+            if statistics.real_sources < self.max_voices
+                && bus.max_voices().is_none_or(|max| bus.voice_count < max)
+            {
+                bus.voice_count += 1;
+                statistics.real_sources += 1;
+                source.set_virtual(false);
+            } else {
+                statistics.culled_sources += 1;
+                source.set_virtual(true);
+            }
+        }
+
+        self.voice_statistics = statistics;
+    }
 }
 
 impl SoundContext {
@@ -334,21 +620,23 @@ impl SoundContext {
     pub(crate) const SAMPLES_PER_CHANNEL: usize =
         Self::HRTF_BLOCK_LEN * Self::HRTF_INTERPOLATION_STEPS;
 
+    /// Default speed of sound (speed of sound in the air in meters per second).
+    pub const DEFAULT_SPEED_OF_SOUND: f32 = 343.3;
+
+    /// Default maximum amount of sound sources that can be mixed at the same time.
+    pub const DEFAULT_MAX_VOICES: usize = 128;
+
+    /// Default virtualization threshold, it is equal to -60 dB.
+    pub const DEFAULT_VIRTUALIZATION_THRESHOLD: f32 = 0.001;
+
     /// Creates new instance of context. Internally context starts new thread which will call render all
     /// sound source and send samples to default output device. This method returns `Arc<Mutex<Context>>`
     /// because separate thread also uses context.
     pub fn new() -> Self {
+        let mut state = State::default();
+        state.prepare_channel_buffers();
         Self {
-            state: Some(Arc::new(Mutex::new(State {
-                sources: Pool::new(),
-                listener: Listener::new(),
-                render_duration: Default::default(),
-                renderer: Renderer::Default,
-                bus_graph: AudioBusGraph::new(),
-                distance_model: DistanceModel::InverseDistance,
-                paused: false,
-                serialization_options: Default::default(),
-            }))),
+            state: Some(Arc::new(Mutex::new(state))),
         }
     }
 
@@ -373,6 +661,70 @@ impl SoundContext {
         self.state.as_ref().unwrap().lock().unwrap()
     }
 
+    /// Prepares effects that require heavy preparation, such as
+    /// [`crate::effects::convolution::ConvolutionReverb`], for rendering. The preparation is done
+    /// without holding the lock of the context, so the audio thread is not blocked meanwhile. It
+    /// also allocates internal buffers of effects (for example, delay lines of
+    /// [`crate::effects::delay::Delay`]), audio bus meters and channel buffers of multichannel
+    /// layouts, because the audio thread never allocates memory. The game engine calls this method automatically every frame, if you're
+    /// using the sound engine standalone, call it after loading a scene or changing effects.
+    pub fn prepare_effects(&self) {
+        {
+            let mut state = self.state();
+            let channel_pair_count = state.channel_layout().channel_pair_count();
+            let bus_graph = state.bus_graph_mut();
+            bus_graph.prepare_channel_pairs(channel_pair_count);
+            for bus in bus_graph.buses_iter_mut() {
+                bus.reserve_effect_buffers();
+                bus.prepare_meter();
+            }
+            state.prepare_channel_buffers();
+        }
+
+        let pending = self
+            .state()
+            .bus_graph_ref()
+            .buses_iter()
+            .flat_map(|bus| bus.effects())
+            .filter_map(|effect| match effect {
+                Effect::ConvolutionReverb(reverb) => reverb.pending_impulse_response().cloned(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if pending.is_empty() {
+            return;
+        }
+
+        let mut prepared = pending
+            .into_iter()
+            .map(|impulse_response| {
+                let processor = ConvolutionProcessor::new(&impulse_response).map(Box::new);
+                (impulse_response, processor)
+            })
+            .collect::<Vec<_>>();
+
+        let mut state = self.state();
+        for effect in state
+            .bus_graph_mut()
+            .buses_iter_mut()
+            .flat_map(|bus| bus.effects_mut())
+        {
+            if let Effect::ConvolutionReverb(reverb) = effect {
+                let Some(impulse_response) = reverb.pending_impulse_response() else {
+                    continue;
+                };
+                if let Some(index) = prepared
+                    .iter()
+                    .position(|(prepared, _)| prepared == impulse_response)
+                {
+                    let (impulse_response, processor) = prepared.swap_remove(index);
+                    reverb.install_processor(&impulse_response, processor);
+                }
+            }
+        }
+    }
+
     /// Creates deep copy instead of shallow which is done by clone().
     pub fn deep_clone(&self) -> SoundContext {
         SoundContext {
@@ -405,7 +757,42 @@ impl Visit for State {
         self.renderer.visit("Renderer", &mut region)?;
         self.paused.visit("Paused", &mut region)?;
         self.distance_model.visit("DistanceModel", &mut region)?;
+        let _ = self.doppler_factor.visit("DopplerFactor", &mut region);
+        let _ = self.speed_of_sound.visit("SpeedOfSound", &mut region);
+        let _ = self.max_voices.visit("MaxVoices", &mut region);
+        let _ = self
+            .virtualization_threshold
+            .visit("VirtualizationThreshold", &mut region);
+        let _ = self.channel_layout.visit("ChannelLayout", &mut region);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{context::SoundContext, layout::ChannelLayout};
+
+    #[test]
+    fn test_channel_buffers_are_prepared_off_audio_thread() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        state.set_channel_layout(ChannelLayout::Surround51);
+
+        let buffers = |state: &super::State| {
+            state
+                .channel_buffers
+                .iter()
+                .map(|buffer| (buffer.as_ptr(), buffer.capacity()))
+                .collect::<Vec<_>>()
+        };
+        let prepared = buffers(&state);
+        assert_eq!(prepared.len(), 3);
+
+        // Rendering must reuse the buffers, that were allocated when the layout was set.
+        let mut output = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render_with_layout(ChannelLayout::Stereo, &mut output);
+        state.render_with_layout(ChannelLayout::Stereo, &mut output);
+        assert_eq!(buffers(&state), prepared);
+    }
+}
@@ -18,23 +18,32 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use std::io::Seek;
+use std::io::{Seek, SeekFrom};
 use std::time::Duration;
 
 use symphonia::core::audio::{AudioBuffer, Signal};
-use symphonia::core::codecs::{Decoder as SymphoniaDecoder, DecoderOptions};
+use symphonia::core::codecs::{
+    CodecType, Decoder as SymphoniaDecoder, DecoderOptions, CODEC_TYPE_FLAC,
+};
 use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
 use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
-use symphonia::core::units::Time;
+use symphonia::core::units::TimeBase;
 use symphonia::default;
 
 use crate::{buffer::DataSource, error::SoundError};
 
 pub(crate) struct Decoder {
-    reader: Box<dyn FormatReader>,
+    // Always `Some`, except the moment when the reader is being re-opened.
+    reader: Option<Box<dyn FormatReader>>,
     decoder: Box<dyn SymphoniaDecoder>,
+    codec: CodecType,
+    time_base: TimeBase,
+    // Timestamp of the packet that will be read next.
+    next_packet_ts: u64,
+    hint: Hint,
+    initial_stream_position: u64,
     samples: std::vec::IntoIter<f32>,
     pub channel_count: usize,
     pub sample_rate: usize,
@@ -61,7 +70,12 @@ impl Iterator for Decoder {
         if let Some(sample) = self.samples.next() {
             Some(sample)
         } else {
-            self.samples = Self::samples(&mut self.reader, &mut self.decoder).ok()?;
+            self.samples = Self::samples(
+                self.reader.as_mut()?,
+                &mut self.decoder,
+                &mut self.next_packet_ts,
+            )
+            .ok()?;
             self.samples.next()
         }
     }
@@ -72,7 +86,6 @@ impl Decoder {
         let initial_stream_position = source.stream_position()?;
 
         let codec_registry = default::get_codecs();
-        let probe = default::get_probe();
 
         let mut hint = Hint::new();
 
@@ -88,57 +101,89 @@ impl Decoder {
         let media_source_stream =
             MediaSourceStream::new(Box::new(source), MediaSourceStreamOptions::default());
 
-        let res = probe.format(
-            &hint,
-            media_source_stream,
-            &FormatOptions::default(),
-            &MetadataOptions::default(),
-        )?;
-
-        let mut reader = res.format;
+        let mut reader = Self::open(&hint, media_source_stream)?;
         let tracks = reader.tracks();
         let first_track = tracks.first().ok_or(SoundError::InvalidHeader)?;
         let codec_params = &first_track.codec_params;
-        let mut decoder = codec_registry.make(codec_params, &DecoderOptions::default())?;
+        let decoder = codec_registry.make(codec_params, &DecoderOptions::default())?;
 
         // Get duration
         let mut last_packet = None;
         while let Ok(packet) = reader.next_packet() {
             last_packet = Some(packet);
         }
-        reader.seek(
-            SeekMode::Accurate,
-            SeekTo::Time {
-                time: Time::new(initial_stream_position, 0.0),
-                track_id: None,
-            },
-        )?;
-
         let channel_duration_in_samples = last_packet.map(|p| p.ts as usize).unwrap_or_default();
 
-        let samples = Self::samples(&mut reader, &mut decoder)?;
-
         let params = &reader
             .tracks()
             .first()
             .ok_or(SoundError::InvalidHeader)?
             .codec_params;
+        let sample_rate = params.sample_rate.ok_or(SoundError::InvalidHeader)?;
 
-        Ok(Self {
-            samples,
+        let mut this = Self {
+            samples: Vec::new().into_iter(),
             channel_count: params.channels.unwrap_or_default().count(),
-            sample_rate: params.sample_rate.ok_or(SoundError::InvalidHeader)? as usize,
-            reader,
+            sample_rate: sample_rate as usize,
+            codec: params.codec,
+            time_base: params.time_base.unwrap_or(TimeBase::new(1, sample_rate)),
+            next_packet_ts: u64::MAX,
+            reader: Some(reader),
             decoder,
+            hint,
+            initial_stream_position,
             channel_duration_in_samples,
-        })
+        };
+
+        this.rewind()?;
+        this.samples = Self::samples(
+            this.reader.as_mut().ok_or(SoundError::InvalidHeader)?,
+            &mut this.decoder,
+            &mut this.next_packet_ts,
+        )?;
+        if this.channel_count == 0 {
+            // Some containers (MP4 with ALAC, for instance) store the channel layout in codec
+            // specific data only, take it from the decoded data in this case.
+            this.channel_count = this.decoder.last_decoded().spec().channels.count();
+        }
+
+        Ok(this)
+    }
+
+    fn open(
+        hint: &Hint,
+        media_source_stream: MediaSourceStream,
+    ) -> Result<Box<dyn FormatReader>, SoundError> {
+        Ok(default::get_probe()
+            .format(
+                hint,
+                media_source_stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )?
+            .format)
+    }
+
+    /// Creates a new format reader that starts from the beginning of the stream. FLAC reader does
+    /// not reset its packet parser on seeking, so it rejects all frames that precede the last read
+    /// one. A fresh reader does not have this issue.
+    fn reopen(
+        reader: Box<dyn FormatReader>,
+        hint: &Hint,
+        initial_stream_position: u64,
+    ) -> Result<Box<dyn FormatReader>, SoundError> {
+        let mut media_source_stream = reader.into_inner();
+        media_source_stream.seek(SeekFrom::Start(initial_stream_position))?;
+        Self::open(hint, media_source_stream)
     }
 
     fn samples(
         reader: &mut Box<dyn FormatReader>,
         decoder: &mut Box<dyn SymphoniaDecoder>,
+        next_packet_ts: &mut u64,
     ) -> Result<std::vec::IntoIter<f32>, SoundError> {
         let packet = reader.next_packet()?;
+        *next_packet_ts = packet.ts + packet.dur;
         let decoded = decoder.decode(&packet)?;
         let mut buffer: AudioBuffer<f32> = decoded.make_equivalent();
         decoded.convert(&mut buffer);
@@ -171,13 +216,35 @@ impl Decoder {
     }
 
     pub fn time_seek(&mut self, location: Duration) -> Result<(), SoundError> {
-        self.reader.seek(
+        let mut reader = self.reader.take().ok_or(SoundError::InvalidHeader)?;
+        if self.codec == CODEC_TYPE_FLAC
+            && self.time_base.calc_timestamp(location.into()) < self.next_packet_ts
+        {
+            reader = Self::reopen(reader, &self.hint, self.initial_stream_position)?;
+        }
+        let reader = self.reader.insert(reader);
+
+        let seeked_to = reader.seek(
             SeekMode::Accurate,
             SeekTo::Time {
                 time: location.into(),
                 track_id: None,
             },
         )?;
+        self.decoder.reset();
+        self.samples = Vec::new().into_iter();
+        self.next_packet_ts = seeked_to.actual_ts;
+
+        // The reader stops at the packet that contains the required timestamp, skip the samples
+        // that precede it.
+        let skip = self
+            .time_base
+            .calc_time(seeked_to.required_ts.saturating_sub(seeked_to.actual_ts));
+        let skip = ((skip.seconds as f64 + skip.frac) * self.sample_rate as f64).round() as usize
+            * self.channel_count;
+        if skip > 0 {
+            self.nth(skip - 1);
+        }
 
         Ok(())
     }
@@ -104,6 +104,17 @@ pub fn hann_window(i: usize, sample_count: usize) -> f32 {
     0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (sample_count - 1) as f32).cos()
 }
 
+/// Converts a value in decibels to linear gain.
+pub fn db_to_linear(db: f32) -> f32 {
+    10.0f32.powf(db / 20.0)
+}
+
+/// Converts linear gain to decibels. Gains below `1e-10` (-200 dB) are clamped to avoid infinite
+/// values.
+pub fn linear_to_db(gain: f32) -> f32 {
+    20.0 * gain.abs().max(1e-10).log10()
+}
+
 /// Creates new window using specified window function.
 /// <https://en.wikipedia.org/wiki/Window_function>
 pub fn make_window<W: Fn(usize, usize) -> f32>(sample_count: usize, func: W) -> Vec<f32> {
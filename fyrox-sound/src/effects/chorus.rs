@@ -0,0 +1,351 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Chorus and flanger effect module.
+//!
+//! # Overview
+//!
+//! Chorus mixes the input signal with its copy delayed by a slowly changing amount of time. This
+//! makes the sound "wider", as if it was played by multiple sources at once. Flanger is the same
+//! effect, but with much shorter delay and with feedback, it produces characteristic "jet plane"
+//! sweeping sound, see [`Chorus::flanger`].
+//!
+//! Delay lines are allocated off the audio thread by
+//! [`crate::context::SoundContext::prepare_effects`], the effect produces the dry signal only
+//! until then. The game engine does this automatically.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::context::SoundContext;
+//! use fyrox_sound::effects::chorus::Chorus;
+//! use fyrox_sound::effects::Effect;
+//!
+//! fn add_flanger(context: &mut SoundContext) {
+//!     let flanger = Chorus::flanger();
+//!     context.state().bus_graph_mut().primary_bus_mut().add_effect(Effect::Chorus(flanger));
+//! }
+//! ```
+
+use crate::{
+    context::SAMPLE_RATE,
+    effects::{
+        delay::{ms_to_samples, DelayBuffer},
+        EffectRenderTrait,
+    },
+};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Chorus {
+    #[reflect(
+        description = "Minimal delay time (in milliseconds) of the delayed copy.",
+        setter = "set_delay_ms",
+        min_value = 0.0,
+        max_value = 100.0
+    )]
+    delay_ms: f32,
+
+    #[reflect(
+        description = "Range (in milliseconds) of delay time modulation.",
+        setter = "set_depth_ms",
+        min_value = 0.0,
+        max_value = 100.0
+    )]
+    depth_ms: f32,
+
+    #[reflect(
+        description = "Frequency (in Hertz) of delay time modulation.",
+        setter = "set_rate_hz",
+        min_value = 0.0
+    )]
+    rate_hz: f32,
+
+    #[reflect(
+        description = "Amount of the delayed signal that is fed back to the delay line. Usually \
+        zero for chorus and non-zero for flanger.",
+        setter = "set_feedback",
+        min_value = -0.95,
+        max_value = 0.95
+    )]
+    feedback: f32,
+
+    #[reflect(
+        description = "Amount of the unprocessed signal in the output.",
+        setter = "set_dry",
+        min_value = 0.0,
+        max_value = 1.0
+    )]
+    dry: f32,
+
+    #[reflect(
+        description = "Amount of the delayed signal in the output.",
+        setter = "set_wet",
+        min_value = 0.0,
+        max_value = 1.0
+    )]
+    wet: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    phase: f32,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    left: DelayBuffer,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    right: DelayBuffer,
+}
+
+impl Default for Chorus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chorus {
+    /// Creates new chorus effect with 15 ms delay, 5 ms depth and 0.8 Hz modulation rate.
+    pub fn new() -> Self {
+        Self {
+            delay_ms: 15.0,
+            depth_ms: 5.0,
+            rate_hz: 0.8,
+            feedback: 0.0,
+            dry: 1.0,
+            wet: 0.5,
+            phase: 0.0,
+            left: Default::default(),
+            right: Default::default(),
+        }
+    }
+
+    /// Creates new flanger effect with 1 ms delay, 2 ms depth, 0.25 Hz modulation rate and 0.6
+    /// feedback.
+    pub fn flanger() -> Self {
+        Self {
+            delay_ms: 1.0,
+            depth_ms: 2.0,
+            rate_hz: 0.25,
+            feedback: 0.6,
+            dry: 1.0,
+            wet: 0.7,
+            ..Self::new()
+        }
+    }
+
+    /// Sets minimal delay time (in milliseconds) of the delayed copy.
+    pub fn set_delay_ms(&mut self, delay_ms: f32) {
+        self.delay_ms = delay_ms.clamp(0.0, 100.0);
+    }
+
+    /// Returns minimal delay time in milliseconds.
+    pub fn delay_ms(&self) -> f32 {
+        self.delay_ms
+    }
+
+    /// Sets range (in milliseconds) of delay time modulation.
+    pub fn set_depth_ms(&mut self, depth_ms: f32) {
+        self.depth_ms = depth_ms.clamp(0.0, 100.0);
+    }
+
+    /// Returns range of delay time modulation in milliseconds.
+    pub fn depth_ms(&self) -> f32 {
+        self.depth_ms
+    }
+
+    /// Sets frequency (in Hertz) of delay time modulation.
+    pub fn set_rate_hz(&mut self, rate_hz: f32) {
+        self.rate_hz = rate_hz.max(0.0);
+    }
+
+    /// Returns frequency of delay time modulation in Hertz.
+    pub fn rate_hz(&self) -> f32 {
+        self.rate_hz
+    }
+
+    /// Sets amount of the delayed signal that is fed back to the delay line. Negative values invert
+    /// the phase of the feedback. The value is clamped to `[-0.95; 0.95]` range.
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(-0.95, 0.95);
+    }
+
+    /// Returns feedback amount.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Sets amount of the unprocessed signal in the output.
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.clamp(0.0, 1.0);
+    }
+
+    /// Returns amount of the unprocessed signal in the output.
+    pub fn dry(&self) -> f32 {
+        self.dry
+    }
+
+    /// Sets amount of the delayed signal in the output.
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Returns amount of the delayed signal in the output.
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+}
+
+impl EffectRenderTrait for Chorus {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let min_delay = ms_to_samples(self.delay_ms).max(1.0);
+        let depth = ms_to_samples(self.depth_ms);
+        let phase_step = TAU * self.rate_hz / SAMPLE_RATE as f32;
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            // Right channel is modulated with 90 degrees phase shift to widen the stereo image.
+            let left_delay = min_delay + depth * 0.5 * (1.0 + self.phase.sin());
+            let right_delay = min_delay + depth * 0.5 * (1.0 + (self.phase + FRAC_PI_2).sin());
+
+            let delayed_left = self.left.read(left_delay);
+            let delayed_right = self.right.read(right_delay);
+
+            self.left.write(*input_left + delayed_left * self.feedback);
+            self.right
+                .write(*input_right + delayed_right * self.feedback);
+
+            *output_left = *input_left * self.dry + delayed_left * self.wet;
+            *output_right = *input_right * self.dry + delayed_right * self.wet;
+
+            self.phase = (self.phase + phase_step) % TAU;
+        }
+    }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        self.delay_ms = prototype.delay_ms;
+        self.depth_ms = prototype.depth_ms;
+        self.rate_hz = prototype.rate_hz;
+        self.feedback = prototype.feedback;
+        self.dry = prototype.dry;
+        self.wet = prototype.wet;
+    }
+
+    fn reserve_buffers(&mut self) {
+        let max_delay = ms_to_samples(self.delay_ms).max(1.0) + ms_to_samples(self.depth_ms);
+        let len = max_delay.ceil() as usize + 2;
+        self.left.reserve(len);
+        self.right.reserve(len);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{chorus::Chorus, EffectRenderTrait};
+
+    fn impulse_response(chorus: &mut Chorus, len: usize) -> Vec<(f32, f32)> {
+        let mut input = vec![(0.0, 0.0); len];
+        input[0] = (1.0, 1.0);
+        let mut output = vec![(0.0, 0.0); len];
+        chorus.render(&input, &mut output);
+        output
+    }
+
+    fn peak_position(samples: impl Iterator<Item = f32>) -> usize {
+        samples
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_chorus_fixed_delay() {
+        let mut chorus = Chorus::new();
+        chorus.set_delay_ms(10.0);
+        chorus.set_depth_ms(0.0);
+        chorus.set_dry(0.0);
+        chorus.set_wet(1.0);
+        chorus.reserve_buffers();
+
+        // 10 ms is 441 samples at 44100 Hz, there is no feedback, so the impulse is repeated once.
+        let output = impulse_response(&mut chorus, 2000);
+        assert_eq!(output[441], (1.0, 1.0));
+        for (i, (left, right)) in output.iter().enumerate() {
+            if i != 441 {
+                assert_eq!((*left, *right), (0.0, 0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_chorus_modulation_widens_stereo() {
+        let mut chorus = Chorus::new();
+        chorus.set_delay_ms(10.0);
+        chorus.set_depth_ms(4.0);
+        chorus.set_rate_hz(0.1);
+        chorus.set_dry(0.0);
+        chorus.set_wet(1.0);
+        chorus.reserve_buffers();
+
+        // At zero phase, left channel is delayed by half of the depth (88 samples) and right channel
+        // is delayed by the full depth (176 samples), because of 90 degrees phase shift.
+        let output = impulse_response(&mut chorus, 2000);
+        let left = peak_position(output.iter().map(|(l, _)| *l));
+        let right = peak_position(output.iter().map(|(_, r)| *r));
+        assert!((529..=531).contains(&left), "{left}");
+        assert!((617..=619).contains(&right), "{right}");
+    }
+
+    #[test]
+    fn test_flanger_feedback() {
+        let mut flanger = Chorus::flanger();
+        flanger.set_depth_ms(0.0);
+        flanger.set_delay_ms(5.0);
+        flanger.set_dry(0.0);
+        flanger.set_wet(1.0);
+        flanger.set_feedback(0.5);
+        flanger.reserve_buffers();
+
+        // Each repetition is attenuated by the feedback.
+        let output = impulse_response(&mut flanger, 2000);
+        let delay = 220.5;
+        for n in 1..4 {
+            let position = delay * n as f32;
+            let energy = output[position as usize - 1..=position.ceil() as usize + 1]
+                .iter()
+                .map(|(l, _)| l)
+                .sum::<f32>();
+            let expected = 0.5f32.powi(n - 1);
+            assert!((energy - expected).abs() < 1.0e-3, "{n}: {energy}");
+        }
+    }
+
+    #[test]
+    fn test_chorus_without_buffers_passes_dry_signal() {
+        let mut chorus = Chorus::new();
+        chorus.set_dry(0.5);
+        let output = impulse_response(&mut chorus, 1000);
+        assert_eq!(output[0], (0.5, 0.5));
+        assert!(output[1..].iter().all(|(l, r)| *l == 0.0 && *r == 0.0));
+    }
+}
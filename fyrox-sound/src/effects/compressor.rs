@@ -0,0 +1,318 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Dynamic range compressor module.
+//!
+//! # Overview
+//!
+//! Compressor reduces the volume of loud sounds, which narrows the dynamic range of a signal. It
+//! is used to keep the overall mix at a consistent level and to prevent clipping. Limiter is a
+//! compressor with very high ratio and fast attack, it makes sure that the signal never exceeds the
+//! threshold, see [`Compressor::limiter`].
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::context::SoundContext;
+//! use fyrox_sound::effects::compressor::Compressor;
+//! use fyrox_sound::effects::Effect;
+//!
+//! fn add_compressor(context: &mut SoundContext) {
+//!     let mut compressor = Compressor::new();
+//!     compressor.set_threshold_db(-18.0);
+//!     compressor.set_ratio(4.0);
+//!     compressor.set_makeup_gain_db(6.0);
+//!     context.state().bus_graph_mut().primary_bus_mut().add_effect(Effect::Compressor(compressor));
+//! }
+//! ```
+
+use crate::{
+    context::SAMPLE_RATE,
+    dsp::{db_to_linear, linear_to_db},
+    effects::EffectRenderTrait,
+};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+
+/// Calculates a coefficient of one-pole smoothing filter for the given time constant.
+pub(crate) fn time_constant_coefficient(time_ms: f32) -> f32 {
+    let samples = time_ms.max(0.0) * 0.001 * SAMPLE_RATE as f32;
+    if samples < 1.0 {
+        0.0
+    } else {
+        (-1.0 / samples).exp()
+    }
+}
+
+/// Calculates static gain reduction (in decibels, non-positive) for the given input level using
+/// soft-knee gain curve.
+pub(crate) fn compute_gain_reduction(
+    level_db: f32,
+    threshold_db: f32,
+    ratio: f32,
+    knee_db: f32,
+) -> f32 {
+    let overshoot = level_db - threshold_db;
+    let slope = 1.0 / ratio - 1.0;
+    if 2.0 * overshoot <= -knee_db {
+        0.0
+    } else if 2.0 * overshoot.abs() < knee_db {
+        let x = overshoot + knee_db * 0.5;
+        slope * x * x / (2.0 * knee_db)
+    } else {
+        slope * overshoot
+    }
+}
+
+/// Moves the envelope (in decibels) towards the target gain reduction using attack coefficient if
+/// the reduction increases and release coefficient otherwise.
+pub(crate) fn follow_envelope(
+    envelope_db: f32,
+    target_db: f32,
+    attack_coefficient: f32,
+    release_coefficient: f32,
+) -> f32 {
+    let coefficient = if target_db < envelope_db {
+        attack_coefficient
+    } else {
+        release_coefficient
+    };
+    target_db + (envelope_db - target_db) * coefficient
+}
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Compressor {
+    #[reflect(
+        description = "A level (in decibels) above which the signal is compressed.",
+        setter = "set_threshold_db",
+        max_value = 0.0
+    )]
+    threshold_db: f32,
+
+    #[reflect(
+        description = "Compression ratio. For example, 4.0 means that every 4 dB above the \
+        threshold will be reduced to 1 dB.",
+        setter = "set_ratio",
+        min_value = 1.0
+    )]
+    ratio: f32,
+
+    #[reflect(
+        description = "Time (in milliseconds) which is needed for the compressor to react on \
+        an increase of the signal level.",
+        setter = "set_attack_ms",
+        min_value = 0.0
+    )]
+    attack_ms: f32,
+
+    #[reflect(
+        description = "Time (in milliseconds) which is needed for the compressor to return \
+        to unity gain after the signal level decreased.",
+        setter = "set_release_ms",
+        min_value = 0.0
+    )]
+    release_ms: f32,
+
+    #[reflect(
+        description = "Width (in decibels) of the soft transition around the threshold. Zero \
+        means hard knee.",
+        setter = "set_knee_db",
+        min_value = 0.0
+    )]
+    knee_db: f32,
+
+    #[reflect(
+        description = "Gain (in decibels) applied after compression to compensate the volume loss.",
+        setter = "set_makeup_gain_db"
+    )]
+    makeup_gain_db: f32,
+
+    // Current (smoothed) gain reduction in decibels, always non-positive.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    envelope_db: f32,
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compressor {
+    /// Creates new compressor with -20 dB threshold, 4:1 ratio, 10 ms attack, 100 ms release and
+    /// 6 dB soft knee.
+    pub fn new() -> Self {
+        Self {
+            threshold_db: -20.0,
+            ratio: 4.0,
+            attack_ms: 10.0,
+            release_ms: 100.0,
+            knee_db: 6.0,
+            makeup_gain_db: 0.0,
+            envelope_db: 0.0,
+        }
+    }
+
+    /// Creates new limiter with the given threshold. Limiter is a compressor with very high
+    /// ratio, almost instant attack and hard knee.
+    pub fn limiter(threshold_db: f32) -> Self {
+        let mut limiter = Self::new();
+        limiter.set_threshold_db(threshold_db);
+        limiter.set_ratio(1000.0);
+        limiter.set_attack_ms(0.0);
+        limiter.set_release_ms(50.0);
+        limiter.set_knee_db(0.0);
+        limiter
+    }
+
+    /// Sets a level (in decibels) above which the signal is compressed.
+    pub fn set_threshold_db(&mut self, threshold_db: f32) {
+        self.threshold_db = threshold_db.min(0.0);
+    }
+
+    /// Returns current threshold in decibels.
+    pub fn threshold_db(&self) -> f32 {
+        self.threshold_db
+    }
+
+    /// Sets compression ratio. For example, 4.0 means that every 4 dB above the threshold will be
+    /// reduced to 1 dB. The value is clamped to `[1.0; +inf)` range.
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.max(1.0);
+    }
+
+    /// Returns current compression ratio.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Sets attack time in milliseconds.
+    pub fn set_attack_ms(&mut self, attack_ms: f32) {
+        self.attack_ms = attack_ms.max(0.0);
+    }
+
+    /// Returns attack time in milliseconds.
+    pub fn attack_ms(&self) -> f32 {
+        self.attack_ms
+    }
+
+    /// Sets release time in milliseconds.
+    pub fn set_release_ms(&mut self, release_ms: f32) {
+        self.release_ms = release_ms.max(0.0);
+    }
+
+    /// Returns release time in milliseconds.
+    pub fn release_ms(&self) -> f32 {
+        self.release_ms
+    }
+
+    /// Sets the width (in decibels) of the soft transition around the threshold.
+    pub fn set_knee_db(&mut self, knee_db: f32) {
+        self.knee_db = knee_db.max(0.0);
+    }
+
+    /// Returns knee width in decibels.
+    pub fn knee_db(&self) -> f32 {
+        self.knee_db
+    }
+
+    /// Sets gain (in decibels) applied after compression.
+    pub fn set_makeup_gain_db(&mut self, makeup_gain_db: f32) {
+        self.makeup_gain_db = makeup_gain_db;
+    }
+
+    /// Returns makeup gain in decibels.
+    pub fn makeup_gain_db(&self) -> f32 {
+        self.makeup_gain_db
+    }
+
+    /// Returns current gain reduction in decibels (non-positive value). Could be used for metering.
+    pub fn gain_reduction_db(&self) -> f32 {
+        self.envelope_db
+    }
+}
+
+impl EffectRenderTrait for Compressor {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let attack_coefficient = time_constant_coefficient(self.attack_ms);
+        let release_coefficient = time_constant_coefficient(self.release_ms);
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            // Both channels share the same gain to keep the stereo image stable.
+            let level_db = linear_to_db(input_left.abs().max(input_right.abs()));
+            let target =
+                compute_gain_reduction(level_db, self.threshold_db, self.ratio, self.knee_db);
+            self.envelope_db = follow_envelope(
+                self.envelope_db,
+                target,
+                attack_coefficient,
+                release_coefficient,
+            );
+
+            let gain = db_to_linear(self.envelope_db + self.makeup_gain_db);
+            *output_left = *input_left * gain;
+            *output_right = *input_right * gain;
+        }
+    }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        self.threshold_db = prototype.threshold_db;
+        self.ratio = prototype.ratio;
+        self.attack_ms = prototype.attack_ms;
+        self.release_ms = prototype.release_ms;
+        self.knee_db = prototype.knee_db;
+        self.makeup_gain_db = prototype.makeup_gain_db;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{compressor::Compressor, EffectRenderTrait};
+
+    #[test]
+    fn test_limiter_keeps_signal_below_threshold() {
+        let mut limiter = Compressor::limiter(-6.0);
+        let input = (0..4410)
+            .map(|i| {
+                let s = (i as f32 * 0.05).sin();
+                (s, -s)
+            })
+            .collect::<Vec<_>>();
+        let mut output = vec![(0.0, 0.0); input.len()];
+        limiter.render(&input, &mut output);
+        let threshold = 10.0f32.powf(-6.0 / 20.0);
+        for (left, right) in output {
+            assert!(left.abs() <= threshold + 0.01);
+            assert!(right.abs() <= threshold + 0.01);
+        }
+    }
+
+    #[test]
+    fn test_compressor_passes_quiet_signal() {
+        let mut compressor = Compressor::new();
+        let input = vec![(0.01, -0.01); 100];
+        let mut output = vec![(0.0, 0.0); input.len()];
+        compressor.render(&input, &mut output);
+        assert_eq!(input, output);
+    }
+}
@@ -0,0 +1,679 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Convolution reverb module.
+//!
+//! # Overview
+//!
+//! Convolution reverb convolves the input signal with an impulse response - a recording of a
+//! short click in a real (or simulated) space. It gives much more realistic results than the
+//! algorithmic [`crate::effects::reverb::Reverb`], but it is more expensive. The implementation
+//! uses uniformly partitioned FFT convolution, so its cost grows linearly with the length of the
+//! impulse response.
+//!
+//! The effect requires some preparation (FFT of the impulse response, memory allocation), which is
+//! done outside of the audio thread: either by [`ConvolutionReverb::prepare`] or by
+//! [`crate::context::SoundContext::prepare_effects`]. Until then the effect passes the dry signal
+//! only.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox_sound::buffer::SoundBufferResource;
+//! use fyrox_sound::context::SoundContext;
+//! use fyrox_sound::effects::convolution::ConvolutionReverb;
+//! use fyrox_sound::effects::Effect;
+//!
+//! fn add_convolution_reverb(context: &SoundContext, impulse_response: SoundBufferResource) {
+//!     let mut reverb = ConvolutionReverb::new();
+//!     reverb.set_impulse_response(Some(impulse_response));
+//!     reverb.set_wet(0.4);
+//!     reverb.set_pre_delay_ms(20.0);
+//!     // Prepare the effect in advance, it takes some time for long impulse responses.
+//!     reverb.prepare();
+//!     context
+//!         .state()
+//!         .bus_graph_mut()
+//!         .primary_bus_mut()
+//!         .add_effect(Effect::ConvolutionReverb(reverb));
+//! }
+//! ```
+
+use crate::{
+    buffer::{SoundBuffer, SoundBufferResource},
+    context::SAMPLE_RATE,
+    effects::{
+        delay::{ms_to_samples, DelayBuffer},
+        EffectRenderTrait,
+    },
+};
+use fyrox_core::{log::Log, reflect::prelude::*, visitor::prelude::*};
+use realfft::{num_complex::Complex, ComplexToReal, RealFftPlanner, RealToComplex};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+// Size of each partition of impulse response, it also defines latency of the convolution.
+const BLOCK_SIZE: usize = 256;
+const FFT_SIZE: usize = 2 * BLOCK_SIZE;
+const SPECTRUM_SIZE: usize = FFT_SIZE / 2 + 1;
+
+/// See module docs.
+#[derive(Clone, Visit, Reflect)]
+pub struct ConvolutionReverb {
+    #[reflect(
+        description = "Impulse response of a space. It must be a non-streaming mono or stereo \
+        sound buffer.",
+        setter = "set_impulse_response"
+    )]
+    impulse_response: Option<SoundBufferResource>,
+
+    #[reflect(
+        description = "Amount of the unprocessed signal in the output.",
+        setter = "set_dry",
+        min_value = 0.0,
+        max_value = 1.0
+    )]
+    dry: f32,
+
+    #[reflect(
+        description = "Amount of the reverberated signal in the output.",
+        setter = "set_wet",
+        min_value = 0.0,
+        max_value = 1.0
+    )]
+    wet: f32,
+
+    #[reflect(
+        description = "Time (in milliseconds) between the dry signal and the beginning of the \
+        reverberation.",
+        setter = "set_pre_delay_ms",
+        min_value = 0.0,
+        max_value = 500.0
+    )]
+    pre_delay_ms: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    processor: Option<Box<ConvolutionProcessor>>,
+
+    // Impulse response for which the preparation was done (successfully or not).
+    #[reflect(hidden)]
+    #[visit(skip)]
+    prepared_impulse_response: Option<SoundBufferResource>,
+}
+
+impl Debug for ConvolutionReverb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConvolutionReverb")
+            .field("impulse_response", &self.impulse_response)
+            .field("dry", &self.dry)
+            .field("wet", &self.wet)
+            .field("pre_delay_ms", &self.pre_delay_ms)
+            .field("ready", &self.is_ready())
+            .finish()
+    }
+}
+
+impl PartialEq for ConvolutionReverb {
+    fn eq(&self, other: &Self) -> bool {
+        self.impulse_response == other.impulse_response
+            && self.dry == other.dry
+            && self.wet == other.wet
+            && self.pre_delay_ms == other.pre_delay_ms
+    }
+}
+
+impl Default for ConvolutionReverb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConvolutionReverb {
+    /// Maximum pre-delay (in milliseconds).
+    pub const MAX_PRE_DELAY_MS: f32 = 500.0;
+
+    /// Maximum length (in seconds) of an impulse response, longer impulse responses are truncated.
+    pub const MAX_IMPULSE_RESPONSE_LENGTH: f32 = 10.0;
+
+    /// Creates new convolution reverb without an impulse response, 1.0 dry, 0.5 wet and no pre-delay.
+    pub fn new() -> Self {
+        Self {
+            impulse_response: None,
+            dry: 1.0,
+            wet: 0.5,
+            pre_delay_ms: 0.0,
+            processor: None,
+            prepared_impulse_response: None,
+        }
+    }
+
+    /// Sets new impulse response. It must be a non-streaming mono or stereo sound buffer, it is
+    /// resampled automatically if its sample rate differs from [`SAMPLE_RATE`]. The impulse response
+    /// is normalized to unit energy, use [`Self::set_wet`] to control its loudness.
+    ///
+    /// The effect must be prepared for the new impulse response, see [`Self::prepare`] for more info.
+    pub fn set_impulse_response(&mut self, impulse_response: Option<SoundBufferResource>) {
+        self.impulse_response = impulse_response;
+    }
+
+    /// Returns current impulse response.
+    pub fn impulse_response(&self) -> Option<&SoundBufferResource> {
+        self.impulse_response.as_ref()
+    }
+
+    /// Sets amount of the unprocessed signal in the output.
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.clamp(0.0, 1.0);
+    }
+
+    /// Returns amount of the unprocessed signal in the output.
+    pub fn dry(&self) -> f32 {
+        self.dry
+    }
+
+    /// Sets amount of the reverberated signal in the output.
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Returns amount of the reverberated signal in the output.
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    /// Sets time (in milliseconds) between the dry signal and the beginning of the reverberation.
+    /// The value is clamped to `[0.0; MAX_PRE_DELAY_MS]` range.
+    pub fn set_pre_delay_ms(&mut self, pre_delay_ms: f32) {
+        self.pre_delay_ms = pre_delay_ms.clamp(0.0, Self::MAX_PRE_DELAY_MS);
+    }
+
+    /// Returns current pre-delay (in milliseconds).
+    pub fn pre_delay_ms(&self) -> f32 {
+        self.pre_delay_ms
+    }
+
+    /// Returns `true` if the effect is prepared for the current impulse response and produces
+    /// reverberation.
+    pub fn is_ready(&self) -> bool {
+        self.processor().is_some()
+    }
+
+    /// Prepares the effect for the current impulse response, if it is loaded. This method allocates
+    /// memory and performs heavy calculations, so it must not be called from the audio thread and
+    /// it is better to call it without holding the lock of a sound context (see
+    /// [`crate::context::SoundContext::prepare_effects`]). Returns `true` if the effect is ready.
+    pub fn prepare(&mut self) -> bool {
+        if let Some(impulse_response) = self.pending_impulse_response().cloned() {
+            let processor = ConvolutionProcessor::new(&impulse_response).map(Box::new);
+            self.install_processor(&impulse_response, processor);
+        } else if self.impulse_response.is_none() {
+            self.processor = None;
+            self.prepared_impulse_response = None;
+        }
+        self.is_ready()
+    }
+
+    // Returns the processor if it matches the current impulse response.
+    fn processor(&self) -> Option<&ConvolutionProcessor> {
+        self.processor
+            .as_deref()
+            .filter(|processor| self.impulse_response.as_ref() == Some(&processor.impulse_response))
+    }
+
+    // Returns the impulse response if it is loaded, but the effect is not prepared for it.
+    pub(crate) fn pending_impulse_response(&self) -> Option<&SoundBufferResource> {
+        let impulse_response = self.impulse_response.as_ref()?;
+        if impulse_response.is_ok()
+            && self.prepared_impulse_response.as_ref() != Some(impulse_response)
+        {
+            Some(impulse_response)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn install_processor(
+        &mut self,
+        impulse_response: &SoundBufferResource,
+        processor: Option<Box<ConvolutionProcessor>>,
+    ) {
+        if self.impulse_response.as_ref() == Some(impulse_response) {
+            self.processor = processor;
+            self.prepared_impulse_response = Some(impulse_response.clone());
+        }
+    }
+
+    // Makes the effect use the impulse response of the prototype. Heavy preparation is done only
+    // for the prototype, so its results are reused.
+    pub(crate) fn share_impulse_response(&mut self, prototype: &Self) {
+        if self.impulse_response != prototype.impulse_response {
+            self.impulse_response
+                .clone_from(&prototype.impulse_response);
+        }
+        if self.prepared_impulse_response != prototype.prepared_impulse_response {
+            self.processor.clone_from(&prototype.processor);
+            self.prepared_impulse_response
+                .clone_from(&prototype.prepared_impulse_response);
+        }
+    }
+}
+
+impl EffectRenderTrait for ConvolutionReverb {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let processor = match self.processor.as_mut() {
+            Some(processor)
+                if self.impulse_response.as_ref() == Some(&processor.impulse_response) =>
+            {
+                processor
+            }
+            _ => {
+                for ((input_left, input_right), (output_left, output_right)) in
+                    input.iter().zip(output.iter_mut())
+                {
+                    *output_left = *input_left * self.dry;
+                    *output_right = *input_right * self.dry;
+                }
+                return;
+            }
+        };
+
+        // Convolution itself adds one block of latency, compensate it if possible.
+        let pre_delay = (ms_to_samples(self.pre_delay_ms) - BLOCK_SIZE as f32).max(0.0);
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            processor.pre_delay_left.write(*input_left);
+            processor.pre_delay_right.write(*input_right);
+            // Delay of 1.0 corresponds to the sample that was just written.
+            let delayed_left = processor.pre_delay_left.read(pre_delay + 1.0);
+            let delayed_right = processor.pre_delay_right.read(pre_delay + 1.0);
+
+            let (wet_left, wet_right) = processor.process(delayed_left, delayed_right);
+
+            *output_left = *input_left * self.dry + wet_left * self.wet;
+            *output_right = *input_right * self.dry + wet_right * self.wet;
+        }
+    }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        self.dry = prototype.dry;
+        self.wet = prototype.wet;
+        self.pre_delay_ms = prototype.pre_delay_ms;
+    }
+}
+
+// Convolution state of a single channel.
+#[derive(Clone)]
+struct Convolver {
+    // Sliding window of two blocks: the previous one and the current one.
+    input: Vec<f32>,
+    // Frequency-domain delay line, contains spectra of the last input blocks.
+    fdl: Vec<Vec<Complex<f32>>>,
+    fdl_position: usize,
+    // Output samples of the last processed block.
+    output: Vec<f32>,
+}
+
+impl Convolver {
+    fn new(partition_count: usize) -> Self {
+        Self {
+            input: vec![0.0; FFT_SIZE],
+            fdl: vec![vec![Complex::default(); SPECTRUM_SIZE]; partition_count],
+            fdl_position: 0,
+            output: vec![0.0; BLOCK_SIZE],
+        }
+    }
+}
+
+// Everything that is needed to render the effect, it is created outside of the audio thread.
+#[derive(Clone)]
+pub(crate) struct ConvolutionProcessor {
+    impulse_response: SoundBufferResource,
+    // Spectra of partitions of the impulse response for each channel.
+    kernel: Arc<Vec<Vec<Vec<Complex<f32>>>>>,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    time: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    accumulator: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+    left: Convolver,
+    right: Convolver,
+    block_position: usize,
+    pre_delay_left: DelayBuffer,
+    pre_delay_right: DelayBuffer,
+}
+
+impl ConvolutionProcessor {
+    pub(crate) fn new(impulse_response: &SoundBufferResource) -> Option<Self> {
+        let channels = {
+            let mut state = impulse_response.state();
+            match state.data() {
+                Some(SoundBuffer::Generic(buffer)) => {
+                    let channel_count = buffer.channel_count();
+                    if channel_count == 0 {
+                        return None;
+                    }
+                    (0..channel_count.min(2))
+                        .map(|channel| {
+                            let samples = buffer
+                                .samples()
+                                .iter()
+                                .skip(channel)
+                                .step_by(channel_count)
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            let mut samples = resample(&samples, buffer.sample_rate());
+                            samples.truncate(Self::max_impulse_response_len());
+                            samples
+                        })
+                        .collect::<Vec<_>>()
+                }
+                Some(SoundBuffer::Streaming(_)) => {
+                    Log::warn("Streaming buffers cannot be used as impulse responses!");
+                    return None;
+                }
+                None => return None,
+            }
+        };
+
+        // Normalize to unit energy, so the reverberation has roughly the same loudness as the
+        // input signal regardless of the impulse response.
+        let energy = channels
+            .iter()
+            .map(|samples| samples.iter().map(|s| s * s).sum::<f32>())
+            .fold(0.0f32, f32::max);
+        let scale = if energy > 0.0 {
+            energy.sqrt().recip()
+        } else {
+            0.0
+        };
+
+        let mut planner = RealFftPlanner::<f32>::new();
+        let forward = planner.plan_fft_forward(FFT_SIZE);
+        let inverse = planner.plan_fft_inverse(FFT_SIZE);
+        let mut scratch =
+            vec![Complex::default(); forward.get_scratch_len().max(inverse.get_scratch_len())];
+        let mut time = vec![0.0; FFT_SIZE];
+
+        let partition_count = channels
+            .iter()
+            .map(|samples| samples.len().div_ceil(BLOCK_SIZE))
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        let kernel = channels
+            .iter()
+            .map(|samples| {
+                (0..partition_count)
+                    .map(|partition| {
+                        time.fill(0.0);
+                        let start = (partition * BLOCK_SIZE).min(samples.len());
+                        let end = (start + BLOCK_SIZE).min(samples.len());
+                        for (dest, src) in time.iter_mut().zip(&samples[start..end]) {
+                            *dest = *src * scale;
+                        }
+                        let mut spectrum = vec![Complex::default(); SPECTRUM_SIZE];
+                        let _ =
+                            forward.process_with_scratch(&mut time, &mut spectrum, &mut scratch);
+                        spectrum
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut pre_delay_left = DelayBuffer::default();
+        let mut pre_delay_right = DelayBuffer::default();
+        let pre_delay_len = ms_to_samples(ConvolutionReverb::MAX_PRE_DELAY_MS).ceil() as usize + 3;
+        pre_delay_left.reserve(pre_delay_len);
+        pre_delay_right.reserve(pre_delay_len);
+
+        Some(Self {
+            impulse_response: impulse_response.clone(),
+            kernel: Arc::new(kernel),
+            forward,
+            inverse,
+            time,
+            spectrum: vec![Complex::default(); SPECTRUM_SIZE],
+            accumulator: vec![Complex::default(); SPECTRUM_SIZE],
+            scratch,
+            left: Convolver::new(partition_count),
+            right: Convolver::new(partition_count),
+            block_position: 0,
+            pre_delay_left,
+            pre_delay_right,
+        })
+    }
+
+    fn max_impulse_response_len() -> usize {
+        (ConvolutionReverb::MAX_IMPULSE_RESPONSE_LENGTH * SAMPLE_RATE as f32) as usize
+    }
+
+    // Feeds a pair of samples and returns a pair of processed samples. The output is delayed by
+    // one block.
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        self.left.input[BLOCK_SIZE + self.block_position] = left;
+        self.right.input[BLOCK_SIZE + self.block_position] = right;
+        let output = (
+            self.left.output[self.block_position],
+            self.right.output[self.block_position],
+        );
+        self.block_position += 1;
+        if self.block_position == BLOCK_SIZE {
+            self.block_position = 0;
+            let right_kernel = self.kernel.len().min(2) - 1;
+            for (convolver, kernel) in [(&mut self.left, 0), (&mut self.right, right_kernel)] {
+                Self::process_block(
+                    convolver,
+                    &self.kernel[kernel],
+                    &*self.forward,
+                    &*self.inverse,
+                    &mut self.time,
+                    &mut self.spectrum,
+                    &mut self.accumulator,
+                    &mut self.scratch,
+                );
+            }
+        }
+        output
+    }
+
+    // Uniformly partitioned overlap-save convolution of a single block.
+    #[allow(clippy::too_many_arguments)]
+    fn process_block(
+        convolver: &mut Convolver,
+        kernel: &[Vec<Complex<f32>>],
+        forward: &dyn RealToComplex<f32>,
+        inverse: &dyn ComplexToReal<f32>,
+        time: &mut [f32],
+        spectrum: &mut [Complex<f32>],
+        accumulator: &mut [Complex<f32>],
+        scratch: &mut [Complex<f32>],
+    ) {
+        // Forward transform destroys its input, so use a copy.
+        time.copy_from_slice(&convolver.input);
+        let _ = forward.process_with_scratch(time, spectrum, scratch);
+
+        let partition_count = convolver.fdl.len();
+        convolver.fdl_position = (convolver.fdl_position + partition_count - 1) % partition_count;
+        convolver.fdl[convolver.fdl_position].copy_from_slice(spectrum);
+
+        accumulator.fill(Complex::default());
+        for (partition, h) in kernel.iter().enumerate() {
+            let x = &convolver.fdl[(convolver.fdl_position + partition) % partition_count];
+            for ((acc, x), h) in accumulator.iter_mut().zip(x).zip(h) {
+                *acc += x * h;
+            }
+        }
+
+        let _ = inverse.process_with_scratch(accumulator, time, scratch);
+
+        // The first half of the result is aliased, the second one is the valid output.
+        let scale = 1.0 / FFT_SIZE as f32;
+        for (output, sample) in convolver.output.iter_mut().zip(&time[BLOCK_SIZE..]) {
+            *output = *sample * scale;
+        }
+
+        convolver.input.copy_within(BLOCK_SIZE.., 0);
+    }
+}
+
+// Converts samples to the sample rate of the engine using linear interpolation.
+fn resample(samples: &[f32], sample_rate: usize) -> Vec<f32> {
+    if sample_rate == SAMPLE_RATE as usize || sample_rate == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = sample_rate as f64 / SAMPLE_RATE as f64;
+    let len = (samples.len() as f64 / ratio).ceil() as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let t = (position - index as f64) as f32;
+            let a = samples[index.min(samples.len() - 1)];
+            let b = samples[(index + 1).min(samples.len() - 1)];
+            a + (b - a) * t
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource, SoundBufferResourceExtension},
+        context::{SoundContext, SAMPLE_RATE},
+        effects::{convolution::ConvolutionReverb, Effect, EffectRenderTrait},
+    };
+
+    fn impulse_response(channels: &[&[f32]]) -> SoundBufferResource {
+        let len = channels[0].len();
+        let samples = (0..len)
+            .flat_map(|i| channels.iter().map(move |channel| channel[i]))
+            .collect();
+        SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: channels.len(),
+            samples,
+        })
+        .unwrap()
+    }
+
+    fn render_impulse(reverb: &mut ConvolutionReverb, len: usize) -> Vec<(f32, f32)> {
+        let mut input = vec![(0.0, 0.0); len];
+        input[0] = (1.0, 1.0);
+        let mut output = vec![(0.0, 0.0); len];
+        // Render in chunks of arbitrary size to check block handling.
+        for (input, output) in input.chunks(300).zip(output.chunks_mut(300)) {
+            reverb.render(input, output);
+        }
+        output
+    }
+
+    fn peak(samples: &[(f32, f32)]) -> usize {
+        samples
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1 .0.abs().total_cmp(&b.1 .0.abs()))
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_convolution_with_delayed_impulse() {
+        let mut ir = vec![0.0; 1000];
+        ir[700] = 0.5;
+        let mut reverb = ConvolutionReverb::new();
+        reverb.set_dry(0.0);
+        reverb.set_wet(1.0);
+        reverb.set_impulse_response(Some(impulse_response(&[&ir])));
+        assert!(!reverb.is_ready());
+        assert!(reverb.prepare());
+
+        let output = render_impulse(&mut reverb, 2000);
+        // Impulse response is normalized, convolution adds one block (256 samples) of latency.
+        let peak = peak(&output);
+        assert_eq!(peak, 256 + 700);
+        assert!((output[peak].0 - 1.0).abs() < 1.0e-4);
+        assert!((output[peak].1 - 1.0).abs() < 1.0e-4);
+        assert!(output
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != peak)
+            .all(|(_, (l, r))| l.abs() < 1.0e-4 && r.abs() < 1.0e-4));
+    }
+
+    #[test]
+    fn test_convolution_stereo_and_pre_delay() {
+        let mut reverb = ConvolutionReverb::new();
+        reverb.set_dry(0.0);
+        reverb.set_wet(1.0);
+        // 10 ms is 441 samples, latency of the convolution is compensated.
+        reverb.set_pre_delay_ms(10.0);
+        reverb.set_impulse_response(Some(impulse_response(&[&[1.0, 0.0], &[0.0, 1.0]])));
+        reverb.prepare();
+
+        let output = render_impulse(&mut reverb, 1000);
+        assert!((output[441].0 - 1.0).abs() < 1.0e-4);
+        assert!(output[441].1.abs() < 1.0e-4);
+        assert!((output[442].1 - 1.0).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_convolution_preparation() {
+        let mut reverb = ConvolutionReverb::new();
+        reverb.set_dry(0.5);
+        assert!(!reverb.is_ready());
+
+        // Not prepared effect passes dry signal only.
+        let output = render_impulse(&mut reverb, 10);
+        assert_eq!(output[0], (0.5, 0.5));
+
+        let context = SoundContext::new();
+        context
+            .state()
+            .bus_graph_mut()
+            .primary_bus_mut()
+            .add_effect(Effect::ConvolutionReverb(reverb));
+        if let Some(Effect::ConvolutionReverb(reverb)) = context
+            .state()
+            .bus_graph_mut()
+            .primary_bus_mut()
+            .effect_mut(0)
+        {
+            reverb.set_impulse_response(Some(impulse_response(&[&[1.0]])));
+        }
+
+        context.prepare_effects();
+
+        let state = context.state();
+        let Some(Effect::ConvolutionReverb(reverb)) =
+            state.bus_graph_ref().primary_bus_ref().effect(0)
+        else {
+            unreachable!()
+        };
+        assert!(reverb.is_ready());
+    }
+}
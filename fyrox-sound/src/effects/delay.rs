@@ -0,0 +1,319 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Delay (echo) effect module.
+//!
+//! # Overview
+//!
+//! Delay effect repeats the input signal after a specified amount of time. The repetitions decay
+//! with each pass through the feedback loop and become more and more muffled, because the
+//! feedback loop has a lowpass filter which simulates absorption of high frequencies.
+//!
+//! Delay lines are allocated off the audio thread by
+//! [`crate::context::SoundContext::prepare_effects`], the effect produces the dry signal only
+//! until then. The game engine does this automatically.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::context::SoundContext;
+//! use fyrox_sound::effects::delay::Delay;
+//! use fyrox_sound::effects::Effect;
+//!
+//! fn add_echo(context: &mut SoundContext) {
+//!     let mut delay = Delay::new();
+//!     delay.set_delay_time_ms(250.0);
+//!     delay.set_feedback(0.5);
+//!     context.state().bus_graph_mut().primary_bus_mut().add_effect(Effect::Delay(delay));
+//! }
+//! ```
+
+use crate::{context::SAMPLE_RATE, dsp::filters::OnePole, effects::EffectRenderTrait};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+
+/// A circular buffer that allows to read samples with fractional delay.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DelayBuffer {
+    samples: Vec<f32>,
+    position: usize,
+}
+
+impl DelayBuffer {
+    /// Makes sure that the buffer can hold at least `len` samples. The content of the buffer is
+    /// discarded on reallocation.
+    pub(crate) fn reserve(&mut self, len: usize) {
+        if self.samples.len() < len {
+            self.samples = vec![0.0; len];
+            self.position = 0;
+        }
+    }
+
+    /// Reads a sample with the given delay (in samples), delay of 1.0 corresponds to the last
+    /// written sample. Linear interpolation is used for fractional delays. The delay is clamped to
+    /// the length of the buffer, empty buffer always returns silence.
+    pub(crate) fn read(&self, delay: f32) -> f32 {
+        let len = self.samples.len();
+        if len < 2 {
+            return 0.0;
+        }
+        let delay = delay.clamp(1.0, (len - 1) as f32);
+        let whole = delay.floor();
+        let t = delay - whole;
+        let index = (self.position + len - whole as usize) % len;
+        let next = (index + len - 1) % len;
+        self.samples[index] + (self.samples[next] - self.samples[index]) * t
+    }
+
+    /// Writes a sample to the buffer.
+    pub(crate) fn write(&mut self, sample: f32) {
+        if self.samples.is_empty() {
+            return;
+        }
+        self.samples[self.position] = sample;
+        self.position = (self.position + 1) % self.samples.len();
+    }
+}
+
+/// Converts milliseconds to amount of samples.
+pub(crate) fn ms_to_samples(ms: f32) -> f32 {
+    ms * SAMPLE_RATE as f32 / 1000.0
+}
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Delay {
+    #[reflect(
+        description = "Time (in milliseconds) between repetitions.",
+        setter = "set_delay_time_ms",
+        min_value = 1.0,
+        max_value = 5000.0
+    )]
+    delay_time_ms: f32,
+
+    #[reflect(
+        description = "Amount of the delayed signal that is fed back to the delay line. Defines \
+        how fast the repetitions decay.",
+        setter = "set_feedback",
+        min_value = 0.0,
+        max_value = 0.99
+    )]
+    feedback: f32,
+
+    #[reflect(
+        description = "Cutoff frequency (in Hertz) of the lowpass filter in the feedback loop.",
+        setter = "set_damping_hz",
+        min_value = 20.0
+    )]
+    damping_hz: f32,
+
+    #[reflect(
+        description = "Amount of the unprocessed signal in the output.",
+        setter = "set_dry",
+        min_value = 0.0,
+        max_value = 1.0
+    )]
+    dry: f32,
+
+    #[reflect(
+        description = "Amount of the delayed signal in the output.",
+        setter = "set_wet",
+        min_value = 0.0,
+        max_value = 1.0
+    )]
+    wet: f32,
+
+    #[reflect(
+        description = "If set, repetitions alternate between left and right channels.",
+        setter = "set_ping_pong"
+    )]
+    ping_pong: bool,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    left: DelayBuffer,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    right: DelayBuffer,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    left_damping: OnePole,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    right_damping: OnePole,
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Delay {
+    /// Creates new delay effect with 350 ms delay time, 0.35 feedback and 4 kHz damping.
+    pub fn new() -> Self {
+        Self {
+            delay_time_ms: 350.0,
+            feedback: 0.35,
+            damping_hz: 4000.0,
+            dry: 1.0,
+            wet: 0.35,
+            ping_pong: false,
+            left: Default::default(),
+            right: Default::default(),
+            left_damping: Default::default(),
+            right_damping: Default::default(),
+        }
+    }
+
+    /// Sets time (in milliseconds) between repetitions. The value is clamped to `[1.0; 5000.0]`
+    /// range.
+    pub fn set_delay_time_ms(&mut self, delay_time_ms: f32) {
+        self.delay_time_ms = delay_time_ms.clamp(1.0, 5000.0);
+    }
+
+    /// Returns time (in milliseconds) between repetitions.
+    pub fn delay_time_ms(&self) -> f32 {
+        self.delay_time_ms
+    }
+
+    /// Sets amount of the delayed signal that is fed back to the delay line. The value is clamped
+    /// to `[0.0; 0.99]` range.
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(0.0, 0.99);
+    }
+
+    /// Returns feedback amount.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Sets cutoff frequency (in Hertz) of the lowpass filter in the feedback loop.
+    pub fn set_damping_hz(&mut self, damping_hz: f32) {
+        self.damping_hz = damping_hz.max(20.0);
+    }
+
+    /// Returns cutoff frequency (in Hertz) of the lowpass filter in the feedback loop.
+    pub fn damping_hz(&self) -> f32 {
+        self.damping_hz
+    }
+
+    /// Sets amount of the unprocessed signal in the output.
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.clamp(0.0, 1.0);
+    }
+
+    /// Returns amount of the unprocessed signal in the output.
+    pub fn dry(&self) -> f32 {
+        self.dry
+    }
+
+    /// Sets amount of the delayed signal in the output.
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Returns amount of the delayed signal in the output.
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    /// Enables or disables ping-pong mode. In this mode, repetitions alternate between left and
+    /// right channels.
+    pub fn set_ping_pong(&mut self, ping_pong: bool) {
+        self.ping_pong = ping_pong;
+    }
+
+    /// Returns `true` if ping-pong mode is enabled, `false` - otherwise.
+    pub fn is_ping_pong(&self) -> bool {
+        self.ping_pong
+    }
+}
+
+impl EffectRenderTrait for Delay {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let delay = ms_to_samples(self.delay_time_ms);
+        let fc = self.damping_hz / SAMPLE_RATE as f32;
+        self.left_damping.set_fc(fc);
+        self.right_damping.set_fc(fc);
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            let delayed_left = self.left.read(delay);
+            let delayed_right = self.right.read(delay);
+
+            let feedback_left = self.left_damping.feed(delayed_left) * self.feedback;
+            let feedback_right = self.right_damping.feed(delayed_right) * self.feedback;
+
+            if self.ping_pong {
+                self.left
+                    .write((*input_left + *input_right) * 0.5 + feedback_right);
+                self.right.write(feedback_left);
+            } else {
+                self.left.write(*input_left + feedback_left);
+                self.right.write(*input_right + feedback_right);
+            }
+
+            *output_left = *input_left * self.dry + delayed_left * self.wet;
+            *output_right = *input_right * self.dry + delayed_right * self.wet;
+        }
+    }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        self.delay_time_ms = prototype.delay_time_ms;
+        self.feedback = prototype.feedback;
+        self.damping_hz = prototype.damping_hz;
+        self.dry = prototype.dry;
+        self.wet = prototype.wet;
+        self.ping_pong = prototype.ping_pong;
+    }
+
+    fn reserve_buffers(&mut self) {
+        let len = ms_to_samples(self.delay_time_ms).ceil() as usize + 2;
+        self.left.reserve(len);
+        self.right.reserve(len);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{delay::Delay, EffectRenderTrait};
+
+    #[test]
+    fn test_delay_echo() {
+        let mut delay = Delay::new();
+        delay.set_delay_time_ms(10.0);
+        delay.set_dry(0.0);
+        delay.set_wet(1.0);
+        delay.reserve_buffers();
+
+        // 10 ms is 441 samples at 44100 Hz.
+        let mut input = vec![(0.0, 0.0); 2000];
+        input[0] = (1.0, 1.0);
+        let mut output = vec![(0.0, 0.0); input.len()];
+        delay.render(&input, &mut output);
+
+        assert_eq!(output[441], (1.0, 1.0));
+        assert!(output[..441].iter().all(|(l, r)| *l == 0.0 && *r == 0.0));
+        // The second repetition is attenuated by feedback and damping.
+        let second = output[882..892].iter().map(|(l, _)| l.abs()).sum::<f32>();
+        assert!(second > 0.0 && second < 0.35);
+    }
+}
@@ -74,6 +74,18 @@ macro_rules! define_filter_effect {
                     *output_right = self.right.feed(*input_right);
                 }
             }
+
+            fn sync_parameters(&mut self, prototype: &Self) {
+                if self.cutoff_frequency_hz != prototype.cutoff_frequency_hz
+                    || self.gain != prototype.gain
+                    || self.quality != prototype.quality
+                {
+                    self.cutoff_frequency_hz = prototype.cutoff_frequency_hz;
+                    self.gain = prototype.gain;
+                    self.quality = prototype.quality;
+                    self.update();
+                }
+            }
         }
 
         impl $name {
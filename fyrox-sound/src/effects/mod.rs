@@ -20,18 +20,28 @@
 
 //! Contins everything related to audio effects that can be applied to an audio bus.
 
-use crate::{
-    effects::filter::{
+use crate::effects::{
+    chorus::Chorus,
+    compressor::Compressor,
+    convolution::ConvolutionReverb,
+    delay::Delay,
+    filter::{
         AllPassFilterEffect, BandPassFilterEffect, HighPassFilterEffect, HighShelfFilterEffect,
         LowPassFilterEffect, LowShelfFilterEffect,
     },
-    effects::reverb::Reverb,
+    reverb::Reverb,
+    waveshaper::Waveshaper,
 };
 use fyrox_core::{reflect::prelude::*, uuid_provider, visitor::prelude::*};
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+pub mod chorus;
+pub mod compressor;
+pub mod convolution;
+pub mod delay;
 pub mod filter;
 pub mod reverb;
+pub mod waveshaper;
 
 /// Attenuation effect.
 #[derive(Debug, Clone, PartialEq, Visit, Reflect)]
@@ -63,6 +73,10 @@ impl EffectRenderTrait for Attenuate {
             *output_right = *input_right * self.gain;
         }
     }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        self.gain = prototype.gain;
+    }
 }
 
 /// Effects is a digital signal processing (DSP) unit that transforms input signal in a specific way.
@@ -86,6 +100,16 @@ pub enum Effect {
     LowShelfFilter(LowShelfFilterEffect),
     /// See [`HighShelfFilterEffect`] docs for more info.
     HighShelfFilter(HighShelfFilterEffect),
+    /// See [`Compressor`] docs for more info.
+    Compressor(Compressor),
+    /// See [`Delay`] docs for more info.
+    Delay(Delay),
+    /// See [`Chorus`] docs for more info.
+    Chorus(Chorus),
+    /// See [`Waveshaper`] docs for more info.
+    Waveshaper(Waveshaper),
+    /// See [`ConvolutionReverb`] docs for more info.
+    ConvolutionReverb(ConvolutionReverb),
 }
 
 uuid_provider!(Effect = "fc52e441-d1ec-4881-937c-9e2e53a6d621");
@@ -98,6 +122,16 @@ impl Default for Effect {
 
 pub(crate) trait EffectRenderTrait {
     fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]);
+
+    // Copies parameters of the prototype, but keeps the internal state (delay lines, envelopes,
+    // etc.) intact. It is used to process extra channel pairs of multichannel layouts with
+    // independent instances of the same effect.
+    fn sync_parameters(&mut self, prototype: &Self);
+
+    // Allocates internal buffers (delay lines, etc.) for current parameters. Rendering must not
+    // allocate memory, so this method is called off the audio thread, see
+    // `SoundContext::prepare_effects`.
+    fn reserve_buffers(&mut self) {}
 }
 
 macro_rules! static_dispatch {
@@ -111,12 +145,62 @@ macro_rules! static_dispatch {
             Effect::AllPassFilter(v) => v.$func($($args),*),
             Effect::LowShelfFilter(v) => v.$func($($args),*),
             Effect::HighShelfFilter(v) => v.$func($($args),*),
+            Effect::Compressor(v) => v.$func($($args),*),
+            Effect::Delay(v) => v.$func($($args),*),
+            Effect::Chorus(v) => v.$func($($args),*),
+            Effect::Waveshaper(v) => v.$func($($args),*),
+            Effect::ConvolutionReverb(v) => v.$func($($args),*),
         }
     };
 }
 
+impl Effect {
+    pub(crate) fn is_same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    // Makes the effect an independent instance of the prototype: copies its parameters and
+    // re-creates the effect if the prototype is of other kind. It allocates memory, so it must be
+    // called off the audio thread.
+    pub(crate) fn prepare_instance(&mut self, prototype: &Self) {
+        if !self.is_same_kind(prototype) {
+            *self = prototype.clone();
+            return;
+        }
+        self.sync_parameters(prototype);
+        if let (Effect::ConvolutionReverb(v), Effect::ConvolutionReverb(p)) = (self, prototype) {
+            v.share_impulse_response(p);
+        }
+    }
+}
+
 impl EffectRenderTrait for Effect {
     fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
         static_dispatch!(self, render, input, output)
     }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        match (self, prototype) {
+            (Effect::Attenuate(v), Effect::Attenuate(p)) => v.sync_parameters(p),
+            (Effect::Reverb(v), Effect::Reverb(p)) => v.sync_parameters(p),
+            (Effect::LowPassFilter(v), Effect::LowPassFilter(p)) => v.sync_parameters(p),
+            (Effect::HighPassFilter(v), Effect::HighPassFilter(p)) => v.sync_parameters(p),
+            (Effect::BandPassFilter(v), Effect::BandPassFilter(p)) => v.sync_parameters(p),
+            (Effect::AllPassFilter(v), Effect::AllPassFilter(p)) => v.sync_parameters(p),
+            (Effect::LowShelfFilter(v), Effect::LowShelfFilter(p)) => v.sync_parameters(p),
+            (Effect::HighShelfFilter(v), Effect::HighShelfFilter(p)) => v.sync_parameters(p),
+            (Effect::Compressor(v), Effect::Compressor(p)) => v.sync_parameters(p),
+            (Effect::Delay(v), Effect::Delay(p)) => v.sync_parameters(p),
+            (Effect::Chorus(v), Effect::Chorus(p)) => v.sync_parameters(p),
+            (Effect::Waveshaper(v), Effect::Waveshaper(p)) => v.sync_parameters(p),
+            (Effect::ConvolutionReverb(v), Effect::ConvolutionReverb(p)) => v.sync_parameters(p),
+            // The effect was replaced by an effect of other kind, it is bypassed by the audio
+            // bus until it is re-created off the audio thread, see `Effect::prepare_instance`.
+            _ => (),
+        }
+    }
+
+    fn reserve_buffers(&mut self) {
+        static_dispatch!(self, reserve_buffers,)
+    }
 }
@@ -167,6 +167,8 @@ impl ChannelReverb {
 pub struct Reverb {
     dry: f32,
     wet: f32,
+    #[reflect(setter = "set_gain", min_value = 0.0)]
+    gain: f32,
     #[reflect(setter = "set_decay_time", min_value = 0.0)]
     decay_time: f32,
     #[reflect(setter = "set_fc", min_value = 0.0, max_value = 1.0)]
@@ -185,6 +187,7 @@ impl Visit for Reverb {
         self.wet.visit("Wet", &mut region)?;
         self.decay_time.visit("DecayTime", &mut region)?;
         self.fc.visit("Fc", &mut region)?;
+        let _ = self.gain.visit("Gain", &mut region);
 
         if region.is_reading() {
             self.left = ChannelReverb::new(0, self.fc, Reverb::FEEDBACK, self.decay_time);
@@ -214,6 +217,7 @@ impl Reverb {
         Self {
             dry: 1.0,
             wet: 1.0,
+            gain: 1.0,
             decay_time: 2.0,
             fc,
             left: ChannelReverb::new(0, fc, Reverb::FEEDBACK, decay_time),
@@ -246,6 +250,17 @@ impl Reverb {
         self.wet
     }
 
+    /// Sets gain of the reverberated signal. It could be used to control the amount of reverberation
+    /// without changing the dry part. Default value is 1.0.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.max(0.0);
+    }
+
+    /// Returns gain of the reverberated signal.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
     /// Sets actual sample rate of effect. It was designed to 44100 Hz sampling rate.
     /// TODO: This shouldn't be in public API.
     pub fn set_sample_rate(&mut self, sample_rate: usize) {
@@ -297,13 +312,25 @@ impl EffectRenderTrait for Reverb {
         for ((out_left, out_right), &(left, right)) in mix_buf.iter_mut().zip(input.iter()) {
             let mid = (left + right) * 0.5;
 
-            let processed_left = self.left.feed(mid);
-            let processed_right = self.right.feed(mid);
+            let processed_left = self.left.feed(mid) * self.gain;
+            let processed_right = self.right.feed(mid) * self.gain;
 
             *out_left = processed_left * wet + processed_right * dry + self.dry * left;
             *out_right = processed_right * wet + processed_left * dry + self.dry * right;
         }
     }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        self.dry = prototype.dry;
+        self.wet = prototype.wet;
+        self.gain = prototype.gain;
+        if self.decay_time != prototype.decay_time {
+            self.set_decay_time(prototype.decay_time);
+        }
+        if self.fc != prototype.fc {
+            self.set_fc(prototype.fc);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,252 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Waveshaper (distortion) effect module.
+//!
+//! # Overview
+//!
+//! Waveshaper amplifies the input signal and then passes it through a non-linear transfer
+//! function (curve), which adds harmonics to the signal. Depending on the curve and the amount of
+//! amplification (drive) it could produce anything from a subtle warmth to a harsh distortion. See
+//! [`WaveshaperCurve`] docs for the list of available curves.
+
+use crate::{dsp::db_to_linear, effects::EffectRenderTrait};
+use fyrox_core::{reflect::prelude::*, uuid_provider, visitor::prelude::*};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Transfer function of a waveshaper.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum WaveshaperCurve {
+    /// Hyperbolic tangent, smooth saturation similar to analog gear.
+    #[default]
+    Tanh,
+    /// Cubic soft clipping, `1.5x - 0.5x^3` in `[-1; 1]` range.
+    SoftClip,
+    /// Hard clipping at `[-1; 1]` range, harsh digital distortion.
+    HardClip,
+    /// The signal that exceeds `[-1; 1]` range is folded back, produces metallic sound.
+    Foldback,
+}
+
+uuid_provider!(WaveshaperCurve = "5d6f0b4e-8c1a-4e7b-9f2d-3a6c8e1b7d40");
+
+impl WaveshaperCurve {
+    /// Applies the curve to the given sample.
+    pub fn apply(self, sample: f32) -> f32 {
+        match self {
+            WaveshaperCurve::Tanh => sample.tanh(),
+            WaveshaperCurve::SoftClip => {
+                let x = sample.clamp(-1.0, 1.0);
+                1.5 * x - 0.5 * x * x * x
+            }
+            WaveshaperCurve::HardClip => sample.clamp(-1.0, 1.0),
+            WaveshaperCurve::Foldback => 1.0 - ((sample + 1.0).rem_euclid(4.0) - 2.0).abs(),
+        }
+    }
+}
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Waveshaper {
+    #[reflect(description = "Transfer function of the waveshaper.")]
+    curve: WaveshaperCurve,
+
+    #[reflect(
+        description = "Amplification (in decibels) of the signal before shaping.",
+        setter = "set_drive_db",
+        min_value = 0.0
+    )]
+    drive_db: f32,
+
+    #[reflect(
+        description = "Gain (in decibels) of the signal after shaping.",
+        setter = "set_output_gain_db"
+    )]
+    output_gain_db: f32,
+
+    #[reflect(
+        description = "Amount of the processed signal in the output, the rest is the unprocessed \
+        signal.",
+        setter = "set_mix",
+        min_value = 0.0,
+        max_value = 1.0
+    )]
+    mix: f32,
+}
+
+impl Default for Waveshaper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Waveshaper {
+    /// Creates new waveshaper with [`WaveshaperCurve::Tanh`] curve and 12 dB drive.
+    pub fn new() -> Self {
+        Self {
+            curve: Default::default(),
+            drive_db: 12.0,
+            output_gain_db: -6.0,
+            mix: 1.0,
+        }
+    }
+
+    /// Sets new transfer function.
+    pub fn set_curve(&mut self, curve: WaveshaperCurve) {
+        self.curve = curve;
+    }
+
+    /// Returns current transfer function.
+    pub fn curve(&self) -> WaveshaperCurve {
+        self.curve
+    }
+
+    /// Sets amplification (in decibels) of the signal before shaping.
+    pub fn set_drive_db(&mut self, drive_db: f32) {
+        self.drive_db = drive_db.max(0.0);
+    }
+
+    /// Returns amplification of the signal before shaping in decibels.
+    pub fn drive_db(&self) -> f32 {
+        self.drive_db
+    }
+
+    /// Sets gain (in decibels) of the signal after shaping.
+    pub fn set_output_gain_db(&mut self, output_gain_db: f32) {
+        self.output_gain_db = output_gain_db;
+    }
+
+    /// Returns gain of the signal after shaping in decibels.
+    pub fn output_gain_db(&self) -> f32 {
+        self.output_gain_db
+    }
+
+    /// Sets amount of the processed signal in the output.
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix = mix.clamp(0.0, 1.0);
+    }
+
+    /// Returns amount of the processed signal in the output.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+}
+
+impl EffectRenderTrait for Waveshaper {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let drive = db_to_linear(self.drive_db);
+        let output_gain = db_to_linear(self.output_gain_db);
+        let dry = 1.0 - self.mix;
+        let wet = self.mix * output_gain;
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            *output_left = *input_left * dry + self.curve.apply(*input_left * drive) * wet;
+            *output_right = *input_right * dry + self.curve.apply(*input_right * drive) * wet;
+        }
+    }
+
+    fn sync_parameters(&mut self, prototype: &Self) {
+        self.curve = prototype.curve;
+        self.drive_db = prototype.drive_db;
+        self.output_gain_db = prototype.output_gain_db;
+        self.mix = prototype.mix;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dsp::db_to_linear,
+        effects::{
+            waveshaper::{Waveshaper, WaveshaperCurve},
+            EffectRenderTrait,
+        },
+    };
+
+    fn shape(waveshaper: &mut Waveshaper, input: &[f32]) -> Vec<f32> {
+        let input = input.iter().map(|s| (*s, -*s)).collect::<Vec<_>>();
+        let mut output = vec![(0.0, 0.0); input.len()];
+        waveshaper.render(&input, &mut output);
+        // Transfer functions are odd, so the right channel must mirror the left one.
+        for (left, right) in output.iter() {
+            assert!((left + right).abs() < 1.0e-6);
+        }
+        output.into_iter().map(|(l, _)| l).collect()
+    }
+
+    #[test]
+    fn test_waveshaper_curves() {
+        let input = [0.0, 0.25, 0.5, 1.0, 2.0, 3.0];
+        for (curve, expected) in [
+            (WaveshaperCurve::HardClip, [0.0, 0.25, 0.5, 1.0, 1.0, 1.0]),
+            (
+                WaveshaperCurve::SoftClip,
+                [0.0, 0.3671875, 0.6875, 1.0, 1.0, 1.0],
+            ),
+            (WaveshaperCurve::Foldback, [0.0, 0.25, 0.5, 1.0, 0.0, -1.0]),
+            (WaveshaperCurve::Tanh, input.map(f32::tanh)),
+        ] {
+            let mut waveshaper = Waveshaper::new();
+            waveshaper.set_curve(curve);
+            waveshaper.set_drive_db(0.0);
+            waveshaper.set_output_gain_db(0.0);
+            for (actual, expected) in shape(&mut waveshaper, &input).iter().zip(expected) {
+                assert!((actual - expected).abs() < 1.0e-6, "{curve:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_waveshaper_drive_and_gain() {
+        let mut waveshaper = Waveshaper::new();
+        waveshaper.set_curve(WaveshaperCurve::HardClip);
+        waveshaper.set_drive_db(20.0);
+        waveshaper.set_output_gain_db(-6.0);
+
+        // Quiet signal is amplified ten times, loud signal is clipped.
+        let output = shape(&mut waveshaper, &[0.01, 0.5]);
+        let gain = db_to_linear(-6.0);
+        assert!((output[0] - 0.1 * gain).abs() < 1.0e-4);
+        assert!((output[1] - gain).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_waveshaper_mix() {
+        let mut waveshaper = Waveshaper::new();
+        waveshaper.set_curve(WaveshaperCurve::HardClip);
+        waveshaper.set_drive_db(0.0);
+        waveshaper.set_output_gain_db(0.0);
+        waveshaper.set_mix(0.25);
+
+        let output = shape(&mut waveshaper, &[2.0]);
+        assert!((output[0] - (2.0 * 0.75 + 0.25)).abs() < 1.0e-6);
+
+        // Zero mix passes the signal unchanged.
+        waveshaper.set_mix(0.0);
+        let input = (0..64)
+            .map(|i| (i as f32 * 0.3).sin() * 3.0)
+            .collect::<Vec<_>>();
+        assert_eq!(shape(&mut waveshaper, &input), input);
+    }
+}
@@ -24,7 +24,10 @@
 //!
 //! Sound engine manages contexts, feeds output device with data.
 
-use crate::context::{SoundContext, SAMPLE_RATE};
+use crate::{
+    context::{SoundContext, SAMPLE_RATE},
+    layout::ChannelLayout,
+};
 use fyrox_core::visitor::{Visit, VisitResult, Visitor};
 use std::error::Error;
 use std::sync::{Arc, Mutex, MutexGuard};
@@ -44,6 +47,7 @@ impl Default for SoundEngine {
 pub struct State {
     contexts: Vec<SoundContext>,
     output_device: Option<tinyaudio::OutputDevice>,
+    channel_layout: ChannelLayout,
 }
 
 impl SoundEngine {
@@ -63,22 +67,26 @@ impl SoundEngine {
         Self(Arc::new(Mutex::new(State {
             contexts: Default::default(),
             output_device: None,
+            channel_layout: Default::default(),
         })))
     }
 
-    /// Tries to initialize default audio output device.
+    /// Tries to initialize default audio output device. The device uses current channel layout of
+    /// the engine (see [`State::set_channel_layout`]).
     pub fn initialize_audio_output_device(&self) -> Result<(), Box<dyn Error>> {
         let state = self.clone();
+        let channel_layout = self.state().channel_layout;
 
         let device = tinyaudio::run_output_device(
             tinyaudio::OutputDeviceParameters {
                 sample_rate: SAMPLE_RATE as usize,
-                channels_count: 2,
+                channels_count: channel_layout.channel_count(),
                 channel_sample_count: SoundContext::SAMPLES_PER_CHANNEL,
             },
             {
                 move |buf| {
-                    // SAFETY: This is safe as long as channels count above is 2.
+                    // SAFETY: This is safe as long as channels count above is even, which is
+                    // true for every channel layout.
                     let data = unsafe {
                         std::slice::from_raw_parts_mut(
                             buf.as_mut_ptr() as *mut (f32, f32),
@@ -86,7 +94,7 @@ impl SoundEngine {
                         )
                     };
 
-                    state.state().render(data);
+                    state.state().render_with_layout(channel_layout, data);
                 }
             },
         )?;
@@ -138,27 +146,45 @@ impl State {
         &self.contexts
     }
 
-    /// Returns the length of buf to be passed to [`Self::render()`].
+    /// Sets the channel layout of the output. Sound contexts with other layouts are remixed to this
+    /// layout. The layout of an output device is defined at its initialization, so the device must
+    /// be re-initialized (see [`SoundEngine::initialize_audio_output_device`]) to use the new
+    /// layout.
+    pub fn set_channel_layout(&mut self, channel_layout: ChannelLayout) {
+        self.channel_layout = channel_layout;
+    }
+
+    /// Returns the channel layout of the output.
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.channel_layout
+    }
+
+    /// Returns the length of buf to be passed to [`Self::render()`] for stereo layout. Multichannel
+    /// layouts require [`ChannelLayout::channel_pair_count`] times more elements.
     pub fn render_buffer_len() -> usize {
         SoundContext::SAMPLES_PER_CHANNEL
     }
 
-    /// Renders the sound into buf. The buf must have at least [`Self::render_buffer_len()`]
-    /// elements. This method must be used if and only if the engine was created via
-    /// [`SoundEngine::without_device`].
+    /// Renders the sound into buf in the channel layout of the engine (see
+    /// [`Self::set_channel_layout`]). The buf must have at least [`Self::render_buffer_len()`]
+    /// elements for each channel pair of the layout. This method must be used if and only if the
+    /// engine was created via [`SoundEngine::without_device`].
     ///
     /// ## Deadlocks
     ///
     /// This method internally locks added sound contexts so it must be called when all the contexts
     /// are unlocked or you'll get a deadlock.
     pub fn render(&mut self, buf: &mut [(f32, f32)]) {
-        buf.fill((0.0, 0.0));
-        self.render_inner(buf);
+        self.render_with_layout(self.channel_layout, buf)
     }
 
-    fn render_inner(&mut self, buf: &mut [(f32, f32)]) {
+    /// Renders the sound into buf in the given channel layout. The buf contains interleaved channel
+    /// pairs of the layout, for example `(FL, FR), (FC, LFE), (BL, BR)` frames for 5.1 layout.
+    /// See [`Self::render`] for more info.
+    pub fn render_with_layout(&mut self, channel_layout: ChannelLayout, buf: &mut [(f32, f32)]) {
+        buf.fill((0.0, 0.0));
         for context in self.contexts.iter_mut() {
-            context.state().render(buf);
+            context.state().render_with_layout(channel_layout, buf);
         }
     }
 }
@@ -69,6 +69,10 @@ pub enum SoundError {
 
     /// A buffer is not loaded yet, consider to `await` it before use.
     BufferIsNotLoaded,
+
+    /// An error occurred while encoding samples (for example, when writing a WAV file). Exact
+    /// reason stored in inner value.
+    EncoderError(String),
 }
 
 impl From<std::io::Error> for SoundError {
@@ -83,6 +87,15 @@ impl<'a, T> From<std::sync::PoisonError<std::sync::MutexGuard<'a, T>>> for Sound
     }
 }
 
+impl From<hound::Error> for SoundError {
+    fn from(e: hound::Error) -> Self {
+        match e {
+            hound::Error::IoError(io) => SoundError::Io(io),
+            _ => SoundError::EncoderError(e.to_string()),
+        }
+    }
+}
+
 impl From<symphonia::core::errors::Error> for SoundError {
     fn from(e: symphonia::core::errors::Error) -> Self {
         SoundError::DecoderError(DecoderError::SymphoniaError(e))
@@ -109,6 +122,7 @@ impl Display for SoundError {
             SoundError::DecoderError(de) => write!(f, "internal decoder error: {de:?}"),
             SoundError::BufferFailedToLoad => write!(f, "a buffer failed to load"),
             SoundError::BufferIsNotLoaded => write!(f, "a buffer is not loaded yet"),
+            SoundError::EncoderError(reason) => write!(f, "encoder error: {reason}"),
         }
     }
 }
@@ -0,0 +1,698 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sound event is a resource that describes a sound with randomized variations. See [`SoundEvent`]
+//! docs for more info.
+
+use crate::{
+    buffer::SoundBufferResource,
+    bus::AudioBusGraph,
+    context::State,
+    source::{SoundSource, SoundSourceBuilder, Status},
+};
+use fyrox_core::{
+    algebra::Vector3,
+    io::FileError,
+    log::Log,
+    pool::{ErasedHandle, Handle},
+    rand::{seq::SliceRandom, thread_rng, Rng},
+    reflect::prelude::*,
+    uuid_provider,
+    visitor::prelude::*,
+    TypeUuidProvider, Uuid,
+};
+use fyrox_resource::{
+    io::ResourceIo,
+    loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+    manager::ResourceManager,
+    state::LoadError,
+    Resource, ResourceData,
+};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines how a sound event selects a buffer for each new instance.
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, Reflect, Visit, AsRefStr, EnumString, VariantNames,
+)]
+pub enum VariationMode {
+    /// Picks a random buffer, but never the same buffer twice in a row (if there are at least two
+    /// buffers).
+    #[default]
+    Random,
+
+    /// Picks buffers one after another in the order they're listed.
+    Sequential,
+
+    /// Plays all buffers in random order, then shuffles them again. No buffer is repeated until
+    /// all the others were played.
+    Shuffle,
+}
+
+uuid_provider!(VariationMode = "6b1f0f0e-1c0b-4a4e-9c4e-8f0b4f3e2d71");
+
+/// A single variation of a sound event, produced by [`SoundEvent::next_variation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundEventVariation {
+    /// Sound buffer to play.
+    pub buffer: SoundBufferResource,
+    /// Gain of the instance.
+    pub gain: f32,
+    /// Pitch of the instance.
+    pub pitch: f64,
+}
+
+#[derive(Debug, Default, Clone)]
+struct VariationState {
+    last: Option<usize>,
+    position: usize,
+    // Remaining buffer indices of the current shuffle round, along with the amount of buffers
+    // the round was made for.
+    shuffle_bag: Vec<usize>,
+    shuffle_bag_size: usize,
+    instances: Vec<ErasedHandle>,
+}
+
+/// Sound event describes a sound with randomized variations: it picks one of the buffers (see
+/// [`VariationMode`]), randomizes gain and pitch within the given ranges and limits the amount
+/// of simultaneously playing instances. This is useful for footsteps, impacts, gun shots, etc.
+/// where repeating exactly the same sound over and over again sounds unnatural.
+///
+/// Sound event is a resource (`*.sound_event` files), it could be created and edited in the
+/// editor. Use [`SoundEvent::play`] to play it in a sound context; fyrox-impl also allows to
+/// instantiate it as a scene node.
+///
+/// # Examples
+///
+/// ```rust
+/// use fyrox_sound::{
+///     algebra::Vector3,
+///     buffer::SoundBufferResource,
+///     context::SoundContext,
+///     event::{SoundEvent, VariationMode},
+/// };
+///
+/// fn play_footstep(context: &SoundContext, steps: Vec<SoundBufferResource>) {
+///     let mut event = SoundEvent::new();
+///     event.set_buffers(steps.into_iter().map(Some).collect());
+///     event.set_variation_mode(VariationMode::Random);
+///     event.set_gain_range(0.8, 1.0);
+///     event.set_pitch_range(0.95, 1.05);
+///     event.set_max_instances(Some(4));
+///
+///     event.play(&mut context.state(), Vector3::new(1.0, 0.0, 2.0));
+/// }
+/// ```
+#[derive(Debug, Clone, Visit, Reflect)]
+pub struct SoundEvent {
+    #[reflect(description = "A set of sound buffers, one of which is played by each instance.")]
+    buffers: Vec<Option<SoundBufferResource>>,
+
+    #[reflect(
+        description = "Defines how a buffer is selected for each new instance.",
+        setter = "set_variation_mode"
+    )]
+    variation_mode: VariationMode,
+
+    #[reflect(
+        description = "Minimal gain of an instance.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    min_gain: f32,
+
+    #[reflect(
+        description = "Maximal gain of an instance.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    max_gain: f32,
+
+    #[reflect(
+        description = "Minimal pitch of an instance.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    min_pitch: f64,
+
+    #[reflect(
+        description = "Maximal pitch of an instance.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    max_pitch: f64,
+
+    #[reflect(
+        description = "Maximum amount of simultaneously playing instances. New instances are \
+        not played if the limit is reached. None means no limit."
+    )]
+    max_instances: Option<usize>,
+
+    #[reflect(description = "A name of an audio bus to which instances are attached.")]
+    audio_bus: String,
+
+    #[reflect(
+        description = "Spatial blend factor of instances, 0.0 - 2D sound, 1.0 - 3D sound.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    spatial_blend: f32,
+
+    #[reflect(
+        description = "Radius of instances, see sound source docs for more info.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    radius: f32,
+
+    #[reflect(
+        description = "Rolloff factor of instances, see sound source docs for more info.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    rolloff_factor: f32,
+
+    #[reflect(
+        description = "Max distance of instances, see sound source docs for more info.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    max_distance: f32,
+
+    #[reflect(description = "Priority of instances, see sound source docs for more info.")]
+    priority: i32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    state: VariationState,
+}
+
+uuid_provider!(SoundEvent = "0b9b8a7e-3b0e-4f43-9d5a-4c3c0c6a9a1e");
+
+impl Default for SoundEvent {
+    fn default() -> Self {
+        Self {
+            buffers: Default::default(),
+            variation_mode: Default::default(),
+            min_gain: 1.0,
+            max_gain: 1.0,
+            min_pitch: 1.0,
+            max_pitch: 1.0,
+            max_instances: None,
+            audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            spatial_blend: 1.0,
+            radius: 10.0,
+            rolloff_factor: 1.0,
+            max_distance: f32::MAX,
+            priority: 0,
+            state: Default::default(),
+        }
+    }
+}
+
+impl SoundEvent {
+    /// Creates new sound event with no buffers. By default, gain and pitch are not randomized and
+    /// the amount of instances is not limited.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a new set of sound buffers.
+    pub fn set_buffers(&mut self, buffers: Vec<Option<SoundBufferResource>>) {
+        self.buffers = buffers;
+        self.state.shuffle_bag.clear();
+    }
+
+    /// Returns a set of sound buffers.
+    pub fn buffers(&self) -> &[Option<SoundBufferResource>] {
+        &self.buffers
+    }
+
+    /// Sets how a buffer is selected for each new instance.
+    pub fn set_variation_mode(&mut self, mode: VariationMode) {
+        self.variation_mode = mode;
+        self.state.shuffle_bag.clear();
+    }
+
+    /// Returns current variation mode.
+    pub fn variation_mode(&self) -> VariationMode {
+        self.variation_mode
+    }
+
+    /// Sets a range of gain of instances.
+    pub fn set_gain_range(&mut self, min: f32, max: f32) {
+        self.min_gain = min.max(0.0);
+        self.max_gain = max.max(0.0);
+    }
+
+    /// Returns a range of gain of instances.
+    pub fn gain_range(&self) -> (f32, f32) {
+        (self.min_gain, self.max_gain)
+    }
+
+    /// Sets a range of pitch of instances.
+    pub fn set_pitch_range(&mut self, min: f64, max: f64) {
+        self.min_pitch = min.max(0.0);
+        self.max_pitch = max.max(0.0);
+    }
+
+    /// Returns a range of pitch of instances.
+    pub fn pitch_range(&self) -> (f64, f64) {
+        (self.min_pitch, self.max_pitch)
+    }
+
+    /// Sets maximum amount of simultaneously playing instances, `None` means no limit.
+    pub fn set_max_instances(&mut self, max_instances: Option<usize>) {
+        self.max_instances = max_instances;
+    }
+
+    /// Returns maximum amount of simultaneously playing instances.
+    pub fn max_instances(&self) -> Option<usize> {
+        self.max_instances
+    }
+
+    /// Sets a name of an audio bus to which instances are attached.
+    pub fn set_audio_bus<S: AsRef<str>>(&mut self, audio_bus: S) {
+        audio_bus.as_ref().clone_into(&mut self.audio_bus);
+    }
+
+    /// Returns a name of an audio bus to which instances are attached.
+    pub fn audio_bus(&self) -> &str {
+        &self.audio_bus
+    }
+
+    /// Sets spatial blend factor of instances. See [`SoundSource::set_spatial_blend`].
+    pub fn set_spatial_blend(&mut self, k: f32) {
+        self.spatial_blend = k.clamp(0.0, 1.0);
+    }
+
+    /// Returns spatial blend factor of instances.
+    pub fn spatial_blend(&self) -> f32 {
+        self.spatial_blend
+    }
+
+    /// Sets radius of instances. See [`SoundSource::set_radius`].
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+    }
+
+    /// Returns radius of instances.
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Sets rolloff factor of instances. See [`SoundSource::set_rolloff_factor`].
+    pub fn set_rolloff_factor(&mut self, rolloff_factor: f32) {
+        self.rolloff_factor = rolloff_factor;
+    }
+
+    /// Returns rolloff factor of instances.
+    pub fn rolloff_factor(&self) -> f32 {
+        self.rolloff_factor
+    }
+
+    /// Sets max distance of instances. See [`SoundSource::set_max_distance`].
+    pub fn set_max_distance(&mut self, max_distance: f32) {
+        self.max_distance = max_distance;
+    }
+
+    /// Returns max distance of instances.
+    pub fn max_distance(&self) -> f32 {
+        self.max_distance
+    }
+
+    /// Sets priority of instances. See [`SoundSource::set_priority`].
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    /// Returns priority of instances.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Returns amount of instances that were alive at the last call of [`Self::next_variation`]
+    /// plus the instances added after it.
+    pub fn instance_count(&self) -> usize {
+        self.state.instances.len()
+    }
+
+    fn select(&mut self, count: usize) -> usize {
+        let mut rng = thread_rng();
+        let state = &mut self.state;
+        let last = state.last.filter(|last| *last < count);
+        let index = match self.variation_mode {
+            VariationMode::Random => match last {
+                Some(last) if count > 1 => {
+                    let index = rng.gen_range(0..count - 1);
+                    if index >= last {
+                        index + 1
+                    } else {
+                        index
+                    }
+                }
+                _ => rng.gen_range(0..count),
+            },
+            VariationMode::Sequential => {
+                let index = state.position % count;
+                state.position = index + 1;
+                index
+            }
+            VariationMode::Shuffle => {
+                if state.shuffle_bag.is_empty() || state.shuffle_bag_size != count {
+                    state.shuffle_bag.clear();
+                    state.shuffle_bag.extend(0..count);
+                    state.shuffle_bag.shuffle(&mut rng);
+                    state.shuffle_bag_size = count;
+                    // Do not repeat the last buffer of the previous round.
+                    if count > 1 && state.shuffle_bag.last().cloned() == last {
+                        state.shuffle_bag.swap(0, count - 1);
+                    }
+                }
+                state.shuffle_bag.pop().unwrap_or_default()
+            }
+        };
+        state.last = Some(index);
+        index
+    }
+
+    /// Produces a new variation of the sound event. `is_instance_alive` is used to check whether
+    /// a previously added instance (see [`Self::add_instance`]) is still playing. Returns `None`
+    /// if there are no buffers or if the instance limit is reached.
+    pub fn next_variation<F>(&mut self, mut is_instance_alive: F) -> Option<SoundEventVariation>
+    where
+        F: FnMut(ErasedHandle) -> bool,
+    {
+        self.state
+            .instances
+            .retain(|instance| is_instance_alive(*instance));
+
+        if self
+            .max_instances
+            .is_some_and(|max_instances| self.state.instances.len() >= max_instances)
+        {
+            return None;
+        }
+
+        let count = self.buffers.iter().flatten().count();
+        if count == 0 {
+            return None;
+        }
+        let index = self.select(count);
+        let buffer = self.buffers.iter().flatten().nth(index)?.clone();
+
+        let mut rng = thread_rng();
+        let gain = if self.min_gain < self.max_gain {
+            rng.gen_range(self.min_gain..=self.max_gain)
+        } else {
+            self.min_gain
+        };
+        let pitch = if self.min_pitch < self.max_pitch {
+            rng.gen_range(self.min_pitch..=self.max_pitch)
+        } else {
+            self.min_pitch
+        };
+
+        Some(SoundEventVariation {
+            buffer,
+            gain,
+            pitch,
+        })
+    }
+
+    /// Registers a new instance of the sound event, so it will be counted by the instance limit.
+    pub fn add_instance(&mut self, instance: ErasedHandle) {
+        self.state.instances.push(instance);
+    }
+
+    /// Creates a new one-shot sound source builder for the given variation, with the parameters of
+    /// the sound event. The source will be removed automatically when it finishes playing.
+    pub fn make_source_builder(&self, variation: SoundEventVariation) -> SoundSourceBuilder {
+        SoundSourceBuilder::new()
+            .with_buffer(variation.buffer)
+            .with_gain(variation.gain)
+            .with_pitch(variation.pitch)
+            .with_bus(&self.audio_bus)
+            .with_spatial_blend_factor(self.spatial_blend)
+            .with_radius(self.radius)
+            .with_rolloff_factor(self.rolloff_factor)
+            .with_max_distance(self.max_distance)
+            .with_priority(self.priority)
+            .with_play_once(true)
+            .with_status(Status::Playing)
+    }
+
+    /// Plays a new instance of the sound event at the given position. Returns a handle of the
+    /// new one-shot sound source or `None` if nothing was played (see [`Self::next_variation`]).
+    /// Keep in mind, that the handle becomes invalid when the sound finishes playing.
+    pub fn play(
+        &mut self,
+        state: &mut State,
+        position: Vector3<f32>,
+    ) -> Option<Handle<SoundSource>> {
+        let variation = self.next_variation(|instance| {
+            state
+                .sources()
+                .try_borrow(instance.into())
+                .is_some_and(|source| source.status() == Status::Playing)
+        })?;
+
+        match self
+            .make_source_builder(variation)
+            .with_position(position)
+            .build()
+        {
+            Ok(source) => {
+                let handle = state.add_source(source);
+                self.add_instance(handle.into());
+                Some(handle)
+            }
+            Err(err) => {
+                Log::err(format!("Unable to play a sound event. Reason: {err:?}"));
+                None
+            }
+        }
+    }
+}
+
+/// An error that may occur during sound event resource loading.
+#[derive(Debug)]
+pub enum SoundEventError {
+    /// An i/o error has occurred.
+    Io(FileError),
+
+    /// An error that may occur due to version incompatibilities.
+    Visit(VisitError),
+}
+
+impl Display for SoundEventError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            Self::Visit(v) => {
+                write!(
+                    f,
+                    "An error that may occur due to version incompatibilities. {v:?}"
+                )
+            }
+        }
+    }
+}
+
+impl From<FileError> for SoundEventError {
+    fn from(e: FileError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<VisitError> for SoundEventError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+impl SoundEvent {
+    /// Loads a sound event from the given file.
+    pub async fn from_file(
+        path: &Path,
+        io: &dyn ResourceIo,
+        resource_manager: ResourceManager,
+    ) -> Result<Self, SoundEventError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        visitor.blackboard.register(Arc::new(resource_manager));
+        let mut sound_event = SoundEvent::default();
+        sound_event.visit("SoundEvent", &mut visitor)?;
+        Ok(sound_event)
+    }
+}
+
+impl ResourceData for SoundEvent {
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("SoundEvent", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+/// A loader for sound event resources.
+pub struct SoundEventLoader {
+    /// Resource manager handle, it is needed to load sound buffers of the sound event.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader for SoundEventLoader {
+    fn extensions(&self) -> &[&str] {
+        &["sound_event"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        <SoundEvent as TypeUuidProvider>::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        Box::pin(async move {
+            let sound_event = SoundEvent::from_file(&path, io.as_ref(), resource_manager)
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(sound_event))
+        })
+    }
+}
+
+/// Sound event resource.
+pub type SoundEventResource = Resource<SoundEvent>;
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource, SoundBufferResourceExtension},
+        context::{SoundContext, SAMPLE_RATE},
+        event::{SoundEvent, VariationMode},
+    };
+    use fyrox_core::algebra::Vector3;
+
+    fn make_event(
+        buffer_count: usize,
+        mode: VariationMode,
+    ) -> (SoundEvent, Vec<SoundBufferResource>) {
+        let buffers = (0..buffer_count)
+            .map(|_| {
+                SoundBufferResource::new_generic(DataSource::Raw {
+                    sample_rate: SAMPLE_RATE as usize,
+                    channel_count: 1,
+                    samples: vec![1.0; 1024],
+                })
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut event = SoundEvent::new();
+        event.set_buffers(buffers.iter().cloned().map(Some).collect());
+        event.set_variation_mode(mode);
+        (event, buffers)
+    }
+
+    fn next_index(event: &mut SoundEvent, buffers: &[SoundBufferResource]) -> usize {
+        let variation = event.next_variation(|_| true).unwrap();
+        buffers.iter().position(|b| *b == variation.buffer).unwrap()
+    }
+
+    #[test]
+    fn test_variation_modes() {
+        let (mut event, buffers) = make_event(4, VariationMode::Random);
+        let mut last = None;
+        for _ in 0..100 {
+            let index = next_index(&mut event, &buffers);
+            assert_ne!(Some(index), last);
+            last = Some(index);
+        }
+
+        let (mut event, buffers) = make_event(3, VariationMode::Sequential);
+        let indices = (0..6)
+            .map(|_| next_index(&mut event, &buffers))
+            .collect::<Vec<_>>();
+        assert_eq!(indices, [0, 1, 2, 0, 1, 2]);
+
+        let (mut event, buffers) = make_event(4, VariationMode::Shuffle);
+        let mut last = None;
+        for _ in 0..10 {
+            let mut round = (0..4)
+                .map(|_| next_index(&mut event, &buffers))
+                .collect::<Vec<_>>();
+            assert_ne!(Some(round[0]), last);
+            last = round.last().cloned();
+            round.sort();
+            assert_eq!(round, [0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn test_gain_and_pitch_ranges() {
+        let (mut event, _) = make_event(1, VariationMode::Random);
+        event.set_gain_range(0.5, 0.75);
+        event.set_pitch_range(0.9, 1.1);
+        for _ in 0..100 {
+            let variation = event.next_variation(|_| true).unwrap();
+            assert!((0.5..=0.75).contains(&variation.gain));
+            assert!((0.9..=1.1).contains(&variation.pitch));
+        }
+
+        // No buffers - no variations.
+        assert!(SoundEvent::new().next_variation(|_| true).is_none());
+    }
+
+    #[test]
+    fn test_instance_limit() {
+        let context = SoundContext::new();
+        let (mut event, _) = make_event(2, VariationMode::Random);
+        event.set_max_instances(Some(2));
+
+        let mut state = context.state();
+        let first = event.play(&mut state, Vector3::default()).unwrap();
+        let second = event.play(&mut state, Vector3::default()).unwrap();
+        assert!(event.play(&mut state, Vector3::default()).is_none());
+        assert_eq!(event.instance_count(), 2);
+        assert!(state.source(first).is_play_once());
+        assert_eq!(state.source(second).gain(), 1.0);
+
+        // Finished one-shot sources are removed, which frees a slot.
+        state.remove_source(first);
+        assert!(event.play(&mut state, Vector3::default()).is_some());
+        assert_eq!(event.instance_count(), 2);
+    }
+}
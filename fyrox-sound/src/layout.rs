@@ -0,0 +1,384 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Output channel layouts.
+//!
+//! # Overview
+//!
+//! Channel layout defines the amount and the placement of speakers of an output device. Sound
+//! contexts can render sounds in any of the supported layouts: spatial sources are panned between
+//! the two closest speakers using vector-base amplitude panning (VBAP), the output of a context is
+//! then remixed to the layout of the output device. Layouts without some speakers of the source
+//! layout get a downmix (for example, 5.1 to stereo), layouts with more speakers simply leave the
+//! missing channels silent.
+//!
+//! Channels of multichannel layouts are stored in interleaved format in pairs, for example each
+//! frame of a 5.1 signal consists of `(FL, FR), (FC, LFE), (BL, BR)` pairs.
+
+use fyrox_core::{reflect::prelude::*, uuid_provider, visitor::prelude::*};
+use std::f32::consts::FRAC_1_SQRT_2;
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// A speaker of an output device.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Speaker {
+    /// Front left speaker.
+    FrontLeft,
+    /// Front right speaker.
+    FrontRight,
+    /// Front center speaker.
+    FrontCenter,
+    /// Low-frequency effects channel (subwoofer).
+    LowFrequency,
+    /// Back (rear) left speaker.
+    BackLeft,
+    /// Back (rear) right speaker.
+    BackRight,
+    /// Side left speaker.
+    SideLeft,
+    /// Side right speaker.
+    SideRight,
+}
+
+/// Channel layout defines the amount, the order and the placement of speakers. See module docs
+/// for more info.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum ChannelLayout {
+    /// Two channels: front left and front right.
+    #[default]
+    Stereo,
+    /// Four channels: front left, front right, back left and back right.
+    Quad,
+    /// Six channels: front left, front right, front center, low-frequency effects, back left and
+    /// back right.
+    Surround51,
+    /// Eight channels: front left, front right, front center, low-frequency effects, back left,
+    /// back right, side left and side right.
+    Surround71,
+}
+
+uuid_provider!(ChannelLayout = "0f3c7b52-9a4e-4d1b-8e6f-2c5a7d9e1b34");
+
+impl ChannelLayout {
+    /// Maximum amount of channels of all the supported layouts.
+    pub const MAX_CHANNELS: usize = 8;
+
+    /// Returns the speakers of the layout in the order of the channels.
+    pub fn speakers(self) -> &'static [Speaker] {
+        use Speaker::*;
+        match self {
+            ChannelLayout::Stereo => &[FrontLeft, FrontRight],
+            ChannelLayout::Quad => &[FrontLeft, FrontRight, BackLeft, BackRight],
+            ChannelLayout::Surround51 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                BackLeft,
+                BackRight,
+            ],
+            ChannelLayout::Surround71 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                BackLeft,
+                BackRight,
+                SideLeft,
+                SideRight,
+            ],
+        }
+    }
+
+    /// Returns the amount of channels of the layout.
+    pub fn channel_count(self) -> usize {
+        self.speakers().len()
+    }
+
+    /// Returns the amount of channel pairs of the layout. Each layout has an even amount of
+    /// channels.
+    pub fn channel_pair_count(self) -> usize {
+        self.channel_count() / 2
+    }
+
+    /// Returns the index of the channel of the given speaker, if the layout has the speaker.
+    pub fn channel_of(self, speaker: Speaker) -> Option<usize> {
+        self.speakers().iter().position(|s| *s == speaker)
+    }
+
+    /// Returns the azimuth (in degrees) of the given speaker in this layout. Zero azimuth is
+    /// straight ahead, positive values are to the right of the listener. The placement follows
+    /// ITU-R BS.775 recommendations. Low-frequency effects channel has no direction, so `None` is
+    /// returned for it.
+    pub fn azimuth(self, speaker: Speaker) -> Option<f32> {
+        let quad = self == ChannelLayout::Quad;
+        match speaker {
+            Speaker::FrontLeft => Some(if quad { -45.0 } else { -30.0 }),
+            Speaker::FrontRight => Some(if quad { 45.0 } else { 30.0 }),
+            Speaker::FrontCenter => Some(0.0),
+            Speaker::LowFrequency => None,
+            Speaker::BackLeft | Speaker::BackRight => {
+                let azimuth = match self {
+                    ChannelLayout::Quad => 135.0,
+                    ChannelLayout::Surround71 => 150.0,
+                    _ => 110.0,
+                };
+                Some(if speaker == Speaker::BackLeft {
+                    -azimuth
+                } else {
+                    azimuth
+                })
+            }
+            Speaker::SideLeft => Some(-90.0),
+            Speaker::SideRight => Some(90.0),
+        }
+    }
+
+    /// Calculates panning gains of each channel for a sound coming from the given azimuth (in
+    /// degrees, see [`Self::azimuth`]) using vector-base amplitude panning. The sound is panned
+    /// between the two closest speakers with constant power, other channels (including
+    /// low-frequency effects channel) get zero gain. `gains` must have at least
+    /// [`Self::channel_count`] elements.
+    pub fn pan(self, azimuth: f32, gains: &mut [f32]) {
+        let gains = &mut gains[..self.channel_count()];
+        gains.fill(0.0);
+
+        // Directional speakers sorted clockwise, starting from the front.
+        let mut ring = [(0usize, 0.0f32); Self::MAX_CHANNELS];
+        let mut ring_len = 0;
+        for (channel, speaker) in self.speakers().iter().enumerate() {
+            if let Some(speaker_azimuth) = self.azimuth(*speaker) {
+                ring[ring_len] = (channel, speaker_azimuth.rem_euclid(360.0));
+                ring_len += 1;
+            }
+        }
+        let ring = &mut ring[..ring_len];
+        ring.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let azimuth = azimuth.rem_euclid(360.0);
+        // Layouts without rear speakers (stereo) cannot place sounds behind the listener, such
+        // sounds are mirrored to the front.
+        let mirrored = (180.0 - azimuth).rem_euclid(360.0);
+        for direction in [azimuth, mirrored] {
+            for i in 0..ring.len() {
+                let (first, first_azimuth) = ring[i];
+                let (second, second_azimuth) = ring[(i + 1) % ring.len()];
+                let span = (second_azimuth - first_azimuth).rem_euclid(360.0);
+                let offset = (direction - first_azimuth).rem_euclid(360.0);
+                if span > 0.0 && span < 180.0 && offset <= span {
+                    let (first_gain, second_gain) =
+                        vbap_pair(first_azimuth, second_azimuth, direction);
+                    gains[first] += first_gain;
+                    gains[second] += second_gain;
+                    return;
+                }
+            }
+        }
+
+        // The direction is outside of any speaker pair, use the closest speaker.
+        if let Some((channel, _)) = ring.iter().min_by(|a, b| {
+            angular_distance(a.1, azimuth).total_cmp(&angular_distance(b.1, azimuth))
+        }) {
+            gains[*channel] = 1.0;
+        }
+    }
+
+    /// Returns a gain with which the given speaker of this layout is mixed into the given speaker
+    /// of the target layout. Speakers present in both layouts are mapped one-to-one. Missing
+    /// speakers are downmixed using ITU-R BS.775 coefficients: center channel goes to the front
+    /// pair at -3 dB, side and back channels go to each other or to the front pair at -3 dB.
+    /// Low-frequency effects channel is discarded, if the target layout has no such channel.
+    pub fn mix_gain(self, speaker: Speaker, target: ChannelLayout, target_speaker: Speaker) -> f32 {
+        use Speaker::*;
+
+        if target.channel_of(speaker).is_some() {
+            return if speaker == target_speaker { 1.0 } else { 0.0 };
+        }
+
+        match (speaker, target_speaker) {
+            (FrontCenter, FrontLeft | FrontRight) => FRAC_1_SQRT_2,
+            (SideLeft, BackLeft) | (BackLeft, SideLeft) => 1.0,
+            (SideRight, BackRight) | (BackRight, SideRight) => 1.0,
+            (SideLeft | BackLeft, FrontLeft) | (SideRight | BackRight, FrontRight)
+                if target.channel_of(Speaker::BackLeft).is_none()
+                    && target.channel_of(Speaker::SideLeft).is_none() =>
+            {
+                FRAC_1_SQRT_2
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Returns a matrix of gains to remix a signal of this layout to the target layout. The
+    /// element `[target_channel][channel]` is the gain of `channel` in `target_channel`.
+    pub fn remix_matrix(
+        self,
+        target: ChannelLayout,
+    ) -> [[f32; Self::MAX_CHANNELS]; Self::MAX_CHANNELS] {
+        let mut matrix = [[0.0; Self::MAX_CHANNELS]; Self::MAX_CHANNELS];
+        for (target_channel, target_speaker) in target.speakers().iter().enumerate() {
+            for (channel, speaker) in self.speakers().iter().enumerate() {
+                matrix[target_channel][channel] = self.mix_gain(*speaker, target, *target_speaker);
+            }
+        }
+        matrix
+    }
+
+    /// Remixes interleaved samples of this layout into interleaved samples of the target layout.
+    /// The result is added to the samples of the output buffer. Only the complete frames of the
+    /// input and the output buffers are processed.
+    pub fn remix(self, target: ChannelLayout, input: &[f32], output: &mut [f32]) {
+        let matrix = self.remix_matrix(target);
+        for (input_frame, output_frame) in input
+            .chunks_exact(self.channel_count())
+            .zip(output.chunks_exact_mut(target.channel_count()))
+        {
+            for (output_sample, gains) in output_frame.iter_mut().zip(matrix.iter()) {
+                for (input_sample, gain) in input_frame.iter().zip(gains) {
+                    *output_sample += *input_sample * *gain;
+                }
+            }
+        }
+    }
+}
+
+fn angular_distance(a: f32, b: f32) -> f32 {
+    let difference = (a - b).rem_euclid(360.0);
+    difference.min(360.0 - difference)
+}
+
+// Calculates normalized gains of two speakers to place a sound in the given direction.
+fn vbap_pair(first_azimuth: f32, second_azimuth: f32, azimuth: f32) -> (f32, f32) {
+    let direction = |azimuth: f32| {
+        let (sin, cos) = azimuth.to_radians().sin_cos();
+        (sin, cos)
+    };
+    let (x1, y1) = direction(first_azimuth);
+    let (x2, y2) = direction(second_azimuth);
+    let (x, y) = direction(azimuth);
+    let determinant = x1 * y2 - y1 * x2;
+    let first_gain = ((x * y2 - y * x2) / determinant).max(0.0);
+    let second_gain = ((x1 * y - y1 * x) / determinant).max(0.0);
+    let norm = (first_gain * first_gain + second_gain * second_gain).sqrt();
+    if norm > f32::EPSILON {
+        (first_gain / norm, second_gain / norm)
+    } else {
+        (FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layout::{ChannelLayout, Speaker};
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    fn pan(layout: ChannelLayout, azimuth: f32) -> Vec<f32> {
+        let mut gains = vec![0.0; layout.channel_count()];
+        layout.pan(azimuth, &mut gains);
+        gains
+    }
+
+    fn assert_gains(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, b) in actual.iter().zip(expected) {
+            assert!((a - b).abs() < 1.0e-4, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_vbap_panning() {
+        let layout = ChannelLayout::Surround51;
+        // Straight at the speakers.
+        assert_gains(&pan(layout, 0.0), &[0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+        assert_gains(&pan(layout, -30.0), &[1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_gains(&pan(layout, 110.0), &[0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        // Right behind the listener - between the back speakers.
+        assert_gains(
+            &pan(layout, 180.0),
+            &[0.0, 0.0, 0.0, 0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+        );
+
+        // Constant power for any direction.
+        for layout in [
+            ChannelLayout::Stereo,
+            ChannelLayout::Quad,
+            ChannelLayout::Surround51,
+            ChannelLayout::Surround71,
+        ] {
+            for azimuth in (-180..180).step_by(7) {
+                let gains = pan(layout, azimuth as f32);
+                let power = gains.iter().map(|g| g * g).sum::<f32>();
+                assert!((power - 1.0).abs() < 1.0e-4, "{layout:?} {azimuth}");
+                if let Some(lfe) = layout.channel_of(Speaker::LowFrequency) {
+                    assert_eq!(gains[lfe], 0.0);
+                }
+            }
+        }
+
+        // Stereo mirrors rear sounds to the front and clamps sounds outside of the front arc.
+        assert_gains(
+            &pan(ChannelLayout::Stereo, 180.0),
+            &pan(ChannelLayout::Stereo, 0.0),
+        );
+        assert_gains(&pan(ChannelLayout::Stereo, 90.0), &[0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_downmix() {
+        let mut output = [0.0; 2];
+        // FL, FR, FC, LFE, BL, BR
+        ChannelLayout::Surround51.remix(
+            ChannelLayout::Stereo,
+            &[1.0, 0.0, 1.0, 1.0, 0.0, 1.0],
+            &mut output,
+        );
+        assert_gains(
+            &output,
+            &[1.0 + FRAC_1_SQRT_2, FRAC_1_SQRT_2 + FRAC_1_SQRT_2],
+        );
+
+        // 7.1 to 5.1 folds side channels into back ones.
+        let mut output = [0.0; 6];
+        ChannelLayout::Surround71.remix(
+            ChannelLayout::Surround51,
+            &[0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.5, 0.25],
+            &mut output,
+        );
+        assert_gains(&output, &[0.0, 0.0, 0.0, 0.0, 1.0, 0.25]);
+
+        // Upmix keeps the front pair and leaves everything else silent.
+        let mut output = [0.0; 8];
+        ChannelLayout::Stereo.remix(ChannelLayout::Surround71, &[0.5, 0.25], &mut output);
+        assert_gains(&output, &[0.5, 0.25, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    }
+}
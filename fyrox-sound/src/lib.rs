@@ -23,10 +23,18 @@
 //! ## Features
 //!
 //! - Generic and spatial sounds.
-//! - WAV and OGG/Vorbis formats support.
+//! - Doppler effect for moving sound sources and listener.
+//! - Voice limiting with priorities and virtualization of inaudible sound sources.
+//! - WAV and OGG/Vorbis formats support, FLAC, MP3 and AAC/ALAC (M4A) formats support behind
+//!   the `flac`, `mp3`, `aac` and `alac` features (all enabled by default).
 //! - Streaming.
+//! - Procedural sounds: oscillators, noise, envelopes, callback and lock-free queue sources.
+//! - Sound events: randomized variations of buffers, gain and pitch with instance limiting.
+//! - Offline rendering into memory buffers or WAV files.
 //! - Head-related transfer function support ([HRTF](https://en.wikipedia.org/wiki/Head-related_transfer_function)).
-//! - Reverb effect.
+//! - Reverb effect (algorithmic and convolution with impulse responses).
+//! - Per-bus metering: peak, RMS, LUFS loudness and magnitude spectrum.
+//! - Stereo, quad, 5.1 and 7.1 output with VBAP panning and downmixing.
 //!
 //! ## Examples
 //!
@@ -95,7 +103,11 @@ pub mod dsp;
 pub mod effects;
 pub mod engine;
 pub mod error;
+pub mod event;
+pub mod layout;
 pub mod listener;
+pub mod meter;
+pub mod offline;
 pub mod renderer;
 pub mod source;
 
@@ -0,0 +1,608 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Audio bus metering and spectrum analysis. See [`MeterSnapshot`] and [`MeterHandle`] docs for
+//! more info.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use fyrox_sound::context::SoundContext;
+//!
+//! let context = SoundContext::new();
+//!
+//! // Enable metering of the primary bus and remember the handle. The handle is cheap to clone
+//! // and can be sent to other threads.
+//! let meter = {
+//!     let mut state = context.state();
+//!     let primary_bus = state.bus_graph_mut().primary_bus_mut();
+//!     primary_bus.set_metering_enabled(true);
+//!     primary_bus.set_spectrum_analysis_enabled(true);
+//!     primary_bus.meter().unwrap()
+//! };
+//!
+//! // Later, for example once per frame, fetch the latest measurements.
+//! let snapshot = meter.snapshot();
+//! println!(
+//!     "Peak: {:?} dB, short-term loudness: {} LUFS",
+//!     snapshot.peak_db(),
+//!     snapshot.short_term_loudness
+//! );
+//! ```
+
+use crate::{
+    context::SAMPLE_RATE,
+    dsp::{
+        filters::{Biquad, BiquadKind},
+        linear_to_db,
+    },
+};
+use realfft::{num_complex::Complex, RealFftPlanner, RealToComplex};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::{Arc, Mutex},
+};
+
+/// Size (in samples) of the window used for spectrum analysis.
+pub const SPECTRUM_WINDOW_SIZE: usize = 1024;
+
+/// Amount of magnitude bins in a spectrum, see [`MeterSnapshot::spectrum`].
+pub const SPECTRUM_BIN_COUNT: usize = SPECTRUM_WINDOW_SIZE / 2;
+
+// Loudness is measured in 100 ms steps, momentary loudness uses 400 ms window, short-term - 3 s.
+const LOUDNESS_STEP: usize = SAMPLE_RATE as usize / 10;
+const MOMENTARY_STEPS: usize = 4;
+const SHORT_TERM_STEPS: usize = 30;
+
+// Gating of integrated loudness as defined in ITU-R BS.1770.
+const ABSOLUTE_GATE: f32 = -70.0;
+const RELATIVE_GATE: f32 = -10.0;
+
+// Gating blocks are accumulated in a histogram with 0.1 LU bins covering [-70; +10] LUFS range,
+// so integrated loudness can be measured for any duration with fixed memory.
+const HISTOGRAM_BIN_WIDTH: f32 = 0.1;
+const HISTOGRAM_BIN_COUNT: usize = 800;
+
+fn loudness(mean_square: f64) -> f32 {
+    if mean_square > 0.0 {
+        (-0.691 + 10.0 * mean_square.log10()) as f32
+    } else {
+        f32::NEG_INFINITY
+    }
+}
+
+/// A set of measurements of an audio bus output made during the last render.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeterSnapshot {
+    /// Peak amplitude of left and right channels over the last rendered block.
+    pub peak: (f32, f32),
+
+    /// Root mean square amplitude of left and right channels over the last rendered block.
+    pub rms: (f32, f32),
+
+    /// Loudness (in LUFS) over the last 400 ms, `-inf` until enough samples were measured.
+    pub momentary_loudness: f32,
+
+    /// Loudness (in LUFS) over the last 3 seconds, `-inf` until enough samples were measured.
+    pub short_term_loudness: f32,
+
+    /// Gated loudness (in LUFS) since metering was enabled or since the last call of
+    /// [`crate::bus::AudioBus::reset_integrated_loudness`].
+    pub integrated_loudness: f32,
+
+    /// Magnitude spectrum of the last [`SPECTRUM_WINDOW_SIZE`] samples of a mono mix of the
+    /// output. It has [`SPECTRUM_BIN_COUNT`] bins (see [`Self::bin_frequency`]) normalized so a
+    /// full-scale sine wave gives magnitude close to `1.0`. Empty if spectrum analysis is disabled.
+    pub spectrum: Vec<f32>,
+}
+
+impl Default for MeterSnapshot {
+    fn default() -> Self {
+        Self {
+            peak: (0.0, 0.0),
+            rms: (0.0, 0.0),
+            momentary_loudness: f32::NEG_INFINITY,
+            short_term_loudness: f32::NEG_INFINITY,
+            integrated_loudness: f32::NEG_INFINITY,
+            spectrum: Default::default(),
+        }
+    }
+}
+
+impl MeterSnapshot {
+    /// Returns peak amplitude of left and right channels in decibels.
+    pub fn peak_db(&self) -> (f32, f32) {
+        (linear_to_db(self.peak.0), linear_to_db(self.peak.1))
+    }
+
+    /// Returns root mean square amplitude of left and right channels in decibels.
+    pub fn rms_db(&self) -> (f32, f32) {
+        (linear_to_db(self.rms.0), linear_to_db(self.rms.1))
+    }
+
+    /// Returns center frequency (in Hz) of a spectrum bin with the given index.
+    pub fn bin_frequency(index: usize) -> f32 {
+        index as f32 * SAMPLE_RATE as f32 / SPECTRUM_WINDOW_SIZE as f32
+    }
+
+    // Copies the snapshot without allocations (if the spectrum of `other` has enough capacity).
+    fn copy_to(&self, other: &mut MeterSnapshot) {
+        other.peak = self.peak;
+        other.rms = self.rms;
+        other.momentary_loudness = self.momentary_loudness;
+        other.short_term_loudness = self.short_term_loudness;
+        other.integrated_loudness = self.integrated_loudness;
+        other.spectrum.clone_from(&self.spectrum);
+    }
+}
+
+/// Thread-safe handle to measurements of an audio bus. It could be obtained by
+/// [`crate::bus::AudioBus::meter`] and it does not require the sound context to be locked to
+/// read the measurements.
+#[derive(Clone, Default, Debug)]
+pub struct MeterHandle(Arc<Mutex<MeterSnapshot>>);
+
+impl MeterHandle {
+    /// Returns a copy of the latest measurements.
+    pub fn snapshot(&self) -> MeterSnapshot {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+// K-weighting filter as defined in ITU-R BS.1770: a high shelf, that models acoustic effects of
+// a head, followed by a high-pass filter.
+#[derive(Clone)]
+struct KWeighting {
+    shelf: Biquad,
+    high_pass: Biquad,
+}
+
+impl Default for KWeighting {
+    fn default() -> Self {
+        Self {
+            shelf: Biquad::new(
+                BiquadKind::HighShelf,
+                1681.97 / SAMPLE_RATE as f32,
+                // +4 dB
+                10.0f32.powf(4.0 / 40.0),
+                0.70718,
+            ),
+            high_pass: Biquad::new(
+                BiquadKind::HighPass,
+                38.135 / SAMPLE_RATE as f32,
+                1.0,
+                0.50033,
+            ),
+        }
+    }
+}
+
+impl KWeighting {
+    fn feed(&mut self, sample: f32) -> f32 {
+        self.high_pass.feed(self.shelf.feed(sample))
+    }
+}
+
+struct LoudnessMeter {
+    filters: [KWeighting; 2],
+    step_energy: f64,
+    step_length: usize,
+    // Ring buffer with mean squares of the last steps.
+    steps: [f64; SHORT_TERM_STEPS],
+    step_position: usize,
+    step_count: usize,
+    histogram_counts: Box<[u32; HISTOGRAM_BIN_COUNT]>,
+    histogram_energy: Box<[f64; HISTOGRAM_BIN_COUNT]>,
+    momentary: f32,
+    short_term: f32,
+    integrated: f32,
+}
+
+impl Default for LoudnessMeter {
+    fn default() -> Self {
+        Self {
+            filters: Default::default(),
+            step_energy: 0.0,
+            step_length: 0,
+            steps: [0.0; SHORT_TERM_STEPS],
+            step_position: 0,
+            step_count: 0,
+            histogram_counts: Box::new([0; HISTOGRAM_BIN_COUNT]),
+            histogram_energy: Box::new([0.0; HISTOGRAM_BIN_COUNT]),
+            momentary: f32::NEG_INFINITY,
+            short_term: f32::NEG_INFINITY,
+            integrated: f32::NEG_INFINITY,
+        }
+    }
+}
+
+impl LoudnessMeter {
+    fn feed(&mut self, left: f32, right: f32) {
+        let left = self.filters[0].feed(left);
+        let right = self.filters[1].feed(right);
+        self.step_energy += (left * left + right * right) as f64;
+        self.step_length += 1;
+        if self.step_length == LOUDNESS_STEP {
+            self.finish_step();
+        }
+    }
+
+    // Mean square of the last `count` steps.
+    fn mean_square(&self, count: usize) -> f64 {
+        (1..=count)
+            .map(|i| self.steps[(self.step_position + SHORT_TERM_STEPS - i) % SHORT_TERM_STEPS])
+            .sum::<f64>()
+            / count as f64
+    }
+
+    fn finish_step(&mut self) {
+        self.steps[self.step_position] = self.step_energy / LOUDNESS_STEP as f64;
+        self.step_position = (self.step_position + 1) % SHORT_TERM_STEPS;
+        self.step_count = (self.step_count + 1).min(SHORT_TERM_STEPS);
+        self.step_energy = 0.0;
+        self.step_length = 0;
+
+        if self.step_count >= SHORT_TERM_STEPS {
+            self.short_term = loudness(self.mean_square(SHORT_TERM_STEPS));
+        }
+
+        if self.step_count >= MOMENTARY_STEPS {
+            // Momentary windows overlap by 75% and they're used as gating blocks too.
+            let block_energy = self.mean_square(MOMENTARY_STEPS);
+            self.momentary = loudness(block_energy);
+            if self.momentary > ABSOLUTE_GATE {
+                let bin = (((self.momentary - ABSOLUTE_GATE) / HISTOGRAM_BIN_WIDTH) as usize)
+                    .min(HISTOGRAM_BIN_COUNT - 1);
+                self.histogram_counts[bin] += 1;
+                self.histogram_energy[bin] += block_energy;
+                self.update_integrated();
+            }
+        }
+    }
+
+    fn gated_mean_square(&self, first_bin: usize) -> Option<f64> {
+        let (count, energy) = self.histogram_counts[first_bin..]
+            .iter()
+            .zip(&self.histogram_energy[first_bin..])
+            .fold((0u64, 0.0), |(count, energy), (bin_count, bin_energy)| {
+                (count + *bin_count as u64, energy + *bin_energy)
+            });
+        (count > 0).then(|| energy / count as f64)
+    }
+
+    fn update_integrated(&mut self) {
+        let Some(absolute_gated) = self.gated_mean_square(0) else {
+            return;
+        };
+        let relative_gate = loudness(absolute_gated) + RELATIVE_GATE;
+        let first_bin = ((relative_gate - ABSOLUTE_GATE) / HISTOGRAM_BIN_WIDTH)
+            .clamp(0.0, (HISTOGRAM_BIN_COUNT - 1) as f32) as usize;
+        if let Some(relative_gated) = self.gated_mean_square(first_bin) {
+            self.integrated = loudness(relative_gated);
+        }
+    }
+
+    fn reset_integrated(&mut self) {
+        self.histogram_counts.fill(0);
+        self.histogram_energy.fill(0.0);
+        self.integrated = f32::NEG_INFINITY;
+    }
+}
+
+struct SpectrumAnalyzer {
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    window_sum: f32,
+    // Ring buffer with the last samples.
+    history: Vec<f32>,
+    history_position: usize,
+    input: Vec<f32>,
+    output: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+}
+
+impl Default for SpectrumAnalyzer {
+    fn default() -> Self {
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(SPECTRUM_WINDOW_SIZE);
+        // Hann window.
+        let window = (0..SPECTRUM_WINDOW_SIZE)
+            .map(|i| {
+                0.5 - 0.5
+                    * (2.0 * std::f32::consts::PI * i as f32 / SPECTRUM_WINDOW_SIZE as f32).cos()
+            })
+            .collect::<Vec<_>>();
+        Self {
+            window_sum: window.iter().sum(),
+            window,
+            history: vec![0.0; SPECTRUM_WINDOW_SIZE],
+            history_position: 0,
+            input: fft.make_input_vec(),
+            output: fft.make_output_vec(),
+            scratch: fft.make_scratch_vec(),
+            fft,
+        }
+    }
+}
+
+impl SpectrumAnalyzer {
+    fn push(&mut self, sample: f32) {
+        self.history[self.history_position] = sample;
+        self.history_position = (self.history_position + 1) % SPECTRUM_WINDOW_SIZE;
+    }
+
+    fn analyze(&mut self, magnitudes: &mut Vec<f32>) {
+        for (i, (input, weight)) in self.input.iter_mut().zip(&self.window).enumerate() {
+            *input = self.history[(self.history_position + i) % SPECTRUM_WINDOW_SIZE] * *weight;
+        }
+
+        let _ = self
+            .fft
+            .process_with_scratch(&mut self.input, &mut self.output, &mut self.scratch);
+
+        magnitudes.resize(SPECTRUM_BIN_COUNT, 0.0);
+        for (magnitude, value) in magnitudes.iter_mut().zip(&self.output) {
+            *magnitude = 2.0 * value.norm() / self.window_sum;
+        }
+    }
+}
+
+struct MeterState {
+    loudness: LoudnessMeter,
+    spectrum: Option<Box<SpectrumAnalyzer>>,
+    snapshot: MeterSnapshot,
+}
+
+/// Measures output of an audio bus and publishes the results through [`MeterHandle`]. Internal
+/// state is allocated only when metering is enabled.
+#[derive(Default)]
+pub(crate) struct BusMeter {
+    state: Option<Box<MeterState>>,
+    handle: MeterHandle,
+}
+
+impl Debug for BusMeter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BusMeter")
+            .field("Active", &self.state.is_some())
+            .finish()
+    }
+}
+
+impl Clone for BusMeter {
+    fn clone(&self) -> Self {
+        // Each bus must have its own measurements.
+        Self::default()
+    }
+}
+
+impl BusMeter {
+    pub(crate) fn handle(&self) -> MeterHandle {
+        self.handle.clone()
+    }
+
+    /// Allocates internal state, so the audio thread won't do it.
+    pub(crate) fn prepare(&mut self, spectrum_analysis: bool) {
+        let state = self.state.get_or_insert_with(|| {
+            Box::new(MeterState {
+                loudness: Default::default(),
+                spectrum: None,
+                snapshot: Default::default(),
+            })
+        });
+
+        if !spectrum_analysis {
+            state.spectrum = None;
+        } else if state.spectrum.is_none() {
+            state.spectrum = Some(Default::default());
+            state.snapshot.spectrum.reserve(SPECTRUM_BIN_COUNT);
+            if let Ok(mut shared) = self.handle.0.lock() {
+                shared.spectrum.reserve(SPECTRUM_BIN_COUNT);
+            }
+        }
+    }
+
+    pub(crate) fn release(&mut self) {
+        self.state = None;
+        if let Ok(mut shared) = self.handle.0.lock() {
+            *shared = Default::default();
+        }
+    }
+
+    pub(crate) fn reset_integrated_loudness(&mut self) {
+        if let Some(state) = self.state.as_mut() {
+            state.loudness.reset_integrated();
+        }
+    }
+
+    // Nothing is measured until the state is allocated by `prepare`, the audio thread never does
+    // that.
+    pub(crate) fn measure(&mut self, samples: &[(f32, f32)], gain: f32) {
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+
+        let mut peak = (0.0f32, 0.0f32);
+        let mut sum = (0.0f32, 0.0f32);
+        for (left, right) in samples {
+            let left = *left * gain;
+            let right = *right * gain;
+
+            peak.0 = peak.0.max(left.abs());
+            peak.1 = peak.1.max(right.abs());
+            sum.0 += left * left;
+            sum.1 += right * right;
+
+            state.loudness.feed(left, right);
+
+            if let Some(spectrum) = state.spectrum.as_mut() {
+                spectrum.push(0.5 * (left + right));
+            }
+        }
+
+        let snapshot = &mut state.snapshot;
+        snapshot.peak = peak;
+        snapshot.rms = if samples.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let len = samples.len() as f32;
+            ((sum.0 / len).sqrt(), (sum.1 / len).sqrt())
+        };
+        snapshot.momentary_loudness = state.loudness.momentary;
+        snapshot.short_term_loudness = state.loudness.short_term;
+        snapshot.integrated_loudness = state.loudness.integrated;
+        if let Some(spectrum) = state.spectrum.as_mut() {
+            spectrum.analyze(&mut snapshot.spectrum);
+        } else {
+            snapshot.spectrum.clear();
+        }
+
+        // Never block the audio thread, readers will get the next snapshot.
+        if let Ok(mut shared) = self.handle.0.try_lock() {
+            snapshot.copy_to(&mut shared);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bus::{AudioBus, AudioBusGraph},
+        context::SAMPLE_RATE,
+        meter::MeterSnapshot,
+    };
+
+    fn render_sine(graph: &mut AudioBusGraph, frequency: f32, amplitude: f32, blocks: usize) {
+        let block_size = 512;
+        let mut time = 0usize;
+        for _ in 0..blocks {
+            let mut output_buffer = vec![(0.0f32, 0.0f32); block_size];
+            graph.begin_render(block_size);
+            for (left, right) in graph.primary_bus_mut().input_buffer() {
+                let sample = amplitude
+                    * (2.0 * std::f32::consts::PI * frequency * time as f32 / SAMPLE_RATE as f32)
+                        .sin();
+                *left = sample;
+                *right = sample;
+                time += 1;
+            }
+            graph.end_render(&mut [&mut output_buffer]);
+        }
+    }
+
+    #[test]
+    fn test_peak_rms_and_loudness() {
+        let mut graph = AudioBusGraph::new();
+        graph.primary_bus_mut().set_metering_enabled(true);
+        let meter = graph.primary_bus_ref().meter().unwrap();
+
+        // 4 seconds of 1 kHz sine at -20 dBFS.
+        render_sine(&mut graph, 1000.0, 0.1, 4 * SAMPLE_RATE as usize / 512);
+
+        let snapshot = meter.snapshot();
+        assert!((snapshot.peak.0 - 0.1).abs() < 0.001, "{:?}", snapshot.peak);
+        assert!(
+            (snapshot.rms.1 - 0.1 / 2.0f32.sqrt()).abs() < 0.001,
+            "{:?}",
+            snapshot.rms
+        );
+        // A stereo 1 kHz sine at -20 dBFS gives about -20 LUFS.
+        for loudness in [
+            snapshot.momentary_loudness,
+            snapshot.short_term_loudness,
+            snapshot.integrated_loudness,
+        ] {
+            assert!((loudness + 20.0).abs() < 0.3, "{loudness}");
+        }
+        assert!(snapshot.spectrum.is_empty());
+
+        // Silence is gated, so it does not affect integrated loudness (except a few blocks that
+        // partially contain the sine).
+        render_sine(&mut graph, 1000.0, 0.0, SAMPLE_RATE as usize / 512);
+        let snapshot = meter.snapshot();
+        assert_eq!(snapshot.momentary_loudness, f32::NEG_INFINITY);
+        assert!(
+            (snapshot.integrated_loudness + 20.0).abs() < 1.0,
+            "{}",
+            snapshot.integrated_loudness
+        );
+
+        graph.primary_bus_mut().reset_integrated_loudness();
+        render_sine(&mut graph, 1000.0, 0.0, 1);
+        assert_eq!(meter.snapshot().integrated_loudness, f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_meter_is_prepared_off_audio_thread() {
+        let mut graph = AudioBusGraph::new();
+        graph.primary_bus_mut().set_metering_enabled(true);
+
+        // Cloned (or deserialized) buses have no metering state, so nothing is measured until it is
+        // prepared.
+        let mut graph = graph.clone();
+        let meter = graph.primary_bus_ref().meter().unwrap();
+        render_sine(&mut graph, 1000.0, 0.1, 4);
+        assert_eq!(meter.snapshot().peak, (0.0, 0.0));
+
+        graph.primary_bus_mut().prepare_meter();
+        render_sine(&mut graph, 1000.0, 0.1, 4);
+        assert!((meter.snapshot().peak.0 - 0.1).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_spectrum() {
+        let mut graph = AudioBusGraph::new();
+        let mut bus = AudioBus::new("Music".to_string());
+        bus.set_metering_enabled(true);
+        bus.set_spectrum_analysis_enabled(true);
+        let bus = graph.add_bus(bus, graph.primary_bus_handle());
+        let meter = graph.try_get_bus_ref(bus).unwrap().meter().unwrap();
+
+        let block_size = 512;
+        let frequency = MeterSnapshot::bin_frequency(40);
+        for block in 0..4 {
+            let mut output_buffer = vec![(0.0f32, 0.0f32); block_size];
+            graph.begin_render(block_size);
+            for (i, (left, right)) in graph
+                .try_get_bus_mut(bus)
+                .unwrap()
+                .input_buffer()
+                .iter_mut()
+                .enumerate()
+            {
+                let time = (block * block_size + i) as f32 / SAMPLE_RATE as f32;
+                *left = (2.0 * std::f32::consts::PI * frequency * time).sin();
+                *right = *left;
+            }
+            graph.end_render(&mut [&mut output_buffer]);
+        }
+
+        let spectrum = meter.snapshot().spectrum;
+        let (peak_bin, magnitude) = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert_eq!(peak_bin, 40);
+        assert!((magnitude - 1.0).abs() < 0.05, "{magnitude}");
+
+        // Primary bus is not metered.
+        assert!(graph.primary_bus_ref().meter().is_none());
+    }
+}
@@ -0,0 +1,429 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Offline rendering module.
+//!
+//! # Overview
+//!
+//! Offline rendering allows you to capture the output of a sound context (or the whole engine)
+//! into a memory buffer or a WAV file. Rendering is done on the calling thread as fast as
+//! possible, it does not depend on real time and it is fully deterministic. It could be used
+//! for automated audio tests or to export soundtracks of cutscenes. Any channel layout could be
+//! rendered, see [`OfflineRenderer::with_channel_layout`].
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox_sound::{
+//!     context::SoundContext,
+//!     engine::SoundEngine,
+//!     offline::{OfflineRenderer, WavSampleFormat},
+//! };
+//! use std::time::Duration;
+//!
+//! // Use an engine without output device, otherwise the device will render the same contexts.
+//! let engine = SoundEngine::without_device();
+//! let context = SoundContext::new();
+//! engine.state().add_context(context.clone());
+//!
+//! // Add some sources to the context here.
+//!
+//! let mut renderer = OfflineRenderer::new();
+//! renderer.render_engine(&mut engine.state(), Duration::from_secs(3));
+//! renderer
+//!     .save_wav("output.wav", WavSampleFormat::Float32)
+//!     .unwrap();
+//! ```
+
+use crate::{
+    context::{self, SoundContext, SAMPLE_RATE},
+    engine,
+    error::SoundError,
+    layout::ChannelLayout,
+};
+use std::{
+    io::{Seek, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// Defines the format of samples in WAV files produced by [`OfflineRenderer`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WavSampleFormat {
+    /// 32-bit floating-point samples. Samples are written as is, without clamping.
+    #[default]
+    Float32,
+    /// 16-bit integer samples. Samples are clamped to `[-1; 1]` range.
+    Int16,
+}
+
+/// Offline renderer captures the output of a sound context or the whole engine. Rendered samples
+/// are accumulated in the renderer, so the rendering could be split in multiple calls (for
+/// example, when you need to update a scene between them). The output has [`SAMPLE_RATE`]
+/// sampling rate and it is stereo by default, other channel layouts could be set via
+/// [`Self::with_channel_layout`].
+///
+/// Sound contexts always render blocks of fixed size, so the renderer keeps the rest of the last
+/// block and uses it on the next call. This means that the samples of a single renderer must come
+/// from the same source (the same engine or the same context), do not mix them.
+#[derive(Debug, Clone)]
+pub struct OfflineRenderer {
+    channel_layout: ChannelLayout,
+    samples: Vec<(f32, f32)>,
+    block: Vec<(f32, f32)>,
+    block_position: usize,
+}
+
+impl Default for OfflineRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OfflineRenderer {
+    /// Creates new offline renderer with no samples.
+    pub fn new() -> Self {
+        Self::with_channel_layout(ChannelLayout::Stereo)
+    }
+
+    /// Creates new offline renderer with no samples, that renders in the given channel layout.
+    /// Sound contexts with other layouts are remixed to the layout of the renderer.
+    pub fn with_channel_layout(channel_layout: ChannelLayout) -> Self {
+        let block = vec![
+            (0.0, 0.0);
+            SoundContext::SAMPLES_PER_CHANNEL * channel_layout.channel_pair_count()
+        ];
+        Self {
+            channel_layout,
+            block_position: block.len(),
+            block,
+            samples: Default::default(),
+        }
+    }
+
+    /// Returns the channel layout of the renderer.
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.channel_layout
+    }
+
+    /// Calculates the amount of samples per channel for the given duration.
+    pub fn duration_to_sample_count(duration: Duration) -> usize {
+        (duration.as_secs_f64() * SAMPLE_RATE as f64).round() as usize
+    }
+
+    fn render_with<F>(&mut self, duration: Duration, mut render: F)
+    where
+        F: FnMut(&mut [(f32, f32)]),
+    {
+        let mut remaining =
+            Self::duration_to_sample_count(duration) * self.channel_layout.channel_pair_count();
+        self.samples.reserve(remaining);
+        while remaining > 0 {
+            if self.block_position >= self.block.len() {
+                self.block.fill((0.0, 0.0));
+                render(&mut self.block);
+                self.block_position = 0;
+            }
+            let count = remaining.min(self.block.len() - self.block_position);
+            self.samples
+                .extend_from_slice(&self.block[self.block_position..(self.block_position + count)]);
+            self.block_position += count;
+            remaining -= count;
+        }
+    }
+
+    /// Renders all contexts of the engine for the given duration and appends the samples to the
+    /// renderer.
+    ///
+    /// # Notes
+    ///
+    /// This method must be used only with engines created via [`engine::SoundEngine::without_device`]
+    /// (or with destroyed output device), otherwise the contexts will be rendered by the output
+    /// device as well.
+    pub fn render_engine(&mut self, engine: &mut engine::State, duration: Duration) {
+        let channel_layout = self.channel_layout;
+        self.render_with(duration, |block| {
+            engine.render_with_layout(channel_layout, block)
+        })
+    }
+
+    /// Renders a single context for the given duration and appends the samples to the renderer.
+    /// The context should not be registered in an engine with an output device, otherwise it
+    /// will be rendered by the output device as well.
+    pub fn render_context(&mut self, context: &mut context::State, duration: Duration) {
+        let channel_layout = self.channel_layout;
+        self.render_with(duration, |block| {
+            context.render_with_layout(channel_layout, block)
+        })
+    }
+
+    /// Returns rendered samples in interleaved format. Each frame of multichannel layouts consists
+    /// of a few channel pairs, for example `(FL, FR), (FC, LFE), (BL, BR)` for 5.1 layout.
+    pub fn samples(&self) -> &[(f32, f32)] {
+        &self.samples
+    }
+
+    /// Takes rendered samples from the renderer, leaving it empty. The rest of the last rendered
+    /// block is kept, so the next call of any `render_` method continues from the same position.
+    pub fn take_samples(&mut self) -> Vec<(f32, f32)> {
+        std::mem::take(&mut self.samples)
+    }
+
+    /// Returns total duration of rendered samples.
+    pub fn duration(&self) -> Duration {
+        let sample_count = self.samples.len() / self.channel_layout.channel_pair_count();
+        Duration::from_secs_f64(sample_count as f64 / SAMPLE_RATE as f64)
+    }
+
+    /// Removes all the rendered samples and the rest of the last rendered block.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.block_position = self.block.len();
+    }
+
+    /// Writes rendered samples in WAV format to the given writer.
+    pub fn write_wav<W>(&self, writer: W, format: WavSampleFormat) -> Result<(), SoundError>
+    where
+        W: Write + Seek,
+    {
+        let spec = hound::WavSpec {
+            channels: self.channel_layout.channel_count() as u16,
+            sample_rate: SAMPLE_RATE,
+            bits_per_sample: match format {
+                WavSampleFormat::Float32 => 32,
+                WavSampleFormat::Int16 => 16,
+            },
+            sample_format: match format {
+                WavSampleFormat::Float32 => hound::SampleFormat::Float,
+                WavSampleFormat::Int16 => hound::SampleFormat::Int,
+            },
+        };
+        let mut wav_writer = hound::WavWriter::new(writer, spec)?;
+        for &(left, right) in self.samples.iter() {
+            for sample in [left, right] {
+                match format {
+                    WavSampleFormat::Float32 => wav_writer.write_sample(sample)?,
+                    WavSampleFormat::Int16 => wav_writer
+                        .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?,
+                }
+            }
+        }
+        wav_writer.finalize()?;
+        Ok(())
+    }
+
+    /// Writes rendered samples into a WAV file at the given path.
+    pub fn save_wav<P>(&self, path: P, format: WavSampleFormat) -> Result<(), SoundError>
+    where
+        P: AsRef<Path>,
+    {
+        self.write_wav(
+            std::io::BufWriter::new(std::fs::File::create(path)?),
+            format,
+        )
+    }
+
+    /// Writes rendered samples into a memory buffer in WAV format.
+    pub fn to_wav_bytes(&self, format: WavSampleFormat) -> Result<Vec<u8>, SoundError> {
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        self.write_wav(&mut cursor, format)?;
+        Ok(cursor.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        algebra::Vector3,
+        buffer::{DataSource, SoundBufferResource, SoundBufferResourceExtension},
+        context::{SoundContext, SAMPLE_RATE},
+        effects::{Attenuate, Effect},
+        engine::SoundEngine,
+        layout::ChannelLayout,
+        offline::{OfflineRenderer, WavSampleFormat},
+        source::{SoundSourceBuilder, Status},
+    };
+    use std::time::Duration;
+
+    fn make_context_at(channel_layout: ChannelLayout, position: Vector3<f32>) -> SoundContext {
+        let samples = (0..SAMPLE_RATE)
+            .map(|i| (i as f32 * 0.01).sin() * 0.5)
+            .collect::<Vec<_>>();
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples,
+        })
+        .unwrap();
+        let context = SoundContext::new();
+        let source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_position(position)
+            .with_status(Status::Playing)
+            .build()
+            .unwrap();
+        let mut state = context.state();
+        state.set_channel_layout(channel_layout);
+        state.add_source(source);
+        drop(state);
+        context
+    }
+
+    fn make_context() -> SoundContext {
+        make_context_at(ChannelLayout::Stereo, Vector3::default())
+    }
+
+    // Returns total energy of each channel.
+    fn channel_energies(renderer: &OfflineRenderer) -> Vec<f32> {
+        let pair_count = renderer.channel_layout().channel_pair_count();
+        let mut energies = vec![0.0; pair_count * 2];
+        for frame in renderer.samples().chunks_exact(pair_count) {
+            for (pair, (left, right)) in frame.iter().enumerate() {
+                energies[pair * 2] += left * left;
+                energies[pair * 2 + 1] += right * right;
+            }
+        }
+        energies
+    }
+
+    #[test]
+    fn test_offline_rendering_is_deterministic() {
+        let mut renderers = [OfflineRenderer::new(), OfflineRenderer::new()];
+        for renderer in renderers.iter_mut() {
+            let engine = SoundEngine::without_device();
+            engine.state().add_context(make_context());
+            renderer.render_engine(&mut engine.state(), Duration::from_millis(500));
+        }
+        assert_eq!(renderers[0].samples().len(), SAMPLE_RATE as usize / 2);
+        assert!(renderers[0].samples().iter().any(|(l, _)| *l != 0.0));
+        assert_eq!(renderers[0].samples(), renderers[1].samples());
+    }
+
+    #[test]
+    fn test_offline_rendering_in_parts() {
+        let mut whole = OfflineRenderer::new();
+        whole.render_context(&mut make_context().state(), Duration::from_millis(300));
+
+        let mut parts = OfflineRenderer::new();
+        let context = make_context();
+        for _ in 0..3 {
+            parts.render_context(&mut context.state(), Duration::from_millis(100));
+        }
+
+        assert_eq!(whole.samples(), parts.samples());
+    }
+
+    #[test]
+    fn test_offline_rendering_to_wav() {
+        let mut renderer = OfflineRenderer::new();
+        renderer.render_context(&mut make_context().state(), Duration::from_millis(100));
+
+        for format in [WavSampleFormat::Float32, WavSampleFormat::Int16] {
+            let bytes = renderer.to_wav_bytes(format).unwrap();
+            let reader = hound::WavReader::new(bytes.as_slice()).unwrap();
+            assert_eq!(reader.spec().channels, 2);
+            assert_eq!(reader.spec().sample_rate, SAMPLE_RATE);
+            assert_eq!(reader.len() as usize, renderer.samples().len() * 2);
+        }
+    }
+
+    #[test]
+    fn test_multichannel_panning() {
+        let layout = ChannelLayout::Surround51;
+        let directions = [(-30.0f32, 0), (0.0, 2), (30.0, 1), (-110.0, 4), (110.0, 5)];
+        for (azimuth, channel) in directions {
+            let (sin, cos) = azimuth.to_radians().sin_cos();
+            let context = make_context_at(layout, Vector3::new(sin, 0.0, cos) * 2.0);
+
+            let mut renderer = OfflineRenderer::with_channel_layout(layout);
+            renderer.render_context(&mut context.state(), Duration::from_millis(100));
+            assert_eq!(
+                renderer.samples().len(),
+                OfflineRenderer::duration_to_sample_count(Duration::from_millis(100)) * 3
+            );
+            assert_eq!(renderer.duration(), Duration::from_millis(100));
+
+            let energies = channel_energies(&renderer);
+            assert!(energies[channel] > 0.0);
+            for (other, energy) in energies.iter().enumerate() {
+                if other != channel {
+                    assert!(
+                        *energy < energies[channel] * 1.0e-6,
+                        "{azimuth}: {energies:?}"
+                    );
+                }
+            }
+        }
+
+        let bytes = OfflineRenderer::with_channel_layout(layout)
+            .to_wav_bytes(WavSampleFormat::Float32)
+            .unwrap();
+        let reader = hound::WavReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.spec().channels, 6);
+    }
+
+    #[test]
+    fn test_multichannel_downmix() {
+        let mut stereo = OfflineRenderer::new();
+        stereo.render_context(&mut make_context().state(), Duration::from_millis(100));
+
+        // Source in front of the listener goes to the center channel, which is then downmixed
+        // to the front pair - the result must be the same as stereo rendering.
+        for layout in [ChannelLayout::Surround51, ChannelLayout::Surround71] {
+            let context = make_context_at(layout, Vector3::default());
+            let mut downmixed = OfflineRenderer::new();
+            downmixed.render_context(&mut context.state(), Duration::from_millis(100));
+
+            assert_eq!(stereo.samples().len(), downmixed.samples().len());
+            for (a, b) in stereo.samples().iter().zip(downmixed.samples()) {
+                assert!((a.0 - b.0).abs() < 1.0e-5 && (a.1 - b.1).abs() < 1.0e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multichannel_bus_effects() {
+        let layout = ChannelLayout::Quad;
+        // Between the back speakers.
+        let position = Vector3::new(0.0, 0.0, -1.0);
+
+        let mut reference = OfflineRenderer::with_channel_layout(layout);
+        reference.render_context(
+            &mut make_context_at(layout, position).state(),
+            Duration::from_millis(100),
+        );
+
+        let context = make_context_at(layout, position);
+        context
+            .state()
+            .bus_graph_mut()
+            .primary_bus_mut()
+            .add_effect(Effect::Attenuate(Attenuate::new(0.5)));
+        let mut attenuated = OfflineRenderer::with_channel_layout(layout);
+        attenuated.render_context(&mut context.state(), Duration::from_millis(100));
+
+        let reference = channel_energies(&reference);
+        let attenuated = channel_energies(&attenuated);
+        assert!(reference[2] > 0.0 && reference[3] > 0.0);
+        for (reference, attenuated) in reference.iter().zip(attenuated) {
+            assert!((reference * 0.25 - attenuated).abs() <= reference * 1.0e-5);
+        }
+    }
+}
@@ -170,7 +170,7 @@ impl HrtfRenderer {
         render_source_2d_only(source, out_buf);
 
         // Then add HRTF part with k = spatial_blend
-        let new_distance_gain = source.gain()
+        let new_distance_gain = source.mixing_gain()
             * source.spatial_blend()
             * source.calculate_distance_gain(listener, distance_model);
         let new_sampling_vector = source.calculate_sampling_vector(listener);
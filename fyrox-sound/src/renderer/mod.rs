@@ -24,12 +24,15 @@
 //!
 //! Renderer processes samples from each sound source before they'll be passed to output device. Exact
 //! behaviour of renderer depends of variant being used.
+//!
+//! Multichannel layouts (see [`crate::layout::ChannelLayout`]) are always rendered using vector-base
+//! amplitude panning, HRTF is used only for stereo layout.
 
 #![allow(clippy::float_cmp)]
 
 use crate::{
-    context::DistanceModel, listener::Listener, math, renderer::hrtf::HrtfRenderer,
-    source::SoundSource,
+    context::DistanceModel, layout::ChannelLayout, listener::Listener, math,
+    renderer::hrtf::HrtfRenderer, source::SoundSource,
 };
 use fyrox_core::math::lerpf;
 use fyrox_core::{
@@ -37,6 +40,7 @@ use fyrox_core::{
     uuid_provider,
     visitor::{Visit, VisitResult, Visitor},
 };
+use std::f32::consts::FRAC_1_SQRT_2;
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 pub mod hrtf;
@@ -112,7 +116,7 @@ pub(crate) fn render_source_default(
         source.calculate_panning(listener),
         source.spatial_blend(),
     );
-    let gain = distance_gain * source.gain();
+    let gain = distance_gain * source.mixing_gain();
     let left_gain = gain * (1.0 + panning);
     let right_gain = gain * (1.0 - panning);
     render_with_params(source, left_gain, right_gain, mix_buffer);
@@ -121,10 +125,76 @@ pub(crate) fn render_source_default(
 }
 
 pub(crate) fn render_source_2d_only(source: &mut SoundSource, mix_buffer: &mut [(f32, f32)]) {
-    let gain = (1.0 - source.spatial_blend()) * source.gain();
+    let gain = (1.0 - source.spatial_blend()) * source.mixing_gain();
     let left_gain = gain * (1.0 + source.panning());
     let right_gain = gain * (1.0 - source.panning());
     render_with_params(source, left_gain, right_gain, mix_buffer);
     source.last_left_gain = Some(left_gain);
     source.last_right_gain = Some(right_gain);
 }
+
+// Calculates gains of each output channel for the left and the right channels of a source. 2D part
+// of the source is rendered to the front pair with stereo panning, spatial part is panned
+// between the closest speakers.
+fn calculate_channel_gains(
+    source: &SoundSource,
+    listener: &Listener,
+    distance_model: DistanceModel,
+    layout: ChannelLayout,
+) -> [(f32, f32); ChannelLayout::MAX_CHANNELS] {
+    let mut gains = [(0.0, 0.0); ChannelLayout::MAX_CHANNELS];
+
+    let spatial_blend = source.spatial_blend();
+    let gain_2d = (1.0 - spatial_blend) * source.mixing_gain();
+    gains[0].0 = gain_2d * (1.0 + source.panning());
+    gains[1].1 = gain_2d * (1.0 - source.panning());
+
+    if spatial_blend > 0.0 {
+        // Spatial sources are panned as mono. The gain is scaled so that a source in front of the
+        // listener has the same loudness as with stereo rendering: 1/sqrt(2) of the sum of the
+        // channels is sqrt(2) of the mean.
+        let gain_3d = spatial_blend
+            * source.mixing_gain()
+            * source.calculate_distance_gain(listener, distance_model)
+            * FRAC_1_SQRT_2;
+        let mut panning = [0.0; ChannelLayout::MAX_CHANNELS];
+        layout.pan(source.calculate_azimuth(listener), &mut panning);
+        for ((left, right), panning) in gains.iter_mut().zip(panning) {
+            *left += gain_3d * panning;
+            *right += gain_3d * panning;
+        }
+    }
+
+    gains
+}
+
+pub(crate) fn render_source_multichannel<'a>(
+    source: &mut SoundSource,
+    listener: &Listener,
+    distance_model: DistanceModel,
+    layout: ChannelLayout,
+    channel_pairs: impl Iterator<Item = &'a mut [(f32, f32)]>,
+) {
+    let gains = calculate_channel_gains(source, listener, distance_model, layout);
+    // Gains of the channels that are not used by the layout are zero, so the channels of the new
+    // layout (or all of them, if the source was just made audible) fade in from silence.
+    let last_gains = *source.last_channel_gains.get_or_insert(gains);
+
+    for (pair, mix_buffer) in channel_pairs.take(layout.channel_pair_count()).enumerate() {
+        let (last_left, last_right) = (last_gains[pair * 2], last_gains[pair * 2 + 1]);
+        let (left, right) = (gains[pair * 2], gains[pair * 2 + 1]);
+        let step = 1.0 / mix_buffer.len() as f32;
+        let mut t = 0.0;
+        for ((out_left, out_right), &(raw_left, raw_right)) in
+            mix_buffer.iter_mut().zip(source.frame_samples.iter())
+        {
+            *out_left += math::lerpf(last_left.0, left.0, t) * raw_left
+                + math::lerpf(last_left.1, left.1, t) * raw_right;
+            *out_right += math::lerpf(last_right.0, right.0, t) * raw_left
+                + math::lerpf(last_right.1, right.1, t) * raw_right;
+            t += step;
+        }
+    }
+
+    source.last_channel_gains = Some(gains);
+}
@@ -50,14 +50,18 @@
 
 use crate::{
     buffer::{streaming::StreamingBuffer, SoundBuffer, SoundBufferResource},
-    bus::AudioBusGraph,
-    context::DistanceModel,
+    bus::{AudioBus, AudioBusGraph},
+    context::{DistanceModel, SAMPLE_RATE},
+    dsp::db_to_linear,
     error::SoundError,
+    layout::ChannelLayout,
     listener::Listener,
 };
 use fyrox_core::{
     algebra::Vector3,
     log::Log,
+    math::lerpf,
+    pool::Handle,
     reflect::prelude::*,
     uuid_provider,
     visitor::{Visit, VisitResult, Visitor},
@@ -132,6 +136,13 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) last_right_gain: Option<f32>,
+    // Gains of each output channel for multichannel layouts, the first element of each pair is
+    // the gain of the left channel of the source, the second one - of the right channel. The
+    // same rules as for the stereo gains above apply, channels that were not used by the previous
+    // layout have zero gains, so they fade in from silence.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) last_channel_gains: Option<[(f32, f32); ChannelLayout::MAX_CHANNELS]>,
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) frame_samples: Vec<(f32, f32)>,
@@ -142,6 +153,8 @@ pub struct SoundSource {
     #[reflect(min_value = 0.0, step = 0.05)]
     radius: f32,
     position: Vector3<f32>,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
     #[reflect(min_value = 0.0, step = 0.05)]
     max_distance: f32,
     #[reflect(min_value = 0.0, step = 0.05)]
@@ -159,6 +172,37 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) prev_distance_gain: Option<f32>,
+    // Pitch multiplier caused by Doppler effect, it is updated by the context before rendering.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) doppler_shift: f64,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    occlusion: f32,
+    // Occlusion from the previous frame, it is used to interpolate occlusion parameters across
+    // the frame to prevent clicks.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    prev_occlusion: Option<f32>,
+    // Last output samples of the occlusion low-pass filter.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_filter_state: (f32, f32),
+    #[visit(optional)]
+    priority: i32,
+    // Virtual sources are not mixed, the context just advances their playback position.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    is_virtual: bool,
+    // The source is rendered one more time with zero gain before becoming virtual, so it fades out
+    // smoothly instead of being cut.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    fading_out: bool,
+    // Handle of the audio bus with the `bus` name, it is resolved by the context once per update.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) bus_handle: Handle<AudioBus>,
 }
 
 impl Default for SoundSource {
@@ -179,21 +223,41 @@ impl Default for SoundSource {
             play_once: false,
             last_left_gain: None,
             last_right_gain: None,
+            last_channel_gains: None,
             frame_samples: Default::default(),
             prev_buffer_sample: (0.0, 0.0),
             radius: 1.0,
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
             prev_distance_gain: None,
+            doppler_shift: 1.0,
+            occlusion: 0.0,
+            prev_occlusion: None,
+            occlusion_filter_state: (0.0, 0.0),
+            priority: 0,
+            is_virtual: false,
+            fading_out: false,
+            bus_handle: Handle::NONE,
         }
     }
 }
 
 impl SoundSource {
+    /// Attenuation (in decibels) of a fully occluded sound source.
+    pub const OCCLUSION_ATTENUATION_DB: f32 = 18.0;
+
+    /// Cutoff frequency (in Hz) of the low-pass filter of a fully occluded sound source.
+    pub const OCCLUSION_CUTOFF_HZ: f32 = 600.0;
+
+    // Cutoff frequency of the low-pass filter of a non-occluded sound source, it is high enough
+    // to keep the sound unchanged.
+    const NO_OCCLUSION_CUTOFF_HZ: f32 = 20000.0;
+
     /// Sets new name of the sound source.
     pub fn set_name<N: AsRef<str>>(&mut self, name: N) {
         name.as_ref().clone_into(&mut self.name);
@@ -377,6 +441,39 @@ impl SoundSource {
         self.position
     }
 
+    /// Sets velocity of source in world space (in units per second). Velocity is used only to
+    /// calculate Doppler effect, it does not move the source.
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) -> &mut Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Returns velocity of source.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Returns current pitch multiplier caused by Doppler effect. It is calculated on every
+    /// render of the sound context, 1.0 means that there is no frequency shift.
+    pub fn doppler_shift(&self) -> f64 {
+        self.doppler_shift
+    }
+
+    /// Sets occlusion factor of the source in `[0; 1]` range. Occlusion defines how much the sound
+    /// is muffled by obstacles between the source and the listener: 0.0 - no obstacles, 1.0 - fully
+    /// occluded sound. Occluded sounds are attenuated (up to [`Self::OCCLUSION_ATTENUATION_DB`])
+    /// and low-pass filtered (down to [`Self::OCCLUSION_CUTOFF_HZ`]). The engine does not calculate
+    /// occlusion by itself, it should be done by the user (for example, by casting rays).
+    pub fn set_occlusion(&mut self, occlusion: f32) -> &mut Self {
+        self.occlusion = occlusion.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns occlusion factor of the source.
+    pub fn occlusion(&self) -> f32 {
+        self.occlusion
+    }
+
     /// Sets radius of imaginable sphere around source in which no distance attenuation is applied.
     pub fn set_radius(&mut self, radius: f32) -> &mut Self {
         self.radius = radius;
@@ -450,6 +547,37 @@ impl SoundSource {
         }
     }
 
+    // Doppler shift formula is taken from OpenAL Specification as well, velocities are projected
+    // on source-listener axis and clamped to the speed of sound, so the shift is always finite.
+    pub(crate) fn calculate_doppler_shift(
+        &self,
+        listener: &Listener,
+        doppler_factor: f32,
+        speed_of_sound: f32,
+    ) -> f64 {
+        if doppler_factor <= 0.0 || speed_of_sound <= 0.0 || self.spatial_blend <= 0.0 {
+            return 1.0;
+        }
+
+        let Some(direction) = (listener.position() - self.position).try_normalize(f32::EPSILON)
+        else {
+            return 1.0;
+        };
+
+        // Keep velocities a bit lower than the speed of sound to prevent division by zero.
+        let max_speed = 0.99 * speed_of_sound;
+        // Positive values mean that the source moves towards the listener.
+        let source_speed =
+            (doppler_factor * self.velocity.dot(&direction)).clamp(-max_speed, max_speed);
+        // Positive values mean that the listener moves away from the source.
+        let listener_speed =
+            (doppler_factor * listener.velocity().dot(&direction)).clamp(-max_speed, max_speed);
+
+        let shift = (speed_of_sound - listener_speed) / (speed_of_sound - source_speed);
+
+        lerpf(1.0, shift, self.spatial_blend) as f64
+    }
+
     pub(crate) fn calculate_panning(&self, listener: &Listener) -> f32 {
         (listener.position() - self.position)
             .try_normalize(f32::EPSILON)
@@ -459,6 +587,19 @@ impl SoundSource {
             .dot(&listener.ear_axis())
     }
 
+    // Azimuth of the source in degrees relative to the listener, positive values are to the right
+    // of the listener (in the sense of stereo panning).
+    pub(crate) fn calculate_azimuth(&self, listener: &Listener) -> f32 {
+        let to_self = self.position - listener.position();
+        let side = to_self.dot(&listener.ear_axis());
+        let front = to_self.dot(&listener.look_axis());
+        if side.abs() <= f32::EPSILON && front.abs() <= f32::EPSILON {
+            0.0
+        } else {
+            side.atan2(front).to_degrees()
+        }
+    }
+
     pub(crate) fn calculate_sampling_vector(&self, listener: &Listener) -> Vector3<f32> {
         let to_self = listener.position() - self.position;
 
@@ -482,28 +623,38 @@ impl SoundSource {
 
     /// Sets playback duration.
     pub fn set_playback_time(&mut self, time: Duration) {
+        let sample_rate = self
+            .buffer
+            .as_ref()
+            .and_then(|buffer| buffer.state().data().map(|buffer| buffer.sample_rate()));
+        if let Some(sample_rate) = sample_rate {
+            self.set_playback_position(time.as_secs_f64() * sample_rate as f64);
+        }
+    }
+
+    // Sets absolute playback position (in samples) and syncs buffer read position with it.
+    fn set_playback_position(&mut self, position: f64) {
         if let Some(buffer) = self.buffer.as_ref() {
             if let Some(buffer) = buffer.state().data() {
-                if let SoundBuffer::Streaming(ref mut streaming) = *buffer {
-                    // Make sure decoder is at right position.
-                    if streaming
-                        .time_seek(time.clamp(Duration::from_secs(0), streaming.duration()))
-                        .is_err()
-                    {
-                        Log::warn("error while setting decoder position");
-                    }
-                }
                 // Set absolute position first.
-                self.playback_pos = (time.as_secs_f64() * buffer.sample_rate as f64)
-                    .clamp(0.0, buffer.duration().as_secs_f64());
+                self.playback_pos = position.clamp(
+                    0.0,
+                    buffer.channel_duration_in_samples().saturating_sub(1) as f64,
+                );
+                let time = Duration::from_secs_f64(self.playback_pos / buffer.sample_rate as f64);
                 // Then adjust buffer read position.
                 self.buf_read_pos = match *buffer {
                     SoundBuffer::Streaming(ref mut streaming) => {
+                        // Make sure decoder is at right position.
+                        if streaming.time_seek(time).is_err() {
+                            Log::warn("error while setting decoder position");
+                        }
                         // Make sure to load correct data into buffer from decoder.
                         streaming.read_next_block();
                         // Streaming sources has different buffer read position because
-                        // buffer contains only small portion of data.
-                        self.playback_pos % (StreamingBuffer::STREAM_SAMPLE_COUNT as f64)
+                        // buffer contains only small portion of data. The decoder was moved
+                        // to the requested position, so the new block starts exactly there.
+                        0.0
                     }
                     SoundBuffer::Generic(_) => self.playback_pos,
                 };
@@ -515,6 +666,137 @@ impl SoundSource {
         }
     }
 
+    /// Sets new priority of the sound source. When there are more playing sources than the voice
+    /// limit of the sound context (or of the audio bus) allows, sources with higher priority are
+    /// mixed first, the rest are virtualized (see [`Self::is_virtual`]). Default is 0.
+    pub fn set_priority(&mut self, priority: i32) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns current priority of the sound source.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Returns `true` if the sound source was virtualized during the last render of the sound
+    /// context. Virtual sources are not mixed at all, they're just advancing their playback time, so
+    /// they can seamlessly continue playing once they become audible again. A source becomes virtual
+    /// if it is too quiet (see [`crate::context::State::set_virtualization_threshold`]) or if it does
+    /// not fit into the voice limit (see [`crate::context::State::set_max_voices`] and
+    /// [`crate::bus::AudioBus::set_max_voices`]). Before becoming virtual, a source that was mixed is
+    /// faded out during one more render of the context.
+    pub fn is_virtual(&self) -> bool {
+        self.is_virtual
+    }
+
+    // Estimated loudness of the source at the listener position, it is used to find sources that
+    // can be virtualized.
+    pub(crate) fn calculate_audibility(
+        &self,
+        listener: &Listener,
+        distance_model: DistanceModel,
+    ) -> f32 {
+        let distance_gain = lerpf(
+            1.0,
+            self.calculate_distance_gain(listener, distance_model),
+            self.spatial_blend,
+        );
+        self.gain * distance_gain * db_to_linear(-Self::OCCLUSION_ATTENUATION_DB * self.occlusion)
+    }
+
+    // Gain that is used for mixing, it is zero while the source is fading out.
+    pub(crate) fn mixing_gain(&self) -> f32 {
+        if self.fading_out {
+            0.0
+        } else {
+            self.gain
+        }
+    }
+
+    pub(crate) fn set_virtual(&mut self, is_virtual: bool) {
+        let was_rendered = self.last_left_gain.is_some()
+            || self.last_channel_gains.is_some()
+            || self.prev_distance_gain.is_some();
+        if is_virtual && !self.is_virtual && !self.fading_out && was_rendered {
+            // Render one more block that fades the source out, it becomes virtual on the next
+            // update.
+            self.fading_out = true;
+            return;
+        }
+        // If the source is made real again while fading out, its gain just goes back up from
+        // the last (faded) one.
+        self.fading_out = false;
+
+        if self.is_virtual && !is_virtual {
+            // Buffer read position is not updated while the source is virtual, sync it with the
+            // playback position first. Sources with unknown duration cannot be seeked, their
+            // data is consumed while they are virtual, so they're already at the right position.
+            if self.has_known_duration() {
+                self.set_playback_position(self.playback_pos);
+            }
+            // Then fade the source in, otherwise there will be an audible click.
+            self.last_left_gain = Some(0.0);
+            self.last_right_gain = Some(0.0);
+            self.last_channel_gains = Some([(0.0, 0.0); ChannelLayout::MAX_CHANNELS]);
+            self.prev_distance_gain = Some(0.0);
+            self.prev_left_samples.fill(0.0);
+            self.prev_right_samples.fill(0.0);
+            self.prev_buffer_sample = (0.0, 0.0);
+            self.occlusion_filter_state = (0.0, 0.0);
+        }
+        self.is_virtual = is_virtual;
+    }
+
+    // Advances playback position of a virtual source by the given amount of output samples without
+    // rendering anything.
+    pub(crate) fn advance(&mut self, amount: usize) {
+        let Some(buffer) = self.buffer.clone() else {
+            return;
+        };
+        let mut state = buffer.state();
+        let Some(buffer) = state.data() else {
+            return;
+        };
+        if self.status != Status::Playing || buffer.is_empty() {
+            return;
+        }
+        let length = buffer.channel_duration_in_samples() as f64;
+        if length == 0.0 {
+            // Duration of raw streaming sources (sample queues, callbacks, etc.) could be unknown,
+            // such sources cannot be seeked later on, so their data is consumed just as if they
+            // were rendered. Otherwise, producers of such sources will stall.
+            if self.frame_samples.capacity() < amount {
+                self.frame_samples = Vec::with_capacity(amount);
+            }
+            self.frame_samples.clear();
+            self.render_playing(buffer, amount);
+            self.frame_samples.clear();
+            return;
+        }
+        let step = self.pitch * self.doppler_shift * self.resampling_multiplier;
+        let position = self.playback_pos + amount as f64 * step;
+        if position < length {
+            self.playback_pos = position;
+        } else if self.looping {
+            self.playback_pos = position % length;
+        } else {
+            drop(state);
+            if self.stop().is_err() {
+                Log::warn("error while rewinding streaming buffer");
+            }
+        }
+    }
+
+    fn has_known_duration(&self) -> bool {
+        self.buffer.as_ref().is_some_and(|buffer| {
+            buffer
+                .state()
+                .data()
+                .is_some_and(|buffer| buffer.channel_duration_in_samples() != 0)
+        })
+    }
+
     pub(crate) fn render(&mut self, amount: usize) {
         if self.frame_samples.capacity() < amount {
             self.frame_samples = Vec::with_capacity(amount);
@@ -532,6 +814,42 @@ impl SoundSource {
         }
         // Fill the remaining part of frame_samples.
         self.frame_samples.resize(amount, (0.0, 0.0));
+
+        self.apply_occlusion();
+    }
+
+    fn apply_occlusion(&mut self) {
+        let prev_occlusion = self.prev_occlusion.replace(self.occlusion);
+        let prev_occlusion = prev_occlusion.unwrap_or(self.occlusion);
+
+        if prev_occlusion == 0.0 && self.occlusion == 0.0 {
+            // Keep the filter in sync with the signal, so it won't click when occlusion changes.
+            self.occlusion_filter_state = self.frame_samples.last().cloned().unwrap_or_default();
+            return;
+        }
+
+        let gain = |occlusion: f32| db_to_linear(-Self::OCCLUSION_ATTENUATION_DB * occlusion);
+        let pole = |occlusion: f32| {
+            let fc = Self::NO_OCCLUSION_CUTOFF_HZ
+                * (Self::OCCLUSION_CUTOFF_HZ / Self::NO_OCCLUSION_CUTOFF_HZ).powf(occlusion);
+            (-2.0 * std::f32::consts::PI * fc / SAMPLE_RATE as f32).exp()
+        };
+
+        let (prev_gain, new_gain) = (gain(prev_occlusion), gain(self.occlusion));
+        let (prev_pole, new_pole) = (pole(prev_occlusion), pole(self.occlusion));
+        let step = 1.0 / self.frame_samples.len().max(1) as f32;
+        let (mut left_state, mut right_state) = self.occlusion_filter_state;
+        for (i, (left, right)) in self.frame_samples.iter_mut().enumerate() {
+            // Interpolate parameters across the frame, otherwise there will be audible clicks.
+            let t = i as f32 * step;
+            let gain = lerpf(prev_gain, new_gain, t);
+            let pole = lerpf(prev_pole, new_pole, t);
+            left_state = (1.0 - pole) * *left + pole * left_state;
+            right_state = (1.0 - pole) * *right + pole * right_state;
+            *left = left_state * gain;
+            *right = right_state * gain;
+        }
+        self.occlusion_filter_state = (left_state, right_state);
     }
 
     fn render_playing(&mut self, buffer: &mut SoundBuffer, amount: usize) {
@@ -547,7 +865,7 @@ impl SoundSource {
             let mut end_reached = true;
             if let SoundBuffer::Streaming(streaming) = buffer {
                 // Means that this is the last available block.
-                if len != channel_count * StreamingBuffer::STREAM_SAMPLE_COUNT {
+                if len != channel_count * streaming.block_size() {
                     let _ = streaming.rewind();
                 } else {
                     end_reached = false;
@@ -558,7 +876,8 @@ impl SoundSource {
             if end_reached {
                 self.buf_read_pos = 0.0;
                 self.playback_pos = 0.0;
-                if !self.looping {
+                // Some raw streaming sources cannot be rewound, stop them to prevent infinite loop.
+                if !self.looping || buffer.samples().is_empty() {
                     self.status = Status::Stopped;
                     return;
                 }
@@ -571,7 +890,7 @@ impl SoundSource {
     // Renders until the end of the block or until amount samples is written and returns
     // the number of written samples.
     fn render_until_block_end(&mut self, buffer: &mut SoundBuffer, mut amount: usize) -> usize {
-        let step = self.pitch * self.resampling_multiplier;
+        let step = self.pitch * self.doppler_shift * self.resampling_multiplier;
         if step == 1.0 {
             if self.buf_read_pos < 0.0 {
                 // This can theoretically happen if we change pitch on the fly.
@@ -738,10 +1057,12 @@ pub struct SoundSourceBuilder {
     playback_time: Duration,
     radius: f32,
     position: Vector3<f32>,
+    velocity: Vector3<f32>,
     max_distance: f32,
     rolloff_factor: f32,
     spatial_blend: f32,
     bus: String,
+    priority: i32,
 }
 
 impl Default for SoundSourceBuilder {
@@ -765,10 +1086,12 @@ impl SoundSourceBuilder {
             playback_time: Default::default(),
             radius: 1.0,
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
             spatial_blend: 1.0,
             bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            priority: 0,
         }
     }
 
@@ -844,6 +1167,18 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_velocity`]
+    pub fn with_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// See [`SoundSource::set_priority`].
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// See `set_radius` of SpatialSource.
     pub fn with_radius(mut self, radius: f32) -> Self {
         self.radius = radius;
@@ -882,12 +1217,15 @@ impl SoundSourceBuilder {
             frame_samples: Default::default(),
             radius: self.radius,
             position: self.position,
+            velocity: self.velocity,
             max_distance: self.max_distance,
             rolloff_factor: self.rolloff_factor,
             spatial_blend: self.spatial_blend,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
+            last_channel_gains: None,
             bus: self.bus,
+            priority: self.priority,
             ..Default::default()
         };
 
@@ -897,3 +1235,327 @@ impl SoundSourceBuilder {
         Ok(source)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{
+            procedural::sample_queue, DataSource, SoundBufferResource, SoundBufferResourceExtension,
+        },
+        context::{SoundContext, SAMPLE_RATE},
+        listener::Listener,
+        source::{SoundSourceBuilder, Status},
+    };
+    use fyrox_core::algebra::Vector3;
+
+    #[test]
+    fn test_doppler_shift() {
+        let listener = Listener::new();
+        let speed_of_sound = SoundContext::DEFAULT_SPEED_OF_SOUND;
+
+        let mut source = SoundSourceBuilder::new()
+            .with_position(Vector3::new(0.0, 0.0, 10.0))
+            .build()
+            .unwrap();
+        assert_eq!(
+            source.calculate_doppler_shift(&listener, 1.0, speed_of_sound),
+            1.0
+        );
+
+        // Approaching source must sound higher.
+        source.set_velocity(Vector3::new(0.0, 0.0, -30.0));
+        let approaching = source.calculate_doppler_shift(&listener, 1.0, speed_of_sound);
+        let expected = speed_of_sound / (speed_of_sound - 30.0);
+        assert!((approaching - expected as f64).abs() < 1.0e-5);
+
+        // Receding source must sound lower.
+        source.set_velocity(Vector3::new(0.0, 0.0, 30.0));
+        assert!(source.calculate_doppler_shift(&listener, 1.0, speed_of_sound) < 1.0);
+
+        // Doppler factor of zero and 2D sources disable the effect.
+        assert_eq!(
+            source.calculate_doppler_shift(&listener, 0.0, speed_of_sound),
+            1.0
+        );
+        source.set_spatial_blend(0.0);
+        assert_eq!(
+            source.calculate_doppler_shift(&listener, 1.0, speed_of_sound),
+            1.0
+        );
+
+        // Shift must stay finite even for supersonic sources.
+        source.set_spatial_blend(1.0);
+        source.set_velocity(Vector3::new(0.0, 0.0, -10.0 * speed_of_sound));
+        assert!(source
+            .calculate_doppler_shift(&listener, 1.0, speed_of_sound)
+            .is_finite());
+    }
+
+    #[test]
+    fn test_doppler_shift_changes_playback_speed() {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples: vec![0.5; SAMPLE_RATE as usize],
+        })
+        .unwrap();
+
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let source = state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_position(Vector3::new(0.0, 0.0, 10.0))
+                .with_velocity(Vector3::new(0.0, 0.0, -50.0))
+                .with_status(Status::Playing)
+                .build()
+                .unwrap(),
+        );
+
+        let mut output = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render(&mut output);
+
+        let source = state.source(source);
+        assert!(source.doppler_shift() > 1.0);
+        assert!(source.playback_pos > SoundContext::SAMPLES_PER_CHANNEL as f64);
+    }
+
+    #[test]
+    fn test_voice_limit() {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples: vec![0.5; SAMPLE_RATE as usize],
+        })
+        .unwrap();
+
+        let context = SoundContext::new();
+        let mut state = context.state();
+        state.set_max_voices(2);
+        let sources = [0, 2, 1].map(|priority| {
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer.clone())
+                    .with_priority(priority)
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            )
+        });
+
+        let mut output = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render(&mut output);
+
+        assert!(state.source(sources[0]).is_virtual());
+        assert!(!state.source(sources[1]).is_virtual());
+        assert!(!state.source(sources[2]).is_virtual());
+        let statistics = state.voice_statistics();
+        assert_eq!(statistics.playing_sources, 3);
+        assert_eq!(statistics.real_sources, 2);
+        assert_eq!(statistics.virtual_sources(), 1);
+
+        // Per-bus limit.
+        state
+            .bus_graph_mut()
+            .primary_bus_mut()
+            .set_max_voices(Some(1));
+        state.render(&mut output);
+
+        // The culled source is faded out first and only then becomes virtual.
+        assert!(state.source(sources[0]).is_virtual());
+        assert!(!state.source(sources[1]).is_virtual());
+        assert!(!state.source(sources[2]).is_virtual());
+        assert_eq!(state.bus_graph_ref().primary_bus_ref().voice_count(), 1);
+        assert_eq!(state.voice_statistics().culled_sources, 2);
+
+        state.render(&mut output);
+
+        assert!(state.source(sources[2]).is_virtual());
+        assert_eq!(state.voice_statistics().culled_sources, 2);
+    }
+
+    #[test]
+    fn test_virtualized_source_fades_out() {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples: vec![0.5; SAMPLE_RATE as usize],
+        })
+        .unwrap();
+
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let source = state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_spatial_blend_factor(0.0)
+                .with_status(Status::Playing)
+                .build()
+                .unwrap(),
+        );
+
+        let mut output = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render(&mut output);
+        assert!(output.iter().all(|(left, _)| (*left - 0.5).abs() < 1.0e-4));
+
+        // No voices left, the source must be faded out instead of being cut.
+        state.set_max_voices(0);
+        output.fill((0.0, 0.0));
+        state.render(&mut output);
+
+        assert!(!state.source(source).is_virtual());
+        assert!((output[0].0 - 0.5).abs() < 1.0e-3);
+        assert!(output.last().unwrap().0.abs() < 1.0e-3);
+        assert!(output.windows(2).all(|w| w[1].0 <= w[0].0));
+
+        output.fill((0.0, 0.0));
+        state.render(&mut output);
+
+        assert!(state.source(source).is_virtual());
+        assert!(output
+            .iter()
+            .all(|(left, right)| *left == 0.0 && *right == 0.0));
+    }
+
+    #[test]
+    fn test_virtual_source_advances_playback() {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples: vec![0.5; SAMPLE_RATE as usize],
+        })
+        .unwrap();
+
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let source = state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_gain(0.0)
+                .with_looping(true)
+                .with_status(Status::Playing)
+                .build()
+                .unwrap(),
+        );
+
+        let mut output = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render(&mut output);
+
+        assert!(state.source(source).is_virtual());
+        assert_eq!(state.voice_statistics().inaudible_sources, 1);
+        assert_eq!(
+            state.source(source).playback_pos,
+            SoundContext::SAMPLES_PER_CHANNEL as f64
+        );
+
+        // Becomes audible again and continues from the right position.
+        state.source_mut(source).set_gain(1.0);
+        state.render(&mut output);
+
+        let source_ref = state.source(source);
+        assert!(!source_ref.is_virtual());
+        assert_eq!(
+            source_ref.playback_pos,
+            2.0 * SoundContext::SAMPLES_PER_CHANNEL as f64
+        );
+        assert_eq!(source_ref.buf_read_pos, source_ref.playback_pos);
+        assert!(output.iter().any(|(left, _)| *left != 0.0));
+
+        // Looping virtual sources must wrap around.
+        state.source_mut(source).set_gain(0.0);
+        for _ in 0..20 {
+            state.render(&mut output);
+        }
+        let source_ref = state.source(source);
+        assert!(source_ref.is_virtual());
+        assert_eq!(source_ref.status(), Status::Playing);
+        assert!(source_ref.playback_pos < SAMPLE_RATE as f64);
+    }
+
+    #[test]
+    fn test_virtual_sample_queue_keeps_draining() {
+        const SAMPLES: usize = 4 * SoundContext::SAMPLES_PER_CHANNEL;
+
+        // Returns the amount of samples left in the queue after each of two rendered blocks,
+        // the source is inaudible during the first one if `virtualize` is set.
+        let render = |virtualize: bool| {
+            let (mut producer, queue) = sample_queue(SAMPLE_RATE as usize, 1, SAMPLES);
+            assert_eq!(producer.push(&[0.5; SAMPLES]), SAMPLES);
+
+            let buffer =
+                SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(queue)))
+                    .unwrap();
+
+            let context = SoundContext::new();
+            let mut state = context.state();
+            let source = state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer)
+                    .with_spatial_blend_factor(0.0)
+                    .with_gain(if virtualize { 0.0 } else { 1.0 })
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            );
+
+            let mut output = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+            state.render(&mut output);
+            assert_eq!(state.source(source).is_virtual(), virtualize);
+            assert_eq!(state.source(source).status(), Status::Playing);
+            let first = producer.len();
+
+            state.source_mut(source).set_gain(1.0);
+            output.fill((0.0, 0.0));
+            state.render(&mut output);
+            assert!(!state.source(source).is_virtual());
+            assert!((output.last().unwrap().0 - 0.5).abs() < 1.0e-3);
+
+            (first, producer.len())
+        };
+
+        let real = render(false);
+        let virtualized = render(true);
+
+        // The queue must be drained at the same pace, no matter if the source is virtual or not,
+        // and no samples must be skipped when the source becomes real again.
+        assert!(real.0 < SAMPLES);
+        assert_eq!(virtualized, real);
+    }
+
+    #[test]
+    fn test_occlusion_muffles_sound() {
+        // High-frequency tone, that must be almost fully removed by the occlusion filter.
+        let samples = (0..SAMPLE_RATE)
+            .map(|i| (i as f32 * 2.0 * std::f32::consts::PI * 5000.0 / SAMPLE_RATE as f32).sin())
+            .collect::<Vec<_>>();
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples,
+        })
+        .unwrap();
+
+        let rms = |occlusion: f32| {
+            let mut source = SoundSourceBuilder::new()
+                .with_buffer(buffer.clone())
+                .with_status(Status::Playing)
+                .build()
+                .unwrap();
+            source.set_occlusion(occlusion);
+            // Skip the first frame to let the filter settle.
+            source.render(SoundContext::SAMPLES_PER_CHANNEL);
+            source.render(SoundContext::SAMPLES_PER_CHANNEL);
+            let sum = source
+                .frame_samples()
+                .iter()
+                .map(|(l, _)| l * l)
+                .sum::<f32>();
+            (sum / source.frame_samples().len() as f32).sqrt()
+        };
+
+        let clear = rms(0.0);
+        let occluded = rms(1.0);
+        assert!((clear - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+        assert!(occluded < clear * 0.02);
+    }
+}
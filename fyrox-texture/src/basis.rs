@@ -0,0 +1,728 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Built-in Basis Universal transcoder, see [`BuiltInBasisTranscoder`] docs for more info.
+
+use crate::ktx::{BasisPayload, BasisTranscoder, TranscodingRequest, TranscodingTarget};
+
+const ETC1_INTENSITY_TABLES: [[i32; 4]; 8] = [
+    [-8, -2, 2, 8],
+    [-17, -5, 5, 17],
+    [-29, -9, 9, 29],
+    [-42, -13, 13, 42],
+    [-60, -18, 18, 60],
+    [-80, -24, 24, 80],
+    [-106, -33, 33, 106],
+    [-183, -47, 47, 183],
+];
+
+const MAX_CODE_SIZE: usize = 16;
+const MAX_SYMBOLS_LOG2: u32 = 14;
+const CODE_LENGTH_CODES: usize = 21;
+const SMALL_ZERO_RUN_CODE: u32 = 17;
+const BIG_ZERO_RUN_CODE: u32 = 18;
+const SMALL_REPEAT_CODE: u32 = 19;
+const SORTED_CODE_LENGTH_CODES: [usize; CODE_LENGTH_CODES] = [
+    17, 18, 19, 20, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15, 16,
+];
+
+const COLOR5_PAL0_PREV_HI: u8 = 9;
+const COLOR5_PAL1_PREV_HI: u8 = 21;
+const ENDPOINT_PRED_REPEAT_LAST_SYMBOL: u32 = 256;
+const ENDPOINT_PRED_MIN_REPEAT_COUNT: u32 = 3;
+const ENDPOINT_PRED_COUNT_VLC_BITS: u32 = 4;
+const SELECTOR_HISTORY_BUF_RLE_COUNT_THRESH: u32 = 3;
+const SELECTOR_HISTORY_BUF_RLE_COUNT_TOTAL: u32 = 64;
+const SELECTOR_HISTORY_BUF_RLE_VLC_BITS: u32 = 7;
+
+const IMAGE_FLAG_P_FRAME: u32 = 2;
+
+/// Reads bits starting from the least significant bit of each byte. Reading past the end of data
+/// yields zeros.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u64,
+    buffer_size: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buffer: 0,
+            buffer_size: 0,
+        }
+    }
+
+    fn read(&mut self, count: u32) -> u32 {
+        debug_assert!(count <= 32);
+        while self.buffer_size < count {
+            let byte = self.data.get(self.position).cloned().unwrap_or_default();
+            self.position += 1;
+            self.buffer |= (byte as u64) << self.buffer_size;
+            self.buffer_size += 8;
+        }
+        let value = (self.buffer & ((1u64 << count) - 1)) as u32;
+        self.buffer >>= count;
+        self.buffer_size -= count;
+        value
+    }
+
+    /// Reads a variable-length number, that is stored as a sequence of chunks. Each chunk is
+    /// followed by a bit that tells whether there's more chunks or not.
+    fn read_vlc(&mut self, chunk_bits: u32) -> u32 {
+        let chunk_size = 1 << chunk_bits;
+        let mut value = 0;
+        let mut offset = 0;
+        loop {
+            let chunk = self.read(chunk_bits + 1);
+            value |= (chunk & (chunk_size - 1)) << offset;
+            offset += chunk_bits;
+            if chunk & chunk_size == 0 || offset >= 32 {
+                return value;
+            }
+        }
+    }
+}
+
+/// Canonical Huffman decoding table.
+#[derive(Default)]
+struct HuffmanTable {
+    counts: [u16; MAX_CODE_SIZE + 1],
+    symbols: Vec<u16>,
+}
+
+impl HuffmanTable {
+    fn new(code_sizes: &[u8]) -> Result<Self, String> {
+        let mut counts = [0u16; MAX_CODE_SIZE + 1];
+        for &size in code_sizes {
+            if size as usize > MAX_CODE_SIZE {
+                return Err(format!("Invalid Huffman code size {size}!"));
+            }
+            counts[size as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; MAX_CODE_SIZE + 2];
+        for size in 1..=MAX_CODE_SIZE {
+            offsets[size + 1] = offsets[size] + counts[size];
+        }
+
+        let mut symbols = vec![0; offsets[MAX_CODE_SIZE + 1] as usize];
+        for (symbol, &size) in code_sizes.iter().enumerate() {
+            if size != 0 {
+                symbols[offsets[size as usize] as usize] = symbol as u16;
+                offsets[size as usize] += 1;
+            }
+        }
+
+        Ok(Self { counts, symbols })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u32, String> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for size in 1..=MAX_CODE_SIZE {
+            code |= reader.read(1) as i32;
+            let count = self.counts[size] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize] as u32);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("Invalid Huffman code!".to_string())
+    }
+
+    fn read(reader: &mut BitReader) -> Result<Self, String> {
+        let total_symbols = reader.read(MAX_SYMBOLS_LOG2) as usize;
+        if total_symbols == 0 {
+            return Ok(Self::default());
+        }
+
+        let code_length_code_count = reader.read(5) as usize;
+        if !(1..=CODE_LENGTH_CODES).contains(&code_length_code_count) {
+            return Err("Invalid amount of code length codes!".to_string());
+        }
+        let mut code_length_code_sizes = [0u8; CODE_LENGTH_CODES];
+        for &code in SORTED_CODE_LENGTH_CODES.iter().take(code_length_code_count) {
+            code_length_code_sizes[code] = reader.read(3) as u8;
+        }
+        let code_length_table = Self::new(&code_length_code_sizes)?;
+        if code_length_table.is_empty() {
+            return Err("Empty code length table!".to_string());
+        }
+
+        let mut code_sizes = vec![0u8; total_symbols];
+        let mut current = 0;
+        while current < total_symbols {
+            let code = code_length_table.decode(reader)?;
+            if code <= MAX_CODE_SIZE as u32 {
+                code_sizes[current] = code as u8;
+                current += 1;
+            } else if code == SMALL_ZERO_RUN_CODE {
+                current += reader.read(3) as usize + 3;
+            } else if code == BIG_ZERO_RUN_CODE {
+                current += reader.read(7) as usize + 11;
+            } else {
+                let previous = match current.checked_sub(1).map(|i| code_sizes[i]) {
+                    Some(previous) if previous != 0 => previous,
+                    _ => return Err("Invalid repeat of code size!".to_string()),
+                };
+                let count = if code == SMALL_REPEAT_CODE {
+                    reader.read(2) as usize + 3
+                } else {
+                    reader.read(7) as usize + 7
+                };
+                if current + count > total_symbols {
+                    return Err("Code size repeat overflow!".to_string());
+                }
+                code_sizes[current..current + count].fill(previous);
+                current += count;
+            }
+        }
+        if current != total_symbols {
+            return Err("Code size run overflow!".to_string());
+        }
+
+        Self::new(&code_sizes)
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct Endpoint {
+    color: [u8; 3],
+    intensity: u8,
+}
+
+impl Endpoint {
+    fn block_colors(&self) -> [[u8; 3]; 4] {
+        let table = &ETC1_INTENSITY_TABLES[self.intensity as usize];
+        table.map(|modifier| {
+            self.color.map(|c| {
+                let base = ((c << 3) | (c >> 2)) as i32;
+                (base + modifier).clamp(0, 255) as u8
+            })
+        })
+    }
+}
+
+/// 2-bit selectors of a 4x4 block, one byte per row.
+#[derive(Copy, Clone, Default)]
+struct Selector([u8; 4]);
+
+impl Selector {
+    fn set_row(&mut self, y: usize, bits: u32) {
+        self.0[y] = bits as u8;
+    }
+
+    fn get(&self, x: usize, y: usize) -> usize {
+        ((self.0[y] >> (x * 2)) & 3) as usize
+    }
+}
+
+fn decode_endpoints(data: &[u8], count: usize) -> Result<Vec<Endpoint>, String> {
+    let mut reader = BitReader::new(data);
+    let color5_delta_models = [
+        HuffmanTable::read(&mut reader)?,
+        HuffmanTable::read(&mut reader)?,
+        HuffmanTable::read(&mut reader)?,
+    ];
+    let intensity_delta_model = HuffmanTable::read(&mut reader)?;
+    let grayscale = reader.read(1) != 0;
+
+    let mut endpoints = Vec::with_capacity(count);
+    let mut previous_color = [16u8; 3];
+    let mut previous_intensity = 0;
+    for _ in 0..count {
+        let intensity = (intensity_delta_model.decode(&mut reader)? + previous_intensity) & 7;
+        previous_intensity = intensity;
+
+        let mut color = [0u8; 3];
+        let component_count = if grayscale { 1 } else { 3 };
+        for c in 0..component_count {
+            let model = if previous_color[c] <= COLOR5_PAL0_PREV_HI {
+                &color5_delta_models[0]
+            } else if previous_color[c] <= COLOR5_PAL1_PREV_HI {
+                &color5_delta_models[1]
+            } else {
+                &color5_delta_models[2]
+            };
+            let delta = model.decode(&mut reader)?;
+            color[c] = ((previous_color[c] as u32 + delta) & 31) as u8;
+            previous_color[c] = color[c];
+        }
+        if grayscale {
+            color[1] = color[0];
+            color[2] = color[0];
+        }
+
+        endpoints.push(Endpoint {
+            color,
+            intensity: intensity as u8,
+        });
+    }
+    Ok(endpoints)
+}
+
+fn decode_selectors(data: &[u8], count: usize) -> Result<Vec<Selector>, String> {
+    let mut reader = BitReader::new(data);
+    if reader.read(1) != 0 {
+        return Err("Global selector codebooks are not supported!".to_string());
+    }
+    if reader.read(1) != 0 {
+        return Err("Hybrid selector codebooks are not supported!".to_string());
+    }
+
+    let mut selectors = vec![Selector::default(); count];
+    if reader.read(1) != 0 {
+        for selector in selectors.iter_mut() {
+            for y in 0..4 {
+                selector.set_row(y, reader.read(8));
+            }
+        }
+    } else {
+        let delta_model = HuffmanTable::read(&mut reader)?;
+        let mut previous = [0u32; 4];
+        for (i, selector) in selectors.iter_mut().enumerate() {
+            for (y, previous) in previous.iter_mut().enumerate() {
+                let row = if i == 0 {
+                    reader.read(8)
+                } else {
+                    delta_model.decode(&mut reader)? ^ *previous
+                };
+                *previous = row;
+                selector.set_row(y, row);
+            }
+        }
+    }
+    Ok(selectors)
+}
+
+struct SliceTables {
+    endpoint_prediction: HuffmanTable,
+    endpoint_delta: HuffmanTable,
+    selector: HuffmanTable,
+    selector_history_rle: HuffmanTable,
+    selector_history_size: usize,
+}
+
+impl SliceTables {
+    fn decode(data: &[u8]) -> Result<Self, String> {
+        let mut reader = BitReader::new(data);
+        let tables = Self {
+            endpoint_prediction: HuffmanTable::read(&mut reader)?,
+            endpoint_delta: HuffmanTable::read(&mut reader)?,
+            selector: HuffmanTable::read(&mut reader)?,
+            selector_history_rle: HuffmanTable::read(&mut reader)?,
+            selector_history_size: reader.read(13) as usize,
+        };
+        if tables.endpoint_prediction.is_empty()
+            || tables.endpoint_delta.is_empty()
+            || tables.selector.is_empty()
+            || tables.selector_history_rle.is_empty()
+            || tables.selector_history_size == 0
+        {
+            return Err("Invalid ETC1S slice tables!".to_string());
+        }
+        Ok(tables)
+    }
+}
+
+/// A history of recently used selectors, that moves frequently used entries closer to the front.
+struct SelectorHistory {
+    values: Vec<usize>,
+    rover: usize,
+}
+
+impl SelectorHistory {
+    fn new(size: usize) -> Self {
+        Self {
+            values: vec![0; size],
+            rover: size / 2,
+        }
+    }
+
+    fn add(&mut self, value: usize) {
+        self.values[self.rover] = value;
+        self.rover += 1;
+        if self.rover == self.values.len() {
+            self.rover = self.values.len() / 2;
+        }
+    }
+
+    fn use_index(&mut self, index: usize) {
+        self.values.swap(index / 2, index);
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct BlockPrediction {
+    endpoint_index: usize,
+    prediction_bits: u32,
+}
+
+struct Codebook {
+    endpoints: Vec<Endpoint>,
+    selectors: Vec<Selector>,
+    tables: SliceTables,
+}
+
+impl Codebook {
+    /// Decodes a single ETC1S slice and passes the colors of each decoded pixel to the given
+    /// closure.
+    fn decode_slice(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        mut write: impl FnMut(usize, [u8; 3]),
+    ) -> Result<(), String> {
+        let endpoint_count = self.endpoints.len();
+        let selector_count = self.selectors.len();
+        let history_first_symbol = selector_count;
+        let history_rle_symbol = history_first_symbol + self.tables.selector_history_size;
+
+        let blocks_x = width.div_ceil(4);
+        let blocks_y = height.div_ceil(4);
+
+        let mut reader = BitReader::new(data);
+        let mut history = SelectorHistory::new(self.tables.selector_history_size);
+        let mut predictions = [
+            vec![BlockPrediction::default(); blocks_x],
+            vec![BlockPrediction::default(); blocks_x],
+        ];
+        let mut selector_rle_count = 0;
+        let mut prediction_bits = 0;
+        let mut previous_prediction_symbol = 0;
+        let mut prediction_repeat_count = 0;
+        let mut previous_endpoint_index = 0;
+
+        for block_y in 0..blocks_y {
+            let current = block_y & 1;
+            for block_x in 0..blocks_x {
+                if block_x & 1 == 0 {
+                    if block_y & 1 == 0 {
+                        if prediction_repeat_count > 0 {
+                            prediction_repeat_count -= 1;
+                            prediction_bits = previous_prediction_symbol;
+                        } else {
+                            prediction_bits =
+                                self.tables.endpoint_prediction.decode(&mut reader)?;
+                            if prediction_bits == ENDPOINT_PRED_REPEAT_LAST_SYMBOL {
+                                prediction_repeat_count = reader
+                                    .read_vlc(ENDPOINT_PRED_COUNT_VLC_BITS)
+                                    + ENDPOINT_PRED_MIN_REPEAT_COUNT
+                                    - 1;
+                                prediction_bits = previous_prediction_symbol;
+                            } else {
+                                previous_prediction_symbol = prediction_bits;
+                            }
+                        }
+                        predictions[current ^ 1][block_x].prediction_bits = prediction_bits >> 4;
+                    } else {
+                        prediction_bits = predictions[current][block_x].prediction_bits;
+                    }
+                }
+
+                let prediction = prediction_bits & 3;
+                prediction_bits >>= 2;
+                let endpoint_index = match prediction {
+                    0 if block_x > 0 => previous_endpoint_index,
+                    1 if block_y > 0 => predictions[current ^ 1][block_x].endpoint_index,
+                    2 if block_x > 0 && block_y > 0 => {
+                        predictions[current ^ 1][block_x - 1].endpoint_index
+                    }
+                    3 => {
+                        let mut index = self.tables.endpoint_delta.decode(&mut reader)? as usize
+                            + previous_endpoint_index;
+                        if index >= endpoint_count {
+                            index -= endpoint_count;
+                        }
+                        index
+                    }
+                    _ => return Err("Invalid endpoint prediction!".to_string()),
+                };
+                predictions[current][block_x].endpoint_index = endpoint_index;
+                previous_endpoint_index = endpoint_index;
+
+                let selector_symbol = if selector_rle_count > 0 {
+                    selector_rle_count -= 1;
+                    history_first_symbol
+                } else {
+                    let symbol = self.tables.selector.decode(&mut reader)? as usize;
+                    if symbol == history_rle_symbol {
+                        let run = self.tables.selector_history_rle.decode(&mut reader)?;
+                        selector_rle_count = if run == SELECTOR_HISTORY_BUF_RLE_COUNT_TOTAL - 1 {
+                            reader.read_vlc(SELECTOR_HISTORY_BUF_RLE_VLC_BITS)
+                                + SELECTOR_HISTORY_BUF_RLE_COUNT_THRESH
+                        } else {
+                            run + SELECTOR_HISTORY_BUF_RLE_COUNT_THRESH
+                        } as usize;
+                        if selector_rle_count > blocks_x * blocks_y {
+                            return Err("Invalid selector run length!".to_string());
+                        }
+                        selector_rle_count -= 1;
+                        history_first_symbol
+                    } else {
+                        symbol
+                    }
+                };
+
+                let selector_index = if selector_symbol >= history_first_symbol {
+                    let history_index = selector_symbol - history_first_symbol;
+                    let index = *history
+                        .values
+                        .get(history_index)
+                        .ok_or_else(|| "Invalid selector history index!".to_string())?;
+                    if history_index != 0 {
+                        history.use_index(history_index);
+                    }
+                    index
+                } else {
+                    history.add(selector_symbol);
+                    selector_symbol
+                };
+
+                let endpoint = self
+                    .endpoints
+                    .get(endpoint_index)
+                    .ok_or_else(|| "Invalid endpoint index!".to_string())?;
+                let selector = self
+                    .selectors
+                    .get(selector_index)
+                    .ok_or_else(|| "Invalid selector index!".to_string())?;
+                let colors = endpoint.block_colors();
+                for y in 0..4 {
+                    let py = block_y * 4 + y;
+                    if py >= height {
+                        break;
+                    }
+                    for x in 0..4 {
+                        let px = block_x * 4 + x;
+                        if px >= width {
+                            break;
+                        }
+                        write(py * width + px, colors[selector.get(x, y)]);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+struct ImageDesc {
+    flags: u32,
+    rgb_slice: (usize, usize),
+    alpha_slice: (usize, usize),
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| "Unexpected end of global data!".to_string())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or_else(|| "Unexpected end of global data!".to_string())
+}
+
+fn sub_slice(data: &[u8], offset: usize, length: usize) -> Result<&[u8], String> {
+    data.get(offset..offset + length)
+        .ok_or_else(|| "Slice is out of bounds!".to_string())
+}
+
+/// Decodes an image with ETC1S payload, supercompressed with BasisLZ, into RGBA8 pixels.
+fn decode_etc1s(request: &TranscodingRequest) -> Result<Vec<[u8; 4]>, String> {
+    let reader = ktx2::Reader::new(request.file).map_err(|err| err.to_string())?;
+    let header = reader.header();
+    let global_data = reader.supercompression_global_data();
+
+    let layer_count = header.layer_count.max(1) as usize;
+    let face_count = header.face_count as usize;
+    let level_count = header.level_count.max(1) as usize;
+    let images_in_level =
+        |level: usize| layer_count * face_count * (header.pixel_depth >> level).max(1) as usize;
+    let image_count = (0..level_count).map(images_in_level).sum::<usize>();
+    let image_index = (0..request.level as usize)
+        .map(images_in_level)
+        .sum::<usize>()
+        + request.face as usize;
+
+    let endpoint_count = read_u16(global_data, 0)? as usize;
+    let selector_count = read_u16(global_data, 2)? as usize;
+    let endpoints_length = read_u32(global_data, 4)? as usize;
+    let selectors_length = read_u32(global_data, 8)? as usize;
+    let tables_length = read_u32(global_data, 12)? as usize;
+
+    const IMAGE_DESCS_OFFSET: usize = 20;
+    const IMAGE_DESC_SIZE: usize = 20;
+    let desc_offset = IMAGE_DESCS_OFFSET + image_index * IMAGE_DESC_SIZE;
+    let desc = ImageDesc {
+        flags: read_u32(global_data, desc_offset)?,
+        rgb_slice: (
+            read_u32(global_data, desc_offset + 4)? as usize,
+            read_u32(global_data, desc_offset + 8)? as usize,
+        ),
+        alpha_slice: (
+            read_u32(global_data, desc_offset + 12)? as usize,
+            read_u32(global_data, desc_offset + 16)? as usize,
+        ),
+    };
+    if desc.flags & IMAGE_FLAG_P_FRAME != 0 {
+        return Err("Video frames are not supported!".to_string());
+    }
+
+    let endpoints_offset = IMAGE_DESCS_OFFSET + image_count * IMAGE_DESC_SIZE;
+    let selectors_offset = endpoints_offset + endpoints_length;
+    let tables_offset = selectors_offset + selectors_length;
+    let codebook = Codebook {
+        endpoints: decode_endpoints(
+            sub_slice(global_data, endpoints_offset, endpoints_length)?,
+            endpoint_count,
+        )?,
+        selectors: decode_selectors(
+            sub_slice(global_data, selectors_offset, selectors_length)?,
+            selector_count,
+        )?,
+        tables: SliceTables::decode(sub_slice(global_data, tables_offset, tables_length)?)?,
+    };
+
+    let level_data = reader
+        .levels()
+        .nth(request.level as usize)
+        .ok_or_else(|| format!("There's no mip level {}!", request.level))?
+        .data;
+
+    let width = request.width as usize;
+    let height = request.height as usize;
+    let mut pixels = vec![[0, 0, 0, u8::MAX]; width * height];
+    codebook.decode_slice(
+        sub_slice(level_data, desc.rgb_slice.0, desc.rgb_slice.1)?,
+        width,
+        height,
+        |i, [r, g, b]| pixels[i] = [r, g, b, u8::MAX],
+    )?;
+    if desc.alpha_slice.1 > 0 {
+        codebook.decode_slice(
+            sub_slice(level_data, desc.alpha_slice.0, desc.alpha_slice.1)?,
+            width,
+            height,
+            |i, [_, g, _]| pixels[i][3] = g,
+        )?;
+    }
+    Ok(pixels)
+}
+
+/// Pure Rust Basis Universal transcoder, that is used by the engine by default. It supports ETC1S
+/// payload (supercompressed with BasisLZ), which is the default mode of Basis Universal encoders.
+/// UASTC payload is not supported, such textures require a third-party transcoder (see
+/// [`BasisTranscoder`] docs for more info).
+///
+/// Images are decoded to RGBA8 first and then compressed to the requested format using the
+/// encoders of this crate, which is slower than direct transcoding, but keeps the transcoder small.
+#[derive(Default, Debug)]
+pub struct BuiltInBasisTranscoder;
+
+impl BasisTranscoder for BuiltInBasisTranscoder {
+    fn transcode(&self, request: &TranscodingRequest) -> Result<Vec<u8>, String> {
+        let pixels = match request.payload {
+            BasisPayload::Etc1s => decode_etc1s(request)?,
+            BasisPayload::Uastc => {
+                return Err(
+                    "UASTC textures are not supported by the built-in Basis Universal \
+                    transcoder, use a third-party transcoder instead!"
+                        .to_string(),
+                )
+            }
+        };
+
+        let width = request.width as usize;
+        let height = request.height as usize;
+        let bytes = fyrox_core::transmute_vec_as_bytes(pixels);
+        Ok(match request.target {
+            TranscodingTarget::Auto | TranscodingTarget::Rgba8 => bytes,
+            TranscodingTarget::Bc1 => {
+                crate::compress_bc1::<tbc::color::Rgba8>(&bytes, width, height)
+            }
+            TranscodingTarget::Bc3 => {
+                crate::compress_bc3::<tbc::color::Rgba8>(&bytes, width, height)
+            }
+            TranscodingTarget::Bc7 => crate::compress_bc7(&bytes, 4, width, height),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::basis::{BitReader, HuffmanTable, SelectorHistory};
+
+    #[test]
+    fn test_canonical_huffman_codes() {
+        // Canonical codes: 1 -> 0, 0 -> 10, 2 -> 110, 3 -> 111. Codes are stored starting from
+        // the most significant bit, bits are read starting from the least significant bit.
+        let table = HuffmanTable::new(&[2, 1, 3, 3]).unwrap();
+        // 0 | 01 | 011 | 111 | 0 -> symbols 1, 0, 2, 3, 1.
+        let bits = [0u32, 1, 0, 1, 1, 0, 1, 1, 1, 0];
+        let mut bytes = [0u8; 2];
+        for (i, bit) in bits.iter().enumerate() {
+            bytes[i / 8] |= (*bit as u8) << (i % 8);
+        }
+        let mut reader = BitReader::new(&bytes);
+        let symbols = (0..5)
+            .map(|_| table.decode(&mut reader).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(symbols, [1, 0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_read_vlc() {
+        // 5 = 0b101 fits into a single 4-bit chunk, 20 = 0b10100 requires two chunks.
+        let value = 0b0_0101 | (0b1_0100 << 5) | (0b0_0001 << 10);
+        let bytes = (value as u32).to_le_bytes();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(reader.read_vlc(4), 5);
+        assert_eq!(reader.read_vlc(4), 20);
+    }
+
+    #[test]
+    fn test_selector_history() {
+        let mut history = SelectorHistory::new(4);
+        history.add(1);
+        history.add(2);
+        history.add(3);
+        assert_eq!(history.values, [0, 0, 3, 2]);
+        history.use_index(3);
+        assert_eq!(history.values, [0, 2, 3, 0]);
+    }
+}
@@ -0,0 +1,550 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! BC6H and BC7 block compression encoders. Both encoders use a single-subset mode with 4-bit
+//! indices (mode 11 for BC6H and mode 6 for BC7), which gives good quality for most textures and
+//! keeps encoding fast enough to be done at load time.
+
+use half::f16;
+
+const WEIGHTS_4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+struct BitWriter {
+    bits: u128,
+    position: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bits: 0,
+            position: 0,
+        }
+    }
+
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= ((value & ((1 << count) - 1)) as u128) << self.position;
+        self.position += count;
+    }
+
+    fn into_bytes(self) -> [u8; 16] {
+        debug_assert_eq!(self.position, 128);
+        self.bits.to_le_bytes()
+    }
+}
+
+/// Finds two endpoints of a line segment that approximates the given set of points. The segment
+/// lies on the principal axis of the set.
+fn principal_endpoints<const N: usize>(points: &[[f32; N]; 16]) -> ([f32; N], [f32; N]) {
+    let mut mean = [0.0; N];
+    for point in points {
+        for (m, c) in mean.iter_mut().zip(point) {
+            *m += c / 16.0;
+        }
+    }
+
+    let mut covariance = [[0.0f32; N]; N];
+    for point in points {
+        for i in 0..N {
+            for j in 0..N {
+                covariance[i][j] += (point[i] - mean[i]) * (point[j] - mean[j]);
+            }
+        }
+    }
+
+    // Power iteration to find the principal axis.
+    let mut axis = [1.0; N];
+    for _ in 0..8 {
+        let mut next = [0.0; N];
+        for i in 0..N {
+            for j in 0..N {
+                next[i] += covariance[i][j] * axis[j];
+            }
+        }
+        let length = next.iter().map(|c| c * c).sum::<f32>().sqrt();
+        if length <= f32::EPSILON {
+            break;
+        }
+        axis = next.map(|c| c / length);
+    }
+
+    let mut min = f32::MAX;
+    let mut max = f32::MIN;
+    for point in points {
+        let t = (0..N).map(|i| (point[i] - mean[i]) * axis[i]).sum::<f32>();
+        min = min.min(t);
+        max = max.max(t);
+    }
+
+    (
+        std::array::from_fn(|i| mean[i] + axis[i] * min),
+        std::array::from_fn(|i| mean[i] + axis[i] * max),
+    )
+}
+
+/// Selects the closest palette entry for every point. Returns indices and total squared error.
+fn select_indices<const N: usize>(
+    points: &[[f32; N]; 16],
+    palette: &[[f32; N]; 16],
+) -> ([u32; 16], f32) {
+    let mut indices = [0; 16];
+    let mut total_error = 0.0;
+    for (point, index) in points.iter().zip(indices.iter_mut()) {
+        let mut best_error = f32::MAX;
+        for (i, entry) in palette.iter().enumerate() {
+            let error = point
+                .iter()
+                .zip(entry)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>();
+            if error < best_error {
+                best_error = error;
+                *index = i as u32;
+            }
+        }
+        total_error += best_error;
+    }
+    (indices, total_error)
+}
+
+/// Finds endpoints that minimize squared error for the given set of points and their indices
+/// (least squares fit). Returns `None` if all points use the same index.
+fn refine_endpoints<const N: usize>(
+    points: &[[f32; N]; 16],
+    indices: &[u32; 16],
+) -> Option<([f32; N], [f32; N])> {
+    let (mut aa, mut ab, mut bb) = (0.0, 0.0, 0.0);
+    let mut ax = [0.0; N];
+    let mut bx = [0.0; N];
+    for (point, index) in points.iter().zip(indices) {
+        let w = WEIGHTS_4[*index as usize] as f32 / 64.0;
+        let a = 1.0 - w;
+        aa += a * a;
+        ab += a * w;
+        bb += w * w;
+        for c in 0..N {
+            ax[c] += a * point[c];
+            bx[c] += w * point[c];
+        }
+    }
+    let det = aa * bb - ab * ab;
+    if det.abs() <= f32::EPSILON {
+        return None;
+    }
+    Some((
+        std::array::from_fn(|c| (bb * ax[c] - ab * bx[c]) / det),
+        std::array::from_fn(|c| (aa * bx[c] - ab * ax[c]) / det),
+    ))
+}
+
+/// Fits quantized endpoints and indices for the given set of points. Endpoints are initially placed
+/// on the principal axis of the set and then refined iteratively.
+fn fit_block<const N: usize, Q: Copy>(
+    points: &[[f32; N]; 16],
+    quantize: impl Fn(&[f32; N]) -> Q,
+    palette: impl Fn(&Q, &Q) -> [[f32; N]; 16],
+) -> (Q, Q, [u32; 16]) {
+    let (mut e0, mut e1) = principal_endpoints(points);
+    let mut best = None;
+    let mut best_error = f32::MAX;
+    for _ in 0..3 {
+        let q0 = quantize(&e0);
+        let q1 = quantize(&e1);
+        let (indices, error) = select_indices(points, &palette(&q0, &q1));
+        if error < best_error {
+            best_error = error;
+            best = Some((q0, q1, indices));
+        }
+        match refine_endpoints(points, &indices) {
+            Some(refined) => (e0, e1) = refined,
+            None => break,
+        }
+    }
+    best.expect("At least one iteration must be done!")
+}
+
+/// Writes indices of a single-subset block. The first index is the anchor index, its most
+/// significant bit must be zero (it is guaranteed by the caller) and it is not stored.
+fn write_indices(writer: &mut BitWriter, indices: &[u32; 16]) {
+    writer.write(indices[0], 3);
+    for &index in &indices[1..] {
+        writer.write(index, 4);
+    }
+}
+
+/// Fetches a 4x4 block of pixels starting at the given coordinates. Pixels outside of the image
+/// are clamped to the nearest edge.
+fn fetch_block<T: Copy>(
+    pixels: &[T],
+    width: usize,
+    height: usize,
+    bx: usize,
+    by: usize,
+) -> [T; 16] {
+    std::array::from_fn(|i| {
+        let x = (bx + i % 4).min(width - 1);
+        let y = (by + i / 4).min(height - 1);
+        pixels[y * width + x]
+    })
+}
+
+fn encode_blocks<T: Copy>(
+    pixels: &[T],
+    width: usize,
+    height: usize,
+    encode_block: impl Fn(&[T; 16]) -> [u8; 16],
+) -> Vec<u8> {
+    let blocks_x = width.div_ceil(4);
+    let blocks_y = height.div_ceil(4);
+    let mut output = Vec::with_capacity(blocks_x * blocks_y * 16);
+    if width == 0 || height == 0 {
+        return output;
+    }
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let block = fetch_block(pixels, width, height, bx * 4, by * 4);
+            output.extend_from_slice(&encode_block(&block));
+        }
+    }
+    output
+}
+
+/// Quantizes an 8-bit value to 7 bits with the given p-bit, returns the 7-bit value.
+fn quantize_bc7(value: f32, p_bit: u32) -> u32 {
+    ((value - p_bit as f32) / 2.0).round().clamp(0.0, 127.0) as u32
+}
+
+fn unquantize_bc7(value: u32, p_bit: u32) -> u32 {
+    (value << 1) | p_bit
+}
+
+/// Quantizes an endpoint to 7 bits per component with a shared p-bit, choosing the p-bit that
+/// gives the smallest error.
+fn quantize_bc7_endpoint(endpoint: &[f32; 4]) -> ([u32; 4], u32) {
+    let mut best = ([0; 4], 0);
+    let mut best_error = f32::MAX;
+    for p_bit in 0..2 {
+        let quantized = endpoint.map(|c| quantize_bc7(c, p_bit));
+        let error = quantized
+            .iter()
+            .zip(endpoint)
+            .map(|(q, c)| {
+                let d = unquantize_bc7(*q, p_bit) as f32 - c;
+                d * d
+            })
+            .sum::<f32>();
+        if error < best_error {
+            best_error = error;
+            best = (quantized, p_bit);
+        }
+    }
+    best
+}
+
+fn interpolate(a: u32, b: u32, index: usize) -> f32 {
+    let w = WEIGHTS_4[index];
+    (((64 - w) * a + w * b + 32) >> 6) as f32
+}
+
+fn encode_bc7_block(block: &[[u8; 4]; 16]) -> [u8; 16] {
+    let points = block.map(|p| p.map(|c| c as f32));
+    let ((mut q0, mut p0), (mut q1, mut p1), mut indices) =
+        fit_block(&points, quantize_bc7_endpoint, |(q0, p0), (q1, p1)| {
+            std::array::from_fn(|i| {
+                std::array::from_fn(|c| {
+                    interpolate(unquantize_bc7(q0[c], *p0), unquantize_bc7(q1[c], *p1), i)
+                })
+            })
+        });
+
+    // The most significant bit of the anchor index must be zero. Swapping endpoints inverts
+    // indices and does not change the decoded result.
+    if indices[0] >= 8 {
+        std::mem::swap(&mut q0, &mut q1);
+        std::mem::swap(&mut p0, &mut p1);
+        indices = indices.map(|i| 15 - i);
+    }
+
+    let mut writer = BitWriter::new();
+    // Mode 6.
+    writer.write(1 << 6, 7);
+    for c in 0..4 {
+        writer.write(q0[c], 7);
+        writer.write(q1[c], 7);
+    }
+    writer.write(p0, 1);
+    writer.write(p1, 1);
+    write_indices(&mut writer, &indices);
+    writer.into_bytes()
+}
+
+/// Encodes the given RGBA8 image using BC7 block compression. Width and height of the image could
+/// be arbitrary, edge pixels are repeated to fill partial blocks. Each 4x4 block takes 16 bytes.
+pub fn encode_bc7(pixels: &[[u8; 4]], width: usize, height: usize) -> Vec<u8> {
+    encode_blocks(pixels, width, height, encode_bc7_block)
+}
+
+/// Converts a floating-point value to the domain in which BC6H (unsigned) interpolation works. It is
+/// the bit representation of a half-float value scaled by 64/31.
+fn to_bc6h_domain(value: f32) -> f32 {
+    let value = if value.is_nan() { 0.0 } else { value };
+    let bits = f16::from_f32(value.clamp(0.0, f16::MAX.to_f32())).to_bits();
+    bits as f32 * 64.0 / 31.0
+}
+
+fn quantize_bc6h(value: f32) -> u32 {
+    ((value - 32.0) / 64.0).round().clamp(0.0, 1023.0) as u32
+}
+
+fn unquantize_bc6h(value: u32) -> u32 {
+    match value {
+        0 => 0,
+        1023 => 0xFFFF,
+        _ => ((value << 16) + 0x8000) >> 10,
+    }
+}
+
+fn encode_bc6h_block(block: &[[f32; 3]; 16]) -> [u8; 16] {
+    let points = block.map(|p| p.map(to_bc6h_domain));
+    let (mut q0, mut q1, mut indices) = fit_block(
+        &points,
+        |e| e.map(quantize_bc6h),
+        |q0, q1| {
+            std::array::from_fn(|i| {
+                std::array::from_fn(|c| {
+                    interpolate(unquantize_bc6h(q0[c]), unquantize_bc6h(q1[c]), i)
+                })
+            })
+        },
+    );
+
+    if indices[0] >= 8 {
+        std::mem::swap(&mut q0, &mut q1);
+        indices = indices.map(|i| 15 - i);
+    }
+
+    let mut writer = BitWriter::new();
+    // Mode 11 - single region, 10-bit endpoints without delta compression.
+    writer.write(0b00011, 5);
+    for q in q0.iter().chain(q1.iter()) {
+        writer.write(*q, 10);
+    }
+    write_indices(&mut writer, &indices);
+    writer.into_bytes()
+}
+
+/// Encodes the given RGB floating-point image using BC6H (unsigned float) block compression.
+/// Negative values are clamped to zero, values that exceed the maximum half-float value are
+/// clamped to it. Width and height of the image could be arbitrary, edge pixels are repeated to
+/// fill partial blocks. Each 4x4 block takes 16 bytes.
+pub fn encode_bc6h(pixels: &[[f32; 3]], width: usize, height: usize) -> Vec<u8> {
+    encode_blocks(pixels, width, height, encode_bc6h_block)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bc::{encode_bc6h, encode_bc7, WEIGHTS_4};
+    use half::f16;
+
+    fn read_bits(block: &[u8], offset: usize, count: usize) -> u32 {
+        let bits = u128::from_le_bytes(block.try_into().unwrap());
+        ((bits >> offset) & ((1u128 << count) - 1)) as u32
+    }
+
+    fn read_indices(block: &[u8], offset: usize) -> [usize; 16] {
+        let mut indices = [0; 16];
+        let mut position = offset;
+        for (i, index) in indices.iter_mut().enumerate() {
+            let count = if i == 0 { 3 } else { 4 };
+            *index = read_bits(block, position, count) as usize;
+            position += count;
+        }
+        indices
+    }
+
+    fn interpolate(a: u32, b: u32, index: usize) -> u32 {
+        let w = WEIGHTS_4[index];
+        ((64 - w) * a + w * b + 32) >> 6
+    }
+
+    // Decodes a BC7 block that uses mode 6, which is the only mode used by the encoder.
+    fn decode_bc7_block(block: &[u8]) -> [[u8; 4]; 16] {
+        assert_eq!(read_bits(block, 0, 7), 1 << 6);
+        let p0 = read_bits(block, 63, 1);
+        let p1 = read_bits(block, 64, 1);
+        let endpoints: [[u32; 4]; 2] = std::array::from_fn(|e| {
+            std::array::from_fn(|c| {
+                let value = read_bits(block, 7 + (c * 2 + e) * 7, 7);
+                (value << 1) | if e == 0 { p0 } else { p1 }
+            })
+        });
+        read_indices(block, 65).map(|index| {
+            std::array::from_fn(|c| interpolate(endpoints[0][c], endpoints[1][c], index) as u8)
+        })
+    }
+
+    // Decodes a BC6H block that uses mode 11, which is the only mode used by the encoder.
+    fn decode_bc6h_block(block: &[u8]) -> [[f32; 3]; 16] {
+        assert_eq!(read_bits(block, 0, 5), 0b00011);
+        let unquantize = |value: u32| match value {
+            0 => 0,
+            1023 => 0xFFFF,
+            _ => ((value << 16) + 0x8000) >> 10,
+        };
+        let endpoints: [[u32; 3]; 2] = std::array::from_fn(|e| {
+            std::array::from_fn(|c| unquantize(read_bits(block, 5 + (e * 3 + c) * 10, 10)))
+        });
+        read_indices(block, 65).map(|index| {
+            std::array::from_fn(|c| {
+                let value = interpolate(endpoints[0][c], endpoints[1][c], index);
+                f16::from_bits(((value * 31) >> 6) as u16).to_f32()
+            })
+        })
+    }
+
+    fn decode<T: Copy + Default>(
+        data: &[u8],
+        width: usize,
+        height: usize,
+        decode_block: impl Fn(&[u8]) -> [T; 16],
+    ) -> Vec<T> {
+        let blocks_x = width.div_ceil(4);
+        assert_eq!(data.len(), blocks_x * height.div_ceil(4) * 16);
+        let mut pixels = vec![T::default(); width * height];
+        for (i, block) in data.chunks_exact(16).enumerate() {
+            let (bx, by) = (i % blocks_x * 4, i / blocks_x * 4);
+            for (j, pixel) in decode_block(block).into_iter().enumerate() {
+                let (x, y) = (bx + j % 4, by + j / 4);
+                if x < width && y < height {
+                    pixels[y * width + x] = pixel;
+                }
+            }
+        }
+        pixels
+    }
+
+    fn max_error<T: Copy, const N: usize>(
+        expected: &[[T; N]],
+        actual: &[[T; N]],
+        error: impl Fn(T, T) -> f32,
+    ) -> f32 {
+        expected
+            .iter()
+            .zip(actual)
+            .flat_map(|(e, a)| e.iter().zip(a).map(|(e, a)| error(*e, *a)))
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_bc7_round_trip() {
+        // Colors of each block lie on a line in RGBA space, so a single subset can represent them
+        // with small error. The size is odd to check partial blocks.
+        let (width, height) = (13, 7);
+        let pixels = (0..width * height)
+            .map(|i| {
+                let t = (i % width * 18 + i / width * 3) as f32 / 255.0;
+                [
+                    (t * 255.0) as u8,
+                    (40.0 + t * 120.0) as u8,
+                    (255.0 - t * 200.0) as u8,
+                    (128.0 + t * 100.0) as u8,
+                ]
+            })
+            .collect::<Vec<_>>();
+        let decoded = decode(
+            &encode_bc7(&pixels, width, height),
+            width,
+            height,
+            decode_bc7_block,
+        );
+        let error = max_error(&pixels, &decoded, |e, a| e.abs_diff(a) as f32);
+        assert!(error <= 4.0, "max error {error}");
+
+        // Arbitrary colors can't be represented exactly, but the error must stay bounded.
+        let (width, height) = (16, 16);
+        let pixels = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                [(x * 16) as u8, (y * 16) as u8, ((x ^ y) * 16) as u8, 255]
+            })
+            .collect::<Vec<_>>();
+        let decoded = decode(
+            &encode_bc7(&pixels, width, height),
+            width,
+            height,
+            decode_bc7_block,
+        );
+        let error = max_error(&pixels, &decoded, |e, a| e.abs_diff(a) as f32);
+        assert!(error <= 48.0, "max error {error}");
+    }
+
+    #[test]
+    fn test_bc7_solid_colors() {
+        for color in [
+            [0, 0, 0, 0],
+            [255; 4],
+            [10, 200, 77, 255],
+            [128, 64, 32, 16],
+        ] {
+            let pixels = vec![color; 16];
+            let decoded = decode(&encode_bc7(&pixels, 4, 4), 4, 4, decode_bc7_block);
+            for actual in decoded {
+                for (e, a) in color.iter().zip(actual.iter()) {
+                    assert!(e.abs_diff(*a) <= 1, "{color:?} != {actual:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bc6h_hdr_round_trip() {
+        // HDR gradients, each row of blocks covers a different order of magnitude.
+        let (width, height) = (8, 12);
+        let pixels = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                let base = 10.0f32.powi(y as i32 / 4 - 1);
+                let t = 1.0 + (x % 4) as f32 * 0.25 + (y % 4) as f32 * 0.0625;
+                [base * t, base * t * 0.5, base * 2.0]
+            })
+            .collect::<Vec<_>>();
+        let decoded = decode(
+            &encode_bc6h(&pixels, width, height),
+            width,
+            height,
+            decode_bc6h_block,
+        );
+        let error = max_error(&pixels, &decoded, |e, a| (e - a).abs() / e);
+        assert!(error <= 0.05, "max relative error {error}");
+        assert!(decoded.iter().flatten().any(|c| *c > 10.0));
+    }
+
+    #[test]
+    fn test_bc6h_clamping() {
+        // Negative values are clamped to zero, huge values - to the maximum half-float value.
+        let pixels = [[-1.0, 1.0e9, 0.0]; 16];
+        let decoded = decode(&encode_bc6h(&pixels, 4, 4), 4, 4, decode_bc6h_block);
+        for pixel in decoded {
+            assert_eq!(pixel[0], 0.0);
+            assert!((pixel[1] - f16::MAX.to_f32()).abs() <= f16::MAX.to_f32() * 0.01);
+            assert_eq!(pixel[2], 0.0);
+        }
+    }
+}
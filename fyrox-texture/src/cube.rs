@@ -0,0 +1,368 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Cube map utilities. Contains CPU-side conversion of equirectangular (latitude-longitude)
+//! panoramas into cube maps. See [`equirectangular_to_cube_faces`] docs for more info.
+
+use fyrox_core::algebra::Vector3;
+use std::f32::consts::PI;
+
+/// A simple CPU-side RGBA image with 32-bit floating-point components.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FloatImage {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// Pixels of the image in row-major order, starting from the top-left corner.
+    pub pixels: Vec<[f32; 4]>,
+}
+
+impl FloatImage {
+    /// Creates a new image of the given size filled with transparent black.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 4]; width as usize * height as usize],
+        }
+    }
+
+    /// Returns a pixel at the given coordinates. Coordinates are clamped to the image bounds.
+    pub fn pixel(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = x.clamp(0, self.width as i64 - 1) as usize;
+        let y = y.clamp(0, self.height as i64 - 1) as usize;
+        self.pixels[y * self.width as usize + x]
+    }
+
+    /// Samples the image using bilinear filtering. `u` coordinate wraps around, `v` coordinate
+    /// is clamped. Such addressing is suitable for equirectangular panoramas.
+    pub fn sample_wrapped(&self, u: f32, v: f32) -> [f32; 4] {
+        let x = u.rem_euclid(1.0) * self.width as f32 - 0.5;
+        let y = v.clamp(0.0, 1.0) * self.height as f32 - 0.5;
+
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+
+        let x0 = x0 as i64;
+        let y0 = y0 as i64;
+        let x1 = (x0 + 1).rem_euclid(self.width as i64);
+        let x0 = x0.rem_euclid(self.width as i64);
+
+        let a = self.pixel(x0, y0);
+        let b = self.pixel(x1, y0);
+        let c = self.pixel(x0, y0 + 1);
+        let d = self.pixel(x1, y0 + 1);
+
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            let top = a[i] + (b[i] - a[i]) * tx;
+            let bottom = c[i] + (d[i] - c[i]) * tx;
+            out[i] = top + (bottom - top) * ty;
+        }
+        out
+    }
+
+    /// Samples the image using bilinear filtering. Both coordinates are clamped to `[0; 1]` range.
+    pub fn sample_clamped(&self, u: f32, v: f32) -> [f32; 4] {
+        let x = u.clamp(0.0, 1.0) * self.width as f32 - 0.5;
+        let y = v.clamp(0.0, 1.0) * self.height as f32 - 0.5;
+
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+
+        let x0 = x0 as i64;
+        let y0 = y0 as i64;
+
+        let a = self.pixel(x0, y0);
+        let b = self.pixel(x0 + 1, y0);
+        let c = self.pixel(x0, y0 + 1);
+        let d = self.pixel(x0 + 1, y0 + 1);
+
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            let top = a[i] + (b[i] - a[i]) * tx;
+            let bottom = c[i] + (d[i] - c[i]) * tx;
+            out[i] = top + (bottom - top) * ty;
+        }
+        out
+    }
+
+    /// Creates a new image of half size using 2x2 box filter. Each dimension of the resulting image
+    /// is at least one pixel.
+    pub fn downsample(&self) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut result = Self::new(width, height);
+        for y in 0..height as i64 {
+            for x in 0..width as i64 {
+                let mut sum = [0.0; 4];
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let pixel = self.pixel(x * 2 + dx, y * 2 + dy);
+                    for i in 0..4 {
+                        sum[i] += pixel[i];
+                    }
+                }
+                result.pixels[y as usize * width as usize + x as usize] = sum.map(|c| c * 0.25);
+            }
+        }
+        result
+    }
+}
+
+/// Returns a normalized direction vector that corresponds to the given texel of the given cube
+/// map face. Faces are indexed in the order: +X, -X, +Y, -Y, +Z, -Z. This is the order in which
+/// faces are stored in cube textures.
+pub fn cube_face_direction(face: usize, x: u32, y: u32, size: u32) -> Vector3<f32> {
+    let u = 2.0 * (x as f32 + 0.5) / size as f32 - 1.0;
+    let v = 2.0 * (y as f32 + 0.5) / size as f32 - 1.0;
+    let direction = match face {
+        0 => Vector3::new(1.0, -v, -u),
+        1 => Vector3::new(-1.0, -v, u),
+        2 => Vector3::new(u, 1.0, v),
+        3 => Vector3::new(u, -1.0, -v),
+        4 => Vector3::new(u, -v, 1.0),
+        _ => Vector3::new(-u, -v, -1.0),
+    };
+    direction.normalize()
+}
+
+/// Returns an index of a cube map face and texture coordinates on it for the given direction. This
+/// is the inverse of [`cube_face_direction`].
+pub fn direction_to_cube_face(direction: &Vector3<f32>) -> (usize, f32, f32) {
+    let ax = direction.x.abs();
+    let ay = direction.y.abs();
+    let az = direction.z.abs();
+    let (face, u, v) = if ax >= ay && ax >= az {
+        if direction.x > 0.0 {
+            (0, -direction.z / ax, -direction.y / ax)
+        } else {
+            (1, direction.z / ax, -direction.y / ax)
+        }
+    } else if ay >= az {
+        if direction.y > 0.0 {
+            (2, direction.x / ay, direction.z / ay)
+        } else {
+            (3, direction.x / ay, -direction.z / ay)
+        }
+    } else if direction.z > 0.0 {
+        (4, direction.x / az, -direction.y / az)
+    } else {
+        (5, -direction.x / az, -direction.y / az)
+    };
+    (face, (u + 1.0) * 0.5, (v + 1.0) * 0.5)
+}
+
+/// Converts the given direction to texture coordinates of an equirectangular panorama. The top
+/// row of the panorama corresponds to +Y direction.
+pub fn direction_to_equirectangular(direction: &Vector3<f32>) -> (f32, f32) {
+    let longitude = direction.z.atan2(direction.x);
+    let latitude = direction.y.clamp(-1.0, 1.0).asin();
+    (0.5 + longitude / (2.0 * PI), 0.5 - latitude / PI)
+}
+
+/// Converts an equirectangular (latitude-longitude) panorama into six cube map faces of the given
+/// size. Faces are returned in the order: +X, -X, +Y, -Y, +Z, -Z.
+pub fn equirectangular_to_cube_faces(panorama: &FloatImage, face_size: u32) -> [FloatImage; 6] {
+    let face_size = face_size.max(1);
+    std::array::from_fn(|face| {
+        let mut image = FloatImage::new(face_size, face_size);
+        for y in 0..face_size {
+            for x in 0..face_size {
+                let direction = cube_face_direction(face, x, y, face_size);
+                let (u, v) = direction_to_equirectangular(&direction);
+                image.pixels[(y * face_size + x) as usize] = panorama.sample_wrapped(u, v);
+            }
+        }
+        image
+    })
+}
+
+/// Calculates the default size of a cube map face for the given panorama width. The size is equal
+/// to a quarter of the panorama width rounded up to the next power of two.
+pub fn default_face_size(panorama_width: u32) -> u32 {
+    (panorama_width / 4).max(1).next_power_of_two()
+}
+
+/// A cube map stored on CPU side. It contains a chain of mip levels, each level contains six
+/// faces in the order: +X, -X, +Y, -Y, +Z, -Z.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CubeMapImage {
+    /// Mip levels of the cube map, starting from the largest one.
+    pub levels: Vec<[FloatImage; 6]>,
+}
+
+impl CubeMapImage {
+    /// Creates a new cube map with a single mip level.
+    pub fn new(faces: [FloatImage; 6]) -> Self {
+        Self {
+            levels: vec![faces],
+        }
+    }
+
+    /// Returns the size of faces of the first mip level.
+    pub fn size(&self) -> u32 {
+        self.levels.first().map_or(0, |faces| faces[0].width)
+    }
+
+    /// Generates the full chain of mip levels using 2x2 box filter. Existing levels, except the
+    /// first one, are discarded.
+    pub fn generate_mips(&mut self) {
+        self.levels.truncate(1);
+        while let Some(last) = self.levels.last() {
+            if last[0].width <= 1 {
+                break;
+            }
+            let next = last.each_ref().map(|face| face.downsample());
+            self.levels.push(next);
+        }
+    }
+
+    /// Samples the given mip level of the cube map in the given direction using bilinear filtering.
+    pub fn sample_level(&self, direction: &Vector3<f32>, level: usize) -> [f32; 4] {
+        let level = level.min(self.levels.len().saturating_sub(1));
+        let (face, u, v) = direction_to_cube_face(direction);
+        self.levels[level][face].sample_clamped(u, v)
+    }
+
+    /// Samples the cube map in the given direction with trilinear filtering between mip levels.
+    pub fn sample(&self, direction: &Vector3<f32>, lod: f32) -> [f32; 4] {
+        let lod = lod.clamp(0.0, self.levels.len().saturating_sub(1) as f32);
+        let lower = lod.floor() as usize;
+        let t = lod - lower as f32;
+        let a = self.sample_level(direction, lower);
+        if t <= f32::EPSILON {
+            return a;
+        }
+        let b = self.sample_level(direction, lower + 1);
+        std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube::{
+        cube_face_direction, default_face_size, direction_to_cube_face,
+        equirectangular_to_cube_faces, CubeMapImage, FloatImage,
+    };
+    use fyrox_core::algebra::Vector3;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_cube_face_direction_round_trip() {
+        let size = 8;
+        for face in 0..6 {
+            for y in 0..size {
+                for x in 0..size {
+                    let direction = cube_face_direction(face, x, y, size);
+                    assert!((direction.norm() - 1.0).abs() < 1.0e-5);
+                    let (face_index, u, v) = direction_to_cube_face(&direction);
+                    assert_eq!(face_index, face);
+                    assert!((u - (x as f32 + 0.5) / size as f32).abs() < 1.0e-5);
+                    assert!((v - (y as f32 + 0.5) / size as f32).abs() < 1.0e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_face_centers() {
+        let expected = [
+            Vector3::x(),
+            -Vector3::x(),
+            Vector3::y(),
+            -Vector3::y(),
+            Vector3::z(),
+            -Vector3::z(),
+        ];
+        for (face, expected) in expected.iter().enumerate() {
+            // Odd size, so there's a texel exactly at the center.
+            let direction = cube_face_direction(face, 1, 1, 3);
+            assert!((direction - expected).norm() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn test_equirectangular_to_cube_faces() {
+        // Each pixel of the panorama contains the direction, that corresponds to its center.
+        let (width, height) = (256, 128);
+        let mut panorama = FloatImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let longitude = ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * PI;
+                let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * PI;
+                panorama.pixels[(y * width + x) as usize] = [
+                    latitude.cos() * longitude.cos(),
+                    latitude.sin(),
+                    latitude.cos() * longitude.sin(),
+                    1.0,
+                ];
+            }
+        }
+
+        let size = 16;
+        let faces = equirectangular_to_cube_faces(&panorama, size);
+        for (face_index, face) in faces.iter().enumerate() {
+            assert_eq!(face.width, size);
+            assert_eq!(face.height, size);
+            for y in 0..size {
+                for x in 0..size {
+                    let direction = cube_face_direction(face_index, x, y, size);
+                    let pixel = face.pixels[(y * size + x) as usize];
+                    let sampled = Vector3::new(pixel[0], pixel[1], pixel[2]);
+                    // Bilinear filtering near the poles gives the largest error.
+                    assert!((sampled - direction).norm() < 0.05);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_face_size() {
+        assert_eq!(default_face_size(2048), 512);
+        assert_eq!(default_face_size(1000), 256);
+        assert_eq!(default_face_size(2), 1);
+    }
+
+    #[test]
+    fn test_generate_mips() {
+        let mut face = FloatImage::new(8, 8);
+        face.pixels.fill([0.5, 1.0, 2.0, 1.0]);
+        let mut cube_map = CubeMapImage::new(std::array::from_fn(|_| face.clone()));
+        cube_map.generate_mips();
+        assert_eq!(cube_map.levels.len(), 4);
+        for (level, faces) in cube_map.levels.iter().enumerate() {
+            for face in faces {
+                assert_eq!(face.width, 8 >> level);
+                assert_eq!(face.height, 8 >> level);
+                assert!(face
+                    .pixels
+                    .iter()
+                    .all(|pixel| *pixel == [0.5, 1.0, 2.0, 1.0]));
+            }
+        }
+        let direction = Vector3::new(0.3, -0.5, 0.8).normalize();
+        assert_eq!(cube_map.sample(&direction, 1.5), [0.5, 1.0, 2.0, 1.0]);
+    }
+}
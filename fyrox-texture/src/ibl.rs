@@ -0,0 +1,423 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Image-based lighting (IBL) utilities. Contains CPU-side computation of diffuse irradiance
+//! (both as spherical harmonics and as a cube map), roughness-prefiltered specular cube maps
+//! and BRDF look-up table for split-sum approximation.
+
+use crate::cube::{cube_face_direction, CubeMapImage, FloatImage};
+use fyrox_core::algebra::Vector3;
+use std::f32::consts::PI;
+
+/// Coefficients of 3rd order (9 coefficients) spherical harmonics for RGB signal.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SphericalHarmonics {
+    /// RGB coefficients of the harmonics.
+    pub coefficients: [Vector3<f32>; 9],
+}
+
+fn sh_basis(d: &Vector3<f32>) -> [f32; 9] {
+    [
+        0.282095,
+        0.488603 * d.y,
+        0.488603 * d.z,
+        0.488603 * d.x,
+        1.092548 * d.x * d.y,
+        1.092548 * d.y * d.z,
+        0.315392 * (3.0 * d.z * d.z - 1.0),
+        1.092548 * d.x * d.z,
+        0.546274 * (d.x * d.x - d.y * d.y),
+    ]
+}
+
+impl SphericalHarmonics {
+    /// Projects radiance of the given cube map (its first mip level) to spherical harmonics.
+    pub fn from_cube_map(cube_map: &CubeMapImage) -> Self {
+        let mut coefficients = [Vector3::default(); 9];
+        let Some(faces) = cube_map.levels.first() else {
+            return Self { coefficients };
+        };
+
+        let size = faces[0].width;
+        let mut total_weight = 0.0;
+        for (face_index, face) in faces.iter().enumerate() {
+            for y in 0..size {
+                for x in 0..size {
+                    let u = 2.0 * (x as f32 + 0.5) / size as f32 - 1.0;
+                    let v = 2.0 * (y as f32 + 0.5) / size as f32 - 1.0;
+                    // Solid angle of the texel.
+                    let weight = 1.0 / (1.0 + u * u + v * v).powf(1.5);
+                    let direction = cube_face_direction(face_index, x, y, size);
+                    let pixel = face.pixels[(y * size + x) as usize];
+                    let radiance = Vector3::new(pixel[0], pixel[1], pixel[2]);
+                    for (coefficient, basis) in coefficients.iter_mut().zip(sh_basis(&direction)) {
+                        *coefficient += radiance * (basis * weight);
+                    }
+                    total_weight += weight;
+                }
+            }
+        }
+
+        // Normalize the sum of weights to the solid angle of the sphere.
+        let normalization = 4.0 * PI / total_weight;
+        for coefficient in coefficients.iter_mut() {
+            *coefficient *= normalization;
+        }
+
+        Self { coefficients }
+    }
+
+    /// Evaluates diffuse irradiance divided by PI in the given direction. In other words, uniform
+    /// environment with radiance `L` will result in `L`. Such values could be directly multiplied
+    /// by albedo of a surface.
+    pub fn irradiance(&self, normal: &Vector3<f32>) -> Vector3<f32> {
+        // Convolution with clamped cosine lobe.
+        const BAND_FACTORS: [f32; 9] = [
+            PI,
+            2.0 * PI / 3.0,
+            2.0 * PI / 3.0,
+            2.0 * PI / 3.0,
+            PI / 4.0,
+            PI / 4.0,
+            PI / 4.0,
+            PI / 4.0,
+            PI / 4.0,
+        ];
+        let mut result = Vector3::default();
+        for ((coefficient, basis), factor) in self
+            .coefficients
+            .iter()
+            .zip(sh_basis(normal))
+            .zip(BAND_FACTORS)
+        {
+            result += coefficient * (basis * factor);
+        }
+        result.map(|c| (c / PI).max(0.0))
+    }
+}
+
+/// Computes diffuse irradiance cube map of the given size. See [`SphericalHarmonics::irradiance`]
+/// for more info about the resulting values.
+pub fn compute_irradiance_map(cube_map: &CubeMapImage, face_size: u32) -> CubeMapImage {
+    let harmonics = SphericalHarmonics::from_cube_map(cube_map);
+    let face_size = face_size.max(1);
+    CubeMapImage::new(std::array::from_fn(|face_index| {
+        let mut face = FloatImage::new(face_size, face_size);
+        for y in 0..face_size {
+            for x in 0..face_size {
+                let direction = cube_face_direction(face_index, x, y, face_size);
+                let irradiance = harmonics.irradiance(&direction);
+                face.pixels[(y * face_size + x) as usize] =
+                    [irradiance.x, irradiance.y, irradiance.z, 1.0];
+            }
+        }
+        face
+    }))
+}
+
+fn radical_inverse(mut bits: u32) -> f32 {
+    bits = bits.rotate_right(16);
+    bits = ((bits & 0x55555555) << 1) | ((bits & 0xAAAAAAAA) >> 1);
+    bits = ((bits & 0x33333333) << 2) | ((bits & 0xCCCCCCCC) >> 2);
+    bits = ((bits & 0x0F0F0F0F) << 4) | ((bits & 0xF0F0F0F0) >> 4);
+    bits = ((bits & 0x00FF00FF) << 8) | ((bits & 0xFF00FF00) >> 8);
+    bits as f32 * 2.328_306_4e-10
+}
+
+fn hammersley(i: u32, count: u32) -> (f32, f32) {
+    (i as f32 / count as f32, radical_inverse(i))
+}
+
+fn importance_sample_ggx(xi: (f32, f32), normal: &Vector3<f32>, roughness: f32) -> Vector3<f32> {
+    let a = roughness * roughness;
+    let phi = 2.0 * PI * xi.0;
+    let cos_theta = ((1.0 - xi.1) / (1.0 + (a * a - 1.0) * xi.1)).sqrt();
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+
+    let up = if normal.z.abs() < 0.999 {
+        Vector3::z()
+    } else {
+        Vector3::x()
+    };
+    let tangent = up.cross(normal).normalize();
+    let bitangent = normal.cross(&tangent);
+
+    (tangent * (phi.cos() * sin_theta) + bitangent * (phi.sin() * sin_theta) + normal * cos_theta)
+        .normalize()
+}
+
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    a2 / (PI * d * d).max(f32::EPSILON)
+}
+
+fn geometry_smith_ibl(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    let k = roughness * roughness / 2.0;
+    let g_v = n_dot_v / (n_dot_v * (1.0 - k) + k);
+    let g_l = n_dot_l / (n_dot_l * (1.0 - k) + k);
+    g_v * g_l
+}
+
+/// Computes roughness-prefiltered specular cube map for split-sum approximation. Each mip level of
+/// the resulting cube map corresponds to a specific roughness: `roughness = level / (levels - 1)`.
+/// The source cube map must have the full chain of mip levels (see [`CubeMapImage::generate_mips`]),
+/// they're used to reduce noise of filtered importance sampling.
+pub fn prefilter_specular(
+    source: &CubeMapImage,
+    face_size: u32,
+    sample_count: u32,
+) -> CubeMapImage {
+    let face_size = face_size.max(1);
+    let sample_count = sample_count.max(1);
+    let level_count = face_size.ilog2() + 1;
+    let source_size = source.size().max(1) as f32;
+    let texel_solid_angle = 4.0 * PI / (6.0 * source_size * source_size);
+
+    let levels = (0..level_count)
+        .map(|level| {
+            let size = (face_size >> level).max(1);
+            let roughness = if level_count > 1 {
+                level as f32 / (level_count - 1) as f32
+            } else {
+                0.0
+            };
+            std::array::from_fn(|face_index| {
+                let mut face = FloatImage::new(size, size);
+                for y in 0..size {
+                    for x in 0..size {
+                        let normal = cube_face_direction(face_index, x, y, size);
+                        let pixel = if roughness == 0.0 {
+                            source.sample(&normal, 0.0)
+                        } else {
+                            prefilter_texel(
+                                source,
+                                &normal,
+                                roughness,
+                                sample_count,
+                                texel_solid_angle,
+                            )
+                        };
+                        face.pixels[(y * size + x) as usize] = pixel;
+                    }
+                }
+                face
+            })
+        })
+        .collect();
+
+    CubeMapImage { levels }
+}
+
+fn prefilter_texel(
+    source: &CubeMapImage,
+    normal: &Vector3<f32>,
+    roughness: f32,
+    sample_count: u32,
+    texel_solid_angle: f32,
+) -> [f32; 4] {
+    let mut sum = [0.0; 4];
+    let mut total_weight = 0.0;
+    for i in 0..sample_count {
+        let h = importance_sample_ggx(hammersley(i, sample_count), normal, roughness);
+        let n_dot_h = normal.dot(&h).max(0.0);
+        let l = h * (2.0 * n_dot_h) - normal;
+        let n_dot_l = normal.dot(&l);
+        if n_dot_l > 0.0 {
+            // Normal and view directions are the same, so n_dot_h == v_dot_h.
+            let pdf = distribution_ggx(n_dot_h, roughness) * 0.25 + 0.0001;
+            let sample_solid_angle = 1.0 / (sample_count as f32 * pdf);
+            let lod = 0.5 * (sample_solid_angle / texel_solid_angle).log2() + 1.0;
+            let radiance = source.sample(&l, lod.max(0.0));
+            for (s, r) in sum.iter_mut().zip(radiance) {
+                *s += r * n_dot_l;
+            }
+            total_weight += n_dot_l;
+        }
+    }
+    if total_weight > 0.0 {
+        sum.map(|s| s / total_weight)
+    } else {
+        sum
+    }
+}
+
+/// Computes BRDF look-up table for split-sum approximation. X axis of the table corresponds to
+/// `dot(N, V)`, Y axis (starting from the top row) corresponds to roughness. Red channel of each
+/// pixel contains scale of Fresnel reflectance at normal incidence (F0), green channel contains
+/// bias to it.
+pub fn compute_brdf_lut(size: u32, sample_count: u32) -> FloatImage {
+    let size = size.max(1);
+    let sample_count = sample_count.max(1);
+    let mut image = FloatImage::new(size, size);
+    let normal = Vector3::z();
+    for y in 0..size {
+        let roughness = (y as f32 + 0.5) / size as f32;
+        for x in 0..size {
+            let n_dot_v = (x as f32 + 0.5) / size as f32;
+            let view = Vector3::new((1.0 - n_dot_v * n_dot_v).sqrt(), 0.0, n_dot_v);
+            let mut scale = 0.0;
+            let mut bias = 0.0;
+            for i in 0..sample_count {
+                let h = importance_sample_ggx(hammersley(i, sample_count), &normal, roughness);
+                let v_dot_h = view.dot(&h).max(0.0);
+                let l = h * (2.0 * v_dot_h) - view;
+                let n_dot_l = l.z.max(0.0);
+                let n_dot_h = h.z.max(0.0);
+                if n_dot_l > 0.0 {
+                    let g = geometry_smith_ibl(n_dot_v, n_dot_l, roughness);
+                    let g_vis = g * v_dot_h / (n_dot_h * n_dot_v).max(f32::EPSILON);
+                    let fc = (1.0 - v_dot_h).powi(5);
+                    scale += (1.0 - fc) * g_vis;
+                    bias += fc * g_vis;
+                }
+            }
+            image.pixels[(y * size + x) as usize] = [
+                scale / sample_count as f32,
+                bias / sample_count as f32,
+                0.0,
+                1.0,
+            ];
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cube::{cube_face_direction, CubeMapImage, FloatImage},
+        ibl::{compute_brdf_lut, compute_irradiance_map, prefilter_specular, SphericalHarmonics},
+    };
+    use fyrox_core::algebra::Vector3;
+
+    fn uniform_cube_map(size: u32, value: [f32; 4]) -> CubeMapImage {
+        let mut face = FloatImage::new(size, size);
+        face.pixels.fill(value);
+        let mut cube_map = CubeMapImage::new(std::array::from_fn(|_| face.clone()));
+        cube_map.generate_mips();
+        cube_map
+    }
+
+    fn assert_close(a: [f32; 4], b: [f32; 4], tolerance: f32) {
+        for (a, b) in a.iter().zip(b.iter()).take(3) {
+            assert!((a - b).abs() <= tolerance, "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn test_uniform_irradiance() {
+        let radiance = [0.25, 0.5, 2.0, 1.0];
+        let irradiance = compute_irradiance_map(&uniform_cube_map(16, radiance), 4);
+        assert_eq!(irradiance.levels.len(), 1);
+        assert_eq!(irradiance.size(), 4);
+        for face in irradiance.levels[0].iter() {
+            for pixel in face.pixels.iter() {
+                assert_close(*pixel, radiance, 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn test_directional_irradiance() {
+        // Only the upper hemisphere (+Y face) emits light.
+        let size = 16;
+        let mut cube_map = uniform_cube_map(size, [0.0; 4]);
+        cube_map.levels.truncate(1);
+        cube_map.levels[0][2].pixels.fill([1.0, 1.0, 1.0, 1.0]);
+
+        let harmonics = SphericalHarmonics::from_cube_map(&cube_map);
+        let up = harmonics.irradiance(&Vector3::y());
+        let side = harmonics.irradiance(&Vector3::x());
+        let down = harmonics.irradiance(&-Vector3::y());
+        assert!(up.x > side.x);
+        assert!(side.x > down.x);
+        assert!(down.x < 0.05);
+        // The face covers 1/6 of the sphere, so the irradiance cannot exceed its radiance.
+        assert!(up.x < 1.0);
+    }
+
+    #[test]
+    fn test_prefilter_uniform_environment() {
+        let radiance = [1.0, 0.5, 0.25, 1.0];
+        let source = uniform_cube_map(32, radiance);
+        let prefiltered = prefilter_specular(&source, 16, 64);
+        // 16, 8, 4, 2, 1
+        assert_eq!(prefiltered.levels.len(), 5);
+        for (level, faces) in prefiltered.levels.iter().enumerate() {
+            for face in faces {
+                assert_eq!(face.width, 16 >> level);
+                for pixel in face.pixels.iter() {
+                    assert_close(*pixel, radiance, 1.0e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefilter_roughness_blurs_reflections() {
+        // Bright spot in +Z direction, everything else is black.
+        let size = 16;
+        let mut face = FloatImage::new(size, size);
+        let mut cube_map = CubeMapImage::new(std::array::from_fn(|face_index| {
+            face.pixels.fill([0.0; 4]);
+            if face_index == 4 {
+                for y in 6..10 {
+                    for x in 6..10 {
+                        face.pixels[(y * size + x) as usize] = [10.0, 10.0, 10.0, 1.0];
+                    }
+                }
+            }
+            face.clone()
+        }));
+        cube_map.generate_mips();
+
+        let prefiltered = prefilter_specular(&cube_map, 8, 256);
+        let center = |level: usize| {
+            let faces = &prefiltered.levels[level];
+            let size = faces[4].width;
+            faces[4].pixels[(size / 2 * size + size / 2) as usize][0]
+        };
+        // Mirror reflection keeps the spot, rough reflections spread its energy.
+        assert!(center(0) > 5.0);
+        let last = prefiltered.levels.len() - 1;
+        assert!(center(last) < center(0));
+        assert!(center(last) > 0.0);
+
+        let side = cube_face_direction(0, 4, 4, 8);
+        let side_pixel = prefiltered.sample_level(&side, 0);
+        assert_eq!(side_pixel[0], 0.0);
+    }
+
+    #[test]
+    fn test_brdf_lut() {
+        let lut = compute_brdf_lut(16, 128);
+        assert_eq!(lut.width, 16);
+        assert_eq!(lut.height, 16);
+        for pixel in lut.pixels.iter() {
+            assert!(pixel[0] >= 0.0 && pixel[1] >= 0.0);
+            assert!(pixel[0] + pixel[1] <= 1.05);
+        }
+        // Smooth surface viewed head-on reflects almost all energy.
+        let smooth = lut.pixels[15];
+        assert!(smooth[0] + smooth[1] > 0.9);
+    }
+}
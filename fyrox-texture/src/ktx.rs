@@ -0,0 +1,769 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! KTX2 container support. KTX2 files could store textures either in a plain GPU format (optionally
+//! supercompressed with Zstandard or ZLIB) or in one of Basis Universal formats (ETC1S or UASTC),
+//! that must be transcoded to a GPU format at load time. See [`BasisTranscoder`] docs for more info
+//! about transcoding.
+
+use crate::{TextureError, TextureKind, TexturePixelKind};
+use fyrox_core::{reflect::prelude::*, uuid_provider};
+use ktx2::{Format, SupercompressionScheme};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Magic bytes of every KTX2 file.
+pub const KTX2_MAGIC: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+/// Checks whether the given data is a KTX2 file or not.
+pub fn is_ktx2(data: &[u8]) -> bool {
+    data.starts_with(&KTX2_MAGIC)
+}
+
+/// A GPU format to which Basis Universal textures are transcoded at load time.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum TranscodingTarget {
+    /// The format is selected automatically depending on the compressed formats supported by the
+    /// graphics server: [`Self::Bc3`] if S3TC is supported, [`Self::Bc7`] if BPTC is supported and
+    /// [`Self::Rgba8`] otherwise. See [`TranscodingTarget::resolve`] for more info.
+    #[default]
+    Auto,
+    /// Uncompressed RGBA, each component by 1 byte. It is supported everywhere, but consumes the
+    /// largest amount of memory.
+    Rgba8,
+    /// BC1 (DXT1) block compression with 1-bit alpha.
+    Bc1,
+    /// BC3 (DXT5) block compression with full alpha.
+    Bc3,
+    /// BC7 block compression. It has the best quality among block-compressed formats, but it
+    /// requires hardware support of BPTC formats.
+    Bc7,
+}
+
+uuid_provider!(TranscodingTarget = "5c0a9e39-8f2f-4cf5-9d2c-bf7c3c5a7a61");
+
+/// A set of block-compressed texture formats supported by a graphics server. It is used to select
+/// the format of transcoded textures, see [`TranscodingTarget::Auto`]. Nothing is supported by
+/// default, until the actual values are taken from a graphics server.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct SupportedCompressedFormats {
+    /// S3TC formats (BC1, BC2, BC3).
+    pub s3tc: bool,
+    /// BPTC formats (BC6H, BC7).
+    pub bptc: bool,
+}
+
+impl TranscodingTarget {
+    /// Resolves [`Self::Auto`] to an actual format, that is supported by a graphics server.
+    pub fn resolve(self, supported_formats: SupportedCompressedFormats) -> Self {
+        match self {
+            Self::Auto => {
+                if supported_formats.s3tc {
+                    Self::Bc3
+                } else if supported_formats.bptc {
+                    Self::Bc7
+                } else {
+                    Self::Rgba8
+                }
+            }
+            _ => self,
+        }
+    }
+
+    /// Returns pixel kind of the transcoded texture. [`Self::Auto`] must be resolved first, otherwise
+    /// [`TexturePixelKind::RGBA8`] is returned.
+    pub fn pixel_kind(self) -> TexturePixelKind {
+        match self {
+            Self::Auto | Self::Rgba8 => TexturePixelKind::RGBA8,
+            Self::Bc1 => TexturePixelKind::DXT1RGBA,
+            Self::Bc3 => TexturePixelKind::DXT5RGBA,
+            Self::Bc7 => TexturePixelKind::BC7,
+        }
+    }
+}
+
+/// Kind of Basis Universal payload of a KTX2 file.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BasisPayload {
+    /// ETC1S payload supercompressed with BasisLZ. It has lower quality, but the smallest size.
+    Etc1s,
+    /// UASTC payload (optionally supercompressed with Zstandard). It has much higher quality than
+    /// ETC1S, but larger size.
+    Uastc,
+}
+
+/// Describes a single image of a KTX2 file that must be transcoded.
+pub struct TranscodingRequest<'a> {
+    /// The entire content of a KTX2 file.
+    pub file: &'a [u8],
+    /// Kind of the payload.
+    pub payload: BasisPayload,
+    /// Index of a mip level to transcode.
+    pub level: u32,
+    /// Index of a cube map face to transcode (zero for non-cube textures).
+    pub face: u32,
+    /// Width of the mip level in pixels.
+    pub width: u32,
+    /// Height of the mip level in pixels.
+    pub height: u32,
+    /// Desired GPU format. It is never [`TranscodingTarget::Auto`].
+    pub target: TranscodingTarget,
+}
+
+/// Basis Universal transcoder. The engine uses [`crate::basis::BuiltInBasisTranscoder`] by default,
+/// which supports ETC1S payload only. A different transcoder (for example, the one that uses
+/// `basis-universal` crate to support UASTC as well) could be plugged in by implementing this trait
+/// and assigning it to [`crate::loader::TextureLoader::basis_transcoder`]. KTX2 files without Basis
+/// Universal payload do not require a transcoder.
+pub trait BasisTranscoder: Send + Sync {
+    /// Transcodes a single image described by the request. The result must contain the image in the
+    /// requested format, block-compressed formats must be padded to 4x4 blocks.
+    fn transcode(&self, request: &TranscodingRequest) -> Result<Vec<u8>, String>;
+}
+
+/// Content of a KTX2 file converted to a form that could be used to create a texture.
+pub struct Ktx2Image {
+    /// Kind of the texture.
+    pub kind: TextureKind,
+    /// Pixel kind of the texture.
+    pub pixel_kind: TexturePixelKind,
+    /// Amount of mip levels.
+    pub mip_count: u32,
+    /// Data of every mip level, starting from the largest one. Each level contains every face of
+    /// a cube map in the order: +X, -X, +Y, -Y, +Z, -Z.
+    pub bytes: Vec<u8>,
+}
+
+fn convert_format(format: Format) -> Option<TexturePixelKind> {
+    let pixel_kind = match format {
+        Format::R8_UNORM | Format::R8_SRGB => TexturePixelKind::R8,
+        Format::R8G8_UNORM => TexturePixelKind::RG8,
+        Format::R8G8B8_UNORM | Format::R8G8B8_SRGB => TexturePixelKind::RGB8,
+        Format::R8G8B8A8_UNORM | Format::R8G8B8A8_SRGB => TexturePixelKind::RGBA8,
+        Format::B8G8R8A8_UNORM | Format::B8G8R8A8_SRGB => TexturePixelKind::BGRA8,
+        Format::R16_UNORM => TexturePixelKind::R16,
+        Format::R16G16_UNORM => TexturePixelKind::RG16,
+        Format::R16G16B16_UNORM => TexturePixelKind::RGB16,
+        Format::R16G16B16A16_UNORM => TexturePixelKind::RGBA16,
+        Format::R16_SFLOAT => TexturePixelKind::R16F,
+        Format::R16G16B16_SFLOAT => TexturePixelKind::RGB16F,
+        Format::R16G16B16A16_SFLOAT => TexturePixelKind::RGBA16F,
+        Format::R32_SFLOAT => TexturePixelKind::R32F,
+        Format::R32G32B32_SFLOAT => TexturePixelKind::RGB32F,
+        Format::R32G32B32A32_SFLOAT => TexturePixelKind::RGBA32F,
+        Format::BC1_RGB_UNORM_BLOCK | Format::BC1_RGB_SRGB_BLOCK => TexturePixelKind::DXT1RGB,
+        Format::BC1_RGBA_UNORM_BLOCK | Format::BC1_RGBA_SRGB_BLOCK => TexturePixelKind::DXT1RGBA,
+        Format::BC2_UNORM_BLOCK | Format::BC2_SRGB_BLOCK => TexturePixelKind::DXT3RGBA,
+        Format::BC3_UNORM_BLOCK | Format::BC3_SRGB_BLOCK => TexturePixelKind::DXT5RGBA,
+        Format::BC4_UNORM_BLOCK => TexturePixelKind::R8RGTC,
+        Format::BC5_UNORM_BLOCK => TexturePixelKind::RG8RGTC,
+        Format::BC6H_UFLOAT_BLOCK => TexturePixelKind::BC6H,
+        Format::BC7_UNORM_BLOCK | Format::BC7_SRGB_BLOCK => TexturePixelKind::BC7,
+        _ => return None,
+    };
+    Some(pixel_kind)
+}
+
+fn decompress_level(
+    data: &[u8],
+    scheme: Option<SupercompressionScheme>,
+    uncompressed_length: usize,
+) -> Result<Vec<u8>, TextureError> {
+    let mut bytes = Vec::with_capacity(uncompressed_length);
+    match scheme {
+        None => bytes.extend_from_slice(data),
+        Some(SupercompressionScheme::Zstandard) => {
+            ruzstd::decoding::StreamingDecoder::new(data)
+                .map_err(|err| TextureError::Ktx2(err.to_string()))?
+                .read_to_end(&mut bytes)?;
+        }
+        Some(SupercompressionScheme::ZLIB) => {
+            flate2::read::ZlibDecoder::new(data).read_to_end(&mut bytes)?;
+        }
+        Some(scheme) => {
+            return Err(TextureError::Ktx2(format!(
+                "Unsupported supercompression scheme {scheme:?}!"
+            )))
+        }
+    }
+    Ok(bytes)
+}
+
+/// Reads a KTX2 file and converts it to a form that could be used to create a texture. Basis
+/// Universal payloads are transcoded using the given transcoder to the given target format (see
+/// [`TranscodingTarget::resolve`]), such files cannot be loaded if there is no transcoder. Array
+/// textures are not supported.
+pub fn read_ktx2(
+    data: &[u8],
+    target: TranscodingTarget,
+    supported_formats: SupportedCompressedFormats,
+    transcoder: Option<&dyn BasisTranscoder>,
+) -> Result<Ktx2Image, TextureError> {
+    let reader = ktx2::Reader::new(data).map_err(|err| TextureError::Ktx2(err.to_string()))?;
+    let header = reader.header();
+
+    if header.layer_count > 1 {
+        return Err(TextureError::Ktx2(
+            "Array textures are not supported!".to_string(),
+        ));
+    }
+
+    let width = header.pixel_width;
+    let height = header.pixel_height.max(1);
+    let kind = if header.face_count == 6 {
+        TextureKind::Cube { width, height }
+    } else if header.pixel_depth > 0 {
+        TextureKind::Volume {
+            width,
+            height,
+            depth: header.pixel_depth,
+        }
+    } else if header.pixel_height == 0 {
+        TextureKind::Line { length: width }
+    } else {
+        TextureKind::Rectangle { width, height }
+    };
+
+    let mip_count = header.level_count.max(1);
+
+    let basis_payload = match (header.format, header.supercompression_scheme) {
+        (_, Some(SupercompressionScheme::BasisLZ)) => Some(BasisPayload::Etc1s),
+        (None, _) => Some(BasisPayload::Uastc),
+        _ => None,
+    };
+
+    let mut bytes = Vec::new();
+
+    let pixel_kind = if let Some(payload) = basis_payload {
+        let transcoder = transcoder.ok_or_else(|| {
+            TextureError::Transcoding(
+                "Unable to load Basis Universal texture, because there's no transcoder!"
+                    .to_string(),
+            )
+        })?;
+        let target = target.resolve(supported_formats);
+        for level in 0..mip_count {
+            for face in 0..header.face_count {
+                let request = TranscodingRequest {
+                    file: data,
+                    payload,
+                    level,
+                    face,
+                    width: (width >> level).max(1),
+                    height: (height >> level).max(1),
+                    target,
+                };
+                bytes.extend(
+                    transcoder
+                        .transcode(&request)
+                        .map_err(TextureError::Transcoding)?,
+                );
+            }
+        }
+        target.pixel_kind()
+    } else {
+        let format = header.format.ok_or(TextureError::UnsupportedFormat)?;
+        let pixel_kind = convert_format(format).ok_or(TextureError::UnsupportedFormat)?;
+        for level in reader.levels().take(mip_count as usize) {
+            bytes.extend(decompress_level(
+                level.data,
+                header.supercompression_scheme,
+                level.uncompressed_byte_length as usize,
+            )?);
+        }
+        pixel_kind
+    };
+
+    Ok(Ktx2Image {
+        kind,
+        pixel_kind,
+        mip_count,
+        bytes,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        basis::BuiltInBasisTranscoder,
+        ktx::{
+            read_ktx2, BasisPayload, BasisTranscoder, SupportedCompressedFormats,
+            TranscodingRequest, TranscodingTarget,
+        },
+        TextureError, TextureKind, TexturePixelKind,
+    };
+    use ktx2::{Format, Header, Index, LevelIndex, SupercompressionScheme};
+    use std::io::Write;
+
+    struct Fixture {
+        format: Option<Format>,
+        scheme: Option<SupercompressionScheme>,
+        width: u32,
+        height: u32,
+        global_data: Vec<u8>,
+        // Stored data and uncompressed length of each level.
+        levels: Vec<(Vec<u8>, usize)>,
+    }
+
+    impl Fixture {
+        fn into_bytes(self) -> Vec<u8> {
+            let level_index_offset = Header::LENGTH;
+            let dfd_offset = level_index_offset + self.levels.len() * LevelIndex::LENGTH;
+            // The smallest possible data format descriptor, that contains its size only.
+            let dfd = 4u32.to_le_bytes();
+            let sgd_offset = dfd_offset + dfd.len();
+            let mut level_offset = sgd_offset + self.global_data.len();
+
+            let header = Header {
+                format: self.format,
+                type_size: 1,
+                pixel_width: self.width,
+                pixel_height: self.height,
+                pixel_depth: 0,
+                layer_count: 0,
+                face_count: 1,
+                level_count: self.levels.len() as u32,
+                supercompression_scheme: self.scheme,
+                index: Index {
+                    dfd_byte_offset: dfd_offset as u32,
+                    dfd_byte_length: dfd.len() as u32,
+                    kvd_byte_offset: 0,
+                    kvd_byte_length: 0,
+                    sgd_byte_offset: if self.global_data.is_empty() {
+                        0
+                    } else {
+                        sgd_offset as u64
+                    },
+                    sgd_byte_length: self.global_data.len() as u64,
+                },
+            };
+
+            let mut bytes = header.as_bytes().to_vec();
+            for (data, uncompressed_length) in self.levels.iter() {
+                let level = LevelIndex {
+                    byte_offset: level_offset as u64,
+                    byte_length: data.len() as u64,
+                    uncompressed_byte_length: *uncompressed_length as u64,
+                };
+                bytes.extend_from_slice(&level.as_bytes());
+                level_offset += data.len();
+            }
+            bytes.extend_from_slice(&dfd);
+            bytes.extend_from_slice(&self.global_data);
+            for (data, _) in self.levels.iter() {
+                bytes.extend_from_slice(data);
+            }
+            bytes
+        }
+    }
+
+    fn plain_fixture(
+        scheme: Option<SupercompressionScheme>,
+        compress: impl Fn(&[u8]) -> Vec<u8>,
+    ) -> (Vec<u8>, Vec<u8>) {
+        let level0 = (0..4 * 4 * 4).map(|i| i as u8).collect::<Vec<_>>();
+        let level1 = (0..2 * 2 * 4).map(|i| 200 - i as u8).collect::<Vec<_>>();
+        let file = Fixture {
+            format: Some(Format::R8G8B8A8_UNORM),
+            scheme,
+            width: 4,
+            height: 4,
+            global_data: Default::default(),
+            levels: vec![
+                (compress(&level0), level0.len()),
+                (compress(&level1), level1.len()),
+            ],
+        }
+        .into_bytes();
+        (file, [level0, level1].concat())
+    }
+
+    fn check_plain_fixture(scheme: Option<SupercompressionScheme>, compress: fn(&[u8]) -> Vec<u8>) {
+        let (file, expected) = plain_fixture(scheme, compress);
+        assert!(super::is_ktx2(&file));
+        let image = read_ktx2(&file, Default::default(), Default::default(), None).unwrap();
+        assert_eq!(image.pixel_kind, TexturePixelKind::RGBA8);
+        assert_eq!(image.mip_count, 2);
+        assert!(matches!(
+            image.kind,
+            TextureKind::Rectangle {
+                width: 4,
+                height: 4
+            }
+        ));
+        assert_eq!(image.bytes, expected);
+    }
+
+    #[test]
+    fn test_read_ktx2_without_supercompression() {
+        check_plain_fixture(None, |data| data.to_vec());
+    }
+
+    #[test]
+    fn test_read_ktx2_zstandard() {
+        check_plain_fixture(Some(SupercompressionScheme::Zstandard), |data| {
+            ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest)
+        });
+    }
+
+    #[test]
+    fn test_read_ktx2_zlib() {
+        check_plain_fixture(Some(SupercompressionScheme::ZLIB), |data| {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        });
+    }
+
+    /// Writes bits starting from the least significant bit of each byte, just like Basis Universal
+    /// encoders do.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        bit_count: usize,
+    }
+
+    impl BitWriter {
+        fn write(&mut self, value: u32, count: u32) {
+            for i in 0..count {
+                if self.bit_count % 8 == 0 {
+                    self.bytes.push(0);
+                }
+                let bit = ((value >> i) & 1) as u8;
+                *self.bytes.last_mut().unwrap() |= bit << (self.bit_count % 8);
+                self.bit_count += 1;
+            }
+        }
+
+        // Huffman codes are written starting from the most significant bit.
+        fn write_code(&mut self, code: u32, size: u32) {
+            for i in (0..size).rev() {
+                self.write((code >> i) & 1, 1);
+            }
+        }
+
+        // Writes a Huffman table, in which every symbol has the same code size. In this case the
+        // code of each symbol is equal to the symbol itself.
+        fn write_flat_huffman_table(&mut self, symbol_count: u32, code_size: u32) {
+            self.write(symbol_count, 14);
+            // Every code length code is 5 bits long, so code length `n` is written as `n`.
+            self.write(21, 5);
+            for _ in 0..21 {
+                self.write(5, 3);
+            }
+            for _ in 0..symbol_count {
+                self.write_code(code_size, 5);
+            }
+        }
+    }
+
+    struct Etc1sEndpoint {
+        color: [u32; 3],
+        intensity: u32,
+    }
+
+    const ENDPOINTS: [Etc1sEndpoint; 3] = [
+        Etc1sEndpoint {
+            color: [31, 0, 0],
+            intensity: 0,
+        },
+        Etc1sEndpoint {
+            color: [0, 20, 31],
+            intensity: 3,
+        },
+        Etc1sEndpoint {
+            color: [10, 10, 10],
+            intensity: 7,
+        },
+    ];
+
+    fn selector_rows(selector: usize) -> [u32; 4] {
+        std::array::from_fn(|y| {
+            (0..4)
+                .map(|x| (((x + y) * selector) as u32 & 3) << (x * 2))
+                .sum()
+        })
+    }
+
+    const SELECTOR_COUNT: usize = 3;
+    const HISTORY_SIZE: u32 = 4;
+
+    fn etc1s_pixel(endpoint: usize, selector: usize, x: usize, y: usize) -> [u8; 3] {
+        const TABLES: [[i32; 4]; 8] = [
+            [-8, -2, 2, 8],
+            [-17, -5, 5, 17],
+            [-29, -9, 9, 29],
+            [-42, -13, 13, 42],
+            [-60, -18, 18, 60],
+            [-80, -24, 24, 80],
+            [-106, -33, 33, 106],
+            [-183, -47, 47, 183],
+        ];
+        let endpoint = &ENDPOINTS[endpoint];
+        let index = (selector_rows(selector)[y] >> (x * 2)) & 3;
+        endpoint.color.map(|c| {
+            let base = ((c << 3) | (c >> 2)) as i32;
+            (base + TABLES[endpoint.intensity as usize][index as usize]).clamp(0, 255) as u8
+        })
+    }
+
+    // Encodes a slice, in which each block uses raw endpoint index and raw selector index.
+    fn write_etc1s_slice(blocks: &[(usize, usize)], blocks_x: usize) -> Vec<u8> {
+        let mut writer = BitWriter::default();
+        let mut previous_endpoint = 0;
+        for (i, &(endpoint, selector)) in blocks.iter().enumerate() {
+            let (x, y) = (i % blocks_x, i / blocks_x);
+            if x % 2 == 0 && y % 2 == 0 {
+                // All four blocks of the 2x2 group use raw endpoint indices.
+                writer.write_code(0xFF, 9);
+            }
+            let delta = (endpoint + ENDPOINTS.len() - previous_endpoint) % ENDPOINTS.len();
+            writer.write_code(delta as u32, 2);
+            previous_endpoint = endpoint;
+            writer.write_code(selector as u32, 3);
+        }
+        writer.bytes
+    }
+
+    fn etc1s_fixture() -> Vec<u8> {
+        let mut endpoints = BitWriter::default();
+        for _ in 0..3 {
+            endpoints.write_flat_huffman_table(32, 5);
+        }
+        endpoints.write_flat_huffman_table(8, 3);
+        // Not grayscale.
+        endpoints.write(0, 1);
+        let mut previous_color = [16; 3];
+        let mut previous_intensity = 0;
+        for endpoint in ENDPOINTS.iter() {
+            endpoints.write_code((endpoint.intensity + 8 - previous_intensity) & 7, 3);
+            previous_intensity = endpoint.intensity;
+            for (previous, color) in previous_color.iter_mut().zip(endpoint.color) {
+                endpoints.write_code((color + 32 - *previous) & 31, 5);
+                *previous = color;
+            }
+        }
+
+        let mut selectors = BitWriter::default();
+        // No global codebook, no hybrid codebook, raw selectors.
+        selectors.write(0, 1);
+        selectors.write(0, 1);
+        selectors.write(1, 1);
+        for selector in 0..SELECTOR_COUNT {
+            for row in selector_rows(selector) {
+                selectors.write(row, 8);
+            }
+        }
+
+        let mut tables = BitWriter::default();
+        tables.write_flat_huffman_table(257, 9);
+        tables.write_flat_huffman_table(ENDPOINTS.len() as u32, 2);
+        tables.write_flat_huffman_table(SELECTOR_COUNT as u32 + HISTORY_SIZE + 1, 3);
+        tables.write_flat_huffman_table(64, 6);
+        tables.write(HISTORY_SIZE, 13);
+
+        // Level 0 is 8x8 (2x2 blocks) with alpha, level 1 is 4x4 (single block) without alpha.
+        let level0_rgb = write_etc1s_slice(&[(0, 0), (1, 1), (1, 2), (0, 1)], 2);
+        let level0_alpha = write_etc1s_slice(&[(2, 0), (2, 1), (2, 2), (2, 0)], 2);
+        let level1_rgb = write_etc1s_slice(&[(1, 2)], 1);
+
+        let image_descs = [
+            [
+                0,
+                0,
+                level0_rgb.len() as u32,
+                level0_rgb.len() as u32,
+                level0_alpha.len() as u32,
+            ],
+            [0, 0, level1_rgb.len() as u32, 0, 0],
+        ];
+
+        let mut global_data = Vec::new();
+        global_data.extend_from_slice(&(ENDPOINTS.len() as u16).to_le_bytes());
+        global_data.extend_from_slice(&(SELECTOR_COUNT as u16).to_le_bytes());
+        global_data.extend_from_slice(&(endpoints.bytes.len() as u32).to_le_bytes());
+        global_data.extend_from_slice(&(selectors.bytes.len() as u32).to_le_bytes());
+        global_data.extend_from_slice(&(tables.bytes.len() as u32).to_le_bytes());
+        global_data.extend_from_slice(&0u32.to_le_bytes());
+        for desc in image_descs.iter().flatten() {
+            global_data.extend_from_slice(&desc.to_le_bytes());
+        }
+        global_data.extend_from_slice(&endpoints.bytes);
+        global_data.extend_from_slice(&selectors.bytes);
+        global_data.extend_from_slice(&tables.bytes);
+
+        Fixture {
+            format: None,
+            scheme: Some(SupercompressionScheme::BasisLZ),
+            width: 8,
+            height: 8,
+            global_data,
+            levels: vec![([level0_rgb, level0_alpha].concat(), 0), (level1_rgb, 0)],
+        }
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_read_ktx2_basis_lz() {
+        let file = etc1s_fixture();
+        let image = read_ktx2(
+            &file,
+            TranscodingTarget::Rgba8,
+            Default::default(),
+            Some(&BuiltInBasisTranscoder),
+        )
+        .unwrap();
+        assert_eq!(image.pixel_kind, TexturePixelKind::RGBA8);
+        assert_eq!(image.mip_count, 2);
+        assert_eq!(image.bytes.len(), (8 * 8 + 4 * 4) * 4);
+
+        let level0_blocks = [(0, 0), (1, 1), (1, 2), (0, 1)];
+        let level0_alpha = [(2, 0), (2, 1), (2, 2), (2, 0)];
+        for y in 0..8 {
+            for x in 0..8 {
+                let block = (y / 4) * 2 + x / 4;
+                let (endpoint, selector) = level0_blocks[block];
+                let rgb = etc1s_pixel(endpoint, selector, x % 4, y % 4);
+                let (endpoint, selector) = level0_alpha[block];
+                let alpha = etc1s_pixel(endpoint, selector, x % 4, y % 4)[1];
+                let offset = (y * 8 + x) * 4;
+                assert_eq!(
+                    image.bytes[offset..offset + 4],
+                    [rgb[0], rgb[1], rgb[2], alpha]
+                );
+            }
+        }
+        let level1 = &image.bytes[8 * 8 * 4..];
+        for y in 0..4 {
+            for x in 0..4 {
+                let rgb = etc1s_pixel(1, 2, x, y);
+                let offset = (y * 4 + x) * 4;
+                assert_eq!(level1[offset..offset + 4], [rgb[0], rgb[1], rgb[2], 255]);
+            }
+        }
+
+        // Auto target must be resolved to a format supported by a graphics server.
+        let compressed = read_ktx2(
+            &file,
+            TranscodingTarget::Auto,
+            SupportedCompressedFormats {
+                s3tc: true,
+                bptc: false,
+            },
+            Some(&BuiltInBasisTranscoder),
+        )
+        .unwrap();
+        assert_eq!(compressed.pixel_kind, TexturePixelKind::DXT5RGBA);
+        assert_eq!(compressed.bytes.len(), (4 + 1) * 16);
+
+        assert!(matches!(
+            read_ktx2(&file, Default::default(), Default::default(), None),
+            Err(TextureError::Transcoding(_))
+        ));
+    }
+
+    struct SolidTranscoder;
+
+    impl BasisTranscoder for SolidTranscoder {
+        fn transcode(&self, request: &TranscodingRequest) -> Result<Vec<u8>, String> {
+            assert_eq!(request.payload, BasisPayload::Uastc);
+            assert_eq!(request.target, TranscodingTarget::Bc7);
+            let blocks = request.width.div_ceil(4) * request.height.div_ceil(4);
+            Ok(vec![request.level as u8; blocks as usize * 16])
+        }
+    }
+
+    #[test]
+    fn test_read_ktx2_uastc() {
+        let file = Fixture {
+            format: None,
+            scheme: None,
+            width: 8,
+            height: 4,
+            global_data: Default::default(),
+            levels: vec![(vec![0; 2 * 16], 2 * 16), (vec![0; 16], 16)],
+        }
+        .into_bytes();
+
+        let image = read_ktx2(
+            &file,
+            TranscodingTarget::Auto,
+            SupportedCompressedFormats {
+                s3tc: false,
+                bptc: true,
+            },
+            Some(&SolidTranscoder),
+        )
+        .unwrap();
+        assert_eq!(image.pixel_kind, TexturePixelKind::BC7);
+        assert_eq!(image.bytes, [vec![0; 2 * 16], vec![1; 16]].concat());
+
+        // The built-in transcoder supports ETC1S only.
+        assert!(matches!(
+            read_ktx2(
+                &file,
+                TranscodingTarget::Rgba8,
+                Default::default(),
+                Some(&BuiltInBasisTranscoder)
+            ),
+            Err(TextureError::Transcoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_transcoding_target_resolve() {
+        let none = SupportedCompressedFormats::default();
+        let bptc = SupportedCompressedFormats {
+            s3tc: false,
+            bptc: true,
+        };
+        let all = SupportedCompressedFormats {
+            s3tc: true,
+            bptc: true,
+        };
+        assert_eq!(
+            TranscodingTarget::Auto.resolve(none),
+            TranscodingTarget::Rgba8
+        );
+        assert_eq!(
+            TranscodingTarget::Auto.resolve(bptc),
+            TranscodingTarget::Bc7
+        );
+        assert_eq!(TranscodingTarget::Auto.resolve(all), TranscodingTarget::Bc3);
+        assert_eq!(TranscodingTarget::Bc1.resolve(none), TranscodingTarget::Bc1);
+    }
+}
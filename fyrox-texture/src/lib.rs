@@ -27,11 +27,32 @@
 //! ## Supported formats
 //!
 //! To load images and decode them, Fyrox uses image and ddsfile crates. Here is the list of
-//! supported formats: png, tga, bmp, dds, jpg, gif, tiff, dds.
+//! supported formats: png, tga, bmp, dds, jpg, gif, tiff, dds, hdr, exr.
+//!
+//! ## HDR textures
+//!
+//! Radiance HDR (`.hdr`) and OpenEXR (`.exr`) images are loaded into floating-point pixel formats.
+//! Precision of such textures could be defined by [`FloatPrecision`] in import options. HDR images
+//! are usually used as equirectangular panoramas for environment maps, such panoramas could be
+//! converted to cube maps at load time, see [`CubeMapConversion`] for more info. Converted cube maps
+//! could be prefiltered for image-based lighting (diffuse irradiance or specular reflections), see
+//! [`EnvironmentMapFilter`] and [`ibl`] module for more info.
 //!
 //! ## Compressed textures
 //!
-//! Fyrox supports most commonly used formats of compressed textures: DXT1, DXT3, DXT5.
+//! Fyrox supports most commonly used formats of compressed textures: DXT1, DXT3, DXT5, BC4, BC5,
+//! BC6H, BC7. Uncompressed images could be compressed at load time, see [`CompressionOptions`] for
+//! more info.
+//!
+//! ## KTX2
+//!
+//! KTX2 files could contain textures either in one of supported GPU formats or in Basis Universal
+//! format, which is transcoded at load time. See [`ktx`] module docs for more info.
+//!
+//! ## Procedural textures
+//!
+//! Textures could be generated at load time from noise and simple math operations described in a
+//! small RON file. See [`procedural`] module docs for more info.
 //!
 //! ## Render target
 //!
@@ -40,6 +61,12 @@
 //! will automatically provide you info about metrics of texture, but it won't give you
 //! access to pixels of render target.
 
+use crate::{
+    basis::BuiltInBasisTranscoder,
+    cube::{CubeMapImage, FloatImage},
+    ktx::{BasisTranscoder, SupportedCompressedFormats, TranscodingTarget},
+    mip::MipContent,
+};
 use ddsfile::{Caps2, D3DFormat};
 use fast_image_resize as fr;
 use fast_image_resize::ResizeOptions;
@@ -61,6 +88,7 @@ use fyrox_resource::{
     embedded_data_source, io::ResourceIo, manager::BuiltInResource, options::ImportOptions,
     untyped::ResourceKind, Resource, ResourceData, TEXTURE_RESOURCE_UUID,
 };
+use half::f16;
 use image::{ColorType, DynamicImage, ImageError, ImageFormat, Pixel};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
@@ -74,7 +102,14 @@ use std::{
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+pub mod basis;
+pub mod bc;
+pub mod cube;
+pub mod ibl;
+pub mod ktx;
 pub mod loader;
+pub mod mip;
+pub mod procedural;
 
 /// Texture kind.
 #[derive(Copy, Clone, Debug, Reflect)]
@@ -321,9 +356,12 @@ impl ResourceData for Texture {
             | TexturePixelKind::DXT5RGBA
             | TexturePixelKind::R8RGTC
             | TexturePixelKind::RG8RGTC
+            | TexturePixelKind::BC6H
+            | TexturePixelKind::BC7
             | TexturePixelKind::BGR8
             | TexturePixelKind::BGRA8
             | TexturePixelKind::RGB16F
+            | TexturePixelKind::RGBA16F
             | TexturePixelKind::R32F
             | TexturePixelKind::R16F => return Err(Box::new(TextureError::UnsupportedFormat)),
         };
@@ -446,6 +484,110 @@ impl MipFilter {
     }
 }
 
+/// Precision of floating-point textures, such as HDR or EXR images. It has no effect on textures
+/// with integer pixel formats.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum FloatPrecision {
+    /// Each color component is stored as 32-bit floating-point number. Pixel format of a texture
+    /// will be either [`TexturePixelKind::RGB32F`] or [`TexturePixelKind::RGBA32F`].
+    #[default]
+    Full,
+    /// Each color component is stored as 16-bit half-precision floating-point number. Such textures
+    /// consume two times less memory than full-precision ones, which is enough for most environment
+    /// maps. Pixel format of a texture will be either [`TexturePixelKind::RGB16F`] or
+    /// [`TexturePixelKind::RGBA16F`].
+    Half,
+}
+
+uuid_provider!(FloatPrecision = "b3c3c4a5-1f0e-4d8b-9a41-62f8f7b1e0d2");
+
+/// Defines whether a texture should be converted to a cube map at load time.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum CubeMapConversion {
+    /// A texture is loaded as is.
+    #[default]
+    None,
+    /// A texture is treated as an equirectangular (latitude-longitude) panorama and converted to
+    /// a cube map with the given size of each face. Zero face size means that the size will be
+    /// calculated automatically (see [`cube::default_face_size`]). Such conversion is typically
+    /// used for HDR environment maps. Textures with integer pixel formats are converted to
+    /// [`TexturePixelKind::RGBA8`], floating-point ones - to either [`TexturePixelKind::RGBA32F`]
+    /// or [`TexturePixelKind::RGBA16F`], depending on [`FloatPrecision`]. Compression is not
+    /// applied to converted textures.
+    Equirectangular {
+        /// Width and height of each face of the cube map.
+        face_size: u32,
+    },
+}
+
+uuid_provider!(CubeMapConversion = "7d0f2f4e-45a8-4f4b-a0a3-8f9b5b1b6c3e");
+
+/// Defines how a cube map, converted from an equirectangular panorama, should be filtered for
+/// image-based lighting. It is used only when [`CubeMapConversion::Equirectangular`] is set.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum EnvironmentMapFilter {
+    /// A cube map is used as is. Suitable for sky boxes.
+    #[default]
+    None,
+    /// A cube map is convolved with cosine lobe, which gives diffuse irradiance for every
+    /// direction. The result is divided by PI, so it could be directly multiplied by albedo.
+    /// Irradiance is a very low-frequency signal, so the face size could be very small (16 or
+    /// 32 pixels is enough in most cases).
+    Irradiance {
+        /// Width and height of each face of the irradiance map.
+        face_size: u32,
+    },
+    /// A cube map is prefiltered for specular reflections using GGX distribution. Each mip level
+    /// of the resulting cube map corresponds to specific roughness: `level / (mip_count - 1)`.
+    /// Mip-mapping is always enabled for such cube maps. See [`ibl::prefilter_specular`] for more
+    /// info.
+    Specular {
+        /// Amount of samples per texel. Larger values reduce noise, but increase loading time.
+        sample_count: u32,
+    },
+}
+
+uuid_provider!(EnvironmentMapFilter = "e2b1f7a4-5c9d-4f0e-8d1b-3a6c9e4f2b70");
+
 /// Allows you to define a set of parameters for a texture resource.
 ///
 /// # Details
@@ -493,6 +635,18 @@ pub struct TextureImportOptions {
     pub(crate) max_lod: f32,
     #[serde(default)]
     pub(crate) lod_bias: f32,
+    #[serde(default)]
+    pub(crate) float_precision: FloatPrecision,
+    #[serde(default)]
+    pub(crate) cube_map_conversion: CubeMapConversion,
+    #[serde(default)]
+    pub(crate) transcoding_target: TranscodingTarget,
+    #[serde(default)]
+    pub(crate) environment_filter: EnvironmentMapFilter,
+    #[serde(default)]
+    pub(crate) mip_content: MipContent,
+    #[serde(default)]
+    pub(crate) gamma_correct_mips: bool,
 }
 
 fn default_max_level() -> usize {
@@ -524,6 +678,12 @@ impl Default for TextureImportOptions {
             min_lod: default_min_lod(),
             max_lod: default_max_lod(),
             lod_bias: 0.0,
+            float_precision: Default::default(),
+            cube_map_conversion: Default::default(),
+            transcoding_target: Default::default(),
+            environment_filter: Default::default(),
+            mip_content: Default::default(),
+            gamma_correct_mips: false,
         }
     }
 }
@@ -667,6 +827,78 @@ impl TextureImportOptions {
     pub fn set_lod_bias(&mut self, lod_bias: f32) {
         self.lod_bias = lod_bias;
     }
+
+    /// Sets desired precision of floating-point textures.
+    pub fn with_float_precision(mut self, float_precision: FloatPrecision) -> Self {
+        self.float_precision = float_precision;
+        self
+    }
+
+    /// Sets desired precision of floating-point textures.
+    pub fn set_float_precision(&mut self, float_precision: FloatPrecision) {
+        self.float_precision = float_precision;
+    }
+
+    /// Sets desired cube map conversion mode.
+    pub fn with_cube_map_conversion(mut self, cube_map_conversion: CubeMapConversion) -> Self {
+        self.cube_map_conversion = cube_map_conversion;
+        self
+    }
+
+    /// Sets desired cube map conversion mode.
+    pub fn set_cube_map_conversion(&mut self, cube_map_conversion: CubeMapConversion) {
+        self.cube_map_conversion = cube_map_conversion;
+    }
+
+    /// Sets desired format for Basis Universal textures.
+    pub fn with_transcoding_target(mut self, transcoding_target: TranscodingTarget) -> Self {
+        self.transcoding_target = transcoding_target;
+        self
+    }
+
+    /// Sets desired format for Basis Universal textures.
+    pub fn set_transcoding_target(&mut self, transcoding_target: TranscodingTarget) {
+        self.transcoding_target = transcoding_target;
+    }
+
+    /// Sets desired filtering of environment maps.
+    pub fn with_environment_filter(mut self, environment_filter: EnvironmentMapFilter) -> Self {
+        self.environment_filter = environment_filter;
+        self
+    }
+
+    /// Sets desired filtering of environment maps.
+    pub fn set_environment_filter(&mut self, environment_filter: EnvironmentMapFilter) {
+        self.environment_filter = environment_filter;
+    }
+
+    /// Sets the content of a texture, that is used for mip-map generation. See [`MipContent`] docs
+    /// for more info.
+    pub fn with_mip_content(mut self, mip_content: MipContent) -> Self {
+        self.mip_content = mip_content;
+        self
+    }
+
+    /// Sets the content of a texture, that is used for mip-map generation. See [`MipContent`] docs
+    /// for more info.
+    pub fn set_mip_content(&mut self, mip_content: MipContent) {
+        self.mip_content = mip_content;
+    }
+
+    /// Defines whether mip levels should be generated in linear color space or not. It should be
+    /// enabled for color textures in sRGB color space (albedo, emission, etc.), otherwise distant
+    /// surfaces will look darker than they should. Works only for RGB8 and RGBA8 images.
+    pub fn with_gamma_correct_mips(mut self, gamma_correct_mips: bool) -> Self {
+        self.gamma_correct_mips = gamma_correct_mips;
+        self
+    }
+
+    /// Defines whether mip levels should be generated in linear color space or not. It should be
+    /// enabled for color textures in sRGB color space (albedo, emission, etc.), otherwise distant
+    /// surfaces will look darker than they should. Works only for RGB8 and RGBA8 images.
+    pub fn set_gamma_correct_mips(&mut self, gamma_correct_mips: bool) {
+        self.gamma_correct_mips = gamma_correct_mips;
+    }
 }
 
 lazy_static! {
@@ -1053,6 +1285,15 @@ pub enum TexturePixelKind {
 
     /// Red component as 2-byte, half-precision float.
     R16F = 24,
+
+    /// Red, green, blue and alpha components, each by 2 byte half-precision float.
+    RGBA16F = 25,
+
+    /// Compressed RGB texture with unsigned half-precision floating-point values (BPTC, BC6H).
+    BC6H = 26,
+
+    /// Compressed RGBA texture (BPTC, BC7).
+    BC7 = 27,
 }
 
 impl TexturePixelKind {
@@ -1083,6 +1324,9 @@ impl TexturePixelKind {
             22 => Ok(Self::RGB16F),
             23 => Ok(Self::R32F),
             24 => Ok(Self::R16F),
+            25 => Ok(Self::RGBA16F),
+            26 => Ok(Self::BC6H),
+            27 => Ok(Self::BC7),
             _ => Err(format!("Invalid texture kind {id}!")),
         }
     }
@@ -1102,7 +1346,7 @@ impl TexturePixelKind {
                 Some(2)
             }
             Self::RGB16 | Self::RGB16F => Some(6),
-            Self::RGBA16 => Some(8),
+            Self::RGBA16 | Self::RGBA16F => Some(8),
             Self::RGB32F => Some(12),
             Self::RGBA32F => Some(16),
             // Pixels of compressed textures consumes less than a byte, so there's no way to express
@@ -1112,7 +1356,9 @@ impl TexturePixelKind {
             | Self::DXT3RGBA
             | Self::DXT5RGBA
             | Self::R8RGTC
-            | Self::RG8RGTC => None,
+            | Self::RG8RGTC
+            | Self::BC6H
+            | Self::BC7 => None,
         }
     }
 }
@@ -1128,6 +1374,12 @@ pub enum TextureError {
     Image(image::ImageError),
     /// An error occurred during file loading.
     FileLoadError(FileError),
+    /// An error occurred during reading of a KTX2 container.
+    Ktx2(String),
+    /// An error occurred during transcoding of a Basis Universal texture.
+    Transcoding(String),
+    /// An error occurred during parsing or baking of a procedural texture.
+    Procedural(String),
 }
 
 impl Display for TextureError {
@@ -1145,6 +1397,15 @@ impl Display for TextureError {
             TextureError::FileLoadError(v) => {
                 write!(f, "A file load error has occurred {v:?}")
             }
+            TextureError::Ktx2(v) => {
+                write!(f, "Unable to read KTX2 container: {v}")
+            }
+            TextureError::Transcoding(v) => {
+                write!(f, "Unable to transcode Basis Universal texture: {v}")
+            }
+            TextureError::Procedural(v) => {
+                write!(f, "Unable to generate procedural texture: {v}")
+            }
         }
     }
 }
@@ -1211,6 +1472,34 @@ pub enum CompressionOptions {
     /// This option is faster than `NoCompression` speed by lower requirements of memory
     /// bandwidth.
     Quality = 2,
+
+    /// An image will be encoded via BC4 (RGTC1) compression if it is not already compressed.
+    /// Only the first channel of an image is preserved, so it is suitable for single-channel masks
+    /// (roughness, metallic, height, etc.).
+    /// Compression ratio is 1:2 for single-channel images, 1:8 for RGBA images.
+    Bc4 = 3,
+
+    /// An image will be encoded via BC5 (RGTC2) compression if it is not already compressed.
+    /// Only the first two channels of an image are preserved, so it is suitable for tangent-space
+    /// normal maps (the blue channel must be reconstructed in a shader).
+    /// Compression ratio is 1:2 for two-channel images, 1:4 for RGBA images.
+    Bc5 = 4,
+
+    /// An image will be encoded via BC6H (unsigned float) compression if it is not already
+    /// compressed. It is applicable only to floating-point (HDR) images, alpha channel is
+    /// discarded and negative values are clamped to zero.
+    /// Compression ratio is 1:6 for RGB half-float images.
+    Bc6h = 5,
+
+    /// An image will be encoded via BC7 compression if it is not already compressed. It gives
+    /// much better quality than DXT formats, especially for images with alpha channel.
+    /// Compression ratio is 1:4 (including alpha).
+    Bc7 = 6,
+
+    /// Compression is selected automatically depending on the pixel format of an image:
+    /// BC4 for single-channel images, BC5 for two-channel images, BC6H for floating-point images
+    /// and BC7 for RGB and RGBA images.
+    Auto = 7,
 }
 
 uuid_provider!(CompressionOptions = "fbdcc081-d0b8-4b62-9925-2de6c013fbf5");
@@ -1263,6 +1552,45 @@ fn compress_rg8_bc4<T: tbc::color::ColorRedGreen8>(
     tbc::encode_image_bc4_rg8_conv_u8::<T>(transmute_slice::<T>(bytes), width, height)
 }
 
+fn extract_channels<const N: usize>(
+    bytes: &[u8],
+    stride: usize,
+    channels: [usize; N],
+) -> Vec<[u8; N]> {
+    bytes
+        .chunks_exact(stride)
+        .map(|pixel| channels.map(|c| pixel[c]))
+        .collect()
+}
+
+fn compress_bc7(bytes: &[u8], stride: usize, width: usize, height: usize) -> Vec<u8> {
+    let pixels = bytes
+        .chunks_exact(stride)
+        .map(|pixel| {
+            [
+                pixel[0],
+                pixel[1],
+                pixel[2],
+                pixel.get(3).cloned().unwrap_or(u8::MAX),
+            ]
+        })
+        .collect::<Vec<_>>();
+    bc::encode_bc7(&pixels, width, height)
+}
+
+fn compress_bc6h(bytes: &[u8], stride: usize, width: usize, height: usize) -> Vec<u8> {
+    let pixels = bytes
+        .chunks_exact(stride * 4)
+        .map(|pixel| {
+            std::array::from_fn(|i| {
+                let c = &pixel[i * 4..i * 4 + 4];
+                f32::from_ne_bytes([c[0], c[1], c[2], c[3]])
+            })
+        })
+        .collect::<Vec<_>>();
+    bc::encode_bc6h(&pixels, width, height)
+}
+
 fn data_hash(data: &[u8]) -> u64 {
     let mut hasher = FxHasher::default();
     data.hash(&mut hasher);
@@ -1307,6 +1635,59 @@ fn try_compress(
             compress_rg8_bc4::<tbc::color::RedGreen8>(bytes, w, h),
             TexturePixelKind::RG8RGTC,
         )),
+        (TexturePixelKind::R8 | TexturePixelKind::Luminance8, CompressionOptions::Bc4)
+        | (TexturePixelKind::R8 | TexturePixelKind::Luminance8, CompressionOptions::Auto) => {
+            try_compress(pixel_kind, bytes, w, h, CompressionOptions::Speed)
+        }
+        (TexturePixelKind::RG8 | TexturePixelKind::LuminanceAlpha8, CompressionOptions::Bc5)
+        | (TexturePixelKind::RG8 | TexturePixelKind::LuminanceAlpha8, CompressionOptions::Auto) => {
+            try_compress(pixel_kind, bytes, w, h, CompressionOptions::Speed)
+        }
+        (TexturePixelKind::RG8 | TexturePixelKind::LuminanceAlpha8, CompressionOptions::Bc4) => {
+            Some((
+                compress_r8_bc4::<tbc::color::Red8>(
+                    &extract_channels(bytes, 2, [0]).concat(),
+                    w,
+                    h,
+                ),
+                TexturePixelKind::R8RGTC,
+            ))
+        }
+        (TexturePixelKind::RGB8 | TexturePixelKind::RGBA8, CompressionOptions::Bc4) => {
+            let stride = pixel_kind.size_in_bytes()?;
+            Some((
+                compress_r8_bc4::<tbc::color::Red8>(
+                    &extract_channels(bytes, stride, [0]).concat(),
+                    w,
+                    h,
+                ),
+                TexturePixelKind::R8RGTC,
+            ))
+        }
+        (TexturePixelKind::RGB8 | TexturePixelKind::RGBA8, CompressionOptions::Bc5) => {
+            let stride = pixel_kind.size_in_bytes()?;
+            Some((
+                compress_rg8_bc4::<tbc::color::RedGreen8>(
+                    &extract_channels(bytes, stride, [0, 1]).concat(),
+                    w,
+                    h,
+                ),
+                TexturePixelKind::RG8RGTC,
+            ))
+        }
+        (TexturePixelKind::RGB8 | TexturePixelKind::RGBA8, CompressionOptions::Bc7)
+        | (TexturePixelKind::RGB8 | TexturePixelKind::RGBA8, CompressionOptions::Auto) => Some((
+            compress_bc7(bytes, pixel_kind.size_in_bytes()?, w, h),
+            TexturePixelKind::BC7,
+        )),
+        (TexturePixelKind::RGB32F, CompressionOptions::Bc6h)
+        | (TexturePixelKind::RGB32F, CompressionOptions::Auto) => {
+            Some((compress_bc6h(bytes, 3, w, h), TexturePixelKind::BC6H))
+        }
+        (TexturePixelKind::RGBA32F, CompressionOptions::Bc6h)
+        | (TexturePixelKind::RGBA32F, CompressionOptions::Auto) => {
+            Some((compress_bc6h(bytes, 4, w, h), TexturePixelKind::BC6H))
+        }
         _ => None,
     }
 }
@@ -1337,7 +1718,7 @@ fn bytes_in_mip_level(kind: TextureKind, pixel_kind: TexturePixelKind, mip: usiz
         | TexturePixelKind::LuminanceAlpha16
         | TexturePixelKind::R32F => 4 * pixel_count,
         TexturePixelKind::RGB16 | TexturePixelKind::RGB16F => 6 * pixel_count,
-        TexturePixelKind::RGBA16 => 8 * pixel_count,
+        TexturePixelKind::RGBA16 | TexturePixelKind::RGBA16F => 8 * pixel_count,
         TexturePixelKind::RGB32F => 12 * pixel_count,
         TexturePixelKind::RGBA32F => 16 * pixel_count,
 
@@ -1347,14 +1728,18 @@ fn bytes_in_mip_level(kind: TextureKind, pixel_kind: TexturePixelKind, mip: usiz
         | TexturePixelKind::DXT3RGBA
         | TexturePixelKind::DXT5RGBA
         | TexturePixelKind::R8RGTC
-        | TexturePixelKind::RG8RGTC => {
+        | TexturePixelKind::RG8RGTC
+        | TexturePixelKind::BC6H
+        | TexturePixelKind::BC7 => {
             let block_size = match pixel_kind {
                 TexturePixelKind::DXT1RGB
                 | TexturePixelKind::DXT1RGBA
                 | TexturePixelKind::R8RGTC => 8,
                 TexturePixelKind::DXT3RGBA
                 | TexturePixelKind::DXT5RGBA
-                | TexturePixelKind::RG8RGTC => 16,
+                | TexturePixelKind::RG8RGTC
+                | TexturePixelKind::BC6H
+                | TexturePixelKind::BC7 => 16,
                 _ => unreachable!(),
             };
             match kind {
@@ -1399,6 +1784,8 @@ fn convert_pixel_type_enum(pixel_kind: TexturePixelKind) -> fr::PixelType {
         TexturePixelKind::RGB16 => fr::PixelType::U16x3,
         TexturePixelKind::RGBA16 => fr::PixelType::U16x4,
         TexturePixelKind::R32F => fr::PixelType::F32,
+        TexturePixelKind::RGB32F => fr::PixelType::F32x3,
+        TexturePixelKind::RGBA32F => fr::PixelType::F32x4,
         _ => unreachable!(),
     }
 }
@@ -1414,6 +1801,28 @@ where
     }
 }
 
+fn float_bytes_to_half(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .chunks_exact(4)
+        .flat_map(|chunk| {
+            let value = f32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            f16::from_f32(value).to_ne_bytes()
+        })
+        .collect()
+}
+
+fn write_float_pixels(pixels: &[[f32; 4]], pixel_kind: TexturePixelKind, bytes: &mut Vec<u8>) {
+    for component in pixels.iter().flatten() {
+        match pixel_kind {
+            TexturePixelKind::RGBA32F => bytes.extend_from_slice(&component.to_ne_bytes()),
+            TexturePixelKind::RGBA16F => {
+                bytes.extend_from_slice(&f16::from_f32(*component).to_ne_bytes())
+            }
+            _ => bytes.push((component.clamp(0.0, 1.0) * 255.0).round() as u8),
+        }
+    }
+}
+
 impl Texture {
     /// Tries to load a texture from given data in one of the following formats: PNG, BMP, TGA, JPG, DDS, GIF. Use
     /// this method if you want to load a texture from embedded data.
@@ -1440,6 +1849,53 @@ impl Texture {
         data: &[u8],
         import_options: TextureImportOptions,
     ) -> Result<Self, TextureError> {
+        Self::load_from_memory_with_transcoder(
+            data,
+            import_options,
+            Some(&BuiltInBasisTranscoder),
+            Default::default(),
+        )
+    }
+
+    /// The same as [`Self::load_from_memory`], but KTX2 textures with Basis Universal payload will be
+    /// transcoded using the given transcoder to one of the given supported formats. See
+    /// [`BasisTranscoder`] and [`TranscodingTarget`] docs for more info.
+    pub fn load_from_memory_with_transcoder(
+        data: &[u8],
+        import_options: TextureImportOptions,
+        transcoder: Option<&dyn BasisTranscoder>,
+        supported_formats: SupportedCompressedFormats,
+    ) -> Result<Self, TextureError> {
+        if ktx::is_ktx2(data) {
+            let image = ktx::read_ktx2(
+                data,
+                import_options.transcoding_target,
+                supported_formats,
+                transcoder,
+            )?;
+            return Ok(Self {
+                pixel_kind: image.pixel_kind,
+                kind: image.kind,
+                modifications_counter: 0,
+                bytes: image.bytes.into(),
+                mip_count: image.mip_count,
+                minification_filter: import_options.minification_filter,
+                magnification_filter: import_options.magnification_filter,
+                s_wrap_mode: import_options.s_wrap_mode,
+                t_wrap_mode: import_options.t_wrap_mode,
+                r_wrap_mode: import_options.r_wrap_mode,
+                base_level: import_options.base_level,
+                max_level: import_options.max_level,
+                min_lod: import_options.min_lod,
+                max_lod: import_options.max_lod,
+                anisotropy: import_options.anisotropy,
+                is_render_target: false,
+                cache_index: Default::default(),
+                lod_bias: import_options.lod_bias,
+                sampler_properties_modifications: 1,
+            });
+        }
+
         // DDS is special. It can contain various kinds of textures as well as textures with
         // various pixel formats.
         //
@@ -1556,6 +2012,16 @@ impl Texture {
                 }
             }
 
+            if let CubeMapConversion::Equirectangular { face_size } =
+                import_options.cube_map_conversion
+            {
+                return Ok(Self::from_equirectangular(
+                    &dyn_img,
+                    face_size,
+                    import_options,
+                ));
+            }
+
             let src_pixel_kind = match dyn_img {
                 DynamicImage::ImageLuma8(_) => TexturePixelKind::Luminance8,
                 DynamicImage::ImageLumaA8(_) => TexturePixelKind::LuminanceAlpha8,
@@ -1576,7 +2042,50 @@ impl Texture {
                 width as usize * height as usize * src_pixel_kind.size_in_bytes().unwrap_or(4),
             );
 
-            if import_options.minification_filter.is_using_mip_mapping() {
+            let mut push_level = |data: &[u8], pixel_kind: TexturePixelKind, w: u32, h: u32| {
+                final_pixel_kind = pixel_kind;
+                if import_options.compression == CompressionOptions::NoCompression {
+                    bytes.extend_from_slice(data)
+                } else if let Some((compressed_data, new_pixel_kind)) = try_compress(
+                    pixel_kind,
+                    data,
+                    w as usize,
+                    h as usize,
+                    import_options.compression,
+                ) {
+                    final_pixel_kind = new_pixel_kind;
+                    bytes.extend_from_slice(&compressed_data);
+                } else {
+                    bytes.extend_from_slice(data)
+                }
+            };
+
+            let is_content_aware = matches!(
+                src_pixel_kind,
+                TexturePixelKind::RGB8 | TexturePixelKind::RGBA8
+            ) && (import_options.mip_content != MipContent::Generic
+                || import_options.gamma_correct_mips);
+
+            if import_options.minification_filter.is_using_mip_mapping() && is_content_aware {
+                let (channels, levels) = mip::generate_mip_chain(
+                    dyn_img.as_bytes(),
+                    src_pixel_kind.size_in_bytes().unwrap_or(4),
+                    width,
+                    height,
+                    import_options.mip_content,
+                    import_options.gamma_correct_mips,
+                    import_options.mip_filter.into_filter_type(),
+                );
+                let level_pixel_kind = if channels == 4 {
+                    TexturePixelKind::RGBA8
+                } else {
+                    TexturePixelKind::RGB8
+                };
+                for level in levels.iter() {
+                    mip_count += 1;
+                    push_level(&level.bytes, level_pixel_kind, level.width, level.height);
+                }
+            } else if import_options.minification_filter.is_using_mip_mapping() {
                 let src_pixel_type = convert_pixel_type_enum(src_pixel_kind);
                 let mut level_width = width;
                 let mut level_height = height;
@@ -1614,20 +2123,12 @@ impl Texture {
 
                     mip_count += 1;
 
-                    if import_options.compression == CompressionOptions::NoCompression {
-                        bytes.extend_from_slice(current_level.buffer())
-                    } else if let Some((compressed_data, new_pixel_kind)) = try_compress(
-                        src_pixel_kind,
+                    push_level(
                         current_level.buffer(),
-                        level_width as usize,
-                        level_height as usize,
-                        import_options.compression,
-                    ) {
-                        final_pixel_kind = new_pixel_kind;
-                        bytes.extend_from_slice(&compressed_data);
-                    } else {
-                        bytes.extend_from_slice(current_level.buffer())
-                    }
+                        src_pixel_kind,
+                        level_width,
+                        level_height,
+                    );
 
                     level_width = level_width.checked_shr(1).unwrap_or_default();
                     level_height = level_height.checked_shr(1).unwrap_or_default();
@@ -1635,19 +2136,18 @@ impl Texture {
             } else {
                 mip_count = 1;
 
-                if import_options.compression == CompressionOptions::NoCompression {
-                    bytes.extend_from_slice(dyn_img.as_bytes());
-                } else if let Some((compressed_data, new_pixel_kind)) = try_compress(
-                    src_pixel_kind,
-                    dyn_img.as_bytes(),
-                    width as usize,
-                    height as usize,
-                    import_options.compression,
-                ) {
-                    final_pixel_kind = new_pixel_kind;
-                    bytes.extend_from_slice(&compressed_data);
-                } else {
-                    bytes.extend_from_slice(dyn_img.as_bytes())
+                push_level(dyn_img.as_bytes(), src_pixel_kind, width, height);
+            }
+
+            if import_options.float_precision == FloatPrecision::Half {
+                let half_pixel_kind = match final_pixel_kind {
+                    TexturePixelKind::RGB32F => Some(TexturePixelKind::RGB16F),
+                    TexturePixelKind::RGBA32F => Some(TexturePixelKind::RGBA16F),
+                    _ => None,
+                };
+                if let Some(half_pixel_kind) = half_pixel_kind {
+                    bytes = float_bytes_to_half(&bytes);
+                    final_pixel_kind = half_pixel_kind;
                 }
             }
 
@@ -1675,6 +2175,97 @@ impl Texture {
         }
     }
 
+    /// Converts the given equirectangular panorama into a cube map. See [`CubeMapConversion`] for
+    /// more info.
+    fn from_equirectangular(
+        dyn_img: &DynamicImage,
+        face_size: u32,
+        import_options: TextureImportOptions,
+    ) -> Self {
+        let pixel_kind = match dyn_img {
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+                match import_options.float_precision {
+                    FloatPrecision::Full => TexturePixelKind::RGBA32F,
+                    FloatPrecision::Half => TexturePixelKind::RGBA16F,
+                }
+            }
+            _ => TexturePixelKind::RGBA8,
+        };
+
+        let rgba = dyn_img.to_rgba32f();
+        let panorama = FloatImage {
+            width: rgba.width(),
+            height: rgba.height(),
+            pixels: rgba.pixels().map(|pixel| pixel.0).collect(),
+        };
+
+        let face_size = if face_size == 0 {
+            cube::default_face_size(panorama.width)
+        } else {
+            face_size
+        };
+
+        let mut cube_map =
+            CubeMapImage::new(cube::equirectangular_to_cube_faces(&panorama, face_size));
+        let mut minification_filter = import_options.minification_filter;
+        match import_options.environment_filter {
+            EnvironmentMapFilter::None => {
+                if minification_filter.is_using_mip_mapping() {
+                    cube_map.generate_mips();
+                }
+            }
+            EnvironmentMapFilter::Irradiance {
+                face_size: irradiance_size,
+            } => {
+                cube_map = ibl::compute_irradiance_map(&cube_map, irradiance_size.max(1));
+                if minification_filter.is_using_mip_mapping() {
+                    cube_map.generate_mips();
+                }
+            }
+            EnvironmentMapFilter::Specular { sample_count } => {
+                cube_map.generate_mips();
+                cube_map = ibl::prefilter_specular(&cube_map, face_size, sample_count);
+                if !minification_filter.is_using_mip_mapping() {
+                    minification_filter = TextureMinificationFilter::LinearMipMapLinear;
+                }
+            }
+        }
+
+        let mut bytes = Vec::new();
+        for faces in cube_map.levels.iter() {
+            for face in faces.iter() {
+                write_float_pixels(&face.pixels, pixel_kind, &mut bytes);
+            }
+        }
+        let mip_count = cube_map.levels.len() as u32;
+        let face_size = cube_map.size();
+
+        Self {
+            pixel_kind,
+            kind: TextureKind::Cube {
+                width: face_size,
+                height: face_size,
+            },
+            modifications_counter: 0,
+            bytes: bytes.into(),
+            mip_count,
+            minification_filter,
+            magnification_filter: import_options.magnification_filter,
+            s_wrap_mode: TextureWrapMode::ClampToEdge,
+            t_wrap_mode: TextureWrapMode::ClampToEdge,
+            r_wrap_mode: TextureWrapMode::ClampToEdge,
+            base_level: import_options.base_level,
+            max_level: import_options.max_level,
+            min_lod: import_options.min_lod,
+            max_lod: import_options.max_lod,
+            anisotropy: import_options.anisotropy,
+            is_render_target: false,
+            cache_index: Default::default(),
+            lod_bias: import_options.lod_bias,
+            sampler_properties_modifications: 1,
+        }
+    }
+
     /// Tries to load a texture from a file.
     ///
     /// # Notes
@@ -1685,9 +2276,11 @@ impl Texture {
         path: P,
         io: &dyn ResourceIo,
         import_options: TextureImportOptions,
+        transcoder: Option<&dyn BasisTranscoder>,
+        supported_formats: SupportedCompressedFormats,
     ) -> Result<Self, TextureError> {
         let data = io.load_file(path.as_ref()).await?;
-        Self::load_from_memory(&data, import_options)
+        Self::load_from_memory_with_transcoder(&data, import_options, transcoder, supported_formats)
     }
 
     /// Creates new texture instance from given parameters.
@@ -1713,6 +2306,32 @@ impl Texture {
         }
     }
 
+    /// Creates a BRDF look-up table for split-sum approximation of image-based lighting. The
+    /// table is stored in [`TexturePixelKind::RG16`] format, see [`ibl::compute_brdf_lut`] for
+    /// more info about its content. 256x256 table with 512 samples per texel is a good default.
+    pub fn new_brdf_lut(size: u32, sample_count: u32) -> Self {
+        let lut = ibl::compute_brdf_lut(size, sample_count);
+        let mut bytes = Vec::with_capacity(lut.pixels.len() * 4);
+        for pixel in lut.pixels.iter() {
+            for component in &pixel[..2] {
+                let value = (component.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16;
+                bytes.extend_from_slice(&value.to_ne_bytes());
+            }
+        }
+        Self {
+            kind: TextureKind::Rectangle {
+                width: lut.width,
+                height: lut.height,
+            },
+            pixel_kind: TexturePixelKind::RG16,
+            bytes: bytes.into(),
+            minification_filter: TextureMinificationFilter::Linear,
+            s_wrap_mode: TextureWrapMode::ClampToEdge,
+            t_wrap_mode: TextureWrapMode::ClampToEdge,
+            ..Default::default()
+        }
+    }
+
     /// Sets new minification filter. It is used when texture becomes smaller.
     #[inline]
     pub fn set_minification_filter(&mut self, filter: TextureMinificationFilter) {
@@ -2020,3 +2639,142 @@ impl TextureDataRefMut<'_> {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        CubeMapConversion, FloatPrecision, Texture, TextureImportOptions, TextureKind,
+        TextureMinificationFilter, TexturePixelKind,
+    };
+    use fyrox_resource::ResourceData;
+    use half::f16;
+
+    fn float_pixels(count: usize, components: usize) -> Vec<f32> {
+        (0..count * components)
+            .map(|i| 0.25 + i as f32 * 1.5)
+            .collect()
+    }
+
+    fn floats(texture: &Texture) -> Vec<f32> {
+        texture
+            .data()
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect()
+    }
+
+    fn halves(texture: &Texture) -> Vec<f32> {
+        texture
+            .data()
+            .chunks_exact(2)
+            .map(|bytes| f16::from_le_bytes(bytes.try_into().unwrap()).to_f32())
+            .collect()
+    }
+
+    fn save_and_load(
+        texture: &mut Texture,
+        file_name: &str,
+        import_options: TextureImportOptions,
+    ) -> Texture {
+        let path = std::env::temp_dir().join(file_name);
+        texture.save(&path).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        Texture::load_from_memory(&data, import_options).unwrap()
+    }
+
+    fn options() -> TextureImportOptions {
+        TextureImportOptions::default().with_minification_filter(TextureMinificationFilter::Linear)
+    }
+
+    #[test]
+    fn test_exr_round_trip() {
+        let pixels = float_pixels(4 * 2, 4);
+        let mut texture = Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: 4,
+                height: 2,
+            },
+            TexturePixelKind::RGBA32F,
+            fyrox_core::transmute_vec_as_bytes(pixels.clone()),
+        )
+        .unwrap();
+
+        let full = save_and_load(&mut texture, "fyrox_texture_round_trip.exr", options());
+        assert_eq!(full.pixel_kind(), TexturePixelKind::RGBA32F);
+        assert!(matches!(
+            full.kind(),
+            TextureKind::Rectangle {
+                width: 4,
+                height: 2
+            }
+        ));
+        assert_eq!(full.mip_count(), 1);
+        assert_eq!(floats(&full), pixels);
+
+        let half = save_and_load(
+            &mut texture,
+            "fyrox_texture_round_trip_half.exr",
+            options().with_float_precision(FloatPrecision::Half),
+        );
+        assert_eq!(half.pixel_kind(), TexturePixelKind::RGBA16F);
+        let loaded = halves(&half);
+        assert_eq!(loaded.len(), pixels.len());
+        for (loaded, expected) in loaded.iter().zip(pixels.iter()) {
+            assert!((loaded - expected).abs() <= expected * 0.001);
+        }
+    }
+
+    #[test]
+    fn test_hdr_round_trip() {
+        let pixels = float_pixels(4 * 2, 3);
+        let mut texture = Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: 4,
+                height: 2,
+            },
+            TexturePixelKind::RGB32F,
+            fyrox_core::transmute_vec_as_bytes(pixels.clone()),
+        )
+        .unwrap();
+
+        let loaded = save_and_load(&mut texture, "fyrox_texture_round_trip.hdr", options());
+        assert_eq!(loaded.pixel_kind(), TexturePixelKind::RGB32F);
+        // Radiance HDR uses shared exponent, so the precision is about 1%.
+        for (loaded, expected) in floats(&loaded).iter().zip(pixels.iter()) {
+            assert!((loaded - expected).abs() <= expected * 0.01);
+        }
+    }
+
+    #[test]
+    fn test_equirectangular_conversion_on_load() {
+        let mut texture = Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: 8,
+                height: 4,
+            },
+            TexturePixelKind::RGBA32F,
+            fyrox_core::transmute_vec_as_bytes(vec![1.0f32; 8 * 4 * 4]),
+        )
+        .unwrap();
+
+        let cube = save_and_load(
+            &mut texture,
+            "fyrox_texture_panorama.exr",
+            options()
+                .with_float_precision(FloatPrecision::Half)
+                .with_cube_map_conversion(CubeMapConversion::Equirectangular { face_size: 2 }),
+        );
+        assert!(matches!(
+            cube.kind(),
+            TextureKind::Cube {
+                width: 2,
+                height: 2
+            }
+        ));
+        assert_eq!(cube.pixel_kind(), TexturePixelKind::RGBA16F);
+        for value in halves(&cube) {
+            assert_eq!(value, 1.0);
+        }
+    }
+}
@@ -20,7 +20,11 @@
 
 //! Texture loader.
 
-use crate::{Texture, TextureImportOptions};
+use crate::{
+    ktx::{BasisTranscoder, SupportedCompressedFormats},
+    procedural::{ProceduralTextureDefinition, PROCEDURAL_TEXTURE_EXTENSION},
+    Texture, TextureImportOptions,
+};
 use fyrox_core::{uuid::Uuid, TypeUuidProvider};
 use fyrox_resource::{
     io::ResourceIo, loader::BoxedImportOptionsLoaderFuture, loader::BoxedLoaderFuture,
@@ -33,12 +37,18 @@ use std::{path::PathBuf, sync::Arc};
 pub struct TextureLoader {
     /// Default import options for textures.
     pub default_import_options: TextureImportOptions,
+    /// Optional transcoder for KTX2 textures with Basis Universal payload. Such textures cannot
+    /// be loaded without a transcoder.
+    pub basis_transcoder: Option<Arc<dyn BasisTranscoder>>,
+    /// Block-compressed formats supported by the current graphics server. They're used to select
+    /// the format of transcoded textures, see [`crate::ktx::TranscodingTarget::Auto`].
+    pub supported_compressed_formats: SupportedCompressedFormats,
 }
 
 impl ResourceLoader for TextureLoader {
     fn extensions(&self) -> &[&str] {
         &[
-            "jpg", "jpeg", "tga", "gif", "bmp", "png", "tiff", "tif", "dds",
+            "jpg", "jpeg", "tga", "gif", "bmp", "png", "tiff", "tif", "dds", "hdr", "exr", "ktx2",
         ]
     }
 
@@ -48,6 +58,8 @@ impl ResourceLoader for TextureLoader {
 
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
         let default_import_options = self.default_import_options.clone();
+        let basis_transcoder = self.basis_transcoder.clone();
+        let supported_compressed_formats = self.supported_compressed_formats;
         Box::pin(async move {
             let io = io.as_ref();
 
@@ -55,9 +67,15 @@ impl ResourceLoader for TextureLoader {
                 .await
                 .unwrap_or(default_import_options);
 
-            let raw_texture = Texture::load_from_file(&path, io, import_options)
-                .await
-                .map_err(LoadError::new)?;
+            let raw_texture = Texture::load_from_file(
+                &path,
+                io,
+                import_options,
+                basis_transcoder.as_deref(),
+                supported_compressed_formats,
+            )
+            .await
+            .map_err(LoadError::new)?;
 
             Ok(LoaderPayload::new(raw_texture))
         })
@@ -77,3 +95,28 @@ impl ResourceLoader for TextureLoader {
         Some(Box::<TextureImportOptions>::default())
     }
 }
+
+/// Loader for procedural textures. It reads a descriptor file and bakes it into a texture, see
+/// [`crate::procedural`] module docs for more info.
+#[derive(Default)]
+pub struct ProceduralTextureLoader;
+
+impl ResourceLoader for ProceduralTextureLoader {
+    fn extensions(&self) -> &[&str] {
+        &[PROCEDURAL_TEXTURE_EXTENSION]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        Texture::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let data = io.load_file(&path).await.map_err(LoadError::new)?;
+            let texture = ProceduralTextureDefinition::from_bytes(&data)
+                .and_then(|definition| definition.bake())
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(texture))
+        })
+    }
+}
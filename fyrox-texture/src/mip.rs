@@ -0,0 +1,458 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Content-aware mip-map generation. Generic resizing works well for color textures, but it breaks
+//! normal maps (averaged normals become shorter) and alpha-tested textures (alpha coverage shrinks
+//! on each level, so foliage "melts" at distance). See [`MipContent`] docs for more info.
+
+use fast_image_resize as fr;
+use fast_image_resize::ResizeOptions;
+use fyrox_core::{reflect::prelude::*, uuid_provider};
+use serde::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines the content of a texture, which is used to generate mip levels correctly. It is taken
+/// into account only for RGB8 and RGBA8 images, all other pixel formats always use generic
+/// resizing.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum MipContent {
+    /// Mip levels are produced by generic resizing.
+    #[default]
+    Generic,
+    /// A texture is a normal map. Normals of each mip level are re-normalized after resizing.
+    /// Normal maps are always treated as linear data, gamma-correct downsampling has no effect on
+    /// them.
+    NormalMap {
+        /// If set, the length of averaged normals (before re-normalization) is written to the
+        /// alpha channel of each mip level (RGB images are converted to RGBA). The length is a
+        /// measure of normal variance within a texel and it could be used to increase roughness of
+        /// distant surfaces to reduce specular aliasing (Toksvig's method), see
+        /// [`toksvig_roughness`] for more info.
+        toksvig: bool,
+    },
+    /// A texture is used for alpha testing. Alpha of each mip level is scaled to preserve the
+    /// coverage (amount of pixels that pass the test) of the first level.
+    AlphaTested {
+        /// Alpha value that is used in alpha test.
+        cutoff: f32,
+    },
+}
+
+uuid_provider!(MipContent = "0c7d6b2e-96f1-4a53-8e0b-5f4c1d2a9b37");
+
+/// Calculates roughness adjusted by Toksvig factor. `normal_length` is the length of an averaged
+/// normal, stored in alpha channel of normal maps with `toksvig` option (see [`MipContent`]).
+/// Normal variance `(1 - length) / length` is added to squared GGX alpha (`roughness^2`).
+pub fn toksvig_roughness(roughness: f32, normal_length: f32) -> f32 {
+    let length = normal_length.clamp(f32::EPSILON, 1.0);
+    let variance = (1.0 - length) / length;
+    let alpha = roughness * roughness;
+    (alpha * alpha + variance).sqrt().sqrt().min(1.0)
+}
+
+/// Converts an sRGB-encoded value to linear color space.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear value to sRGB color space.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// A mip level produced by [`generate_mip_chain`].
+pub(crate) struct MipLevel {
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+}
+
+fn alpha_coverage(pixels: &[f32], cutoff: f32, scale: f32) -> f32 {
+    let count = pixels
+        .chunks_exact(4)
+        .filter(|pixel| pixel[3] * scale > cutoff)
+        .count();
+    count as f32 / (pixels.len() / 4).max(1) as f32
+}
+
+/// Finds a scale of alpha, that gives the desired coverage.
+fn find_alpha_scale(pixels: &[f32], cutoff: f32, desired_coverage: f32) -> f32 {
+    let mut min = 0.0;
+    let mut max = 16.0;
+    for _ in 0..20 {
+        let middle = (min + max) * 0.5;
+        if alpha_coverage(pixels, cutoff, middle) < desired_coverage {
+            min = middle;
+        } else {
+            max = middle;
+        }
+    }
+    (min + max) * 0.5
+}
+
+/// Generates mip levels for an RGB8 or RGBA8 image with respect to its content. Resizing is done in
+/// linear space using 32-bit floats. Returns the number of channels in the resulting levels and
+/// the levels themselves, starting from the first (original size) one.
+pub(crate) fn generate_mip_chain(
+    bytes: &[u8],
+    channels: usize,
+    width: u32,
+    height: u32,
+    content: MipContent,
+    gamma_correct: bool,
+    filter: fr::FilterType,
+) -> (usize, Vec<MipLevel>) {
+    let is_normal_map = matches!(content, MipContent::NormalMap { .. });
+    let gamma_correct = gamma_correct && !is_normal_map;
+    let output_channels = match content {
+        MipContent::NormalMap { toksvig: true } => 4,
+        _ => channels,
+    };
+
+    let decoded = bytes
+        .chunks_exact(channels)
+        .flat_map(|pixel| {
+            let mut rgba = [0.0, 0.0, 0.0, 1.0];
+            for (i, component) in pixel.iter().enumerate() {
+                let value = *component as f32 / 255.0;
+                rgba[i] = if i == 3 {
+                    value
+                } else if is_normal_map {
+                    value * 2.0 - 1.0
+                } else if gamma_correct {
+                    srgb_to_linear(value)
+                } else {
+                    value
+                };
+            }
+            rgba
+        })
+        .collect::<Vec<f32>>();
+
+    let base_coverage = match content {
+        MipContent::AlphaTested { cutoff } => alpha_coverage(&decoded, cutoff, 1.0),
+        _ => 0.0,
+    };
+
+    let mut levels = Vec::new();
+    let mut level_width = width;
+    let mut level_height = height;
+    let mut current = decoded;
+    let mut current_image = None;
+    while level_width != 0 && level_height != 0 {
+        if let Some(src) = current_image.as_ref() {
+            let mut dst = fr::images::Image::new(level_width, level_height, fr::PixelType::F32x4);
+            fr::Resizer::new()
+                .resize(
+                    src,
+                    &mut dst,
+                    Some(&ResizeOptions {
+                        algorithm: fr::ResizeAlg::Convolution(filter),
+                        cropping: Default::default(),
+                        mul_div_alpha: channels == 4 && !is_normal_map,
+                    }),
+                )
+                .expect("Pixel types must match!");
+            current = dst
+                .buffer()
+                .chunks_exact(4)
+                .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                .collect();
+            current_image = Some(dst);
+        } else {
+            current_image = Some(
+                fr::images::Image::from_vec_u8(
+                    level_width,
+                    level_height,
+                    current.iter().flat_map(|c| c.to_ne_bytes()).collect(),
+                    fr::PixelType::F32x4,
+                )
+                .expect("Buffer size must match!"),
+            );
+        }
+
+        let alpha_scale = match content {
+            MipContent::AlphaTested { cutoff } if !levels.is_empty() => {
+                find_alpha_scale(&current, cutoff, base_coverage)
+            }
+            _ => 1.0,
+        };
+
+        let mut level_bytes =
+            Vec::with_capacity(level_width as usize * level_height as usize * output_channels);
+        for pixel in current.chunks_exact(4) {
+            let mut rgba = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            if is_normal_map {
+                let length = (rgba[0] * rgba[0] + rgba[1] * rgba[1] + rgba[2] * rgba[2]).sqrt();
+                let normal = if length > f32::EPSILON {
+                    [rgba[0] / length, rgba[1] / length, rgba[2] / length]
+                } else {
+                    [0.0, 0.0, 1.0]
+                };
+                for i in 0..3 {
+                    rgba[i] = normal[i] * 0.5 + 0.5;
+                }
+                if let MipContent::NormalMap { toksvig: true } = content {
+                    rgba[3] = length;
+                }
+            } else if gamma_correct {
+                for component in &mut rgba[..3] {
+                    *component = linear_to_srgb(*component);
+                }
+            }
+            rgba[3] *= alpha_scale;
+            for component in &rgba[..output_channels] {
+                level_bytes.push((component.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+
+        levels.push(MipLevel {
+            width: level_width,
+            height: level_height,
+            bytes: level_bytes,
+        });
+
+        level_width = level_width.checked_shr(1).unwrap_or_default();
+        level_height = level_height.checked_shr(1).unwrap_or_default();
+    }
+
+    (output_channels, levels)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::mip::{generate_mip_chain, linear_to_srgb, srgb_to_linear, MipContent};
+    use fast_image_resize as fr;
+
+    fn sizes(width: u32, height: u32, channels: usize, content: MipContent) -> Vec<(u32, u32)> {
+        let bytes = vec![128; width as usize * height as usize * channels];
+        let (output_channels, levels) = generate_mip_chain(
+            &bytes,
+            channels,
+            width,
+            height,
+            content,
+            false,
+            fr::FilterType::Box,
+        );
+        levels
+            .iter()
+            .map(|level| {
+                assert_eq!(
+                    level.bytes.len(),
+                    level.width as usize * level.height as usize * output_channels
+                );
+                (level.width, level.height)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mip_chain_sizes() {
+        assert_eq!(
+            sizes(16, 16, 4, MipContent::Generic),
+            [(16, 16), (8, 8), (4, 4), (2, 2), (1, 1)]
+        );
+        assert_eq!(sizes(8, 2, 3, MipContent::Generic), [(8, 2), (4, 1)]);
+        assert_eq!(
+            sizes(7, 5, 3, MipContent::Generic),
+            [(7, 5), (3, 2), (1, 1)]
+        );
+        assert_eq!(
+            sizes(13, 3, 4, MipContent::NormalMap { toksvig: false }),
+            [(13, 3), (6, 1)]
+        );
+        assert_eq!(sizes(1, 1, 4, MipContent::Generic), [(1, 1)]);
+    }
+
+    #[test]
+    fn test_toksvig_adds_alpha_channel() {
+        let bytes = [128, 128, 255].repeat(4);
+        let (channels, levels) = generate_mip_chain(
+            &bytes,
+            3,
+            2,
+            2,
+            MipContent::NormalMap { toksvig: true },
+            false,
+            fr::FilterType::Box,
+        );
+        assert_eq!(channels, 4);
+        assert_eq!(levels.len(), 2);
+        // Flat normals have unit length.
+        assert!(levels[1].bytes[3] >= 254);
+    }
+
+    #[test]
+    fn test_odd_size_filtering() {
+        // A solid color must stay the same on every level regardless of the size.
+        let color = [200, 100, 50, 255];
+        let bytes = color.repeat(7 * 5);
+        for gamma_correct in [false, true] {
+            let (_, levels) = generate_mip_chain(
+                &bytes,
+                4,
+                7,
+                5,
+                MipContent::Generic,
+                gamma_correct,
+                fr::FilterType::Lanczos3,
+            );
+            for level in levels.iter() {
+                for pixel in level.bytes.chunks_exact(4) {
+                    for (expected, actual) in color.iter().zip(pixel) {
+                        assert!(expected.abs_diff(*actual) <= 1, "{pixel:?}");
+                    }
+                }
+            }
+        }
+
+        // A horizontal gradient must stay monotonic and within the source range.
+        let (width, height) = (9, 3);
+        let bytes = (0..width * height)
+            .flat_map(|i| [(i % width * 30) as u8; 3])
+            .collect::<Vec<_>>();
+        let (_, levels) = generate_mip_chain(
+            &bytes,
+            3,
+            width as u32,
+            height as u32,
+            MipContent::Generic,
+            false,
+            fr::FilterType::Box,
+        );
+        assert_eq!(levels.len(), 2);
+        let level = &levels[1];
+        assert_eq!((level.width, level.height), (4, 1));
+        let row = level
+            .bytes
+            .chunks_exact(3)
+            .map(|p| p[0])
+            .collect::<Vec<_>>();
+        assert!(row.windows(2).all(|pair| pair[0] < pair[1]), "{row:?}");
+        assert!(row[3] <= 240);
+    }
+
+    #[test]
+    fn test_gamma_correct_downsampling() {
+        // A black and white checkerboard averages to 50% of linear intensity.
+        let bytes = (0..4 * 4)
+            .flat_map(|i| [if (i % 4 + i / 4) % 2 == 0 { 0 } else { 255 }; 3])
+            .collect::<Vec<_>>();
+        let downsample = |gamma_correct| {
+            let (_, levels) = generate_mip_chain(
+                &bytes,
+                3,
+                4,
+                4,
+                MipContent::Generic,
+                gamma_correct,
+                fr::FilterType::Box,
+            );
+            levels[1].bytes[0]
+        };
+        assert_eq!(downsample(false), 128);
+        assert_eq!(
+            downsample(true),
+            (linear_to_srgb(0.5) * 255.0).round() as u8
+        );
+        assert!((srgb_to_linear(linear_to_srgb(0.25)) - 0.25).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_normal_map_renormalization() {
+        // Two opposite tilted normals average to a short normal, which must be re-normalized.
+        let bytes = (0..4)
+            .flat_map(|i| {
+                if i % 2 == 0 {
+                    [218, 128, 218]
+                } else {
+                    [38, 128, 218]
+                }
+            })
+            .collect::<Vec<_>>();
+        let (_, levels) = generate_mip_chain(
+            &bytes,
+            3,
+            2,
+            2,
+            MipContent::NormalMap { toksvig: false },
+            false,
+            fr::FilterType::Box,
+        );
+        assert_eq!(levels[1].bytes, [128, 128, 255]);
+    }
+
+    #[test]
+    fn test_alpha_coverage_preservation() {
+        // Sparse opaque pixels disappear with generic resizing, but not with alpha-tested content.
+        let (width, height) = (16, 16);
+        let bytes = (0..width * height)
+            .flat_map(|i: usize| {
+                let is_opaque = (i.wrapping_mul(2654435761) >> 7) % 10 < 3;
+                [255, 255, 255, if is_opaque { 255 } else { 0 }]
+            })
+            .collect::<Vec<_>>();
+        let coverage = |bytes: &[u8]| {
+            let count = bytes.chunks_exact(4).filter(|p| p[3] > 127).count();
+            count as f32 / (bytes.len() / 4) as f32
+        };
+        let base = coverage(&bytes);
+        let levels = |content| {
+            generate_mip_chain(
+                &bytes,
+                4,
+                width as u32,
+                height as u32,
+                content,
+                false,
+                fr::FilterType::Box,
+            )
+            .1
+        };
+        let generic = levels(MipContent::Generic);
+        let alpha_tested = levels(MipContent::AlphaTested { cutoff: 0.5 });
+        // Generic resizing loses coverage on small levels.
+        assert_eq!(coverage(&generic[3].bytes), 0.0);
+        for level in alpha_tested.iter().filter(|level| level.width >= 2) {
+            assert!((coverage(&level.bytes) - base).abs() <= 0.1);
+        }
+    }
+}
@@ -0,0 +1,862 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Procedural textures. A procedural texture is described by a small RON file (with `.ptex`
+//! extension), which contains size of a texture and a tree of nodes for each channel. Textures are
+//! baked at load time, so they behave exactly as any other texture and they are re-generated
+//! automatically when a descriptor file changes. Typical content of a descriptor file looks like
+//! this:
+//!
+//! ```text
+//! (
+//!     width: 256,
+//!     height: 256,
+//!     channels: [
+//!         Multiply(
+//!             Fbm(kind: Perlin, frequency: 4.0, octaves: 5),
+//!             RadialGradient(center: (0.5, 0.5, 0.5), radius: 0.5),
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! Every node produces a value in `[0; 1]` range (unless it is modified by math nodes). Texture
+//! coordinates are in `[0; 1]` range as well. See [`ProceduralNode`] docs for the list of available
+//! nodes.
+
+use crate::{Texture, TextureError, TextureKind, TextureMinificationFilter, TexturePixelKind};
+use fyrox_core::algebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// Extension of procedural texture descriptor files.
+pub const PROCEDURAL_TEXTURE_EXTENSION: &str = "ptex";
+
+/// Kind of noise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoiseKind {
+    /// Classic gradient noise by Ken Perlin.
+    Perlin,
+    /// Simplex noise. It has fewer directional artifacts than Perlin noise. Seamless simplex noise
+    /// is produced by blending shifted copies of the noise, which slightly reduces its contrast in
+    /// the middle of a texture.
+    Simplex,
+    /// Cellular noise by Steven Worley (distance to the closest feature point).
+    Worley,
+}
+
+/// An axis of a linear gradient.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GradientAxis {
+    /// Horizontal axis of a texture.
+    X,
+    /// Vertical axis of a texture.
+    Y,
+    /// Depth axis of a volume texture.
+    Z,
+}
+
+fn default_lacunarity() -> f32 {
+    2.0
+}
+
+fn default_gain() -> f32 {
+    0.5
+}
+
+fn default_octaves() -> u32 {
+    4
+}
+
+/// A node of a procedural texture.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ProceduralNode {
+    /// Constant value.
+    Constant(f32),
+    /// A single octave of noise.
+    Noise {
+        /// Kind of the noise.
+        kind: NoiseKind,
+        /// Frequency of the noise (the number of noise cells per texture). It is rounded to the
+        /// closest integer value for seamless textures.
+        frequency: f32,
+        /// Seed of the noise. Different seeds produce different patterns.
+        #[serde(default)]
+        seed: u32,
+    },
+    /// Fractional Brownian motion - a sum of octaves of noise with increasing frequency and
+    /// decreasing amplitude.
+    Fbm {
+        /// Kind of the noise.
+        kind: NoiseKind,
+        /// Frequency of the first octave.
+        frequency: f32,
+        /// Amount of octaves.
+        #[serde(default = "default_octaves")]
+        octaves: u32,
+        /// Frequency multiplier for each next octave.
+        #[serde(default = "default_lacunarity")]
+        lacunarity: f32,
+        /// Amplitude multiplier for each next octave.
+        #[serde(default = "default_gain")]
+        gain: f32,
+        /// Seed of the noise.
+        #[serde(default)]
+        seed: u32,
+    },
+    /// Linear gradient from zero to one along the given axis. Gradients are not periodic, so they
+    /// break seamless tiling.
+    LinearGradient {
+        /// Axis of the gradient.
+        axis: GradientAxis,
+    },
+    /// Radial gradient, which is equal to one at the center and falls off to zero at the given
+    /// radius. The third component of the center is ignored for 2D textures.
+    RadialGradient {
+        /// Center of the gradient in texture coordinates.
+        center: (f32, f32, f32),
+        /// Radius of the gradient in texture coordinates.
+        radius: f32,
+    },
+    /// Sum of two nodes.
+    Add(Box<ProceduralNode>, Box<ProceduralNode>),
+    /// Difference of two nodes.
+    Subtract(Box<ProceduralNode>, Box<ProceduralNode>),
+    /// Product of two nodes.
+    Multiply(Box<ProceduralNode>, Box<ProceduralNode>),
+    /// Minimum of two nodes.
+    Min(Box<ProceduralNode>, Box<ProceduralNode>),
+    /// Maximum of two nodes.
+    Max(Box<ProceduralNode>, Box<ProceduralNode>),
+    /// Linear interpolation between two nodes.
+    Mix {
+        /// The first node.
+        a: Box<ProceduralNode>,
+        /// The second node.
+        b: Box<ProceduralNode>,
+        /// Interpolation factor (zero gives `a`, one gives `b`).
+        factor: Box<ProceduralNode>,
+    },
+    /// `1 - value` of a node.
+    Invert(Box<ProceduralNode>),
+    /// Linearly maps a value of a node from one range to another.
+    Remap {
+        /// Source node.
+        source: Box<ProceduralNode>,
+        /// Source range.
+        from: (f32, f32),
+        /// Destination range.
+        to: (f32, f32),
+    },
+    /// Clamps a value of a node to the given range.
+    Clamp {
+        /// Source node.
+        source: Box<ProceduralNode>,
+        /// Minimum value.
+        min: f32,
+        /// Maximum value.
+        max: f32,
+    },
+    /// Raises a value of a node to the given power. It could be used to change contrast.
+    Power {
+        /// Source node.
+        source: Box<ProceduralNode>,
+        /// Exponent.
+        exponent: f32,
+    },
+    /// Produces one if a value of a node is larger than the edge, zero - otherwise. Non-zero
+    /// smoothness gives smooth transition of the given width around the edge.
+    Threshold {
+        /// Source node.
+        source: Box<ProceduralNode>,
+        /// Threshold value.
+        edge: f32,
+        /// Width of the transition.
+        #[serde(default)]
+        smoothness: f32,
+    },
+}
+
+/// Pixel format of a procedural texture.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProceduralTextureFormat {
+    /// Each channel is stored as a normalized byte. Values are clamped to `[0; 1]` range.
+    #[default]
+    Unorm8,
+    /// Each channel is stored as a 32-bit float. Two-channel textures are stored as RGB textures.
+    Float32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Descriptor of a procedural texture. See [module docs](self) for more info.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProceduralTextureDefinition {
+    /// Width of the texture in pixels.
+    pub width: u32,
+    /// Height of the texture in pixels.
+    pub height: u32,
+    /// Depth of the texture in pixels. Zero means that the texture is two-dimensional, any other
+    /// value produces a volume texture.
+    #[serde(default)]
+    pub depth: u32,
+    /// Defines whether the texture should tile without seams or not.
+    #[serde(default = "default_true")]
+    pub seamless: bool,
+    /// Defines whether mip levels should be generated or not.
+    #[serde(default = "default_true")]
+    pub generate_mips: bool,
+    /// Pixel format of the texture.
+    #[serde(default)]
+    pub format: ProceduralTextureFormat,
+    /// Nodes for each channel of the texture (from one to four).
+    pub channels: Vec<ProceduralNode>,
+}
+
+fn hash(x: i32, y: i32, z: i32, seed: u32) -> u32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ (z as u32).wrapping_mul(0xcb1a_b31f)
+        ^ seed.wrapping_mul(0x9e37_79b9);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^ (h >> 16)
+}
+
+fn hash_to_unit(h: u32) -> f32 {
+    (h >> 8) as f32 / (1 << 24) as f32
+}
+
+/// Wraps the given lattice coordinate if the period is non-zero.
+fn wrap(i: i32, period: i32) -> i32 {
+    if period > 0 {
+        i.rem_euclid(period)
+    } else {
+        i
+    }
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+const GRADIENTS: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+fn gradient_dot(h: u32, x: f32, y: f32, z: f32) -> f32 {
+    let g = GRADIENTS[(h % 12) as usize];
+    g[0] * x + g[1] * y + g[2] * z
+}
+
+/// Perlin noise in `[-1; 1]` range, periodic with the given period (if it is non-zero).
+fn perlin(p: Vector3<f32>, period: Vector3<i32>, seed: u32) -> f32 {
+    let cell = p.map(|c| c.floor());
+    let f = p - cell;
+    let i = cell.map(|c| c as i32);
+    let u = f.map(fade);
+
+    let corner = |dx: i32, dy: i32, dz: i32| {
+        let h = hash(
+            wrap(i.x + dx, period.x),
+            wrap(i.y + dy, period.y),
+            wrap(i.z + dz, period.z),
+            seed,
+        );
+        gradient_dot(h, f.x - dx as f32, f.y - dy as f32, f.z - dz as f32)
+    };
+
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u.x);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u.x);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u.x);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u.x);
+    lerp(lerp(x00, x10, u.y), lerp(x01, x11, u.y), u.z).clamp(-1.0, 1.0)
+}
+
+/// Worley noise (distance to the closest feature point) in `[0; 1]` range.
+fn worley(p: Vector3<f32>, period: Vector3<i32>, seed: u32, is_volume: bool) -> f32 {
+    let cell = p.map(|c| c.floor());
+    let i = cell.map(|c| c as i32);
+    let z_range = if is_volume { -1..=1 } else { 0..=0 };
+    let mut min_distance = f32::MAX;
+    for dz in z_range {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let h = hash(
+                    wrap(i.x + dx, period.x),
+                    wrap(i.y + dy, period.y),
+                    wrap(i.z + dz, period.z),
+                    seed,
+                );
+                let feature = Vector3::new(
+                    cell.x + dx as f32 + hash_to_unit(h),
+                    cell.y + dy as f32 + hash_to_unit(hash(h as i32, 1, 0, seed)),
+                    if is_volume {
+                        cell.z + dz as f32 + hash_to_unit(hash(h as i32, 2, 0, seed))
+                    } else {
+                        p.z
+                    },
+                );
+                min_distance = min_distance.min((feature - p).norm());
+            }
+        }
+    }
+    min_distance.min(1.0)
+}
+
+/// Simplex noise in `[-1; 1]` range.
+fn simplex(p: Vector3<f32>, seed: u32) -> f32 {
+    const F3: f32 = 1.0 / 3.0;
+    const G3: f32 = 1.0 / 6.0;
+
+    let s = (p.x + p.y + p.z) * F3;
+    let i = (p.x + s).floor();
+    let j = (p.y + s).floor();
+    let k = (p.z + s).floor();
+    let t = (i + j + k) * G3;
+    let x0 = Vector3::new(p.x - (i - t), p.y - (j - t), p.z - (k - t));
+
+    let (o1, o2) = if x0.x >= x0.y {
+        if x0.y >= x0.z {
+            ([1, 0, 0], [1, 1, 0])
+        } else if x0.x >= x0.z {
+            ([1, 0, 0], [1, 0, 1])
+        } else {
+            ([0, 0, 1], [1, 0, 1])
+        }
+    } else if x0.y < x0.z {
+        ([0, 0, 1], [0, 1, 1])
+    } else if x0.x < x0.z {
+        ([0, 1, 0], [0, 1, 1])
+    } else {
+        ([0, 1, 0], [1, 1, 0])
+    };
+
+    let (i, j, k) = (i as i32, j as i32, k as i32);
+    let mut sum = 0.0;
+    for (n, offset) in [[0, 0, 0], o1, o2, [1, 1, 1]].into_iter().enumerate() {
+        let x = x0 - Vector3::new(offset[0] as f32, offset[1] as f32, offset[2] as f32)
+            + Vector3::repeat(n as f32 * G3);
+        let weight = 0.6 - x.norm_squared();
+        if weight > 0.0 {
+            let h = hash(i + offset[0], j + offset[1], k + offset[2], seed);
+            sum += weight.powi(4) * gradient_dot(h, x.x, x.y, x.z);
+        }
+    }
+    (32.0 * sum).clamp(-1.0, 1.0)
+}
+
+struct SampleContext {
+    seamless: bool,
+    is_volume: bool,
+}
+
+impl SampleContext {
+    /// Samples signed noise (except Worley, which is unsigned) at the given texture coordinates.
+    fn noise(&self, kind: NoiseKind, uvw: Vector3<f32>, frequency: f32, seed: u32) -> f32 {
+        let frequency = if self.seamless {
+            frequency.round().max(1.0)
+        } else {
+            frequency
+        };
+        let period = if self.seamless {
+            Vector3::new(
+                frequency as i32,
+                frequency as i32,
+                if self.is_volume { frequency as i32 } else { 0 },
+            )
+        } else {
+            Vector3::repeat(0)
+        };
+        match kind {
+            NoiseKind::Perlin => perlin(uvw * frequency, period, seed),
+            NoiseKind::Worley => worley(uvw * frequency, period, seed, self.is_volume),
+            NoiseKind::Simplex => {
+                if self.seamless {
+                    // Blend shifted copies of the noise, so the opposite edges of the texture
+                    // match each other.
+                    let z_range = if self.is_volume { 0..2 } else { 0..1 };
+                    let mut sum = 0.0;
+                    for dz in z_range {
+                        for dy in 0..2 {
+                            for dx in 0..2 {
+                                let offset = Vector3::new(dx as f32, dy as f32, dz as f32);
+                                let weight = (if dx == 0 { 1.0 - uvw.x } else { uvw.x })
+                                    * (if dy == 0 { 1.0 - uvw.y } else { uvw.y })
+                                    * if !self.is_volume {
+                                        1.0
+                                    } else if dz == 0 {
+                                        1.0 - uvw.z
+                                    } else {
+                                        uvw.z
+                                    };
+                                sum += weight * simplex((uvw - offset) * frequency, seed);
+                            }
+                        }
+                    }
+                    sum
+                } else {
+                    simplex(uvw * frequency, seed)
+                }
+            }
+        }
+    }
+
+    fn eval(&self, node: &ProceduralNode, uvw: Vector3<f32>) -> f32 {
+        match node {
+            ProceduralNode::Constant(value) => *value,
+            ProceduralNode::Noise {
+                kind,
+                frequency,
+                seed,
+            } => {
+                let value = self.noise(*kind, uvw, *frequency, *seed);
+                match kind {
+                    NoiseKind::Worley => value,
+                    _ => value * 0.5 + 0.5,
+                }
+            }
+            ProceduralNode::Fbm {
+                kind,
+                frequency,
+                octaves,
+                lacunarity,
+                gain,
+                seed,
+            } => {
+                let mut sum = 0.0;
+                let mut amplitude = 1.0;
+                let mut total_amplitude = 0.0;
+                let mut octave_frequency = *frequency;
+                for octave in 0..(*octaves).max(1) {
+                    sum += amplitude
+                        * self.noise(*kind, uvw, octave_frequency, seed.wrapping_add(octave));
+                    total_amplitude += amplitude;
+                    amplitude *= gain;
+                    octave_frequency *= lacunarity;
+                }
+                let value = sum / total_amplitude.max(f32::EPSILON);
+                match kind {
+                    NoiseKind::Worley => value,
+                    _ => value * 0.5 + 0.5,
+                }
+            }
+            ProceduralNode::LinearGradient { axis } => match axis {
+                GradientAxis::X => uvw.x,
+                GradientAxis::Y => uvw.y,
+                GradientAxis::Z => uvw.z,
+            },
+            ProceduralNode::RadialGradient { center, radius } => {
+                let center = Vector3::new(
+                    center.0,
+                    center.1,
+                    if self.is_volume { center.2 } else { uvw.z },
+                );
+                (1.0 - (uvw - center).norm() / radius.max(f32::EPSILON)).max(0.0)
+            }
+            ProceduralNode::Add(a, b) => self.eval(a, uvw) + self.eval(b, uvw),
+            ProceduralNode::Subtract(a, b) => self.eval(a, uvw) - self.eval(b, uvw),
+            ProceduralNode::Multiply(a, b) => self.eval(a, uvw) * self.eval(b, uvw),
+            ProceduralNode::Min(a, b) => self.eval(a, uvw).min(self.eval(b, uvw)),
+            ProceduralNode::Max(a, b) => self.eval(a, uvw).max(self.eval(b, uvw)),
+            ProceduralNode::Mix { a, b, factor } => {
+                lerp(self.eval(a, uvw), self.eval(b, uvw), self.eval(factor, uvw))
+            }
+            ProceduralNode::Invert(source) => 1.0 - self.eval(source, uvw),
+            ProceduralNode::Remap { source, from, to } => {
+                let t = (self.eval(source, uvw) - from.0) / (from.1 - from.0);
+                lerp(to.0, to.1, if t.is_finite() { t } else { 0.0 })
+            }
+            ProceduralNode::Clamp { source, min, max } => {
+                self.eval(source, uvw).max(*min).min(*max)
+            }
+            ProceduralNode::Power { source, exponent } => {
+                self.eval(source, uvw).max(0.0).powf(*exponent)
+            }
+            ProceduralNode::Threshold {
+                source,
+                edge,
+                smoothness,
+            } => {
+                let value = self.eval(source, uvw);
+                if *smoothness > 0.0 {
+                    let t = ((value - (edge - smoothness * 0.5)) / smoothness).clamp(0.0, 1.0);
+                    t * t * (3.0 - 2.0 * t)
+                } else if value > *edge {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Downsamples the given level by the factor of two in every dimension using box filter.
+fn downsample(pixels: &[f32], channels: usize, size: [usize; 3], new_size: [usize; 3]) -> Vec<f32> {
+    let mut result = vec![0.0; new_size[0] * new_size[1] * new_size[2] * channels];
+    let index = |x: usize, y: usize, z: usize, size: [usize; 3]| {
+        ((z * size[1] + y) * size[0] + x) * channels
+    };
+    for z in 0..new_size[2] {
+        for y in 0..new_size[1] {
+            for x in 0..new_size[0] {
+                let mut count = 0;
+                let dst = index(x, y, z, new_size);
+                for sz in (z * 2)..(z * 2 + 2).min(size[2]) {
+                    for sy in (y * 2)..(y * 2 + 2).min(size[1]) {
+                        for sx in (x * 2)..(x * 2 + 2).min(size[0]) {
+                            let src = index(sx, sy, sz, size);
+                            for c in 0..channels {
+                                result[dst + c] += pixels[src + c];
+                            }
+                            count += 1;
+                        }
+                    }
+                }
+                for c in 0..channels {
+                    result[dst + c] /= count.max(1) as f32;
+                }
+            }
+        }
+    }
+    result
+}
+
+impl ProceduralTextureDefinition {
+    /// Parses a definition from the given RON data.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, TextureError> {
+        ron::de::from_bytes(data).map_err(|err| TextureError::Procedural(err.to_string()))
+    }
+
+    /// Bakes the definition into a texture.
+    pub fn bake(&self) -> Result<Texture, TextureError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(TextureError::Procedural(
+                "Width and height of a procedural texture must be non-zero!".to_string(),
+            ));
+        }
+        if self.channels.is_empty() || self.channels.len() > 4 {
+            return Err(TextureError::Procedural(format!(
+                "A procedural texture must have from one to four channels, got {}!",
+                self.channels.len()
+            )));
+        }
+
+        let is_volume = self.depth > 0;
+        let context = SampleContext {
+            seamless: self.seamless,
+            is_volume,
+        };
+
+        let channels = self.channels.len();
+        let stored_channels = match (self.format, channels) {
+            (ProceduralTextureFormat::Float32, 2) => 3,
+            _ => channels,
+        };
+        let pixel_kind = match (self.format, stored_channels) {
+            (ProceduralTextureFormat::Unorm8, 1) => TexturePixelKind::R8,
+            (ProceduralTextureFormat::Unorm8, 2) => TexturePixelKind::RG8,
+            (ProceduralTextureFormat::Unorm8, 3) => TexturePixelKind::RGB8,
+            (ProceduralTextureFormat::Unorm8, _) => TexturePixelKind::RGBA8,
+            (ProceduralTextureFormat::Float32, 1) => TexturePixelKind::R32F,
+            (ProceduralTextureFormat::Float32, 3) => TexturePixelKind::RGB32F,
+            (ProceduralTextureFormat::Float32, _) => TexturePixelKind::RGBA32F,
+        };
+
+        let mut size = [
+            self.width as usize,
+            self.height as usize,
+            self.depth.max(1) as usize,
+        ];
+        let mut pixels = Vec::with_capacity(size[0] * size[1] * size[2] * stored_channels);
+        for z in 0..size[2] {
+            for y in 0..size[1] {
+                for x in 0..size[0] {
+                    let uvw = Vector3::new(
+                        (x as f32 + 0.5) / size[0] as f32,
+                        (y as f32 + 0.5) / size[1] as f32,
+                        if is_volume {
+                            (z as f32 + 0.5) / size[2] as f32
+                        } else {
+                            0.0
+                        },
+                    );
+                    for node in self.channels.iter() {
+                        pixels.push(context.eval(node, uvw));
+                    }
+                    pixels.resize(pixels.len() + stored_channels - channels, 0.0);
+                }
+            }
+        }
+
+        let mut bytes = Vec::new();
+        let mut mip_count = 0;
+        loop {
+            for value in pixels.iter() {
+                match self.format {
+                    ProceduralTextureFormat::Unorm8 => {
+                        bytes.push((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+                    }
+                    ProceduralTextureFormat::Float32 => {
+                        bytes.extend_from_slice(&value.to_ne_bytes())
+                    }
+                }
+            }
+            mip_count += 1;
+
+            let new_size = [
+                size[0] / 2,
+                size[1] / 2,
+                if is_volume { size[2] / 2 } else { 1 },
+            ];
+            if !self.generate_mips || new_size.contains(&0) {
+                break;
+            }
+            pixels = downsample(&pixels, stored_channels, size, new_size);
+            size = new_size;
+        }
+
+        let kind = if is_volume {
+            TextureKind::Volume {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+            }
+        } else {
+            TextureKind::Rectangle {
+                width: self.width,
+                height: self.height,
+            }
+        };
+
+        Ok(Texture {
+            kind,
+            pixel_kind,
+            bytes: bytes.into(),
+            mip_count,
+            minification_filter: if self.generate_mips {
+                TextureMinificationFilter::LinearMipMapLinear
+            } else {
+                TextureMinificationFilter::Linear
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        procedural::{
+            NoiseKind, ProceduralNode, ProceduralTextureDefinition, ProceduralTextureFormat,
+        },
+        TextureKind, TexturePixelKind,
+    };
+
+    fn fbm(kind: NoiseKind, seed: u32) -> ProceduralNode {
+        ProceduralNode::Fbm {
+            kind,
+            frequency: 4.0,
+            octaves: 3,
+            lacunarity: 2.0,
+            gain: 0.5,
+            seed,
+        }
+    }
+
+    fn definition(channels: Vec<ProceduralNode>) -> ProceduralTextureDefinition {
+        ProceduralTextureDefinition {
+            width: 16,
+            height: 8,
+            depth: 0,
+            seamless: true,
+            generate_mips: false,
+            format: ProceduralTextureFormat::Unorm8,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_generation_is_deterministic_per_seed() {
+        for kind in [NoiseKind::Perlin, NoiseKind::Simplex, NoiseKind::Worley] {
+            let bake = |seed| definition(vec![fbm(kind, seed)]).bake().unwrap();
+            let first = bake(1);
+            assert_eq!(first.data(), bake(1).data(), "{kind:?}");
+            assert_ne!(first.data(), bake(2).data(), "{kind:?}");
+            // Noise must not be flat.
+            assert!(first.data().iter().any(|v| *v != first.data()[0]));
+        }
+    }
+
+    #[test]
+    fn test_dimensions_and_formats() {
+        let channels = |count| vec![fbm(NoiseKind::Perlin, 0); count];
+        for (format, count, pixel_kind, pixel_size) in [
+            (ProceduralTextureFormat::Unorm8, 1, TexturePixelKind::R8, 1),
+            (ProceduralTextureFormat::Unorm8, 2, TexturePixelKind::RG8, 2),
+            (
+                ProceduralTextureFormat::Unorm8,
+                3,
+                TexturePixelKind::RGB8,
+                3,
+            ),
+            (
+                ProceduralTextureFormat::Unorm8,
+                4,
+                TexturePixelKind::RGBA8,
+                4,
+            ),
+            (
+                ProceduralTextureFormat::Float32,
+                1,
+                TexturePixelKind::R32F,
+                4,
+            ),
+            (
+                ProceduralTextureFormat::Float32,
+                2,
+                TexturePixelKind::RGB32F,
+                12,
+            ),
+            (
+                ProceduralTextureFormat::Float32,
+                4,
+                TexturePixelKind::RGBA32F,
+                16,
+            ),
+        ] {
+            let texture = ProceduralTextureDefinition {
+                format,
+                ..definition(channels(count))
+            }
+            .bake()
+            .unwrap();
+            assert_eq!(texture.pixel_kind(), pixel_kind);
+            assert_eq!(texture.mip_count(), 1);
+            assert!(matches!(
+                texture.kind(),
+                TextureKind::Rectangle {
+                    width: 16,
+                    height: 8
+                }
+            ));
+            assert_eq!(texture.data().len(), 16 * 8 * pixel_size);
+        }
+    }
+
+    #[test]
+    fn test_volume_with_mips() {
+        let texture = ProceduralTextureDefinition {
+            depth: 4,
+            generate_mips: true,
+            ..definition(vec![fbm(NoiseKind::Worley, 3)])
+        }
+        .bake()
+        .unwrap();
+        assert!(matches!(
+            texture.kind(),
+            TextureKind::Volume {
+                width: 16,
+                height: 8,
+                depth: 4
+            }
+        ));
+        // 16x8x4, 8x4x2, 4x2x1
+        assert_eq!(texture.mip_count(), 3);
+        assert_eq!(texture.data().len(), 16 * 8 * 4 + 8 * 4 * 2 + 4 * 2);
+    }
+
+    #[test]
+    fn test_node_evaluation() {
+        let texture = definition(vec![
+            ProceduralNode::Constant(0.25),
+            ProceduralNode::Invert(Box::new(ProceduralNode::Constant(0.25))),
+            ProceduralNode::Threshold {
+                source: Box::new(ProceduralNode::Constant(0.75)),
+                edge: 0.5,
+                smoothness: 0.0,
+            },
+        ])
+        .bake()
+        .unwrap();
+        for pixel in texture.data().chunks_exact(3) {
+            assert_eq!(pixel, [64, 191, 255]);
+        }
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        assert!(definition(vec![]).bake().is_err());
+        assert!(definition(vec![ProceduralNode::Constant(0.0); 5])
+            .bake()
+            .is_err());
+        assert!(ProceduralTextureDefinition {
+            width: 0,
+            ..definition(vec![ProceduralNode::Constant(0.0)])
+        }
+        .bake()
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_definition() {
+        let definition = ProceduralTextureDefinition::from_bytes(
+            br#"(
+                width: 32,
+                height: 32,
+                channels: [Noise(kind: Perlin, frequency: 8.0)],
+            )"#,
+        )
+        .unwrap();
+        assert!(definition.seamless);
+        assert!(definition.generate_mips);
+        assert_eq!(definition.format, ProceduralTextureFormat::Unorm8);
+        assert_eq!(
+            definition.channels,
+            [ProceduralNode::Noise {
+                kind: NoiseKind::Perlin,
+                frequency: 8.0,
+                seed: 0
+            }]
+        );
+        let texture = definition.bake().unwrap();
+        // 32, 16, 8, 4, 2, 1
+        assert_eq!(texture.mip_count(), 6);
+    }
+}